@@ -0,0 +1,479 @@
+//! Conversion of the `build` sections of a [`Compose`](crate::Compose) project into a
+//! `docker buildx bake` definition.
+//!
+//! [`Bake`] serializes into the JSON format of a bake file, e.g. `docker-bake.json`. Each service
+//! with a `build` section becomes a [`Target`], and the `default` group builds them all.
+//!
+//! [Bake file reference](https://docs.docker.com/build/bake/reference/)
+
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    engine::Project,
+    secret::{self, Secret},
+    service::{
+        build::{Cache, Context, Dockerfile, Network, SshAuth},
+        Build, ConfigOrSecret,
+    },
+    Identifier, InvalidMapKeyError, ListOrMap, Map, Resource, ShortOrLong,
+};
+
+/// Name of the group containing all targets.
+const DEFAULT_GROUP: &str = "default";
+
+/// A `docker buildx bake` definition with a [`Target`] for each service with a `build` section.
+///
+/// [Bake file reference](https://docs.docker.com/build/bake/reference/)
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Bake {
+    /// Groups of targets, keyed by name.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub group: IndexMap<String, Group>,
+
+    /// Build targets, keyed by name.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub target: IndexMap<String, Target>,
+}
+
+impl Bake {
+    /// Convert the `build` sections of the services of a `project` into bake [`Target`]s named
+    /// after the services, and a `default` group containing them all.
+    ///
+    /// Target names may not contain dots (.), so they are replaced with underscores (_), e.g. the
+    /// target of service `web.v2` is `web_v2`. Additional contexts referencing another service,
+    /// `service:{service}`, become `target:{target}`.
+    ///
+    /// Services without a `build` section are skipped. Relative build contexts, SSH key, and
+    /// secret file paths are resolved against the project's working directory, while `dockerfile`
+    /// stays relative to the context, as in Compose. Images are tagged with the service's `image`,
+    /// or `{project}-{service}` if not set, followed by `build.tags`. Build arguments without a
+    /// value are `null`. The `extra_hosts`, `isolation`, and `privileged` fields have no bake
+    /// equivalent and are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a key of `args` or `labels` is invalid, if a build secret is not
+    /// defined in the project or is external, or if two services have the same target name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use compose_spec::{bake::Bake, engine::Project, Compose, Name};
+    ///
+    /// let yaml = "\
+    /// services:
+    ///   app:
+    ///     build:
+    ///       context: ./app
+    ///       args:
+    ///         VERSION: '1.0'
+    ///       cache_from:
+    ///         - type=gha,scope=app
+    ///       platforms:
+    ///         - linux/amd64
+    ///   caddy:
+    ///     image: docker.io/library/caddy:latest
+    /// ";
+    /// let compose: Compose = serde_yaml::from_str(yaml)?;
+    /// let name = Name::new("web")?;
+    /// let project = Project::new(&name, &compose, Path::new("/srv/web"));
+    ///
+    /// let bake = Bake::from_project(&project)?;
+    /// assert_eq!(bake.group["default"].targets, ["app"]);
+    ///
+    /// let app = &bake.target["app"];
+    /// assert_eq!(app.context.as_deref(), Some("/srv/web/app"));
+    /// assert_eq!(app.args["VERSION"].as_deref(), Some("1.0"));
+    /// assert_eq!(app.cache_from, ["type=gha,scope=app"]);
+    /// assert_eq!(app.platforms, ["linux/amd64"]);
+    /// assert_eq!(app.tags, ["web-app"]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_project(project: &Project) -> Result<Self, BakeError> {
+        let mut target = IndexMap::new();
+        let mut services: IndexMap<String, &Identifier> = IndexMap::new();
+        for (name, service) in &project.compose.services {
+            if let Some(build) = &service.build {
+                let target_name = target_name(name.as_str());
+                if let Some(first) = services.insert(target_name.clone(), name) {
+                    return Err(BakeError::TargetName {
+                        first: first.clone(),
+                        second: name.clone(),
+                        target: target_name,
+                    });
+                }
+                let tags = vec![service
+                    .image
+                    .as_ref()
+                    .map_or_else(|| format!("{}-{name}", project.name), ToString::to_string)];
+                let build = Target::from_build(project, name, build.clone().into_long(), tags)?;
+                target.insert(target_name, build);
+            }
+        }
+
+        let group = if target.is_empty() {
+            IndexMap::new()
+        } else {
+            IndexMap::from([(
+                DEFAULT_GROUP.to_owned(),
+                Group {
+                    targets: target.keys().cloned().collect(),
+                },
+            )])
+        };
+
+        Ok(Self { group, target })
+    }
+}
+
+/// A named group of bake [`Target`]s which are built together.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct Group {
+    /// Names of the targets in the group.
+    pub targets: Vec<String>,
+}
+
+/// A bake target, the build of a single image.
+///
+/// [Bake file reference](https://docs.docker.com/build/bake/reference/#target)
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "kebab-case")]
+pub struct Target {
+    /// Build context, a path or URL.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub context: Option<String>,
+
+    /// Additional named contexts.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub contexts: IndexMap<String, String>,
+
+    /// Path of the Dockerfile, relative to the context.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dockerfile: Option<PathBuf>,
+
+    /// Contents of the Dockerfile.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dockerfile_inline: Option<String>,
+
+    /// Build arguments, [`None`] values are not set.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub args: IndexMap<String, Option<String>>,
+
+    /// SSH agent sockets or keys exposed to the build, in the format `default` or `{id}={path}`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ssh: Vec<String>,
+
+    /// Cache sources, in the format `type={type}[,{key}={value}...]`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cache_from: Vec<String>,
+
+    /// Cache export destinations, in the format `type={type}[,{key}={value}...]`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cache_to: Vec<String>,
+
+    /// Platforms to build for, e.g. `linux/amd64`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub platforms: Vec<String>,
+
+    /// Image names and tags of the build result.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub tags: Vec<String>,
+
+    /// Stage of a multi-stage Dockerfile to build.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub target: Option<String>,
+
+    /// Secrets exposed to the build, in the format `id={id},src={path}` or `id={id},env={var}`.
+    #[serde(rename = "secret", skip_serializing_if = "Vec::is_empty")]
+    pub secrets: Vec<String>,
+
+    /// Network mode of `RUN` instructions.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network: Option<String>,
+
+    /// Size of `/dev/shm` for `RUN` instructions, e.g. `128m`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shm_size: Option<String>,
+
+    /// Resource limits of `RUN` instructions, in the format `{type}={soft}:{hard}`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ulimits: Vec<String>,
+
+    /// Whether to build without using the cache.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub no_cache: bool,
+
+    /// Whether to always pull referenced images.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub pull: bool,
+
+    /// Image labels.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub labels: IndexMap<String, String>,
+
+    /// Privileged entitlements allowed during the build, e.g. `network.host`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub entitlements: Vec<String>,
+}
+
+impl Target {
+    /// Convert the [`Build`] of the service `name` into a [`Target`], adding `build.tags` to the
+    /// given `tags`.
+    fn from_build(
+        project: &Project,
+        name: &Identifier,
+        Build {
+            context,
+            dockerfile,
+            args,
+            ssh,
+            cache_from,
+            cache_to,
+            additional_contexts,
+            entitlements,
+            extra_hosts: _,
+            isolation: _,
+            privileged: _,
+            labels,
+            no_cache,
+            pull,
+            network,
+            shm_size,
+            target,
+            secrets,
+            tags,
+            ulimits,
+            platforms,
+            extensions: _,
+        }: Build,
+        mut image_tags: Vec<String>,
+    ) -> Result<Self, BakeError> {
+        let (dockerfile, dockerfile_inline) = match dockerfile {
+            Some(Dockerfile::File(path)) => (Some(path), None),
+            Some(Dockerfile::Inline(contents)) => (None, Some(contents)),
+            None => (None, None),
+        };
+
+        let args = map(name, "args", args)?;
+        let labels = map(name, "labels", labels)?
+            .into_iter()
+            .map(|(key, value)| (key, value.unwrap_or_default()))
+            .collect();
+
+        image_tags.extend(tags.iter().map(ToString::to_string));
+
+        let secrets = secrets
+            .into_iter()
+            .map(|secret| secret_string(project, name, secret.into_long()))
+            .collect::<Result<_, _>>()?;
+
+        let ulimits = ulimits
+            .into_iter()
+            .map(|(resource, ulimit)| {
+                let (soft, hard) = match ulimit {
+                    ShortOrLong::Short(limit) => (limit, limit),
+                    ShortOrLong::Long(ulimit) => (ulimit.soft, ulimit.hard),
+                };
+                format!("{resource}={soft}:{hard}")
+            })
+            .collect();
+
+        Ok(Self {
+            context: Some(context.map_or_else(
+                || project.working_dir.display().to_string(),
+                |context| context_string(project, &context),
+            )),
+            contexts: additional_contexts
+                .iter()
+                .map(|(key, context)| (key.to_string(), context_string(project, context)))
+                .collect(),
+            dockerfile,
+            dockerfile_inline,
+            args,
+            ssh: ssh
+                .iter()
+                .map(|ssh| match ssh {
+                    SshAuth::Default => ssh.to_string(),
+                    SshAuth::Id(id) => {
+                        format!("{}={}", id.id(), project.host_path(id.path()).display())
+                    }
+                })
+                .collect(),
+            cache_from: cache_from.into_iter().map(cache_string).collect(),
+            cache_to: cache_to.into_iter().map(cache_string).collect(),
+            platforms: platforms.iter().map(ToString::to_string).collect(),
+            tags: image_tags,
+            target,
+            secrets,
+            network: network.as_ref().map(|network| match network {
+                Network::Identifier(network) => network.to_string(),
+                Network::None => "none".to_owned(),
+            }),
+            shm_size: shm_size.map(|shm_size| shm_size.to_string()),
+            ulimits,
+            no_cache,
+            pull,
+            labels,
+            entitlements,
+        })
+    }
+}
+
+/// Name of the bake [`Target`] of a service, with dots (.) replaced with underscores (_).
+fn target_name(service: &str) -> String {
+    service.replace('.', "_")
+}
+
+/// Convert a [`Context`] into a string, resolving relative paths against the project's working
+/// directory.
+///
+/// References to other services, `service:{service}`, become `target:{target}`.
+fn context_string(project: &Project, context: &Context) -> String {
+    match context {
+        Context::Path(path) => project.host_path(path).display().to_string(),
+        Context::Url(url) if url.scheme() == "service" => {
+            format!("target:{}", target_name(url.path()))
+        }
+        Context::Url(url) => url.to_string(),
+    }
+}
+
+/// Convert a [`Cache`] into the `type={type}[,{key}={value}...]` format.
+fn cache_string(cache: Cache) -> String {
+    cache
+        .into_options()
+        .into_iter()
+        .map(|(key, value)| format!("{key}={value}"))
+        .collect::<Vec<_>>()
+        .join(",")
+}
+
+/// Convert a [`ListOrMap`] into a map of strings.
+fn map(
+    service: &Identifier,
+    field: &'static str,
+    list_or_map: ListOrMap,
+) -> Result<IndexMap<String, Option<String>>, BakeError> {
+    let map: Map = list_or_map.into_map().map_err(|source| BakeError::MapKey {
+        service: service.clone(),
+        field,
+        source,
+    })?;
+    Ok(map
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value.map(|value| value.to_string())))
+        .collect())
+}
+
+/// Convert a build secret into the `id={id},src={path}` or `id={id},env={var}` format.
+///
+/// The ID is the secret's `target`, or its `source` if not set.
+fn secret_string(
+    project: &Project,
+    service: &Identifier,
+    secret: ConfigOrSecret,
+) -> Result<String, BakeError> {
+    let Some(Resource::Compose(Secret { source, .. })) =
+        project.compose.secrets.get(&secret.source)
+    else {
+        return Err(BakeError::Secret {
+            service: service.clone(),
+            secret: secret.source,
+        });
+    };
+    let id = secret.target.map_or_else(
+        || secret.source.to_string(),
+        |target| target.display().to_string(),
+    );
+    Ok(match source {
+        secret::Source::File(path) => format!("id={id},src={}", project.host_path(path).display()),
+        secret::Source::Environment(variable) => format!("id={id},env={variable}"),
+    })
+}
+
+/// Error returned when converting a [`Compose`](crate::Compose) project into a [`Bake`]
+/// definition.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum BakeError {
+    /// A key of a list or map field was invalid.
+    #[error("invalid key in `build.{field}` of service `{service}`")]
+    MapKey {
+        /// Name of the service.
+        service: Identifier,
+
+        /// The field containing the invalid key.
+        field: &'static str,
+
+        /// Source of the error.
+        source: InvalidMapKeyError,
+    },
+
+    /// A build secret was not defined in the project, or was external.
+    #[error(
+        "build secret `{secret}` of service `{service}` is not defined in the project or is external"
+    )]
+    Secret {
+        /// Name of the service.
+        service: Identifier,
+
+        /// Name of the secret.
+        secret: Identifier,
+    },
+
+    /// Two services would have targets with the same name, e.g. `web.v2` and `web_v2`.
+    #[error("services `{first}` and `{second}` would both have the bake target `{target}`")]
+    TargetName {
+        /// Name of the first service.
+        first: Identifier,
+
+        /// Name of the second service.
+        second: Identifier,
+
+        /// The shared target name.
+        target: String,
+    },
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::path::Path;
+
+    use crate::{Compose, Name};
+
+    use super::*;
+
+    #[test]
+    fn full() {
+        let compose: Compose = serde_yaml::from_str(include_str!("bake/test-full.yaml")).unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+
+        let bake = Bake::from_project(&project).unwrap();
+
+        // JSON is valid YAML.
+        let expected: serde_yaml::Value =
+            serde_yaml::from_str(include_str!("bake/test-full.json")).unwrap();
+        assert_eq!(serde_yaml::to_value(&bake).unwrap(), expected);
+    }
+
+    #[test]
+    fn target_name_collision() {
+        let compose: Compose =
+            serde_yaml::from_str("services: {web.v2: {build: ./v2}, web_v2: {build: ./v2}}")
+                .unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+
+        assert_eq!(
+            Bake::from_project(&project).unwrap_err().to_string(),
+            "services `web.v2` and `web_v2` would both have the bake target `web_v2`",
+        );
+    }
+}
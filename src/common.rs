@@ -139,6 +139,23 @@ where
     }
 }
 
+/// Convert items into an [`ItemOrList`], [`None`] if there are no items.
+pub(crate) fn item_or_list<T: Eq + Hash>(items: Vec<T>) -> Option<ItemOrList<T>> {
+    match <[T; 1]>::try_from(items) {
+        Ok([item]) => Some(ItemOrList::Item(item)),
+        Err(items) => (!items.is_empty()).then(|| ItemOrList::List(items.into_iter().collect())),
+    }
+}
+
+/// Parse a boolean flag or option value, as accepted by the `docker` and `podman` command lines.
+pub(crate) fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "1" | "t" | "T" | "true" | "TRUE" | "True" => Some(true),
+        "0" | "f" | "F" | "false" | "FALSE" | "False" => Some(false),
+        _ => None,
+    }
+}
+
 /// A list of unique strings or a map with optional single values.
 #[derive(Serialize, Debug, Clone, PartialEq)]
 #[serde(untagged)]
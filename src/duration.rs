@@ -88,6 +88,18 @@ fn push_value(string: &mut String, value: u64, unit: &str) {
     }
 }
 
+/// Convert a [`Duration`] into whole seconds, rounding up.
+///
+/// Used where only whole seconds are supported, so that a non-zero duration never becomes zero.
+pub(crate) const fn seconds(duration: Duration) -> u64 {
+    let seconds = duration.as_secs();
+    if duration.subsec_nanos() == 0 {
+        seconds
+    } else {
+        seconds.saturating_add(1)
+    }
+}
+
 /// Parse a [`Duration`] from a string in the
 /// [compose-spec duration format](https://github.com/compose-spec/compose-spec/blob/master/11-extension.md#specifying-durations).
 ///
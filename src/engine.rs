@@ -0,0 +1,1845 @@
+//! Conversion of a [`Service`] into the body of a Docker Engine API container create request.
+//!
+//! The body of a `POST /containers/create` request is represented by [`ContainerCreate`], which
+//! is made up of the container [`Config`], its [`HostConfig`], and its [`NetworkingConfig`].
+//! Services can also be converted into the body of a Swarm service create request, see [`swarm`].
+//!
+//! [Docker Engine API](https://docs.docker.com/engine/api/v1.45/#tag/Container/operation/ContainerCreate)
+
+use std::{
+    path::{Component, Path, PathBuf},
+    time::Duration,
+};
+
+use indexmap::IndexMap;
+use serde::{ser::SerializeMap, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::{
+    config, duration,
+    network::Network,
+    secret,
+    service::{
+        blkio_config::{BpsLimit, IopsLimit},
+        deploy::resources::{Cpus, Resources},
+        device::Permissions,
+        healthcheck::{self, Test},
+        network_config::{self, NetworkMode},
+        ports,
+        volumes::{
+            self,
+            mount::{self, BindRecursive},
+        },
+        ByteValue, Command, ConfigOrSecret, Deploy, Device, EngineUlimit, Healthcheck, Ipc, Limit,
+        Link, Logging, NetworkConfig, Pid, Restart, VolumesFrom, VolumesFromSource,
+    },
+    Compose, Identifier, InvalidMapKeyError, ListOrMap, Map, Name, Resource, Service, ShortOrLong,
+    Volume,
+};
+
+pub mod swarm;
+
+/// Context needed to convert a [`Service`] of a [`Compose`] project into a [`ContainerCreate`].
+#[derive(Debug, Clone, Copy)]
+pub struct Project<'a> {
+    /// Name of the project.
+    ///
+    /// Used to prefix the names of containers, networks, and volumes, and for the
+    /// `com.docker.compose.project` label.
+    pub name: &'a Name,
+
+    /// The [`Compose`] file of the project, used to resolve networks, volumes, configs, secrets,
+    /// and references to other services.
+    pub compose: &'a Compose,
+
+    /// Directory relative host paths are resolved against.
+    ///
+    /// Usually the directory containing the Compose file.
+    pub working_dir: &'a Path,
+}
+
+impl<'a> Project<'a> {
+    /// Create a new [`Project`].
+    #[must_use]
+    pub const fn new(name: &'a Name, compose: &'a Compose, working_dir: &'a Path) -> Self {
+        Self {
+            name,
+            compose,
+            working_dir,
+        }
+    }
+
+    /// Name of the container of a [`Service`].
+    ///
+    /// The service's `container_name` if set, otherwise `{project}-{service}-1`.
+    #[must_use]
+    pub fn container_name(&self, service: &str) -> String {
+        self.compose
+            .services
+            .get(service)
+            .and_then(|service| service.container_name.as_ref())
+            .map_or_else(|| format!("{}-{service}-1", self.name), ToString::to_string)
+    }
+
+    /// Name of a [`Network`] as it exists on the platform.
+    ///
+    /// The network's `name` if set, the key of external networks, or `{project}_{network}`.
+    #[must_use]
+    pub fn network_name(&self, network: &str) -> String {
+        match self.compose.networks.get(network) {
+            Some(Some(Resource::External { name })) => {
+                name.clone().unwrap_or_else(|| network.to_owned())
+            }
+            Some(Some(Resource::Compose(Network {
+                name: Some(name), ..
+            }))) => name.clone(),
+            _ => format!("{}_{network}", self.name),
+        }
+    }
+
+    /// Name of a [`Volume`] as it exists on the platform.
+    ///
+    /// The volume's `name` if set, the key of external volumes, or `{project}_{volume}`.
+    #[must_use]
+    pub fn volume_name(&self, volume: &str) -> String {
+        match self.compose.volumes.get(volume) {
+            Some(Some(Resource::External { name })) => {
+                name.clone().unwrap_or_else(|| volume.to_owned())
+            }
+            Some(Some(Resource::Compose(Volume {
+                name: Some(name), ..
+            }))) => name.clone(),
+            _ => format!("{}_{volume}", self.name),
+        }
+    }
+
+    /// Network mode as passed to the engine.
+    ///
+    /// Services are referenced by their container name, i.e. `service:{service}` becomes
+    /// `container:{container_name}`.
+    #[must_use]
+    pub fn network_mode(&self, network_mode: &NetworkMode) -> String {
+        match network_mode {
+            NetworkMode::Service(service) => {
+                format!("container:{}", self.container_name(service.as_str()))
+            }
+            network_mode => network_mode.to_string(),
+        }
+    }
+
+    /// Host path and container target of a config with a file source.
+    ///
+    /// The default target is `/{source}`.
+    pub(crate) fn config_file(
+        &self,
+        config: &ShortOrLong<Identifier, ConfigOrSecret>,
+    ) -> Option<(PathBuf, PathBuf)> {
+        let (source, target) = config_or_secret(config);
+        let Some(Resource::Compose(config::Config {
+            source: config::Source::File(path),
+            ..
+        })) = self.compose.configs.get(source)
+        else {
+            return None;
+        };
+        let target = Path::new("/").join(target.unwrap_or_else(|| Path::new(source.as_str())));
+        Some((self.host_path(path), target))
+    }
+
+    /// Host path and container target of a secret with a file source.
+    ///
+    /// Secrets are mounted in `/run/secrets`, the default target is the `source`.
+    pub(crate) fn secret_file(
+        &self,
+        secret: &ShortOrLong<Identifier, ConfigOrSecret>,
+    ) -> Option<(PathBuf, PathBuf)> {
+        let (source, target) = config_or_secret(secret);
+        let Some(Resource::Compose(secret::Secret {
+            source: secret::Source::File(path),
+            ..
+        })) = self.compose.secrets.get(source)
+        else {
+            return None;
+        };
+        let target =
+            Path::new("/run/secrets").join(target.unwrap_or_else(|| Path::new(source.as_str())));
+        Some((self.host_path(path), target))
+    }
+
+    /// Resolve a host path against the `working_dir` if it is relative.
+    pub(crate) fn host_path(&self, path: &Path) -> PathBuf {
+        if path.is_absolute() {
+            path.to_owned()
+        } else {
+            self.working_dir.join(
+                path.components()
+                    .filter(|component| !matches!(component, Component::CurDir))
+                    .collect::<PathBuf>(),
+            )
+        }
+    }
+}
+
+/// Body of a Docker Engine API `POST /containers/create` request.
+///
+/// The name of the container is passed as a query parameter, see
+/// [`Project::container_name()`].
+///
+/// [Docker Engine API](https://docs.docker.com/engine/api/v1.45/#tag/Container/operation/ContainerCreate)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerCreate {
+    /// Configuration of the container which is portable between hosts.
+    #[serde(flatten)]
+    pub config: Config,
+
+    /// Configuration of the container which depends on the host.
+    pub host_config: HostConfig,
+
+    /// Networks the container is connected to.
+    pub networking_config: NetworkingConfig,
+}
+
+impl ContainerCreate {
+    /// Convert the [`Service`] named `name` of the `project` into a [`ContainerCreate`] body.
+    ///
+    /// Requires Docker Engine API version 1.44 or later, as the container is connected to all of
+    /// its networks on creation.
+    ///
+    /// Fields which are handled by the Compose implementation rather than the engine, such as
+    /// `build`, `depends_on`, `develop`, `pull_policy`, and `scale`, are ignored. Resource limits
+    /// and reservations from `deploy` are used when the equivalent service field is not set.
+    /// Values from `env_file` and `label_file` are not read, use [`Service::resolve_labels()`]
+    /// and [`EnvFile`](crate::service::EnvFile) to merge them beforehand. Environment variables without
+    /// a value are passed as is, which unsets them. Configs and secrets with a file source are
+    /// bind mounted, others must be copied into the container after it is created.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `entrypoint` or `command` could not be split into words, a key of
+    /// `labels`, `annotations`, or `sysctls` is invalid, a byte value overflows, or a bind mount
+    /// sets both `selinux` and `recursive`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use compose_spec::{
+    ///     engine::{ContainerCreate, Project},
+    ///     Compose, Identifier, Name,
+    /// };
+    ///
+    /// let yaml = "\
+    /// services:
+    ///   caddy:
+    ///     image: docker.io/library/caddy:latest
+    ///     cpus: 0.5
+    ///     mem_limit: 256mb
+    ///     ports:
+    ///       - 8080:80
+    /// ";
+    /// let compose: Compose = serde_yaml::from_str(yaml)?;
+    /// let name = Name::new("app")?;
+    /// let project = Project::new(&name, &compose, Path::new("/srv/app"));
+    ///
+    /// let caddy = Identifier::new("caddy")?;
+    /// let body = ContainerCreate::from_service(&project, &caddy, &compose.services[&caddy])?;
+    /// assert_eq!(project.container_name(caddy.as_str()), "app-caddy-1");
+    /// assert_eq!(body.host_config.nano_cpus, Some(500_000_000));
+    /// assert_eq!(body.host_config.memory, Some(256_000_000));
+    /// assert_eq!(
+    ///     body.host_config.network_mode.as_deref(),
+    ///     Some("app_default"),
+    /// );
+    /// assert!(body.config.exposed_ports.contains_key("80/tcp"));
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[allow(clippy::too_many_lines)]
+    pub fn from_service(
+        project: &Project,
+        name: &Identifier,
+        service: &Service,
+    ) -> Result<Self, ContainerCreateError> {
+        let Service {
+            attach: _,
+            build,
+            blkio_config,
+            cpu_count,
+            cpu_percent,
+            cpu_shares,
+            cpu_period,
+            cpu_quota,
+            cpu_rt_runtime,
+            cpu_rt_period,
+            cpus,
+            cpuset,
+            cap_add,
+            cap_drop,
+            cgroup,
+            cgroup_parent,
+            command,
+            configs,
+            container_name: _,
+            credential_spec: _,
+            depends_on: _,
+            deploy,
+            develop: _,
+            device_cgroup_rules,
+            devices,
+            dns,
+            dns_opt,
+            dns_search,
+            domain_name,
+            entrypoint,
+            env_file: _,
+            environment,
+            expose,
+            extends: _,
+            annotations,
+            external_links,
+            extra_hosts,
+            group_add,
+            healthcheck,
+            hostname,
+            image,
+            init,
+            ipc,
+            uts,
+            isolation,
+            labels,
+            label_file: _,
+            links,
+            logging,
+            network_config,
+            mac_address,
+            mem_limit,
+            mem_reservation,
+            mem_swappiness,
+            memswap_limit,
+            oom_kill_disable,
+            oom_score_adj,
+            pid,
+            pids_limit,
+            platform: _,
+            ports,
+            privileged,
+            profiles: _,
+            pull_policy: _,
+            read_only,
+            restart,
+            runtime,
+            scale: _,
+            secrets,
+            security_opt,
+            shm_size,
+            stdin_open,
+            stop_grace_period,
+            stop_signal,
+            storage_opt,
+            sysctls,
+            tmpfs,
+            tty,
+            ulimits,
+            user,
+            userns_mode,
+            volumes,
+            volumes_from,
+            working_dir,
+            extensions: _,
+        } = service;
+
+        let (limits, reservations) = deploy
+            .as_ref()
+            .and_then(|Deploy { resources, .. }| resources.as_ref())
+            .map_or(
+                (None, None),
+                |Resources {
+                     limits,
+                     reservations,
+                     ..
+                 }| { (limits.as_ref(), reservations.as_ref()) },
+            );
+
+        let mut labels = string_map("labels", labels)?;
+        for (key, value) in [
+            ("com.docker.compose.project", project.name.as_str()),
+            ("com.docker.compose.service", name.as_str()),
+            ("com.docker.compose.oneoff", "False"),
+            ("com.docker.compose.container-number", "1"),
+        ] {
+            labels.insert(key.to_owned(), value.to_owned());
+        }
+
+        let mut exposed_ports = IndexMap::new();
+        let mut port_bindings: IndexMap<String, Vec<PortBinding>> = IndexMap::new();
+        for port in ports::into_long_iter(ports.clone()) {
+            let key = port_key(port.target, port.protocol.as_ref());
+            exposed_ports.insert(key.clone(), EmptyObject);
+            port_bindings.entry(key).or_default().push(PortBinding {
+                host_ip: port.host_ip.map(|host_ip| host_ip.to_string()),
+                host_port: port.published.map(|published| published.to_string()),
+            });
+        }
+        for expose in expose {
+            for port in expose.range {
+                exposed_ports.insert(port_key(port, expose.protocol.as_ref()), EmptyObject);
+            }
+        }
+
+        let config = Config {
+            hostname: hostname
+                .as_ref()
+                .map(|hostname| hostname.as_str().to_owned()),
+            domainname: domain_name
+                .as_ref()
+                .map(|domain_name| domain_name.as_str().to_owned()),
+            user: user.as_ref().map(ToString::to_string),
+            exposed_ports,
+            tty: *tty,
+            open_stdin: *stdin_open,
+            env: environment.clone().into_list().into_iter().collect(),
+            cmd: command
+                .as_ref()
+                .map(|command| split_command("command", command))
+                .transpose()?,
+            healthcheck: healthcheck.as_ref().map(HealthConfig::from_healthcheck),
+            image: image.as_ref().map_or_else(
+                || build.is_some().then(|| format!("{}-{name}", project.name)),
+                |image| Some(image.to_string()),
+            ),
+            working_dir: working_dir
+                .as_ref()
+                .map(|working_dir| working_dir.as_path().to_owned()),
+            entrypoint: entrypoint
+                .as_ref()
+                .map(|entrypoint| split_command("entrypoint", entrypoint))
+                .transpose()?,
+            labels,
+            stop_signal: stop_signal.as_ref().map(ToString::to_string),
+            stop_timeout: stop_grace_period.map(duration::seconds),
+        };
+
+        let mut mounts = Mounts::default();
+        for mount in volumes::into_long_iter(volumes.clone()) {
+            mounts.push(project, mount)?;
+        }
+        for (source, target) in configs
+            .iter()
+            .filter_map(|config| project.config_file(config))
+            .chain(
+                secrets
+                    .iter()
+                    .filter_map(|secret| project.secret_file(secret)),
+            )
+        {
+            mounts.bind_file(&source, target);
+        }
+
+        let (network_mode, networking_config) =
+            NetworkingConfig::new(project, name, network_config.as_ref(), mac_address.as_ref());
+
+        let blkio_config = blkio_config.clone().unwrap_or_default();
+
+        let host_config = HostConfig {
+            cpu_shares: *cpu_shares,
+            memory: mem_limit
+                .or_else(|| limits.and_then(|limits| limits.memory))
+                .map(|memory| bytes("mem_limit", memory))
+                .transpose()?,
+            cgroup_parent: cgroup_parent.as_ref().map(ToString::to_string),
+            blkio_weight: blkio_config.weight.map(Into::into),
+            blkio_weight_device: blkio_config
+                .weight_device
+                .iter()
+                .map(|device| WeightDevice {
+                    path: device.path.as_path().to_owned(),
+                    weight: device.weight.into(),
+                })
+                .collect(),
+            blkio_device_read_bps: bps_limits(
+                "blkio_config.device_read_bps",
+                &blkio_config.device_read_bps,
+            )?,
+            blkio_device_write_bps: bps_limits(
+                "blkio_config.device_write_bps",
+                &blkio_config.device_write_bps,
+            )?,
+            blkio_device_read_iops: iops_limits(&blkio_config.device_read_iops),
+            blkio_device_write_iops: iops_limits(&blkio_config.device_write_iops),
+            cpu_period: cpu_period.map(micros),
+            cpu_quota: cpu_quota.map(micros),
+            cpu_realtime_period: cpu_rt_period.map(micros),
+            cpu_realtime_runtime: cpu_rt_runtime.map(micros),
+            cpuset_cpus: (!cpuset.is_empty()).then(|| cpuset.to_string()),
+            devices: devices.iter().map(DeviceMapping::from).collect(),
+            device_cgroup_rules: device_cgroup_rules
+                .iter()
+                .map(ToString::to_string)
+                .collect(),
+            memory_reservation: mem_reservation
+                .or_else(|| reservations.and_then(|reservations| reservations.memory))
+                .map(|memory| bytes("mem_reservation", memory))
+                .transpose()?,
+            memory_swap: memswap_limit
+                .map(|limit| match limit {
+                    Limit::Value(memory) => bytes("memswap_limit", memory).map(saturating_i64),
+                    Limit::Unlimited => Ok(-1),
+                })
+                .transpose()?,
+            memory_swappiness: mem_swappiness.map(Into::into),
+            nano_cpus: cpus
+                .or_else(|| limits.and_then(|limits| limits.cpus))
+                .map(nano_cpus),
+            oom_kill_disable: *oom_kill_disable,
+            init: *init,
+            pids_limit: pids_limit
+                .or_else(|| limits.and_then(|limits| limits.pids))
+                .map(|limit| match limit {
+                    Limit::Value(pids) => pids.into(),
+                    Limit::Unlimited => -1,
+                }),
+            ulimits: EngineUlimit::from_ulimits(ulimits),
+            cpu_count: *cpu_count,
+            cpu_percent: cpu_percent.map(Into::into),
+            binds: mounts.binds,
+            log_config: logging.as_ref().map(LogConfig::from),
+            network_mode,
+            port_bindings,
+            restart_policy: restart.map(RestartPolicy::from),
+            volumes_from: volumes_from
+                .iter()
+                .map(|volumes_from| volumes_from_reference(project, volumes_from))
+                .collect(),
+            mounts: mounts.mounts,
+            annotations: string_map("annotations", annotations)?,
+            cap_add: cap_add.iter().map(ToString::to_string).collect(),
+            cap_drop: cap_drop.iter().map(ToString::to_string).collect(),
+            cgroupns_mode: cgroup.map(|cgroup| cgroup.as_str().to_owned()),
+            dns: dns
+                .iter()
+                .flat_map(|dns| dns.clone().into_list())
+                .map(|dns| dns.to_string())
+                .collect(),
+            dns_options: dns_opt.iter().cloned().collect(),
+            dns_search: dns_search
+                .iter()
+                .flat_map(|dns_search| dns_search.clone().into_list())
+                .map(|dns_search| dns_search.as_str().to_owned())
+                .collect(),
+            extra_hosts: extra_hosts
+                .iter()
+                .map(|(host, ip)| format!("{host}:{ip}"))
+                .collect(),
+            group_add: group_add.iter().map(ToString::to_string).collect(),
+            ipc_mode: ipc.as_ref().map(|ipc| match ipc {
+                Ipc::Service(service) => {
+                    format!("container:{}", project.container_name(service.as_str()))
+                }
+                ipc => ipc.to_string(),
+            }),
+            links: links
+                .iter()
+                .map(|link| link_reference(project, link, true))
+                .chain(
+                    external_links
+                        .iter()
+                        .map(|link| link_reference(project, link, false)),
+                )
+                .collect(),
+            oom_score_adj: oom_score_adj.map(Into::into),
+            pid_mode: pid.as_ref().map(|pid| match pid {
+                Pid::Service(service) => {
+                    format!("container:{}", project.container_name(service.as_str()))
+                }
+                pid => pid.to_string(),
+            }),
+            privileged: *privileged,
+            readonly_rootfs: *read_only,
+            security_opt: security_opt.iter().map(ToString::to_string).collect(),
+            storage_opt: storage_opt
+                .iter()
+                .map(|(key, value)| (key.to_string(), value_string(value.as_ref())))
+                .collect(),
+            tmpfs: tmpfs
+                .iter()
+                .flat_map(|tmpfs| tmpfs.clone().into_list())
+                .map(|tmpfs| {
+                    let options: Vec<String> =
+                        tmpfs.options.iter().map(ToString::to_string).collect();
+                    (tmpfs.path.as_path().to_owned(), options.join(","))
+                })
+                .collect(),
+            uts_mode: uts.map(|uts| uts.as_str().to_owned()),
+            userns_mode: userns_mode.as_ref().map(ToString::to_string),
+            shm_size: shm_size
+                .map(|shm_size| bytes("shm_size", shm_size))
+                .transpose()?,
+            sysctls: string_map("sysctls", sysctls)?,
+            runtime: runtime.clone(),
+            isolation: isolation.clone(),
+        };
+
+        Ok(Self {
+            config,
+            host_config,
+            networking_config,
+        })
+    }
+}
+
+/// Error returned when converting a [`Service`] into a [`ContainerCreate`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ContainerCreateError {
+    /// A command could not be split into words as it contains an unterminated quote or ends with
+    /// a backslash.
+    #[error("`{field}` could not be split into words")]
+    SplitCommand {
+        /// The field containing the command.
+        field: &'static str,
+    },
+
+    /// A key of a list or map field was invalid.
+    #[error("invalid key in `{field}`")]
+    MapKey {
+        /// The field containing the invalid key.
+        field: &'static str,
+
+        /// Source of the error.
+        source: InvalidMapKeyError,
+    },
+
+    /// A [`ByteValue`] overflowed when converted into bytes.
+    #[error("`{field}` is too large")]
+    ByteValueOverflow {
+        /// The field containing the byte value.
+        field: &'static str,
+    },
+
+    /// A bind mount set both `selinux` and `recursive`, which is not supported by the engine.
+    #[error("bind mount of `{path}` cannot set both `selinux` and `recursive`")]
+    BindSELinuxRecursive {
+        /// Host path of the bind mount.
+        path: PathBuf,
+    },
+}
+
+/// Configuration of a container which is portable between hosts.
+///
+/// [Docker Engine API](https://docs.docker.com/engine/api/v1.45/#tag/Container/operation/ContainerCreate)
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Config {
+    /// Hostname of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+
+    /// Domain name of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub domainname: Option<String>,
+
+    /// User the commands are run as inside the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// Exposed ports, keys are in the format `{port}/{protocol}`.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub exposed_ports: IndexMap<String, EmptyObject>,
+
+    /// Whether to allocate a pseudo-TTY.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub tty: bool,
+
+    /// Whether to keep stdin open.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub open_stdin: bool,
+
+    /// Environment variables in the format `{key}[={value}]`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+
+    /// Command to run in the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cmd: Option<Vec<String>>,
+
+    /// Healthcheck of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub healthcheck: Option<HealthConfig>,
+
+    /// Image to create the container from.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    /// Working directory for commands run in the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<PathBuf>,
+
+    /// Entrypoint of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub entrypoint: Option<Vec<String>>,
+
+    /// Container labels.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub labels: IndexMap<String, String>,
+
+    /// Signal to stop the container with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_signal: Option<String>,
+
+    /// Seconds to wait for the container to stop before killing it.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_timeout: Option<u64>,
+}
+
+/// Empty JSON object, used as the value of [`Config::exposed_ports`].
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct EmptyObject;
+
+impl Serialize for EmptyObject {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        serializer.serialize_map(Some(0))?.end()
+    }
+}
+
+/// Healthcheck of a container.
+///
+/// Durations are in nanoseconds.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct HealthConfig {
+    /// Test to perform, either `["NONE"]`, `["CMD", args...]`, or `["CMD-SHELL", command]`.
+    ///
+    /// Inherited from the image if empty.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub test: Vec<String>,
+
+    /// Time to wait between checks in nanoseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub interval: Option<u64>,
+
+    /// Time to wait before considering a check to have hung in nanoseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub timeout: Option<u64>,
+
+    /// Number of consecutive failures needed to consider the container as unhealthy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub retries: Option<u64>,
+
+    /// Start period for the container to initialize before failures count, in nanoseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_period: Option<u64>,
+
+    /// Time to wait between checks during the start period in nanoseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub start_interval: Option<u64>,
+}
+
+impl HealthConfig {
+    /// Convert a [`Healthcheck`] into a [`HealthConfig`].
+    fn from_healthcheck(healthcheck: &Healthcheck) -> Self {
+        match healthcheck {
+            Healthcheck::Command(healthcheck::Command {
+                test,
+                interval,
+                timeout,
+                retries,
+                start_period,
+                start_interval,
+                extensions: _,
+            }) => Self {
+                test: match test {
+                    Some(Test::Command(command)) => std::iter::once("CMD".to_owned())
+                        .chain(command.clone())
+                        .collect(),
+                    Some(Test::ShellCommand(command)) => {
+                        vec!["CMD-SHELL".to_owned(), command.clone()]
+                    }
+                    None => Vec::new(),
+                },
+                interval: interval.map(nanos),
+                timeout: timeout.map(nanos),
+                retries: *retries,
+                start_period: start_period.map(nanos),
+                start_interval: start_interval.map(nanos),
+            },
+            Healthcheck::Disable => Self {
+                test: vec!["NONE".to_owned()],
+                ..Self::default()
+            },
+        }
+    }
+}
+
+/// Configuration of a container which depends on the host.
+///
+/// Durations are in microseconds, sizes are in bytes.
+///
+/// [Docker Engine API](https://docs.docker.com/engine/api/v1.45/#tag/Container/operation/ContainerCreate)
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct HostConfig {
+    /// Relative CPU weight.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_shares: Option<u64>,
+
+    /// Memory limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory: Option<u64>,
+
+    /// Parent cgroup of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cgroup_parent: Option<String>,
+
+    /// Relative block IO weight.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub blkio_weight: Option<u16>,
+
+    /// Relative block IO weight by device.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blkio_weight_device: Vec<WeightDevice>,
+
+    /// Limit of read rate in bytes per second by device.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blkio_device_read_bps: Vec<ThrottleDevice>,
+
+    /// Limit of write rate in bytes per second by device.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub blkio_device_write_bps: Vec<ThrottleDevice>,
+
+    /// Limit of read rate in IO operations per second by device.
+    #[serde(rename = "BlkioDeviceReadIOps", skip_serializing_if = "Vec::is_empty")]
+    pub blkio_device_read_iops: Vec<ThrottleDevice>,
+
+    /// Limit of write rate in IO operations per second by device.
+    #[serde(rename = "BlkioDeviceWriteIOps", skip_serializing_if = "Vec::is_empty")]
+    pub blkio_device_write_iops: Vec<ThrottleDevice>,
+
+    /// CPU CFS period.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_period: Option<u64>,
+
+    /// CPU CFS quota.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_quota: Option<u64>,
+
+    /// CPU real-time period.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_realtime_period: Option<u64>,
+
+    /// CPU real-time runtime.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_realtime_runtime: Option<u64>,
+
+    /// CPUs the container may run on, e.g. `0-3,5`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpuset_cpus: Option<String>,
+
+    /// Devices to add to the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub devices: Vec<DeviceMapping>,
+
+    /// Rules to add to the container's device cgroup.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub device_cgroup_rules: Vec<String>,
+
+    /// Memory soft limit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_reservation: Option<u64>,
+
+    /// Total memory limit, memory plus swap. `-1` is unlimited swap.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_swap: Option<i64>,
+
+    /// Percentage of anonymous pages the host kernel can swap out.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_swappiness: Option<u8>,
+
+    /// CPU quota in units of 10<sup>-9</sup> CPUs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub nano_cpus: Option<u64>,
+
+    /// Whether to disable the OOM killer for the container.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub oom_kill_disable: bool,
+
+    /// Whether to run an init inside the container.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub init: bool,
+
+    /// Process limit of the container. `-1` is unlimited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pids_limit: Option<i64>,
+
+    /// Resource limits of the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ulimits: Vec<EngineUlimit>,
+
+    /// Number of usable CPUs, Windows only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_count: Option<u64>,
+
+    /// Usable percentage of the available CPUs, Windows only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cpu_percent: Option<u8>,
+
+    /// Bind mounts in the format `{source}:{target}[:{options}]`.
+    ///
+    /// Used for bind mounts with SELinux relabeling, which [`Mount`] does not support.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub binds: Vec<String>,
+
+    /// Logging configuration of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_config: Option<LogConfig>,
+
+    /// Network mode of the container, or the name of the first network it is connected to.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub network_mode: Option<String>,
+
+    /// Ports published to the host, keys are in the format `{port}/{protocol}`.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub port_bindings: IndexMap<String, Vec<PortBinding>>,
+
+    /// Behavior when the container exits.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_policy: Option<RestartPolicy>,
+
+    /// Containers to mount volumes from in the format `{container}[:ro]`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub volumes_from: Vec<String>,
+
+    /// Mounts to add to the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mounts: Vec<Mount>,
+
+    /// Arbitrary non-identifying metadata attached to the container.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub annotations: IndexMap<String, String>,
+
+    /// Kernel capabilities to add to the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cap_add: Vec<String>,
+
+    /// Kernel capabilities to drop from the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub cap_drop: Vec<String>,
+
+    /// Cgroup namespace mode of the container, `host` or `private`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub cgroupns_mode: Option<String>,
+
+    /// DNS servers for the container to use.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dns: Vec<String>,
+
+    /// DNS resolver options.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dns_options: Vec<String>,
+
+    /// DNS search domains.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub dns_search: Vec<String>,
+
+    /// Entries to add to the container's `/etc/hosts` in the format `{host}:{ip}`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub extra_hosts: Vec<String>,
+
+    /// Additional groups the container process runs as.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub group_add: Vec<String>,
+
+    /// IPC namespace mode of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub ipc_mode: Option<String>,
+
+    /// Links to other containers in the format `{container}:{alias}`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub links: Vec<String>,
+
+    /// Adjustment of the container's OOM killer preference.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub oom_score_adj: Option<i16>,
+
+    /// PID namespace mode of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pid_mode: Option<String>,
+
+    /// Whether to give the container extended privileges.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub privileged: bool,
+
+    /// Whether to mount the container's root filesystem as read-only.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub readonly_rootfs: bool,
+
+    /// Security options of the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub security_opt: Vec<String>,
+
+    /// Storage driver options of the container.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub storage_opt: IndexMap<String, String>,
+
+    /// Tmpfs mounts, the values are comma (,) separated mount options.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub tmpfs: IndexMap<PathBuf, String>,
+
+    /// UTS namespace mode of the container.
+    #[serde(rename = "UTSMode", skip_serializing_if = "Option::is_none")]
+    pub uts_mode: Option<String>,
+
+    /// User namespace mode of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub userns_mode: Option<String>,
+
+    /// Size of `/dev/shm`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub shm_size: Option<u64>,
+
+    /// Namespaced kernel parameters to set in the container.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub sysctls: IndexMap<String, String>,
+
+    /// Runtime to use for the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub runtime: Option<String>,
+
+    /// Isolation technology of the container, Windows only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isolation: Option<String>,
+}
+
+/// Relative block IO weight of a device.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct WeightDevice {
+    /// Path to the device.
+    pub path: PathBuf,
+
+    /// Relative weight.
+    pub weight: u16,
+}
+
+/// Block IO rate limit of a device.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ThrottleDevice {
+    /// Path to the device.
+    pub path: PathBuf,
+
+    /// Rate limit in bytes or IO operations per second.
+    pub rate: u64,
+}
+
+/// Device to add to a container.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DeviceMapping {
+    /// Path to the device on the host.
+    pub path_on_host: PathBuf,
+
+    /// Path to the device inside the container.
+    pub path_in_container: PathBuf,
+
+    /// Device cgroup permissions, e.g. `rwm`.
+    pub cgroup_permissions: String,
+}
+
+impl From<&Device> for DeviceMapping {
+    fn from(
+        Device {
+            host_path,
+            container_path,
+            permissions,
+        }: &Device,
+    ) -> Self {
+        Self {
+            path_on_host: host_path.clone(),
+            path_in_container: container_path.as_path().to_owned(),
+            cgroup_permissions: if *permissions == Permissions::default() {
+                "rwm".to_owned()
+            } else {
+                permissions.to_string()
+            },
+        }
+    }
+}
+
+/// Logging configuration of a container.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LogConfig {
+    /// Logging driver, the daemon's default is used if empty.
+    #[serde(rename = "Type", skip_serializing_if = "String::is_empty")]
+    pub driver: String,
+
+    /// Logging driver options.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub config: IndexMap<String, String>,
+}
+
+impl From<&Logging> for LogConfig {
+    fn from(
+        Logging {
+            driver,
+            options,
+            extensions: _,
+        }: &Logging,
+    ) -> Self {
+        Self {
+            driver: driver.clone().unwrap_or_default(),
+            config: options
+                .iter()
+                .map(|(key, value)| {
+                    (
+                        key.to_string(),
+                        value.as_ref().map(ToString::to_string).unwrap_or_default(),
+                    )
+                })
+                .collect(),
+        }
+    }
+}
+
+/// Host address a container port is published to.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PortBinding {
+    /// Host IP address, all addresses if [`None`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_ip: Option<String>,
+
+    /// Host port or port range, a random port if [`None`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub host_port: Option<String>,
+}
+
+/// Behavior when a container exits.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RestartPolicy {
+    /// Name of the policy, `no`, `always`, `on-failure`, or `unless-stopped`.
+    pub name: String,
+
+    /// Maximum number of restart attempts for the `on-failure` policy.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub maximum_retry_count: Option<u64>,
+}
+
+impl From<Restart> for RestartPolicy {
+    fn from(restart: Restart) -> Self {
+        Self {
+            name: restart.as_str().to_owned(),
+            maximum_retry_count: restart.max_retries(),
+        }
+    }
+}
+
+/// Mount to add to a container.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Mount {
+    /// Path inside the container.
+    pub target: PathBuf,
+
+    /// Mount source, e.g. a host path or volume name.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub source: Option<String>,
+
+    /// Type of mount, `bind`, `volume`, `image`, `tmpfs`, `npipe`, or `cluster`.
+    #[serde(rename = "Type")]
+    pub kind: String,
+
+    /// Whether the mount is read-only.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+
+    /// Consistency requirement of the mount.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub consistency: Option<String>,
+
+    /// Options for `bind` mounts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub bind_options: Option<BindOptions>,
+
+    /// Options for `volume` mounts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub volume_options: Option<VolumeOptions>,
+
+    /// Options for `image` mounts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub image_options: Option<ImageOptions>,
+
+    /// Options for `tmpfs` mounts.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub tmpfs_options: Option<TmpfsOptions>,
+}
+
+/// Options for a `bind` [`Mount`].
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct BindOptions {
+    /// Propagation mode of the mount.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub propagation: Option<String>,
+
+    /// Whether to disable recursive bind mounts.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub non_recursive: bool,
+
+    /// Whether to create the source path on the host if it is missing.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub create_mountpoint: bool,
+
+    /// Whether submounts of a read-only mount are writable.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub read_only_non_recursive: bool,
+
+    /// Whether submounts are read-only, even on kernels without recursive read-only mounts.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub read_only_force_recursive: bool,
+}
+
+/// Options for a `volume` [`Mount`].
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct VolumeOptions {
+    /// Whether to disable copying data from the container to a new volume.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub no_copy: bool,
+
+    /// Path within the volume to mount.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<PathBuf>,
+}
+
+/// Options for an `image` [`Mount`].
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ImageOptions {
+    /// Path within the image to mount.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<PathBuf>,
+}
+
+/// Options for a `tmpfs` [`Mount`].
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TmpfsOptions {
+    /// Size of the tmpfs in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub size_bytes: Option<u64>,
+
+    /// Permission mode of the tmpfs.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<u32>,
+}
+
+/// Networks a container is connected to.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct NetworkingConfig {
+    /// Endpoint configuration by network name.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub endpoints_config: IndexMap<String, EndpointSettings>,
+}
+
+impl NetworkingConfig {
+    /// Create the [`NetworkingConfig`] of a service, also returns the `NetworkMode` of the
+    /// [`HostConfig`].
+    ///
+    /// The first network, see [`service_networks()`], is used as the `NetworkMode`.
+    fn new(
+        project: &Project,
+        service: &Identifier,
+        network_config: Option<&NetworkConfig>,
+        mac_address: Option<&network_config::MacAddress>,
+    ) -> (Option<String>, Self) {
+        let networks = match service_networks(network_config) {
+            Ok(networks) => networks,
+            Err(network_mode) => {
+                return (Some(project.network_mode(network_mode)), Self::default())
+            }
+        };
+
+        let mut mac_address = mac_address;
+        let endpoints_config: IndexMap<_, _> = networks
+            .into_iter()
+            .map(|(network, config)| {
+                let mut endpoint =
+                    config.map_or_else(EndpointSettings::default, EndpointSettings::from);
+                endpoint.aliases.insert(0, service.to_string());
+                // The service's MAC address applies to the first network.
+                if let Some(mac_address) = mac_address.take() {
+                    endpoint
+                        .mac_address
+                        .get_or_insert_with(|| mac_address_string(*mac_address));
+                }
+                (project.network_name(network), endpoint)
+            })
+            .collect();
+
+        let network_mode = endpoints_config.keys().next().cloned();
+        (network_mode, Self { endpoints_config })
+    }
+}
+
+/// Networks a service is connected to, or its network mode.
+///
+/// Services without a network config are connected to the `default` network. Networks are
+/// ordered by their `priority`, highest first.
+pub(crate) fn service_networks(
+    network_config: Option<&NetworkConfig>,
+) -> Result<Vec<(&str, Option<&network_config::Network>)>, &NetworkMode> {
+    let mut networks: Vec<_> = match network_config {
+        Some(NetworkConfig::NetworkMode(network_mode)) => return Err(network_mode),
+        Some(NetworkConfig::Networks(ShortOrLong::Short(networks))) => networks
+            .iter()
+            .map(|network| (network.as_str(), None))
+            .collect(),
+        Some(NetworkConfig::Networks(ShortOrLong::Long(networks))) => networks
+            .iter()
+            .map(|(network, config)| (network.as_str(), config.as_ref()))
+            .collect(),
+        None => vec![("default", None)],
+    };
+    networks.sort_by_key(|(_, network)| {
+        std::cmp::Reverse(
+            network
+                .and_then(|network| network.priority)
+                .unwrap_or_default(),
+        )
+    });
+    Ok(networks)
+}
+
+/// Configuration of a container's connection to a network.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct EndpointSettings {
+    /// Static IP addresses of the container in the network.
+    #[serde(rename = "IPAMConfig", skip_serializing_if = "Option::is_none")]
+    pub ipam_config: Option<EndpointIpamConfig>,
+
+    /// Aliases of the container in the network.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+
+    /// MAC address of the container's network interface.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mac_address: Option<String>,
+
+    /// Network driver options of the endpoint.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub driver_opts: IndexMap<String, String>,
+
+    /// Priority of the network's gateway when choosing the container's default gateway.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub gw_priority: Option<i64>,
+}
+
+impl From<&network_config::Network> for EndpointSettings {
+    fn from(
+        network_config::Network {
+            aliases,
+            ipv4_address,
+            ipv6_address,
+            link_local_ips,
+            mac_address,
+            driver_opts,
+            priority: _,
+            interface_name,
+            gw_priority,
+            extensions: _,
+        }: &network_config::Network,
+    ) -> Self {
+        let ipam_config = (ipv4_address.is_some()
+            || ipv6_address.is_some()
+            || !link_local_ips.is_empty())
+        .then(|| EndpointIpamConfig {
+            ipv4_address: ipv4_address.map(|address| address.to_string()),
+            ipv6_address: ipv6_address.map(|address| address.to_string()),
+            link_local_ips: link_local_ips.iter().map(ToString::to_string).collect(),
+        });
+
+        let mut driver_opts: IndexMap<String, String> = driver_opts
+            .iter()
+            .map(|(key, value)| (key.to_string(), value.to_string()))
+            .collect();
+        if let Some(interface_name) = interface_name {
+            driver_opts.insert(
+                "com.docker.network.endpoint.ifname".to_owned(),
+                interface_name.clone(),
+            );
+        }
+
+        Self {
+            ipam_config,
+            aliases: aliases
+                .iter()
+                .map(|alias| alias.as_str().to_owned())
+                .collect(),
+            mac_address: mac_address.map(mac_address_string),
+            driver_opts,
+            gw_priority: *gw_priority,
+        }
+    }
+}
+
+/// Static IP addresses of a container in a network.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct EndpointIpamConfig {
+    /// IPv4 address.
+    #[serde(rename = "IPv4Address", skip_serializing_if = "Option::is_none")]
+    pub ipv4_address: Option<String>,
+
+    /// IPv6 address.
+    #[serde(rename = "IPv6Address", skip_serializing_if = "Option::is_none")]
+    pub ipv6_address: Option<String>,
+
+    /// Link-local IP addresses.
+    #[serde(rename = "LinkLocalIPs", skip_serializing_if = "Vec::is_empty")]
+    pub link_local_ips: Vec<String>,
+}
+
+/// Mounts of a container, split between [`HostConfig::binds`] and [`HostConfig::mounts`].
+#[derive(Debug, Default)]
+struct Mounts {
+    /// Bind mounts with SELinux relabeling.
+    binds: Vec<String>,
+
+    /// All other mounts.
+    mounts: Vec<Mount>,
+}
+
+impl Mounts {
+    /// Convert a service volume [`mount::Mount`] and add it.
+    fn push(
+        &mut self,
+        project: &Project,
+        mount: volumes::Mount,
+    ) -> Result<(), ContainerCreateError> {
+        let kind = mount.kind().to_owned();
+        let mount = match mount {
+            volumes::Mount::Volume(mount::Volume {
+                source,
+                volume,
+                common,
+            }) => Mount {
+                volume_options: volume.map(
+                    |mount::VolumeOptions {
+                         nocopy,
+                         subpath,
+                         extensions: _,
+                     }| VolumeOptions {
+                        no_copy: nocopy,
+                        subpath,
+                    },
+                ),
+                ..Mount::new(
+                    kind,
+                    source.map(|source| project.volume_name(source.as_str())),
+                    common,
+                )
+            },
+            volumes::Mount::Bind(bind) => return self.bind(project, bind),
+            volumes::Mount::Tmpfs(mount::Tmpfs { tmpfs, common }) => Mount {
+                tmpfs_options: tmpfs
+                    .map(
+                        |mount::TmpfsOptions {
+                             size,
+                             mode,
+                             extensions: _,
+                         }| {
+                            size.map(|size| bytes("volumes.tmpfs.size", size))
+                                .transpose()
+                                .map(|size_bytes| TmpfsOptions { size_bytes, mode })
+                        },
+                    )
+                    .transpose()?,
+                ..Mount::new(kind, None, common)
+            },
+            volumes::Mount::NamedPipe(mount::NamedPipe { source, common }) => {
+                Mount::new(kind, Some(source.as_path().display().to_string()), common)
+            }
+            volumes::Mount::Cluster(mount::Cluster { source, common }) => {
+                Mount::new(kind, Some(source), common)
+            }
+            volumes::Mount::Image(mount::Image {
+                source,
+                image,
+                common,
+            }) => Mount {
+                image_options: image.map(
+                    |mount::ImageOptions {
+                         subpath,
+                         extensions: _,
+                     }| ImageOptions { subpath },
+                ),
+                ..Mount::new(kind, Some(source.to_string()), common)
+            },
+        };
+        self.mounts.push(mount);
+        Ok(())
+    }
+
+    /// Convert a bind [`mount::Mount`] and add it.
+    ///
+    /// Bind mounts with SELinux relabeling are added to the binds, as [`Mount`] does not support
+    /// it.
+    fn bind(
+        &mut self,
+        project: &Project,
+        mount::Bind {
+            source,
+            bind,
+            common,
+        }: mount::Bind,
+    ) -> Result<(), ContainerCreateError> {
+        let source = project.host_path(source.as_path());
+        let bind = bind.unwrap_or_default();
+
+        if let Some(selinux) = bind.selinux {
+            if bind.recursive.is_some() {
+                return Err(ContainerCreateError::BindSELinuxRecursive { path: source });
+            }
+            let mut options = Vec::new();
+            if common.read_only {
+                options.push("ro".to_owned());
+            }
+            options.extend(bind.propagation.map(|propagation| propagation.to_string()));
+            options.push(selinux.to_string());
+            self.binds.push(format!(
+                "{}:{}:{}",
+                source.display(),
+                common.target.as_path().display(),
+                options.join(",")
+            ));
+            return Ok(());
+        }
+
+        let recursive = bind.recursive.unwrap_or(BindRecursive::Enabled);
+        let bind_options = BindOptions {
+            propagation: bind.propagation.map(|propagation| propagation.to_string()),
+            non_recursive: recursive == BindRecursive::Disabled,
+            create_mountpoint: bind.create_host_path,
+            read_only_non_recursive: recursive == BindRecursive::Writable,
+            read_only_force_recursive: recursive == BindRecursive::ReadOnly,
+        };
+        self.mounts.push(Mount {
+            bind_options: (bind_options != BindOptions::default()).then_some(bind_options),
+            ..Mount::new(
+                "bind".to_owned(),
+                Some(source.display().to_string()),
+                common,
+            )
+        });
+        Ok(())
+    }
+
+    /// Add a read-only bind mount of a file.
+    fn bind_file(&mut self, source: &Path, target: PathBuf) {
+        self.mounts.push(Mount {
+            target,
+            source: Some(source.display().to_string()),
+            kind: "bind".to_owned(),
+            read_only: true,
+            consistency: None,
+            bind_options: None,
+            volume_options: None,
+            image_options: None,
+            tmpfs_options: None,
+        });
+    }
+}
+
+impl Mount {
+    /// Create a [`Mount`] without any type specific options.
+    fn new(kind: String, source: Option<String>, common: mount::Common) -> Self {
+        let mount::Common {
+            target,
+            read_only,
+            consistency,
+            extensions: _,
+        } = common;
+        Self {
+            target: target.into_inner(),
+            source,
+            kind,
+            read_only,
+            consistency,
+            bind_options: None,
+            volume_options: None,
+            image_options: None,
+            tmpfs_options: None,
+        }
+    }
+}
+
+/// Source and target of a config or secret.
+fn config_or_secret(
+    config: &ShortOrLong<Identifier, ConfigOrSecret>,
+) -> (&Identifier, Option<&Path>) {
+    match config {
+        ShortOrLong::Short(source) => (source, None),
+        ShortOrLong::Long(ConfigOrSecret { source, target, .. }) => (source, target.as_deref()),
+    }
+}
+
+/// Key of a port in [`Config::exposed_ports`] and [`HostConfig::port_bindings`].
+fn port_key(port: u16, protocol: Option<&ports::Protocol>) -> String {
+    format!("{port}/{}", protocol.map_or("tcp", ports::Protocol::as_str))
+}
+
+/// Split a [`Command`] string into words like a shell would.
+fn split_command(
+    field: &'static str,
+    command: &Command,
+) -> Result<Vec<String>, ContainerCreateError> {
+    match command {
+        Command::String(command) => {
+            crate::shell::split(command).ok_or(ContainerCreateError::SplitCommand { field })
+        }
+        Command::List(command) => Ok(command.clone()),
+    }
+}
+
+/// Convert a [`ListOrMap`] into a map of strings, missing values become empty strings.
+fn string_map(
+    field: &'static str,
+    list_or_map: &ListOrMap,
+) -> Result<IndexMap<String, String>, ContainerCreateError> {
+    let map: Map = list_or_map
+        .clone()
+        .into_map()
+        .map_err(|source| ContainerCreateError::MapKey { field, source })?;
+    Ok(map
+        .into_iter()
+        .map(|(key, value)| (key.to_string(), value_string(value.as_ref())))
+        .collect())
+}
+
+/// Convert an optional map value into a string, [`None`] becomes an empty string.
+fn value_string<T: ToString>(value: Option<&T>) -> String {
+    value.map(ToString::to_string).unwrap_or_default()
+}
+
+/// Convert a [`ByteValue`] into bytes.
+fn bytes(field: &'static str, value: ByteValue) -> Result<u64, ContainerCreateError> {
+    value
+        .into_bytes()
+        .ok_or(ContainerCreateError::ByteValueOverflow { field })
+}
+
+/// Convert a list of [`BpsLimit`]s into [`ThrottleDevice`]s.
+fn bps_limits(
+    field: &'static str,
+    limits: &[BpsLimit],
+) -> Result<Vec<ThrottleDevice>, ContainerCreateError> {
+    limits
+        .iter()
+        .map(|BpsLimit { path, rate }| {
+            Ok(ThrottleDevice {
+                path: path.as_path().to_owned(),
+                rate: bytes(field, *rate)?,
+            })
+        })
+        .collect()
+}
+
+/// Convert a list of [`IopsLimit`]s into [`ThrottleDevice`]s.
+fn iops_limits(limits: &[IopsLimit]) -> Vec<ThrottleDevice> {
+    limits
+        .iter()
+        .map(|IopsLimit { path, rate }| ThrottleDevice {
+            path: path.as_path().to_owned(),
+            rate: *rate,
+        })
+        .collect()
+}
+
+/// Convert an unsigned integer into an [`i64`], saturating at [`i64::MAX`].
+fn saturating_i64(value: u64) -> i64 {
+    value.try_into().unwrap_or(i64::MAX)
+}
+
+/// Convert a [`Duration`] into nanoseconds, saturating at [`u64::MAX`].
+fn nanos(duration: Duration) -> u64 {
+    duration.as_nanos().try_into().unwrap_or(u64::MAX)
+}
+
+/// Convert a [`Duration`] into microseconds, saturating at [`u64::MAX`].
+fn micros(duration: Duration) -> u64 {
+    duration.as_micros().try_into().unwrap_or(u64::MAX)
+}
+
+/// Convert [`Cpus`] into units of 10<sup>-9</sup> CPUs.
+#[allow(
+    clippy::as_conversions,
+    clippy::cast_possible_truncation,
+    clippy::cast_sign_loss
+)]
+fn nano_cpus(cpus: Cpus) -> u64 {
+    // `Cpus` is always positive and finite, float to int casts saturate.
+    (cpus.into_inner() * 1e9).round() as u64
+}
+
+/// Format a [`MacAddress`](network_config::MacAddress) as six colon (:) separated pairs of
+/// hexadecimal digits, the format expected by the engine.
+pub(crate) fn mac_address_string(mac_address: network_config::MacAddress) -> String {
+    let bytes: Vec<String> = mac_address
+        .0
+        .iter()
+        .map(|byte| format!("{byte:02x}"))
+        .collect();
+    bytes.join(":")
+}
+
+/// Reference to a container in [`HostConfig::volumes_from`].
+fn volumes_from_reference(
+    project: &Project,
+    VolumesFrom { source, read_only }: &VolumesFrom,
+) -> String {
+    let container = match source {
+        VolumesFromSource::Service(service) => project.container_name(service.as_str()),
+        VolumesFromSource::Container(container) => container.to_string(),
+    };
+    if *read_only {
+        format!("{container}:ro")
+    } else {
+        container
+    }
+}
+
+/// Reference to a container in [`HostConfig::links`].
+///
+/// If `service` is `true`, the link is to another service of the project, otherwise to an
+/// external container.
+fn link_reference(
+    project: &Project,
+    Link {
+        service: target,
+        alias,
+    }: &Link,
+    service: bool,
+) -> String {
+    let container = if service {
+        project.container_name(target.as_str())
+    } else {
+        target.to_string()
+    };
+    let alias = alias.as_deref().unwrap_or(target.as_str());
+    format!("{container}:{alias}")
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn full() {
+        let compose: Compose = serde_yaml::from_str(include_str!("engine/test-full.yaml")).unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+
+        let bodies: IndexMap<&Identifier, ContainerCreate> = compose
+            .services
+            .iter()
+            .map(|(name, service)| {
+                (
+                    name,
+                    ContainerCreate::from_service(&project, name, service).unwrap(),
+                )
+            })
+            .collect();
+
+        // JSON is valid YAML.
+        let expected: serde_yaml::Value =
+            serde_yaml::from_str(include_str!("engine/test-full.json")).unwrap();
+        assert_eq!(serde_yaml::to_value(&bodies).unwrap(), expected);
+    }
+
+    #[test]
+    fn swarm() {
+        let compose: Compose =
+            serde_yaml::from_str(include_str!("engine/test-swarm.yaml")).unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+
+        let specs: IndexMap<&Identifier, swarm::ServiceSpec> = compose
+            .services
+            .iter()
+            .map(|(name, service)| {
+                (
+                    name,
+                    swarm::ServiceSpec::from_service(&project, name, service).unwrap(),
+                )
+            })
+            .collect();
+
+        // JSON is valid YAML.
+        let expected: serde_yaml::Value =
+            serde_yaml::from_str(include_str!("engine/test-swarm.json")).unwrap();
+        assert_eq!(untag(serde_yaml::to_value(&specs).unwrap()), expected);
+
+        let unsupported: Vec<(&Identifier, &str)> = specs
+            .iter()
+            .flat_map(|(name, spec)| {
+                spec.unsupported
+                    .iter()
+                    .map(move |field| (*name, field.as_str()))
+            })
+            .collect();
+        let worker = Identifier::new("worker").unwrap();
+        assert_eq!(
+            unsupported,
+            [(&worker, "build"), (&worker, "devices"), (&worker, "tmpfs")],
+        );
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn stop_timeout_rounds_up() {
+        let compose: Compose = serde_yaml::from_str(
+            "
+            services:
+              app:
+                image: app
+                stop_grace_period: 500ms
+            ",
+        )
+        .unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+        let app = Identifier::new("app").unwrap();
+
+        let body = ContainerCreate::from_service(&project, &app, &compose.services[&app]).unwrap();
+        assert_eq!(body.config.stop_timeout, Some(1));
+    }
+
+    /// Replace YAML tags, which enums are serialized with, with a map from the tag to the value,
+    /// as enums are serialized into JSON.
+    fn untag(value: serde_yaml::Value) -> serde_yaml::Value {
+        match value {
+            serde_yaml::Value::Tagged(tagged) => {
+                let serde_yaml::value::TaggedValue { tag, value } = *tagged;
+                let tag = tag.to_string();
+                let tag = tag.trim_start_matches('!').to_owned();
+                serde_yaml::Value::Mapping(std::iter::once((tag.into(), untag(value))).collect())
+            }
+            serde_yaml::Value::Sequence(sequence) => {
+                serde_yaml::Value::Sequence(sequence.into_iter().map(untag).collect())
+            }
+            serde_yaml::Value::Mapping(mapping) => serde_yaml::Value::Mapping(
+                mapping
+                    .into_iter()
+                    .map(|(key, value)| (key, untag(value)))
+                    .collect(),
+            ),
+            value => value,
+        }
+    }
+}
@@ -0,0 +1,1051 @@
+//! Conversion of a [`Service`] into the body of a Docker Engine API Swarm service create request.
+//!
+//! The body of a `POST /services/create` request is represented by [`ServiceSpec`]. Its
+//! [`TaskSpec`] describes the containers of the service, and the rest of the spec, converted from
+//! the service's `deploy` field, how they are scheduled and updated.
+//!
+//! [Docker Engine API](https://docs.docker.com/engine/api/v1.45/#tag/Service/operation/ServiceCreate)
+
+use std::path::PathBuf;
+
+use indexmap::IndexMap;
+use serde::Serialize;
+use thiserror::Error;
+
+use crate::{
+    service::{
+        deploy::{
+            self,
+            resources::{self, DiscreteResourceSpec},
+            Preference, RestartCondition, UpdateOrRollbackConfig,
+        },
+        ports, volumes, ByteValue, Deploy, EngineUlimit, Limit, NetworkConfig, Restart,
+    },
+    Identifier, Service,
+};
+
+use super::{
+    bytes, nano_cpus, nanos, service_networks, split_command, string_map, ContainerCreateError,
+    EmptyObject, HealthConfig, LogConfig, Mount, Mounts, Project,
+};
+
+/// Label with the name of the stack, i.e. the project, a service is part of.
+const NAMESPACE_LABEL: &str = "com.docker.stack.namespace";
+
+/// Specification of a Swarm service, the body of a service create request.
+///
+/// Durations are in nanoseconds, sizes are in bytes.
+///
+/// [Docker Engine API](https://docs.docker.com/engine/api/v1.45/#tag/Service/operation/ServiceCreate)
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ServiceSpec {
+    /// Name of the service.
+    pub name: String,
+
+    /// Service labels.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub labels: IndexMap<String, String>,
+
+    /// Specification of the service's tasks.
+    pub task_template: TaskSpec,
+
+    /// Scheduling mode of the service.
+    pub mode: ServiceMode,
+
+    /// How the service is updated.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub update_config: Option<UpdateConfig>,
+
+    /// How the service is rolled back in case of a failing update.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub rollback_config: Option<UpdateConfig>,
+
+    /// How the service is discovered and its ports are published.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub endpoint_spec: Option<EndpointSpec>,
+
+    /// Fields of the service which have no Swarm equivalent and were not converted, e.g.
+    /// `devices` or `secrets`. Not part of the request body.
+    #[serde(skip)]
+    pub unsupported: Vec<String>,
+}
+
+impl ServiceSpec {
+    /// Convert the [`Service`] named `name` of the `project` into a [`ServiceSpec`] body.
+    ///
+    /// Like `docker stack deploy`, the service is named `{project}_{service}` and labeled with
+    /// `com.docker.stack.namespace: {project}`. Its networks and volumes are resolved with the
+    /// `project` and must already exist.
+    ///
+    /// The scheduling mode, placement, and update and rollback configs come from `deploy`.
+    /// Resource limits and reservations come from `deploy.resources`, or the equivalent service
+    /// field, and the restart policy from `deploy.restart_policy`, or `restart`. Fields without a
+    /// Swarm equivalent, such as `network_mode`, `devices`, `links`, or `blkio_config`, are listed
+    /// in [`unsupported`](Self::unsupported). Configs and secrets are not converted, as Swarm
+    /// references them by ID, and are also listed. Published port ranges are published to their
+    /// first port.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the `entrypoint` or `command` could not be split into words, a key of
+    /// `labels`, `deploy.labels`, or `sysctls` is invalid, a byte value overflows, or a bind mount
+    /// sets `selinux`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use compose_spec::{
+    ///     engine::{swarm::ServiceSpec, Project},
+    ///     Compose, Identifier, Name,
+    /// };
+    ///
+    /// let yaml = "\
+    /// services:
+    ///   caddy:
+    ///     image: docker.io/library/caddy:latest
+    ///     deploy:
+    ///       replicas: 2
+    ///       resources:
+    ///         limits:
+    ///           cpus: 0.5
+    ///       update_config:
+    ///         delay: 10s
+    /// ";
+    /// let compose: Compose = serde_yaml::from_str(yaml)?;
+    /// let name = Name::new("app")?;
+    /// let project = Project::new(&name, &compose, Path::new("/srv/app"));
+    ///
+    /// let caddy = Identifier::new("caddy")?;
+    /// let spec = ServiceSpec::from_service(&project, &caddy, &compose.services[&caddy])?;
+    /// assert_eq!(spec.name, "app_caddy");
+    /// let limits = spec.task_template.resources.unwrap().limits.unwrap();
+    /// assert_eq!(limits.nano_cpus, Some(500_000_000));
+    /// assert_eq!(spec.update_config.unwrap().delay, Some(10_000_000_000));
+    /// assert!(spec.unsupported.is_empty());
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_service(
+        project: &Project,
+        name: &Identifier,
+        service: &Service,
+    ) -> Result<Self, ServiceSpecError> {
+        let Deploy {
+            endpoint_mode,
+            labels,
+            mode,
+            placement,
+            replicas,
+            resources,
+            restart_policy,
+            rollback_config,
+            update_config,
+            extensions: _,
+        } = service.deploy.clone().unwrap_or_default();
+
+        let mut labels = string_map("deploy.labels", &labels)?;
+        labels.insert(NAMESPACE_LABEL.to_owned(), project.name.to_string());
+
+        let networks =
+            service_networks(service.network_config.as_ref())
+                .map(|networks| {
+                    networks
+                        .into_iter()
+                        .map(|(network, config)| NetworkAttachmentConfig {
+                            target: project.network_name(network),
+                            aliases: std::iter::once(name.to_string())
+                                .chain(config.into_iter().flat_map(|config| {
+                                    config.aliases.iter().map(ToString::to_string)
+                                }))
+                                .collect(),
+                        })
+                        .collect()
+                })
+                .unwrap_or_default();
+
+        let task_template = TaskSpec {
+            container_spec: ContainerSpec::from_service(project, name, service)?,
+            resources: ResourceRequirements::new(service, resources.unwrap_or_default())?,
+            restart_policy: restart_policy
+                .map(RestartPolicy::from)
+                .or_else(|| service.restart.map(RestartPolicy::from)),
+            placement: placement.map(Placement::from),
+            networks,
+            log_driver: service.logging.as_ref().map(|logging| {
+                let LogConfig { driver, config } = LogConfig::from(logging);
+                LogDriver {
+                    name: driver,
+                    options: config,
+                }
+            }),
+        };
+
+        let mode = match mode.unwrap_or_default() {
+            deploy::Mode::Global => ServiceMode::Global(EmptyObject),
+            deploy::Mode::Replicated => ServiceMode::Replicated(ReplicatedService {
+                replicas: replicas.or(service.scale),
+            }),
+        };
+
+        let ports: Vec<EndpointPortConfig> = ports::into_long_iter(service.ports.clone())
+            .map(EndpointPortConfig::from)
+            .collect();
+        let endpoint_spec = (endpoint_mode.is_some() || !ports.is_empty()).then(|| EndpointSpec {
+            mode: endpoint_mode.as_ref().map(|mode| mode.as_str().to_owned()),
+            ports,
+        });
+
+        Ok(Self {
+            name: format!("{}_{name}", project.name),
+            labels,
+            task_template,
+            mode,
+            update_config: update_config.map(UpdateConfig::from),
+            rollback_config: rollback_config.map(UpdateConfig::from),
+            endpoint_spec,
+            unsupported: unsupported_fields(service),
+        })
+    }
+}
+
+/// Fields of a [`Service`] which are not converted into a [`ServiceSpec`].
+#[allow(clippy::too_many_lines)]
+fn unsupported_fields(service: &Service) -> Vec<String> {
+    let Service {
+        attach: _,
+        build,
+        blkio_config,
+        cpu_count,
+        cpu_percent,
+        cpu_shares,
+        cpu_period,
+        cpu_quota,
+        cpu_rt_runtime,
+        cpu_rt_period,
+        cpus: _,
+        cpuset,
+        cap_add: _,
+        cap_drop: _,
+        cgroup,
+        cgroup_parent,
+        command: _,
+        configs,
+        container_name,
+        credential_spec,
+        depends_on,
+        deploy,
+        develop,
+        device_cgroup_rules,
+        devices,
+        dns: _,
+        dns_opt: _,
+        dns_search: _,
+        domain_name,
+        entrypoint: _,
+        env_file,
+        environment: _,
+        expose,
+        extends,
+        annotations,
+        external_links,
+        extra_hosts: _,
+        group_add: _,
+        healthcheck: _,
+        hostname: _,
+        image: _,
+        init: _,
+        ipc,
+        uts,
+        isolation: _,
+        labels: _,
+        label_file,
+        links,
+        logging: _,
+        network_config,
+        mac_address,
+        mem_limit: _,
+        mem_reservation: _,
+        mem_swappiness,
+        memswap_limit,
+        oom_kill_disable,
+        oom_score_adj,
+        pid,
+        pids_limit: _,
+        platform: _,
+        ports: _,
+        privileged,
+        profiles: _,
+        pull_policy: _,
+        read_only: _,
+        restart: _,
+        runtime,
+        scale: _,
+        secrets,
+        security_opt,
+        shm_size,
+        stdin_open: _,
+        stop_grace_period: _,
+        stop_signal: _,
+        storage_opt,
+        sysctls: _,
+        tmpfs,
+        tty: _,
+        ulimits: _,
+        user: _,
+        userns_mode,
+        volumes: _,
+        volumes_from,
+        working_dir: _,
+        extensions: _,
+    } = service;
+
+    let reservation_devices = deploy
+        .as_ref()
+        .and_then(|deploy| deploy.resources.as_ref())
+        .and_then(|resources| resources.reservations.as_ref())
+        .is_some_and(|reservations| !reservations.devices.is_empty());
+
+    [
+        ("build", build.is_some()),
+        ("blkio_config", blkio_config.is_some()),
+        ("cpu_count", cpu_count.is_some()),
+        ("cpu_percent", cpu_percent.is_some()),
+        ("cpu_shares", cpu_shares.is_some()),
+        ("cpu_period", cpu_period.is_some()),
+        ("cpu_quota", cpu_quota.is_some()),
+        ("cpu_rt_runtime", cpu_rt_runtime.is_some()),
+        ("cpu_rt_period", cpu_rt_period.is_some()),
+        ("cpuset", !cpuset.is_empty()),
+        ("cgroup", cgroup.is_some()),
+        ("cgroup_parent", cgroup_parent.is_some()),
+        ("configs", !configs.is_empty()),
+        ("container_name", container_name.is_some()),
+        ("credential_spec", credential_spec.is_some()),
+        (
+            "depends_on",
+            !crate::service::depends_on_is_empty(depends_on),
+        ),
+        ("deploy.resources.reservations.devices", reservation_devices),
+        ("develop", develop.is_some()),
+        ("device_cgroup_rules", !device_cgroup_rules.is_empty()),
+        ("devices", !devices.is_empty()),
+        ("domain_name", domain_name.is_some()),
+        ("env_file", env_file.is_some()),
+        ("expose", !expose.is_empty()),
+        ("extends", extends.is_some()),
+        ("annotations", !annotations.is_empty()),
+        ("external_links", !external_links.is_empty()),
+        ("ipc", ipc.is_some()),
+        ("uts", uts.is_some()),
+        ("label_file", label_file.is_some()),
+        ("links", !links.is_empty()),
+        ("mac_address", mac_address.is_some()),
+        ("mem_swappiness", mem_swappiness.is_some()),
+        ("memswap_limit", memswap_limit.is_some()),
+        (
+            "network_mode",
+            matches!(network_config, Some(NetworkConfig::NetworkMode(_))),
+        ),
+        ("oom_kill_disable", *oom_kill_disable),
+        ("oom_score_adj", oom_score_adj.is_some()),
+        ("pid", pid.is_some()),
+        ("privileged", *privileged),
+        ("runtime", runtime.is_some()),
+        ("secrets", !secrets.is_empty()),
+        ("security_opt", !security_opt.is_empty()),
+        ("shm_size", shm_size.is_some()),
+        ("storage_opt", !storage_opt.is_empty()),
+        ("tmpfs", tmpfs.is_some()),
+        ("userns_mode", userns_mode.is_some()),
+        ("volumes_from", !volumes_from.is_empty()),
+    ]
+    .into_iter()
+    .filter(|&(_, unsupported)| unsupported)
+    .map(|(field, _)| field.to_owned())
+    .collect()
+}
+
+/// Error returned when converting a [`Service`] into a [`ServiceSpec`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ServiceSpecError {
+    /// Error converting a field shared with containers.
+    #[error(transparent)]
+    Container(#[from] ContainerCreateError),
+
+    /// A bind mount set `selinux`, which is not supported by Swarm.
+    #[error("bind mount of `{path}` cannot set `selinux` in a Swarm service")]
+    BindSELinux {
+        /// Host path of the bind mount.
+        path: PathBuf,
+    },
+}
+
+/// Specification of the tasks of a Swarm [`ServiceSpec`].
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct TaskSpec {
+    /// Specification of the container of each task.
+    pub container_spec: ContainerSpec,
+
+    /// Resource limits and reservations of each task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourceRequirements>,
+
+    /// If and how tasks are restarted when they exit.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub restart_policy: Option<RestartPolicy>,
+
+    /// Constraints and preferences for the nodes tasks are scheduled on.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub placement: Option<Placement>,
+
+    /// Networks tasks are attached to.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub networks: Vec<NetworkAttachmentConfig>,
+
+    /// Logging driver of the tasks.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub log_driver: Option<LogDriver>,
+}
+
+/// Specification of the container of a Swarm [`TaskSpec`].
+#[allow(clippy::struct_excessive_bools)]
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ContainerSpec {
+    /// Image to create the container from.
+    pub image: String,
+
+    /// Container labels.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub labels: IndexMap<String, String>,
+
+    /// Command to run, the image's entrypoint is used if [`None`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub command: Option<Vec<String>>,
+
+    /// Arguments to the command, the image's command is used if [`None`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub args: Option<Vec<String>>,
+
+    /// Hostname of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+
+    /// Environment variables in the format `{key}[={value}]`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<String>,
+
+    /// Working directory for commands run in the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub dir: Option<PathBuf>,
+
+    /// User the commands are run as inside the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub user: Option<String>,
+
+    /// Additional groups the user is a member of.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub groups: Vec<String>,
+
+    /// Whether to allocate a pseudo-TTY.
+    #[serde(rename = "TTY", skip_serializing_if = "std::ops::Not::not")]
+    pub tty: bool,
+
+    /// Whether to keep stdin open.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub open_stdin: bool,
+
+    /// Whether the root filesystem of the container is read-only.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+
+    /// Mounts of the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub mounts: Vec<Mount>,
+
+    /// Signal to stop the container with.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_signal: Option<String>,
+
+    /// Time to wait for the container to stop before killing it, in nanoseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub stop_grace_period: Option<u64>,
+
+    /// Healthcheck of the container.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub health_check: Option<HealthConfig>,
+
+    /// Entries to add to the container's `/etc/hosts` file, in the format `{ip} {hostname}`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub hosts: Vec<String>,
+
+    /// DNS configuration of the container.
+    #[serde(rename = "DNSConfig", skip_serializing_if = "Option::is_none")]
+    pub dns_config: Option<DnsConfig>,
+
+    /// Whether to run an init process in the container.
+    #[serde(skip_serializing_if = "std::ops::Not::not")]
+    pub init: bool,
+
+    /// Namespaced kernel parameters to set in the container.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub sysctls: IndexMap<String, String>,
+
+    /// Kernel capabilities to add to the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub capability_add: Vec<String>,
+
+    /// Kernel capabilities to drop from the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub capability_drop: Vec<String>,
+
+    /// Resource limits of the container.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ulimits: Vec<EngineUlimit>,
+
+    /// Isolation technology of the container, Windows only.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub isolation: Option<String>,
+}
+
+impl ContainerSpec {
+    /// Convert the container related fields of a [`Service`] into a [`ContainerSpec`].
+    fn from_service(
+        project: &Project,
+        name: &Identifier,
+        service: &Service,
+    ) -> Result<Self, ServiceSpecError> {
+        let mut labels = string_map("labels", &service.labels)?;
+        labels.insert(NAMESPACE_LABEL.to_owned(), project.name.to_string());
+
+        let mut mounts = Mounts::default();
+        for mount in volumes::into_long_iter(service.volumes.clone()) {
+            if let volumes::Mount::Bind(bind) = &mount {
+                if bind
+                    .bind
+                    .as_ref()
+                    .is_some_and(|options| options.selinux.is_some())
+                {
+                    return Err(ServiceSpecError::BindSELinux {
+                        path: project.host_path(bind.source.as_path()),
+                    });
+                }
+            }
+            mounts.push(project, mount)?;
+        }
+
+        let dns_config = DnsConfig {
+            nameservers: service
+                .dns
+                .iter()
+                .flat_map(|dns| dns.clone().into_list())
+                .map(|dns| dns.to_string())
+                .collect(),
+            search: service
+                .dns_search
+                .iter()
+                .flat_map(|dns_search| dns_search.clone().into_list())
+                .map(|dns_search| dns_search.as_str().to_owned())
+                .collect(),
+            options: service.dns_opt.iter().cloned().collect(),
+        };
+
+        Ok(Self {
+            image: service
+                .image
+                .as_ref()
+                .map_or_else(|| format!("{}-{name}", project.name), ToString::to_string),
+            labels,
+            command: service
+                .entrypoint
+                .as_ref()
+                .map(|entrypoint| split_command("entrypoint", entrypoint))
+                .transpose()?,
+            args: service
+                .command
+                .as_ref()
+                .map(|command| split_command("command", command))
+                .transpose()?,
+            hostname: service
+                .hostname
+                .as_ref()
+                .map(|hostname| hostname.as_str().to_owned()),
+            env: service
+                .environment
+                .clone()
+                .into_list()
+                .into_iter()
+                .collect(),
+            dir: service
+                .working_dir
+                .as_ref()
+                .map(|working_dir| working_dir.as_path().to_owned()),
+            user: service.user.as_ref().map(ToString::to_string),
+            groups: service.group_add.iter().map(ToString::to_string).collect(),
+            tty: service.tty,
+            open_stdin: service.stdin_open,
+            read_only: service.read_only,
+            mounts: mounts.mounts,
+            stop_signal: service.stop_signal.as_ref().map(ToString::to_string),
+            stop_grace_period: service.stop_grace_period.map(nanos),
+            health_check: service
+                .healthcheck
+                .as_ref()
+                .map(HealthConfig::from_healthcheck),
+            hosts: service
+                .extra_hosts
+                .iter()
+                .map(|(host, ip)| format!("{ip} {host}"))
+                .collect(),
+            dns_config: (!dns_config.is_empty()).then_some(dns_config),
+            init: service.init,
+            sysctls: string_map("sysctls", &service.sysctls)?,
+            capability_add: service.cap_add.iter().map(ToString::to_string).collect(),
+            capability_drop: service.cap_drop.iter().map(ToString::to_string).collect(),
+            ulimits: EngineUlimit::from_ulimits(&service.ulimits),
+            isolation: service.isolation.clone(),
+        })
+    }
+}
+
+/// DNS configuration of a [`ContainerSpec`].
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DnsConfig {
+    /// IP addresses of the name servers.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub nameservers: Vec<String>,
+
+    /// Domains to search when resolving hostnames.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub search: Vec<String>,
+
+    /// Options for the resolver.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<String>,
+}
+
+impl DnsConfig {
+    /// Returns `true` if all fields are empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            nameservers,
+            search,
+            options,
+        } = self;
+
+        nameservers.is_empty() && search.is_empty() && options.is_empty()
+    }
+}
+
+/// Resource limits and reservations of a [`TaskSpec`].
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ResourceRequirements {
+    /// Limits on resources a task may use.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub limits: Option<Limits>,
+
+    /// Resources reserved for a task.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub reservations: Option<Reservations>,
+}
+
+impl ResourceRequirements {
+    /// Convert [`Resources`](resources::Resources) into [`ResourceRequirements`], using the
+    /// equivalent fields of the `service` for limits and reservations which are not set.
+    ///
+    /// Returns [`None`] if no limits or reservations are set.
+    fn new(
+        service: &Service,
+        resources::Resources {
+            limits,
+            reservations,
+            extensions: _,
+        }: resources::Resources,
+    ) -> Result<Option<Self>, ContainerCreateError> {
+        let limits = limits.unwrap_or_default();
+        let limits = Limits {
+            nano_cpus: limits.cpus.or(service.cpus).map(nano_cpus),
+            memory_bytes: memory_bytes("deploy.resources.limits.memory", limits.memory)?
+                .or(memory_bytes("mem_limit", service.mem_limit)?),
+            pids: limits.pids.or(service.pids_limit).map(|limit| match limit {
+                Limit::Value(pids) => pids.into(),
+                Limit::Unlimited => 0,
+            }),
+        };
+
+        let reservations = reservations.unwrap_or_default();
+        let reservations = Reservations {
+            nano_cpus: reservations.cpus.map(nano_cpus),
+            memory_bytes: memory_bytes(
+                "deploy.resources.reservations.memory",
+                reservations.memory,
+            )?
+            .or(memory_bytes("mem_reservation", service.mem_reservation)?),
+            generic_resources: reservations
+                .generic_resources
+                .iter()
+                .filter_map(GenericResource::new)
+                .collect(),
+        };
+
+        let limits = (limits != Limits::default()).then_some(limits);
+        let reservations = (reservations != Reservations::default()).then_some(reservations);
+        Ok(
+            (limits.is_some() || reservations.is_some()).then_some(Self {
+                limits,
+                reservations,
+            }),
+        )
+    }
+}
+
+/// Convert an optional [`ByteValue`] into bytes.
+fn memory_bytes(
+    field: &'static str,
+    value: Option<ByteValue>,
+) -> Result<Option<u64>, ContainerCreateError> {
+    value.map(|value| bytes(field, value)).transpose()
+}
+
+/// Limits on resources a task may use.
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Limits {
+    /// CPU limit in units of 10<sup>-9</sup> CPUs.
+    #[serde(rename = "NanoCPUs", skip_serializing_if = "Option::is_none")]
+    pub nano_cpus: Option<u64>,
+
+    /// Memory limit in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_bytes: Option<u64>,
+
+    /// Limit on the number of processes, `0` is unlimited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub pids: Option<u64>,
+}
+
+/// Resources reserved for a task.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Reservations {
+    /// Reserved CPUs in units of 10<sup>-9</sup> CPUs.
+    #[serde(rename = "NanoCPUs", skip_serializing_if = "Option::is_none")]
+    pub nano_cpus: Option<u64>,
+
+    /// Reserved memory in bytes.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub memory_bytes: Option<u64>,
+
+    /// Reserved user-defined resources, e.g. GPUs.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub generic_resources: Vec<GenericResource>,
+}
+
+/// A user-defined resource reserved for a task.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct GenericResource {
+    /// An amount of a discrete resource.
+    pub discrete_resource_spec: DiscreteGenericResource,
+}
+
+impl GenericResource {
+    /// Convert a [`resources::GenericResource`] into a [`GenericResource`].
+    ///
+    /// Returns [`None`] if its `kind` or `value` is not set.
+    fn new(resource: &resources::GenericResource) -> Option<Self> {
+        let DiscreteResourceSpec { kind, value, .. } = resource.discrete_resource_spec.as_ref()?;
+        Some(Self {
+            discrete_resource_spec: DiscreteGenericResource {
+                kind: kind.clone()?,
+                value: (*value)?,
+            },
+        })
+    }
+}
+
+/// An amount of a discrete user-defined resource.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct DiscreteGenericResource {
+    /// Kind of resource, e.g. `gpu`.
+    pub kind: String,
+
+    /// Amount of the resource.
+    pub value: u64,
+}
+
+/// If and how the tasks of a [`ServiceSpec`] are restarted when they exit.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct RestartPolicy {
+    /// When to restart tasks, `none`, `on-failure`, or `any`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub condition: Option<String>,
+
+    /// Time to wait between restart attempts, in nanoseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u64>,
+
+    /// Maximum number of restart attempts, `0` is unlimited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_attempts: Option<u64>,
+
+    /// Time to wait before deciding if a restart has succeeded, in nanoseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub window: Option<u64>,
+}
+
+impl From<deploy::RestartPolicy> for RestartPolicy {
+    fn from(
+        deploy::RestartPolicy {
+            condition,
+            delay,
+            max_attempts,
+            window,
+            extensions: _,
+        }: deploy::RestartPolicy,
+    ) -> Self {
+        Self {
+            condition: condition.map(|condition| condition.as_str().to_owned()),
+            delay: delay.map(nanos),
+            max_attempts,
+            window: window.map(nanos),
+        }
+    }
+}
+
+impl From<Restart> for RestartPolicy {
+    fn from(restart: Restart) -> Self {
+        let condition = match restart {
+            Restart::No => RestartCondition::None,
+            Restart::Always | Restart::UnlessStopped => RestartCondition::Any,
+            Restart::OnFailure { .. } => RestartCondition::OnFailure,
+        };
+        Self {
+            condition: Some(condition.as_str().to_owned()),
+            max_attempts: restart.max_retries(),
+            ..Self::default()
+        }
+    }
+}
+
+/// Constraints and preferences for the nodes the tasks of a [`ServiceSpec`] are scheduled on.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Placement {
+    /// Properties a node must fulfill, e.g. `node.role==manager`.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub constraints: Vec<String>,
+
+    /// Properties tasks are spread evenly over.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub preferences: Vec<PlacementPreference>,
+
+    /// Maximum number of tasks per node, `0` is unlimited.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_replicas: Option<u64>,
+}
+
+impl From<deploy::Placement> for Placement {
+    fn from(
+        deploy::Placement {
+            constraints,
+            preferences,
+            max_replicas_per_node,
+            extensions: _,
+        }: deploy::Placement,
+    ) -> Self {
+        Self {
+            constraints,
+            preferences: preferences
+                .into_iter()
+                .filter_map(|Preference { spread, .. }| {
+                    spread.map(|spread_descriptor| PlacementPreference {
+                        spread: Spread { spread_descriptor },
+                    })
+                })
+                .collect(),
+            max_replicas: max_replicas_per_node,
+        }
+    }
+}
+
+/// A property the tasks of a [`ServiceSpec`] are spread evenly over.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct PlacementPreference {
+    /// Spread tasks over the values of a node label.
+    pub spread: Spread,
+}
+
+/// Spread tasks evenly over the values of a node label.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct Spread {
+    /// The label, e.g. `node.labels.datacenter`.
+    pub spread_descriptor: String,
+}
+
+/// A network the tasks of a [`ServiceSpec`] are attached to.
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct NetworkAttachmentConfig {
+    /// Name of the network.
+    pub target: String,
+
+    /// Aliases of the service in the network.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub aliases: Vec<String>,
+}
+
+/// Logging driver of the tasks of a [`ServiceSpec`].
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct LogDriver {
+    /// Name of the logging driver, the daemon's default is used if empty.
+    #[serde(skip_serializing_if = "String::is_empty")]
+    pub name: String,
+
+    /// Logging driver options.
+    #[serde(skip_serializing_if = "IndexMap::is_empty")]
+    pub options: IndexMap<String, String>,
+}
+
+/// Scheduling mode of a [`ServiceSpec`].
+///
+/// Serialized as an object with the mode as its only key.
+#[derive(Serialize, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ServiceMode {
+    /// Run a number of replicas of the task.
+    Replicated(ReplicatedService),
+
+    /// Run one task on each node.
+    Global(EmptyObject),
+}
+
+/// Options of the [`Replicated`](ServiceMode::Replicated) mode.
+#[derive(Serialize, Debug, Default, Clone, Copy, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct ReplicatedService {
+    /// Number of tasks to run, `1` if [`None`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub replicas: Option<u64>,
+}
+
+/// How a [`ServiceSpec`] is updated or rolled back.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct UpdateConfig {
+    /// Number of tasks updated at a time, `0` is all at once.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub parallelism: Option<u64>,
+
+    /// Time to wait between updates of groups of tasks, in nanoseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub delay: Option<u64>,
+
+    /// What to do if an update fails, `continue`, `pause`, or `rollback`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub failure_action: Option<String>,
+
+    /// Time to monitor each updated task for failure, in nanoseconds.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub monitor: Option<u64>,
+
+    /// Failure rate to tolerate during an update.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub max_failure_ratio: Option<u64>,
+
+    /// Order of operations, `stop-first` or `start-first`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub order: Option<String>,
+}
+
+impl From<UpdateOrRollbackConfig> for UpdateConfig {
+    fn from(
+        UpdateOrRollbackConfig {
+            parallelism,
+            delay,
+            failure_action,
+            monitor,
+            max_failure_ratio,
+            order,
+            extensions: _,
+        }: UpdateOrRollbackConfig,
+    ) -> Self {
+        Self {
+            parallelism,
+            delay: delay.map(nanos),
+            failure_action: failure_action.map(|action| action.as_str().to_owned()),
+            monitor: monitor.map(nanos),
+            max_failure_ratio,
+            order: order.map(|order| order.as_str().to_owned()),
+        }
+    }
+}
+
+/// How a [`ServiceSpec`] is discovered and its ports are published.
+#[derive(Serialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct EndpointSpec {
+    /// Service discovery mode, `vip` or `dnsrr`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub mode: Option<String>,
+
+    /// Published ports of the service.
+    #[serde(skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<EndpointPortConfig>,
+}
+
+/// A published port of a [`ServiceSpec`].
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct EndpointPortConfig {
+    /// Name of the port.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Protocol of the port, `tcp`, `udp`, or `sctp`.
+    pub protocol: String,
+
+    /// Port inside the container.
+    pub target_port: u16,
+
+    /// Port on the swarm hosts, a random port if [`None`].
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub published_port: Option<u16>,
+
+    /// How the port is published, `ingress` or `host`.
+    #[serde(skip_serializing_if = "Option::is_none")]
+    pub publish_mode: Option<String>,
+}
+
+impl From<ports::Port> for EndpointPortConfig {
+    fn from(
+        ports::Port {
+            name,
+            target,
+            published,
+            host_ip: _,
+            protocol,
+            app_protocol: _,
+            mode,
+            extensions: _,
+        }: ports::Port,
+    ) -> Self {
+        Self {
+            name,
+            protocol: protocol.map_or_else(|| "tcp".to_owned(), |protocol| protocol.to_string()),
+            target_port: target,
+            published_port: published.map(|published| published.start()),
+            publish_mode: mode.map(|mode| mode.as_str().to_owned()),
+        }
+    }
+}
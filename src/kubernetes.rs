@@ -0,0 +1,1060 @@
+//! Provides [`Manifests`] for converting a [`Compose`] project into Kubernetes objects, and
+//! Kubernetes objects into a [`Compose`] file.
+//!
+//! Each [`Service`](crate::Service) becomes a [`Deployment`](Object::Deployment), or a
+//! [`StatefulSet`](Object::StatefulSet) if it is the only service using a volume managed by
+//! Compose, and a [`Service`] if it has `ports` or `expose`. Volumes used by a single service
+//! become [`PersistentVolumeClaim`] templates of its stateful set, volumes shared by multiple
+//! services become a standalone [`PersistentVolumeClaim`] they all mount, configs become
+//! [`ConfigMap`]s, and secrets become [`Secret`]s.
+//!
+//! Fields without a Kubernetes equivalent, such as `network_mode`, `devices`, or bind mounts, are
+//! reported as [`Unsupported`].
+//!
+//! [`Manifests::from_yaml()`] reads objects, and [`Manifests::to_compose()`] converts their
+//! containers into services, e.g. to run production manifests locally.
+//!
+//! [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/)
+
+mod import;
+mod workload;
+
+use std::{
+    env::{self, VarError},
+    fmt::{self, Display, Formatter},
+    fs, io, iter,
+    path::PathBuf,
+};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Deserializer, Serialize, Serializer};
+use thiserror::Error;
+
+use crate::{
+    config,
+    engine::Project,
+    secret,
+    service::{volumes, ByteValue},
+    Compose, Identifier, ListOrMap, Resource, StringOrNumber, YamlValue,
+};
+
+pub use self::workload::{
+    Capabilities, ConfigMapVolumeSource, Container, ContainerPort, EmptyDirVolumeSource,
+    EnvFromSource, EnvVar, EnvVarSource, ExecAction, HostAlias, HostPathVolumeSource,
+    HttpGetAction, KeySelector, LabelSelector, ObjectReference, PersistentVolumeClaimVolumeSource,
+    Pod, PodDnsConfig, PodDnsConfigOption, PodSecurityContext, PodSpec, PodTemplateSpec, Probe,
+    ResourceRequirements, SecretVolumeSource, SecurityContext, Sysctl, TcpSocketAction, Volume,
+    VolumeMount, VolumeSource, Workload, WorkloadSpec,
+};
+
+/// Label used to select the pods of a service.
+const NAME_LABEL: &str = "app.kubernetes.io/name";
+
+/// Label with the name of the project all objects are part of.
+const PART_OF_LABEL: &str = "app.kubernetes.io/part-of";
+
+/// Storage requested by [`PersistentVolumeClaim`]s, as volumes do not have a size.
+const DEFAULT_STORAGE: &str = "1Gi";
+
+/// Kubernetes objects converted from a [`Compose`] project.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Manifests {
+    /// Converted objects.
+    pub objects: Vec<Object>,
+
+    /// Fields which have no Kubernetes equivalent and were not converted.
+    pub unsupported: Vec<Unsupported>,
+}
+
+impl Manifests {
+    /// Convert the [`Compose`] file of a `project` into Kubernetes objects.
+    ///
+    /// Object names are the keys of the services, volumes, configs, and secrets, converted into
+    /// valid Kubernetes names, e.g. `my_volume` becomes `my-volume`. All objects are labeled with
+    /// `app.kubernetes.io/part-of: {project}`, and pods with `app.kubernetes.io/name: {service}`.
+    ///
+    /// Each pod of a stateful set gets its own claim for each volume only its service mounts, so
+    /// replicas do not share a `ReadWriteOnce` claim. Volumes used by multiple services become a
+    /// single `ReadWriteMany` claim which all of their pods mount, so the services share data.
+    /// Shared volumes must be defined in the top-level `volumes` field, otherwise they are
+    /// reported as unsupported. External volumes are mounted as existing claims.
+    ///
+    /// Health checks become both liveness and readiness probes. Resource limits and reservations
+    /// come from `deploy.resources`, or the equivalent service fields, and replicas from
+    /// `deploy.replicas` or `scale`. Configs and secrets with a file source are read relative to
+    /// the project's working directory, and those with an environment source are read from the
+    /// current environment. Services do not have their own network, so `networks` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if two services, volumes, configs, or secrets have the same object name, if
+    /// a command could not be split into words, or if a config or secret could not be read.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use compose_spec::{engine::Project, kubernetes::Manifests, Compose, Name};
+    ///
+    /// let yaml = "\
+    /// services:
+    ///   caddy:
+    ///     image: docker.io/library/caddy:latest
+    ///     ports:
+    ///       - 8080:80
+    ///     devices:
+    ///       - /dev/fuse:/dev/fuse
+    /// ";
+    /// let compose: Compose = serde_yaml::from_str(yaml)?;
+    /// let name = Name::new("app")?;
+    /// let project = Project::new(&name, &compose, Path::new("/srv/app"));
+    ///
+    /// let manifests = Manifests::from_project(&project)?;
+    /// let kinds: Vec<&str> = manifests.objects.iter().map(|object| object.kind()).collect();
+    /// assert_eq!(kinds, ["Service", "Deployment"]);
+    /// assert_eq!(
+    ///     manifests.unsupported[0].to_string(),
+    ///     "`devices` of service `caddy` has no Kubernetes equivalent",
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn from_project(project: &Project) -> Result<Self, ManifestsError> {
+        let mut manifests = Self {
+            objects: Vec::new(),
+            unsupported: Vec::new(),
+        };
+        let compose = project.compose;
+
+        check_names(
+            ResourceKind::Service,
+            compose
+                .services
+                .keys()
+                .map(|name| (name, object_name(name.as_str()))),
+        )?;
+        check_names(
+            ResourceKind::Volume,
+            compose
+                .volumes
+                .keys()
+                .map(|name| (name, claim_name(compose, name))),
+        )?;
+        check_names(
+            ResourceKind::Config,
+            compose
+                .configs
+                .keys()
+                .map(|name| (name, object_name(name.as_str()))),
+        )?;
+        check_names(
+            ResourceKind::Secret,
+            compose
+                .secrets
+                .keys()
+                .map(|name| (name, object_name(name.as_str()))),
+        )?;
+
+        for (name, config) in &compose.configs {
+            if let Resource::Compose(config) = config {
+                let config_map = config_map(project, name, config)?;
+                manifests.objects.push(Object::ConfigMap(config_map));
+            }
+        }
+        for (name, secret) in &compose.secrets {
+            if let Resource::Compose(secret) = secret {
+                let secret = manifests.secret(project, name, secret)?;
+                manifests.objects.push(Object::Secret(secret));
+            }
+        }
+        for (name, volume) in &compose.volumes {
+            if let Some(Resource::Compose(crate::Volume {
+                driver,
+                driver_opts,
+                ..
+            })) = volume
+            {
+                for (field, unsupported) in [
+                    ("driver", driver.is_some()),
+                    ("driver_opts", !driver_opts.is_empty()),
+                ] {
+                    if unsupported {
+                        manifests.unsupported(ResourceKind::Volume, name, field);
+                    }
+                }
+            }
+            if !matches!(volume, Some(Resource::External { .. })) && is_shared(compose, name) {
+                let claim = persistent_volume_claim(project, name, true);
+                manifests.objects.push(Object::PersistentVolumeClaim(claim));
+            }
+        }
+        for (name, service) in &compose.services {
+            let objects = workload::convert(project, name, service, &mut manifests.unsupported)?;
+            manifests.objects.extend(objects);
+        }
+
+        Ok(manifests)
+    }
+
+    /// Serialize the objects into YAML documents, separated by `---`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if an object could not be serialized.
+    pub fn to_yaml(&self) -> Result<String, serde_yaml::Error> {
+        let documents = self
+            .objects
+            .iter()
+            .map(serde_yaml::to_string)
+            .collect::<Result<Vec<_>, _>>()?;
+        Ok(documents.join("---\n"))
+    }
+
+    /// Deserialize objects from YAML documents, separated by `---`.
+    ///
+    /// [`Pod`]s, [`Deployment`](Object::Deployment)s, [`StatefulSet`](Object::StatefulSet)s,
+    /// [`Service`]s, [`PersistentVolumeClaim`]s, [`ConfigMap`]s, and [`Secret`]s are read,
+    /// including those in the `items` of a `List`. Objects of other kinds, and fields without a
+    /// Compose equivalent, are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the YAML is invalid or an object of a supported kind could not be
+    /// deserialized.
+    pub fn from_yaml(yaml: &str) -> Result<Self, serde_yaml::Error> {
+        let mut objects = Vec::new();
+        for document in serde_yaml::Deserializer::from_str(yaml) {
+            push_objects(&mut objects, YamlValue::deserialize(document)?)?;
+        }
+        Ok(Self {
+            objects,
+            unsupported: Vec::new(),
+        })
+    }
+
+    /// Convert the objects into a [`Compose`] file, e.g. to run production manifests locally.
+    ///
+    /// Each container of a [`Pod`], [`Deployment`](Object::Deployment), or
+    /// [`StatefulSet`](Object::StatefulSet) becomes a service. The first container's service is
+    /// named after the object, and other containers, such as sidecars, become services named
+    /// `{object}-{container}` which share its network with `network_mode: service:{object}`.
+    ///
+    /// - `containerPort`s become `ports`, published to their `hostPort` or to the port of a
+    ///   [`Service`] which selects the pod.
+    /// - `env` and `envFrom` become `environment`, with values from [`ConfigMap`]s and
+    ///   [`Secret`]s inlined.
+    /// - `resources` become `deploy.resources`.
+    /// - Liveness or readiness probes become the `healthcheck`. HTTP and TCP probes become shell
+    ///   commands using `curl` and `nc`, which must be available in the container.
+    /// - `volumeMounts` become `volumes`. Persistent volume claims become named volumes, and
+    ///   `emptyDir` volumes become named volumes shared by the pod's containers, or tmpfs mounts if
+    ///   backed by memory. Keys of config maps and secrets mounted as volumes become `configs`.
+    ///
+    /// Values without a Compose equivalent, or which are invalid, are ignored.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compose_spec::{kubernetes::Manifests, service::NetworkConfig};
+    ///
+    /// let yaml = "\
+    /// apiVersion: v1
+    /// kind: Pod
+    /// metadata:
+    ///   name: web
+    /// spec:
+    ///   containers:
+    ///     - name: nginx
+    ///       image: docker.io/library/nginx
+    ///       ports:
+    ///         - containerPort: 80
+    ///           hostPort: 8080
+    ///     - name: proxy
+    ///       image: docker.io/library/envoy
+    /// ";
+    /// let compose = Manifests::from_yaml(yaml)?.to_compose();
+    ///
+    /// let web = &compose.services["web"];
+    /// assert_eq!(web.ports.len(), 1);
+    /// assert_eq!(
+    ///     compose.services["web-proxy"].network_config,
+    ///     Some(NetworkConfig::NetworkMode("service:web".parse()?)),
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn to_compose(&self) -> Compose {
+        import::convert(&self.objects)
+    }
+
+    /// Add an unsupported field.
+    fn unsupported(&mut self, kind: ResourceKind, name: &Identifier, field: impl Into<String>) {
+        self.unsupported.push(Unsupported {
+            kind,
+            name: name.clone(),
+            field: field.into(),
+        });
+    }
+
+    /// Convert a top-level secret into a [`Secret`].
+    fn secret(
+        &mut self,
+        project: &Project,
+        name: &Identifier,
+        secret: &secret::Secret,
+    ) -> Result<Secret, ManifestsError> {
+        let secret::Secret {
+            source,
+            labels,
+            driver,
+            driver_opts,
+            extensions: _,
+        } = secret;
+
+        for (field, unsupported) in [
+            ("driver", driver.is_some()),
+            ("driver_opts", !driver_opts.is_empty()),
+        ] {
+            if unsupported {
+                self.unsupported(ResourceKind::Secret, name, field);
+            }
+        }
+        let data = match source {
+            secret::Source::File(path) => read_file(project, path)?,
+            secret::Source::Environment(variable) => read_variable(variable)?,
+        };
+
+        Ok(Secret {
+            metadata: ObjectMeta::new(project, object_name(name.as_str()), labels),
+            string_data: iter::once((name.to_string(), data)).collect(),
+            data: IndexMap::new(),
+        })
+    }
+}
+
+/// Convert a top-level volume managed by Compose into a [`PersistentVolumeClaim`].
+///
+/// A `shared` claim is mounted by the pods of multiple services, which may run on different
+/// nodes, otherwise the claim is a template for the pods of a single stateful set. Unsupported
+/// fields of the volume are reported by [`Manifests::from_project()`].
+fn persistent_volume_claim(
+    project: &Project,
+    name: &Identifier,
+    shared: bool,
+) -> PersistentVolumeClaim {
+    let labels = match project.compose.volumes.get(name) {
+        Some(Some(Resource::Compose(volume))) => volume.labels.clone(),
+        _ => ListOrMap::default(),
+    };
+    let access_mode = if shared {
+        "ReadWriteMany"
+    } else {
+        "ReadWriteOnce"
+    };
+
+    PersistentVolumeClaim {
+        metadata: ObjectMeta::new(project, claim_name(project.compose, name), &labels),
+        spec: PersistentVolumeClaimSpec {
+            access_modes: vec![access_mode.to_owned()],
+            resources: VolumeResourceRequirements {
+                requests: iter::once(("storage".to_owned(), DEFAULT_STORAGE.to_owned())).collect(),
+            },
+        },
+    }
+}
+
+/// Returns `true` if the named `volume` is used by more than one service.
+fn is_shared(compose: &Compose, volume: &Identifier) -> bool {
+    compose
+        .services
+        .values()
+        .filter(|service| {
+            volumes::named_volumes_iter(&service.volumes).any(|source| source == volume)
+        })
+        .nth(1)
+        .is_some()
+}
+
+/// Check that no two resources of the same `kind` are converted into the same object name.
+fn check_names<'a>(
+    kind: ResourceKind,
+    names: impl IntoIterator<Item = (&'a Identifier, String)>,
+) -> Result<(), ManifestsError> {
+    let mut seen = IndexMap::new();
+    for (resource, object_name) in names {
+        if let Some(first) = seen.insert(object_name.clone(), resource) {
+            return Err(ManifestsError::NameCollision {
+                kind,
+                first: first.clone(),
+                second: resource.clone(),
+                name: object_name,
+            });
+        }
+    }
+    Ok(())
+}
+
+/// Convert a top-level config into a [`ConfigMap`].
+fn config_map(
+    project: &Project,
+    name: &Identifier,
+    config: &config::Config,
+) -> Result<ConfigMap, ManifestsError> {
+    let config::Config {
+        source,
+        labels,
+        extensions: _,
+    } = config;
+
+    let data = match source {
+        config::Source::File(path) => read_file(project, path)?,
+        config::Source::Environment(variable) => read_variable(variable)?,
+        config::Source::Content(content) => content.clone(),
+    };
+
+    Ok(ConfigMap {
+        metadata: ObjectMeta::new(project, object_name(name.as_str()), labels),
+        data: iter::once((name.to_string(), data)).collect(),
+    })
+}
+
+/// Read the contents of a config or secret file.
+fn read_file(project: &Project, path: &std::path::Path) -> Result<String, ManifestsError> {
+    let path = project.host_path(path);
+    fs::read_to_string(&path).map_err(|source| ManifestsError::Read { path, source })
+}
+
+/// Read the value of an environment variable for a config or secret.
+fn read_variable(variable: &str) -> Result<String, ManifestsError> {
+    env::var(variable).map_err(|source| ManifestsError::Environment {
+        variable: variable.to_owned(),
+        source,
+    })
+}
+
+/// Convert a name into a valid Kubernetes object name.
+///
+/// Object names may only contain lowercase alphanumeric characters and dashes (-), and must
+/// start and end with an alphanumeric character.
+pub(crate) fn object_name(name: &str) -> String {
+    let name: String = name
+        .chars()
+        .map(|char| {
+            if char.is_ascii_alphanumeric() {
+                char.to_ascii_lowercase()
+            } else {
+                '-'
+            }
+        })
+        .collect();
+    name.trim_matches('-').to_owned()
+}
+
+/// Name of the [`PersistentVolumeClaim`] for a top-level volume.
+///
+/// External volumes are claims which already exist.
+fn claim_name(compose: &Compose, volume: &Identifier) -> String {
+    match compose.volumes.get(volume) {
+        Some(Some(Resource::External { name: Some(name) })) => name.clone(),
+        Some(Some(Resource::Compose(crate::Volume {
+            name: Some(name), ..
+        }))) => object_name(name),
+        _ => object_name(volume.as_str()),
+    }
+}
+
+/// Convert a [`ByteValue`] into a Kubernetes quantity, e.g. `512mb` becomes `512M`.
+fn quantity(value: ByteValue) -> String {
+    match value {
+        ByteValue::Bytes(bytes) => bytes.to_string(),
+        ByteValue::Kilobytes(kilobytes) => format!("{kilobytes}k"),
+        ByteValue::Megabytes(megabytes) => format!("{megabytes}M"),
+        ByteValue::Gigabytes(gigabytes) => format!("{gigabytes}G"),
+    }
+}
+
+/// Convert a [`ListOrMap`] into a map of strings, missing values become empty strings.
+fn string_map(list_or_map: &ListOrMap) -> IndexMap<String, String> {
+    list_or_map
+        .clone()
+        .into_list()
+        .into_iter()
+        .map(|item| match item.split_once('=') {
+            Some((key, value)) => (key.to_owned(), value.to_owned()),
+            None => (item, String::new()),
+        })
+        .collect()
+}
+
+/// Deserialize a map of resource quantities, which may be strings or numbers, e.g. `cpu: 1`.
+fn quantities<'de, D: Deserializer<'de>>(
+    deserializer: D,
+) -> Result<IndexMap<String, String>, D::Error> {
+    let quantities = IndexMap::<String, StringOrNumber>::deserialize(deserializer)?;
+    Ok(quantities
+        .into_iter()
+        .map(|(resource, quantity)| (resource, quantity.to_string()))
+        .collect())
+}
+
+/// Deserialize the object in a YAML `value` and add it to `objects`.
+///
+/// A `List` adds each of its `items`. Objects of unsupported kinds are ignored.
+fn push_objects(objects: &mut Vec<Object>, value: YamlValue) -> Result<(), serde_yaml::Error> {
+    let object = match value.get("kind").and_then(YamlValue::as_str) {
+        Some("Pod") => Object::Pod(serde_yaml::from_value(value)?),
+        Some("Deployment") => Object::Deployment(serde_yaml::from_value(value)?),
+        Some("StatefulSet") => Object::StatefulSet(serde_yaml::from_value(value)?),
+        Some("Service") => Object::Service(serde_yaml::from_value(value)?),
+        Some("PersistentVolumeClaim") => {
+            Object::PersistentVolumeClaim(serde_yaml::from_value(value)?)
+        }
+        Some("ConfigMap") => Object::ConfigMap(serde_yaml::from_value(value)?),
+        Some("Secret") => Object::Secret(serde_yaml::from_value(value)?),
+        Some("List") => {
+            if let Some(YamlValue::Sequence(items)) = value.get("items") {
+                for item in items {
+                    push_objects(objects, item.clone())?;
+                }
+            }
+            return Ok(());
+        }
+        _ => return Ok(()),
+    };
+    objects.push(object);
+    Ok(())
+}
+
+/// A Kubernetes object.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub enum Object {
+    /// Runs a group of containers. Only read by [`Manifests::from_yaml()`].
+    Pod(Pod),
+
+    /// Runs replicas of a service's pod.
+    Deployment(Workload),
+
+    /// Runs replicas of a service's pod with stable identities, used for services with named
+    /// volumes.
+    StatefulSet(Workload),
+
+    /// Exposes the ports of a service's pods.
+    Service(Service),
+
+    /// Storage for a named volume.
+    PersistentVolumeClaim(PersistentVolumeClaim),
+
+    /// Data of a config.
+    ConfigMap(ConfigMap),
+
+    /// Data of a secret.
+    Secret(Secret),
+}
+
+impl Object {
+    /// API version of the object, e.g. `apps/v1`.
+    #[must_use]
+    pub const fn api_version(&self) -> &'static str {
+        match self {
+            Self::Deployment(_) | Self::StatefulSet(_) => "apps/v1",
+            Self::Pod(_)
+            | Self::Service(_)
+            | Self::PersistentVolumeClaim(_)
+            | Self::ConfigMap(_)
+            | Self::Secret(_) => "v1",
+        }
+    }
+
+    /// Kind of the object, e.g. `Deployment`.
+    #[must_use]
+    pub const fn kind(&self) -> &'static str {
+        match self {
+            Self::Pod(_) => "Pod",
+            Self::Deployment(_) => "Deployment",
+            Self::StatefulSet(_) => "StatefulSet",
+            Self::Service(_) => "Service",
+            Self::PersistentVolumeClaim(_) => "PersistentVolumeClaim",
+            Self::ConfigMap(_) => "ConfigMap",
+            Self::Secret(_) => "Secret",
+        }
+    }
+
+    /// Metadata of the object.
+    #[must_use]
+    pub const fn metadata(&self) -> &ObjectMeta {
+        match self {
+            Self::Pod(Pod { metadata, .. })
+            | Self::Deployment(Workload { metadata, .. })
+            | Self::StatefulSet(Workload { metadata, .. })
+            | Self::Service(Service { metadata, .. })
+            | Self::PersistentVolumeClaim(PersistentVolumeClaim { metadata, .. })
+            | Self::ConfigMap(ConfigMap { metadata, .. })
+            | Self::Secret(Secret { metadata, .. }) => metadata,
+        }
+    }
+}
+
+impl Serialize for Object {
+    fn serialize<S: Serializer>(&self, serializer: S) -> Result<S::Ok, S::Error> {
+        /// An object with its API version and kind.
+        #[derive(Serialize)]
+        #[serde(rename_all = "camelCase")]
+        struct Tagged<'a, T> {
+            /// [`Object::api_version()`]
+            api_version: &'static str,
+
+            /// [`Object::kind()`]
+            kind: &'static str,
+
+            /// The object's fields.
+            #[serde(flatten)]
+            object: &'a T,
+        }
+
+        let api_version = self.api_version();
+        let kind = self.kind();
+        match self {
+            Self::Pod(object) => Tagged {
+                api_version,
+                kind,
+                object,
+            }
+            .serialize(serializer),
+            Self::Deployment(object) | Self::StatefulSet(object) => Tagged {
+                api_version,
+                kind,
+                object,
+            }
+            .serialize(serializer),
+            Self::Service(object) => Tagged {
+                api_version,
+                kind,
+                object,
+            }
+            .serialize(serializer),
+            Self::PersistentVolumeClaim(object) => Tagged {
+                api_version,
+                kind,
+                object,
+            }
+            .serialize(serializer),
+            Self::ConfigMap(object) => Tagged {
+                api_version,
+                kind,
+                object,
+            }
+            .serialize(serializer),
+            Self::Secret(object) => Tagged {
+                api_version,
+                kind,
+                object,
+            }
+            .serialize(serializer),
+        }
+    }
+}
+
+/// Metadata of an [`Object`] or [`PodTemplateSpec`].
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ObjectMeta {
+    /// Name of the object, unique within its kind and namespace.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Labels used to select objects.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub labels: IndexMap<String, String>,
+
+    /// Arbitrary non-identifying metadata.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub annotations: IndexMap<String, String>,
+}
+
+impl ObjectMeta {
+    /// Metadata for an object named `name` that is part of the `project`, with the Compose
+    /// `labels` as annotations.
+    fn new(project: &Project, name: String, labels: &ListOrMap) -> Self {
+        Self {
+            name: Some(name),
+            labels: iter::once((PART_OF_LABEL.to_owned(), project.name.to_string())).collect(),
+            annotations: string_map(labels),
+        }
+    }
+}
+
+/// Exposes the ports of a service's pods.
+///
+/// [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/service-resources/service-v1/)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Service {
+    /// Metadata of the service.
+    pub metadata: ObjectMeta,
+
+    /// Specification of the service.
+    pub spec: ServiceSpec,
+}
+
+/// Specification of a [`Service`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServiceSpec {
+    /// `None` for a headless service, used by [`StatefulSet`](Object::StatefulSet)s of services
+    /// without ports.
+    #[serde(rename = "clusterIP", default, skip_serializing_if = "Option::is_none")]
+    pub cluster_ip: Option<String>,
+
+    /// Labels of the pods the service routes traffic to.
+    #[serde(default)]
+    pub selector: IndexMap<String, String>,
+
+    /// Ports exposed by the service.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<ServicePort>,
+}
+
+/// A port exposed by a [`Service`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ServicePort {
+    /// Name of the port, e.g. `8080-tcp`.
+    #[serde(default, skip_serializing_if = "String::is_empty")]
+    pub name: String,
+
+    /// Port exposed by the service.
+    pub port: u16,
+
+    /// Port of the pods traffic is routed to, defaults to [`port`](Self::port).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub target_port: Option<TargetPort>,
+
+    /// Protocol of the port, `TCP`, `UDP`, or `SCTP`.
+    #[serde(default = "default_protocol")]
+    pub protocol: String,
+}
+
+/// Port of the pods a [`ServicePort`] routes traffic to, or a [`Probe`] connects to.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(untagged)]
+pub enum TargetPort {
+    /// Port number within the pods.
+    Number(u16),
+
+    /// Name of a [`ContainerPort`].
+    Name(String),
+}
+
+/// Default protocol of ports, `TCP`.
+fn default_protocol() -> String {
+    "TCP".to_owned()
+}
+
+/// Storage for a named volume.
+///
+/// [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/config-and-storage-resources/persistent-volume-claim-v1/)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PersistentVolumeClaim {
+    /// Metadata of the claim.
+    pub metadata: ObjectMeta,
+
+    /// Specification of the claim.
+    pub spec: PersistentVolumeClaimSpec,
+}
+
+/// Specification of a [`PersistentVolumeClaim`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentVolumeClaimSpec {
+    /// How the volume may be mounted, e.g. `ReadWriteOnce`.
+    #[serde(default)]
+    pub access_modes: Vec<String>,
+
+    /// Storage requested for the volume.
+    #[serde(default)]
+    pub resources: VolumeResourceRequirements,
+}
+
+/// Storage requested by a [`PersistentVolumeClaim`].
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct VolumeResourceRequirements {
+    /// Requested resources, e.g. `storage: 1Gi`.
+    #[serde(default, deserialize_with = "quantities")]
+    pub requests: IndexMap<String, String>,
+}
+
+/// Data of a config.
+///
+/// [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/config-and-storage-resources/config-map-v1/)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigMap {
+    /// Metadata of the config map.
+    pub metadata: ObjectMeta,
+
+    /// Contents of the config, keyed by the name of the config.
+    #[serde(default)]
+    pub data: IndexMap<String, String>,
+}
+
+/// Data of a secret.
+///
+/// [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/config-and-storage-resources/secret-v1/)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Secret {
+    /// Metadata of the secret.
+    pub metadata: ObjectMeta,
+
+    /// Contents of the secret, keyed by the name of the secret.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub string_data: IndexMap<String, String>,
+
+    /// Base64 encoded contents of the secret.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub data: IndexMap<String, String>,
+}
+
+/// A field which has no Kubernetes equivalent and was not converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+    /// Kind of the resource containing the field.
+    pub kind: ResourceKind,
+
+    /// Name of the service, volume, config, or secret containing the field.
+    pub name: Identifier,
+
+    /// Name of the unsupported field, e.g. `network_mode` or `volumes.bind`.
+    pub field: String,
+}
+
+impl Display for Unsupported {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self { kind, name, field } = self;
+        write!(
+            f,
+            "`{field}` of {kind} `{name}` has no Kubernetes equivalent"
+        )
+    }
+}
+
+/// Kind of Compose resource an [`Unsupported`] field is in.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum ResourceKind {
+    /// A [`Service`](crate::Service).
+    Service,
+
+    /// A top-level [`Volume`](crate::Volume).
+    Volume,
+
+    /// A top-level [`Config`](crate::Config).
+    Config,
+
+    /// A top-level [`Secret`](crate::Secret).
+    Secret,
+}
+
+impl ResourceKind {
+    /// Resource kind as a static string slice.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Service => "service",
+            Self::Volume => "volume",
+            Self::Config => "config",
+            Self::Secret => "secret",
+        }
+    }
+}
+
+impl Display for ResourceKind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Error returned when converting a [`Compose`] project into [`Manifests`].
+#[derive(Error, Debug)]
+pub enum ManifestsError {
+    /// Two resources of the same kind would become objects with the same name, e.g. `my_app` and
+    /// `my-app`.
+    #[error("{kind}s `{first}` and `{second}` would both become objects named `{name}`")]
+    NameCollision {
+        /// Kind of the resources.
+        kind: ResourceKind,
+
+        /// Name of the first resource.
+        first: Identifier,
+
+        /// Name of the second resource.
+        second: Identifier,
+
+        /// The shared object name.
+        name: String,
+    },
+
+    /// A command of a service could not be split into words as it contains an unterminated quote
+    /// or ends with a backslash.
+    #[error("`{field}` of service `{service}` could not be split into words")]
+    SplitCommand {
+        /// Name of the service.
+        service: Identifier,
+
+        /// The field containing the command.
+        field: &'static str,
+    },
+
+    /// Error reading the file of a config or secret.
+    #[error("error reading `{}`", path.display())]
+    Read {
+        /// Path of the file.
+        path: PathBuf,
+
+        /// Source of the error.
+        source: io::Error,
+    },
+
+    /// Error reading the environment variable of a config or secret.
+    #[error("error reading environment variable `{variable}`")]
+    Environment {
+        /// Name of the environment variable.
+        variable: String,
+
+        /// Source of the error.
+        source: VarError,
+    },
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::path::Path;
+
+    use crate::Name;
+
+    use super::*;
+
+    #[test]
+    fn full() {
+        let compose: Compose =
+            serde_yaml::from_str(include_str!("kubernetes/test-full.yaml")).unwrap();
+        let name = Name::new("app").unwrap();
+        let working_dir = Path::new(env!("CARGO_MANIFEST_DIR")).join("src/kubernetes");
+        let project = Project::new(&name, &compose, &working_dir);
+
+        let manifests = Manifests::from_project(&project).unwrap();
+        assert_eq!(
+            manifests.to_yaml().unwrap(),
+            include_str!("kubernetes/test-full.k8s.yaml"),
+        );
+
+        let unsupported: Vec<String> = manifests
+            .unsupported
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            unsupported,
+            [
+                "`driver_opts` of volume `db_data` has no Kubernetes equivalent",
+                "`volumes.bind` of service `web` has no Kubernetes equivalent",
+                "`devices` of service `worker` has no Kubernetes equivalent",
+                "`network_mode` of service `worker` has no Kubernetes equivalent",
+                "`build` of service `worker` has no Kubernetes equivalent",
+            ],
+        );
+
+        // Volume claim templates and shared claims are mounted as named volumes.
+        let compose = manifests.to_compose();
+        assert!(compose.volumes.contains_key("db-data"));
+        assert!(compose.volumes.contains_key("uploads"));
+    }
+
+    #[test]
+    fn shared_volume_undefined() {
+        let compose: Compose = serde_yaml::from_str(
+            "
+            services:
+              writer:
+                image: alpine
+                volumes:
+                  - uploads:/uploads
+              reader:
+                image: alpine
+                volumes:
+                  - uploads:/uploads
+            ",
+        )
+        .unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+
+        let manifests = Manifests::from_project(&project).unwrap();
+        let kinds: Vec<&str> = manifests.objects.iter().map(Object::kind).collect();
+        assert_eq!(kinds, ["Deployment", "Deployment"]);
+
+        let unsupported: Vec<String> = manifests
+            .unsupported
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            unsupported,
+            [
+                "`volumes.uploads` of service `writer` has no Kubernetes equivalent",
+                "`volumes.uploads` of service `reader` has no Kubernetes equivalent",
+            ],
+        );
+    }
+
+    #[test]
+    fn missing_variable() {
+        let compose: Compose = serde_yaml::from_str(
+            "{services: {}, secrets: {api_key: {environment: COMPOSE_SPEC_KUBERNETES_TEST_UNSET}}}",
+        )
+        .unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+
+        let error = Manifests::from_project(&project).unwrap_err();
+        assert!(matches!(
+            error,
+            ManifestsError::Environment {
+                source: VarError::NotPresent,
+                ..
+            }
+        ));
+    }
+
+    #[test]
+    fn import() {
+        let manifests = Manifests::from_yaml(include_str!("kubernetes/test-import.yaml")).unwrap();
+        let kinds: Vec<&str> = manifests.objects.iter().map(Object::kind).collect();
+        assert_eq!(
+            kinds,
+            ["ConfigMap", "Secret", "Service", "Deployment", "Pod"]
+        );
+
+        let compose = manifests.to_compose();
+        let expected: Compose =
+            serde_yaml::from_str(include_str!("kubernetes/test-import.compose.yaml")).unwrap();
+        assert_eq!(compose, expected);
+    }
+
+    #[test]
+    fn object_names() {
+        assert_eq!(object_name("my_volume"), "my-volume");
+        assert_eq!(object_name("_App.Data_"), "app-data");
+    }
+
+    #[test]
+    fn object_name_collision() {
+        let compose: Compose =
+            serde_yaml::from_str("services: {my_app: {image: app}, my-app: {image: app}}").unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+
+        let error = Manifests::from_project(&project).unwrap_err();
+        assert_eq!(
+            error.to_string(),
+            "services `my_app` and `my-app` would both become objects named `my-app`",
+        );
+    }
+}
@@ -0,0 +1,913 @@
+//! Conversion of Kubernetes [`Object`]s into a [`Compose`] file.
+
+use std::{net::IpAddr, time::Duration};
+
+use indexmap::{IndexMap, IndexSet};
+
+use crate::{
+    common::item_or_list,
+    config,
+    service::{
+        deploy::resources::{Cpus, Limits, Reservations, Resources},
+        healthcheck::{self, Test},
+        network_config::NetworkMode,
+        ports::{Port, Protocol},
+        user::IdOrName,
+        volumes::{
+            mount::{self, Bind, Common, Tmpfs, TmpfsOptions, VolumeOptions},
+            AbsolutePath, HostPath, Mount,
+        },
+        ByteValue, Capability, Command, ConfigOrSecret, Deploy, Healthcheck, Hostname,
+        NetworkConfig, User,
+    },
+    shell, Compose, Config, Identifier, ListOrMap, Map, MapKey, Resource, Service, ShortOrLong,
+    Value,
+};
+
+use super::{
+    object_name, Container, ContainerPort, EnvFromSource, EnvVar, EnvVarSource, HttpGetAction,
+    KeySelector, Object, ObjectMeta, PersistentVolumeClaim, PersistentVolumeClaimVolumeSource, Pod,
+    PodDnsConfig, PodDnsConfigOption, PodSecurityContext, PodSpec, PodTemplateSpec, Probe,
+    ResourceRequirements, SecurityContext, ServicePort, TargetPort, TcpSocketAction, Volume,
+    VolumeMount, VolumeSource, Workload, WorkloadSpec,
+};
+
+/// Convert Kubernetes [`Object`]s into a [`Compose`] file.
+pub(super) fn convert(objects: &[Object]) -> Compose {
+    let mut config_maps = IndexMap::new();
+    let mut secrets = IndexMap::new();
+    let mut services = Vec::new();
+    for object in objects {
+        match object {
+            Object::ConfigMap(config_map) => {
+                if let Some(name) = &config_map.metadata.name {
+                    config_maps.insert(name.as_str(), config_map.data.clone());
+                }
+            }
+            Object::Secret(secret) => {
+                if let Some(name) = &secret.metadata.name {
+                    let mut data: IndexMap<String, String> = secret
+                        .data
+                        .iter()
+                        .filter_map(|(key, value)| Some((key.clone(), decode_base64(value)?)))
+                        .collect();
+                    data.extend(secret.string_data.clone());
+                    secrets.insert(name.as_str(), data);
+                }
+            }
+            Object::Service(service) => services.push(&service.spec),
+            Object::Pod(_)
+            | Object::Deployment(_)
+            | Object::StatefulSet(_)
+            | Object::PersistentVolumeClaim(_) => {}
+        }
+    }
+
+    let mut importer = Importer {
+        compose: Compose::default(),
+        config_maps,
+        secrets,
+        services,
+    };
+    for object in objects {
+        let (metadata, replicas, template_metadata, spec, claim_templates) = match object {
+            Object::Pod(Pod { metadata, spec }) => (metadata, None, metadata, spec, &[][..]),
+            Object::Deployment(Workload {
+                metadata,
+                spec:
+                    WorkloadSpec {
+                        replicas,
+                        template:
+                            PodTemplateSpec {
+                                metadata: template_metadata,
+                                spec,
+                            },
+                        volume_claim_templates,
+                        ..
+                    },
+            })
+            | Object::StatefulSet(Workload {
+                metadata,
+                spec:
+                    WorkloadSpec {
+                        replicas,
+                        template:
+                            PodTemplateSpec {
+                                metadata: template_metadata,
+                                spec,
+                            },
+                        volume_claim_templates,
+                        ..
+                    },
+            }) => (
+                metadata,
+                *replicas,
+                template_metadata,
+                spec,
+                volume_claim_templates.as_slice(),
+            ),
+            Object::Service(_)
+            | Object::PersistentVolumeClaim(_)
+            | Object::ConfigMap(_)
+            | Object::Secret(_) => continue,
+        };
+        if let Some(name) = metadata
+            .name
+            .as_ref()
+            .and_then(|name| Identifier::new(name.as_str()).ok())
+        {
+            importer.pod(&name, replicas, template_metadata, spec, claim_templates);
+        }
+    }
+
+    importer.compose
+}
+
+/// State for converting Kubernetes [`Object`]s into a [`Compose`] file.
+struct Importer<'a> {
+    /// The [`Compose`] file being created.
+    compose: Compose,
+
+    /// Data of the config maps, by name.
+    config_maps: IndexMap<&'a str, IndexMap<String, String>>,
+
+    /// Decoded data of the secrets, by name.
+    secrets: IndexMap<&'a str, IndexMap<String, String>>,
+
+    /// Specifications of the services, used to publish the ports of pods they select.
+    services: Vec<&'a super::ServiceSpec>,
+}
+
+impl Importer<'_> {
+    /// Convert the containers of a pod into services.
+    ///
+    /// The first container's service is named after the pod, the others are named
+    /// `{pod}-{container}` and share its network with `network_mode: service:{pod}`. Volume claim
+    /// templates of a stateful set are mounted like persistent volume claims of the same name.
+    fn pod(
+        &mut self,
+        name: &Identifier,
+        replicas: Option<u64>,
+        metadata: &ObjectMeta,
+        spec: &PodSpec,
+        claim_templates: &[PersistentVolumeClaim],
+    ) {
+        let PodSpec {
+            containers,
+            volumes,
+            hostname,
+            host_aliases,
+            dns_config,
+            security_context,
+            termination_grace_period_seconds,
+        } = spec;
+        let volumes: Vec<Volume> = volumes
+            .iter()
+            .cloned()
+            .chain(claim_templates.iter().filter_map(|claim| {
+                let name = claim.metadata.name.clone()?;
+                Some(Volume {
+                    name: name.clone(),
+                    source: Some(VolumeSource::PersistentVolumeClaim(
+                        PersistentVolumeClaimVolumeSource {
+                            claim_name: name,
+                            read_only: false,
+                        },
+                    )),
+                })
+            }))
+            .collect();
+
+        for (index, container) in containers.iter().enumerate() {
+            let service_name = if index == 0 {
+                name.clone()
+            } else if let Ok(service_name) = Identifier::new(format!("{name}-{}", container.name)) {
+                service_name
+            } else {
+                continue;
+            };
+
+            let mut service = self.container(name, container, &volumes);
+            if replicas.is_some() {
+                service.deploy.get_or_insert_with(Deploy::default).replicas = replicas;
+            }
+            service.labels = list_or_map(&metadata.labels);
+            service.annotations = list_or_map(&metadata.annotations);
+            service.stop_grace_period = termination_grace_period_seconds.map(Duration::from_secs);
+
+            if index == 0 {
+                // Network settings belong to the container owning the network namespace.
+                service.ports = self.ports(&metadata.labels, containers);
+                service.hostname = hostname
+                    .as_ref()
+                    .and_then(|hostname| Hostname::new(hostname.as_str()).ok());
+                for alias in host_aliases {
+                    let Ok(ip) = alias.ip.parse::<IpAddr>() else {
+                        continue;
+                    };
+                    for hostname in &alias.hostnames {
+                        if let Ok(hostname) = Hostname::new(hostname.as_str()) {
+                            service.extra_hosts.insert(hostname, ip);
+                        }
+                    }
+                }
+                if let Some(dns_config) = dns_config {
+                    dns(&mut service, dns_config);
+                }
+                if let Some(PodSecurityContext { sysctls }) = security_context {
+                    let sysctls: Map = sysctls
+                        .iter()
+                        .filter_map(|sysctl| {
+                            Some((
+                                MapKey::new(sysctl.name.as_str()).ok()?,
+                                Some(Value::String(sysctl.value.clone())),
+                            ))
+                        })
+                        .collect();
+                    if !sysctls.is_empty() {
+                        service.sysctls = ListOrMap::Map(sysctls);
+                    }
+                }
+            } else {
+                service.network_config = Some(NetworkConfig::NetworkMode(NetworkMode::Service(
+                    name.clone(),
+                )));
+            }
+
+            self.compose.services.insert(service_name, service);
+        }
+    }
+
+    /// Convert a [`Container`] of a pod into a [`Service`], without its ports.
+    fn container(
+        &mut self,
+        pod: &Identifier,
+        container: &Container,
+        volumes: &[Volume],
+    ) -> Service {
+        let Container {
+            name: _,
+            image,
+            command,
+            args,
+            working_dir,
+            env,
+            env_from,
+            ports,
+            resources,
+            volume_mounts,
+            liveness_probe,
+            readiness_probe,
+            security_context,
+            stdin,
+            tty,
+        } = container;
+
+        let mut service = Service {
+            image: image
+                .as_ref()
+                .and_then(|image| crate::service::Image::parse(image.as_str()).ok()),
+            entrypoint: (!command.is_empty()).then(|| Command::List(command.clone())),
+            command: (!args.is_empty()).then(|| Command::List(args.clone())),
+            working_dir: working_dir
+                .as_ref()
+                .and_then(|working_dir| AbsolutePath::new(working_dir.as_str()).ok()),
+            healthcheck: liveness_probe
+                .as_ref()
+                .or(readiness_probe.as_ref())
+                .and_then(|probe| healthcheck(probe, ports)),
+            stdin_open: *stdin,
+            tty: *tty,
+            ..Service::default()
+        };
+
+        let environment = self.environment(env_from, env);
+        if !environment.is_empty() {
+            service.environment = ListOrMap::Map(environment);
+        }
+
+        if let Some(resources) = resources.as_ref().and_then(deploy_resources) {
+            service.deploy = Some(Deploy {
+                resources: Some(resources),
+                ..Deploy::default()
+            });
+        }
+
+        if let Some(SecurityContext {
+            privileged,
+            read_only_root_filesystem,
+            run_as_user,
+            run_as_group,
+            capabilities,
+        }) = security_context
+        {
+            service.privileged = *privileged;
+            service.read_only = *read_only_root_filesystem;
+            service.user = run_as_user.map(|user| User {
+                user: IdOrName::Id(user),
+                group: run_as_group.map(IdOrName::Id),
+            });
+            if let Some(capabilities) = capabilities {
+                service.cap_add = capabilities.add.iter().map(Capability::parse).collect();
+                service.cap_drop = capabilities.drop.iter().map(Capability::parse).collect();
+            }
+        }
+
+        for volume_mount in volume_mounts {
+            self.volume_mount(&mut service, pod, volume_mount, volumes);
+        }
+
+        service
+    }
+
+    /// Convert the environment variables of a [`Container`].
+    ///
+    /// Values from [`ConfigMap`](super::ConfigMap)s and [`Secret`](super::Secret)s are inlined if
+    /// the objects were converted. Variables from `env` take precedence over those from
+    /// `envFrom`.
+    fn environment(&self, env_from: &[EnvFromSource], env: &[EnvVar]) -> Map {
+        let mut environment = Map::new();
+        for EnvFromSource {
+            prefix,
+            config_map_ref,
+            secret_ref,
+        } in env_from
+        {
+            let data = config_map_ref
+                .as_ref()
+                .and_then(|reference| self.config_maps.get(reference.name.as_str()))
+                .into_iter()
+                .chain(
+                    secret_ref
+                        .as_ref()
+                        .and_then(|reference| self.secrets.get(reference.name.as_str())),
+                );
+            for (key, value) in data.flatten() {
+                let key = format!("{}{key}", prefix.as_deref().unwrap_or_default());
+                if let Ok(key) = MapKey::new(key) {
+                    environment.insert(key, Some(Value::String(value.clone())));
+                }
+            }
+        }
+
+        for EnvVar {
+            name,
+            value,
+            value_from,
+        } in env
+        {
+            let value = value.clone().or_else(|| {
+                let EnvVarSource {
+                    config_map_key_ref,
+                    secret_key_ref,
+                } = value_from.as_ref()?;
+                config_map_key_ref
+                    .as_ref()
+                    .and_then(|selector| key_value(&self.config_maps, selector))
+                    .or_else(|| {
+                        secret_key_ref
+                            .as_ref()
+                            .and_then(|selector| key_value(&self.secrets, selector))
+                    })
+            });
+            if let (Ok(key), Some(value)) = (MapKey::new(name.as_str()), value) {
+                environment.insert(key, Some(Value::String(value)));
+            }
+        }
+
+        environment
+    }
+
+    /// Convert a [`VolumeMount`] into a volume, config, or tmpfs mount of the `service`.
+    ///
+    /// Persistent volume claims become named volumes, and `emptyDir` volumes become named volumes
+    /// prefixed with the `pod` name so they are shared by its containers, or tmpfs mounts if
+    /// backed by memory. Keys of config maps and secrets become configs with their content, as
+    /// Compose secrets cannot have inline content.
+    fn volume_mount(
+        &mut self,
+        service: &mut Service,
+        pod: &Identifier,
+        volume_mount: &VolumeMount,
+        volumes: &[Volume],
+    ) {
+        let VolumeMount {
+            name,
+            mount_path,
+            sub_path,
+            read_only,
+        } = volume_mount;
+        let Some(source) = volumes
+            .iter()
+            .find(|volume| volume.name == *name)
+            .and_then(|volume| volume.source.as_ref())
+        else {
+            return;
+        };
+
+        let (data, object) = match source {
+            VolumeSource::ConfigMap(config_map) => (
+                self.config_maps.get(config_map.name.as_str()),
+                &config_map.name,
+            ),
+            VolumeSource::Secret(secret) => (
+                self.secrets.get(secret.secret_name.as_str()),
+                &secret.secret_name,
+            ),
+            VolumeSource::PersistentVolumeClaim(_)
+            | VolumeSource::EmptyDir(_)
+            | VolumeSource::HostPath(_) => {
+                if let Ok(target) = AbsolutePath::new(mount_path.as_str()) {
+                    let mut common = Common::new(target);
+                    common.read_only = *read_only;
+                    if let Some(mount) = self.mount(pod, name, source, sub_path.as_deref(), common)
+                    {
+                        service.volumes.insert(
+                            mount
+                                .into_short()
+                                .map_or_else(ShortOrLong::Long, ShortOrLong::Short),
+                        );
+                    }
+                }
+                return;
+            }
+        };
+
+        let Some(data) = data else {
+            return;
+        };
+        let files: Vec<(&String, String)> = sub_path.as_ref().map_or_else(
+            || {
+                data.keys()
+                    .map(|key| (key, format!("{}/{key}", mount_path.trim_end_matches('/'))))
+                    .collect()
+            },
+            |key| vec![(key, mount_path.clone())],
+        );
+        for (key, target) in files {
+            let Some(content) = data.get(key) else {
+                continue;
+            };
+            let source = if object_name(key) == *object {
+                key.clone()
+            } else {
+                format!("{object}-{key}")
+            };
+            let Ok(source) = Identifier::new(source) else {
+                continue;
+            };
+            self.compose.configs.insert(
+                source.clone(),
+                Resource::Compose(Config::from(config::Source::Content(content.clone()))),
+            );
+            service.configs.push(ShortOrLong::Long(ConfigOrSecret {
+                source,
+                target: Some(target.into()),
+                uid: None,
+                gid: None,
+                mode: None,
+                extensions: crate::Extensions::default(),
+            }));
+        }
+    }
+
+    /// Create a [`Mount`] for a persistent volume claim, `emptyDir`, or host path volume.
+    fn mount(
+        &mut self,
+        pod: &Identifier,
+        name: &str,
+        source: &VolumeSource,
+        sub_path: Option<&str>,
+        mut common: Common,
+    ) -> Option<Mount> {
+        let volume = match source {
+            VolumeSource::PersistentVolumeClaim(claim) => {
+                common.read_only |= claim.read_only;
+                Identifier::new(claim.claim_name.as_str()).ok()?
+            }
+            VolumeSource::EmptyDir(empty_dir) if empty_dir.medium.as_deref() == Some("Memory") => {
+                let size = empty_dir.size_limit.as_deref().and_then(byte_value);
+                return Some(Mount::Tmpfs(Tmpfs {
+                    tmpfs: size.map(|size| TmpfsOptions {
+                        size: Some(size),
+                        ..TmpfsOptions::default()
+                    }),
+                    common,
+                }));
+            }
+            VolumeSource::EmptyDir(_) => Identifier::new(format!("{pod}-{name}")).ok()?,
+            VolumeSource::HostPath(host_path) => {
+                let path = std::path::Path::new(&host_path.path);
+                let path = sub_path.map_or_else(|| path.to_owned(), |sub_path| path.join(sub_path));
+                return Some(Mount::Bind(Bind::new(HostPath::new(path).ok()?, common)));
+            }
+            VolumeSource::ConfigMap(_) | VolumeSource::Secret(_) => return None,
+        };
+
+        self.compose.volumes.entry(volume.clone()).or_insert(None);
+        Some(Mount::Volume(mount::Volume {
+            source: Some(volume),
+            volume: sub_path.map(|sub_path| VolumeOptions {
+                subpath: Some(sub_path.into()),
+                ..VolumeOptions::default()
+            }),
+            common,
+        }))
+    }
+
+    /// Ports of a pod with the given `labels`.
+    ///
+    /// Container ports are published to their host port, or to the port of a
+    /// [`Service`](super::Service) which selects the pod.
+    fn ports(
+        &self,
+        labels: &IndexMap<String, String>,
+        containers: &[Container],
+    ) -> crate::service::Ports {
+        let container_ports = containers.iter().flat_map(|container| &container.ports);
+
+        let mut ports: Vec<Port> = Vec::new();
+        for ContainerPort {
+            name: _,
+            container_port,
+            host_port,
+            protocol: port_protocol,
+        } in container_ports.clone()
+        {
+            let port = Port {
+                published: host_port.map(Into::into),
+                protocol: protocol(port_protocol),
+                ..Port::new(*container_port)
+            };
+            if !ports.contains(&port) {
+                ports.push(port);
+            }
+        }
+
+        let service_ports = self
+            .services
+            .iter()
+            .filter(|service| {
+                !service.selector.is_empty()
+                    && service
+                        .selector
+                        .iter()
+                        .all(|(key, value)| labels.get(key) == Some(value))
+            })
+            .flat_map(|service| &service.ports);
+        for ServicePort {
+            name: _,
+            port: published,
+            target_port,
+            protocol: port_protocol,
+        } in service_ports
+        {
+            let target = match target_port {
+                None => Some(*published),
+                Some(TargetPort::Number(target)) => Some(*target),
+                Some(TargetPort::Name(name)) => container_ports
+                    .clone()
+                    .find(|port| port.name.as_ref() == Some(name))
+                    .map(|port| port.container_port),
+            };
+            let Some(target) = target else {
+                continue;
+            };
+            let protocol = protocol(port_protocol);
+            let published = Some((*published).into());
+            if let Some(port) = ports.iter_mut().find(|port| {
+                port.target == target && port.protocol == protocol && port.published.is_none()
+            }) {
+                port.published = published;
+            } else {
+                let port = Port {
+                    published,
+                    protocol,
+                    ..Port::new(target)
+                };
+                if !ports.contains(&port) {
+                    ports.push(port);
+                }
+            }
+        }
+
+        ports
+            .into_iter()
+            .map(|port| {
+                port.into_short()
+                    .map_or_else(ShortOrLong::Long, ShortOrLong::Short)
+            })
+            .collect()
+    }
+}
+
+/// Convert a Kubernetes port protocol into a [`Protocol`], [`None`] for the default TCP.
+fn protocol(protocol: &str) -> Option<Protocol> {
+    let protocol = Protocol::parse(protocol.to_ascii_lowercase());
+    (!protocol.is_tcp()).then_some(protocol)
+}
+
+/// Value of a key selected from config map or secret `data`.
+fn key_value(
+    data: &IndexMap<&str, IndexMap<String, String>>,
+    KeySelector { name, key }: &KeySelector,
+) -> Option<String> {
+    data.get(name.as_str())?.get(key).cloned()
+}
+
+/// Convert a map of labels or annotations into a [`ListOrMap`], empty if `map` is.
+fn list_or_map(map: &IndexMap<String, String>) -> ListOrMap {
+    if map.is_empty() {
+        return ListOrMap::default();
+    }
+    ListOrMap::Map(
+        map.iter()
+            .filter_map(|(key, value)| {
+                Some((
+                    MapKey::new(key.as_str()).ok()?,
+                    Some(Value::String(value.clone())),
+                ))
+            })
+            .collect(),
+    )
+}
+
+/// Convert a [`PodDnsConfig`] into the DNS fields of a `service`.
+fn dns(service: &mut Service, dns_config: &PodDnsConfig) {
+    let PodDnsConfig {
+        nameservers,
+        searches,
+        options,
+    } = dns_config;
+
+    service.dns = item_or_list(
+        nameservers
+            .iter()
+            .filter_map(|nameserver| nameserver.parse().ok())
+            .collect(),
+    );
+    service.dns_search = item_or_list(
+        searches
+            .iter()
+            .filter_map(|search| Hostname::new(search.as_str()).ok())
+            .collect(),
+    );
+    service.dns_opt = options
+        .iter()
+        .map(|PodDnsConfigOption { name, value }| {
+            value
+                .as_ref()
+                .map_or_else(|| name.clone(), |value| format!("{name}:{value}"))
+        })
+        .collect::<IndexSet<_>>();
+}
+
+/// Convert a [`Probe`] into a [`Healthcheck`].
+///
+/// HTTP and TCP probes become shell commands using `curl` and `nc`. Returns [`None`] if the probe
+/// has no action, or if its port is the name of a port the container does not have.
+fn healthcheck(probe: &Probe, ports: &[ContainerPort]) -> Option<Healthcheck> {
+    let Probe {
+        exec,
+        http_get,
+        tcp_socket,
+        initial_delay_seconds,
+        period_seconds,
+        timeout_seconds,
+        failure_threshold,
+    } = probe;
+
+    let test = if let Some(exec) = exec {
+        match exec.command.as_slice() {
+            [shell, flag, command] if shell == "/bin/sh" && flag == "-c" => {
+                Test::ShellCommand(command.clone())
+            }
+            command => Test::Command(command.to_vec()),
+        }
+    } else if let Some(HttpGetAction {
+        path,
+        port,
+        host,
+        scheme,
+    }) = http_get
+    {
+        let port = probe_port(port, ports)?;
+        let host = host.as_deref().unwrap_or("localhost");
+        let path = path.as_deref().unwrap_or("/");
+        // Kubernetes does not verify the certificates of HTTPS probes.
+        if scheme
+            .as_deref()
+            .is_some_and(|scheme| scheme.eq_ignore_ascii_case("https"))
+        {
+            let url = format!("https://{host}:{port}{path}");
+            Test::ShellCommand(format!("curl -fk {}", shell::quote(&url)))
+        } else {
+            let url = format!("http://{host}:{port}{path}");
+            Test::ShellCommand(format!("curl -f {}", shell::quote(&url)))
+        }
+    } else {
+        let TcpSocketAction { port, host } = tcp_socket.as_ref()?;
+        let port = probe_port(port, ports)?;
+        let host = host.as_deref().unwrap_or("localhost");
+        Test::ShellCommand(format!("nc -z {} {port}", shell::quote(host)))
+    };
+
+    Some(Healthcheck::Command(healthcheck::Command {
+        test: Some(test),
+        interval: period_seconds.map(Duration::from_secs),
+        timeout: timeout_seconds.map(Duration::from_secs),
+        retries: *failure_threshold,
+        start_period: initial_delay_seconds.map(Duration::from_secs),
+        ..healthcheck::Command::default()
+    }))
+}
+
+/// Resolve the port of an HTTP or TCP [`Probe`], which may be the name of a [`ContainerPort`].
+fn probe_port(port: &TargetPort, ports: &[ContainerPort]) -> Option<u16> {
+    match port {
+        TargetPort::Number(port) => Some(*port),
+        TargetPort::Name(name) => ports
+            .iter()
+            .find(|port| port.name.as_ref() == Some(name))
+            .map(|port| port.container_port),
+    }
+}
+
+/// Convert [`ResourceRequirements`] into [`Resources`].
+///
+/// Returns [`None`] if no CPU or memory limits or requests are set.
+fn deploy_resources(resources: &ResourceRequirements) -> Option<Resources> {
+    let ResourceRequirements { limits, requests } = resources;
+    let cpus =
+        |resources: &IndexMap<String, String>| resources.get("cpu").and_then(|cpu| cpus(cpu));
+    let memory = |resources: &IndexMap<String, String>| {
+        resources
+            .get("memory")
+            .and_then(|memory| byte_value(memory))
+    };
+
+    let limits = Limits {
+        cpus: cpus(limits),
+        memory: memory(limits),
+        ..Limits::default()
+    };
+    let reservations = Reservations {
+        cpus: cpus(requests),
+        memory: memory(requests),
+        ..Reservations::default()
+    };
+    let limits = (limits.cpus.is_some() || limits.memory.is_some()).then_some(limits);
+    let reservations =
+        (reservations.cpus.is_some() || reservations.memory.is_some()).then_some(reservations);
+
+    (limits.is_some() || reservations.is_some()).then(|| Resources {
+        limits,
+        reservations,
+        ..Resources::default()
+    })
+}
+
+/// Convert a Kubernetes CPU quantity, e.g. `500m` or `2`, into [`Cpus`].
+fn cpus(quantity: &str) -> Option<Cpus> {
+    let cpus = match quantity.strip_suffix('m') {
+        Some(millicpus) => millicpus.parse::<f64>().ok()? / 1000.0_f64,
+        None => quantity.parse().ok()?,
+    };
+    Cpus::new(cpus).ok()
+}
+
+/// Convert a Kubernetes memory quantity, e.g. `512Mi` or `1G`, into a [`ByteValue`].
+///
+/// Only whole numbers are supported. The value uses the largest unit it is a multiple of.
+fn byte_value(quantity: &str) -> Option<ByteValue> {
+    /// Suffixes of quantities and their multipliers.
+    const SUFFIXES: [(&str, u64); 12] = [
+        ("Ki", 1 << 10),
+        ("Mi", 1 << 20),
+        ("Gi", 1 << 30),
+        ("Ti", 1 << 40),
+        ("Pi", 1 << 50),
+        ("Ei", 1 << 60),
+        ("k", 1_000),
+        ("M", 1_000_000),
+        ("G", 1_000_000_000),
+        ("T", 1_000_000_000_000),
+        ("P", 1_000_000_000_000_000),
+        ("E", 1_000_000_000_000_000_000),
+    ];
+
+    let (number, multiplier) = SUFFIXES
+        .iter()
+        .find_map(|(suffix, multiplier)| {
+            quantity
+                .strip_suffix(suffix)
+                .map(|number| (number, *multiplier))
+        })
+        .unwrap_or((quantity, 1));
+    let bytes = number.parse::<u64>().ok()?.checked_mul(multiplier)?;
+
+    // divisions are exact, as checked by the remainders
+    #[allow(clippy::integer_division)]
+    let byte_value = if bytes % 1_000_000_000 == 0 {
+        ByteValue::Gigabytes(bytes / 1_000_000_000)
+    } else if bytes % 1_000_000 == 0 {
+        ByteValue::Megabytes(bytes / 1_000_000)
+    } else if bytes % 1_000 == 0 {
+        ByteValue::Kilobytes(bytes / 1_000)
+    } else {
+        ByteValue::Bytes(bytes)
+    };
+    Some(byte_value)
+}
+
+/// Decode a base64 encoded UTF-8 string, as used for the data of secrets.
+///
+/// Returns [`None`] if the string is not valid base64 or does not decode into UTF-8.
+fn decode_base64(encoded: &str) -> Option<String> {
+    let mut bytes = Vec::new();
+    let mut buffer: u32 = 0;
+    let mut bits: u32 = 0;
+    let mut chars = encoded.bytes().filter(|char| !char.is_ascii_whitespace());
+    for char in chars.by_ref() {
+        let value = match char {
+            b'A'..=b'Z' => char - b'A',
+            b'a'..=b'z' => char - b'a' + 26,
+            b'0'..=b'9' => char - b'0' + 52,
+            b'+' => 62,
+            b'/' => 63,
+            b'=' => break,
+            _ => return None,
+        };
+        buffer = (buffer << 6_u32) | u32::from(value);
+        bits += 6;
+        if bits >= 8 {
+            bits -= 8;
+            bytes.push(u8::try_from((buffer >> bits) & 0xFF).ok()?);
+            buffer &= (1 << bits) - 1;
+        }
+    }
+    // Only padding may follow padding, and a single trailing character is not a whole byte.
+    if bits >= 6 || chars.any(|char| char != b'=') {
+        return None;
+    }
+    String::from_utf8(bytes).ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn base64_padding() {
+        assert_eq!(decode_base64("aHVudGVyMg==").unwrap(), "hunter2");
+        assert_eq!(decode_base64("aHVudGVyMg").unwrap(), "hunter2");
+        assert_eq!(decode_base64("aHVu\ndGVy").unwrap(), "hunter");
+        assert_eq!(decode_base64("aHVudGVyMjE=").unwrap(), "hunter21");
+        assert_eq!(decode_base64("").unwrap(), "");
+    }
+
+    #[test]
+    fn base64_invalid() {
+        assert_eq!(decode_base64("aHVu-GVy"), None);
+        assert_eq!(decode_base64("aHVudGVyM"), None);
+        assert_eq!(decode_base64("aHVu=GVy"), None);
+        // `/w==` decodes into `0xFF`, which is not valid UTF-8.
+        assert_eq!(decode_base64("/w=="), None);
+    }
+
+    #[test]
+    fn probes() {
+        let ports = [ContainerPort {
+            name: Some("http".to_owned()),
+            container_port: 8080,
+            host_port: None,
+            protocol: "TCP".to_owned(),
+        }];
+        let test = |probe: &str| {
+            let probe: Probe = serde_yaml::from_str(probe).unwrap();
+            match healthcheck(&probe, &ports) {
+                Some(Healthcheck::Command(command)) => command.test,
+                _ => None,
+            }
+        };
+
+        assert_eq!(
+            test("httpGet: {path: /health, port: http}"),
+            Some(Test::ShellCommand(
+                "curl -f http://localhost:8080/health".to_owned()
+            )),
+        );
+        assert_eq!(
+            test("httpGet: {port: 8443, host: example.com, scheme: HTTPS}"),
+            Some(Test::ShellCommand(
+                "curl -fk https://example.com:8443/".to_owned()
+            )),
+        );
+        assert_eq!(
+            test("tcpSocket: {port: 5432}"),
+            Some(Test::ShellCommand("nc -z localhost 5432".to_owned())),
+        );
+        assert_eq!(test("tcpSocket: {port: missing}"), None);
+    }
+}
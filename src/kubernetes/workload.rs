@@ -0,0 +1,1482 @@
+//! Conversion of a [`Service`](crate::Service) into a [`Workload`] and [`Service`].
+
+use std::iter;
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+
+use crate::{
+    duration,
+    engine::Project,
+    service::{
+        deploy::resources::{Cpus, Limits, Reservations, Resources},
+        healthcheck::{self, Test},
+        ports::{self, Protocol},
+        user::IdOrName,
+        volumes::{
+            self,
+            mount::{self, Tmpfs, TmpfsOptions, VolumeOptions},
+            Mount,
+        },
+        ByteValue, Command, ConfigOrSecret, Deploy, Healthcheck, NetworkConfig, Restart,
+        TmpfsOption, User,
+    },
+    Identifier, Resource, ShortOrLong,
+};
+
+use super::{
+    claim_name, default_protocol, is_shared, object_name, persistent_volume_claim, quantities,
+    quantity, string_map, ManifestsError, Object, ObjectMeta, PersistentVolumeClaim, ResourceKind,
+    Service, ServicePort, ServiceSpec, TargetPort, Unsupported, NAME_LABEL, PART_OF_LABEL,
+};
+
+/// Convert a [`Service`](crate::Service) into a [`Deployment`](Object::Deployment), or a
+/// [`StatefulSet`](Object::StatefulSet) if it is the only service mounting a volume managed by
+/// Compose, and a [`Service`] if it has ports or is a stateful set.
+#[allow(clippy::too_many_lines)]
+pub(super) fn convert(
+    project: &Project,
+    name: &Identifier,
+    service: &crate::Service,
+    unsupported_fields: &mut Vec<Unsupported>,
+) -> Result<Vec<Object>, ManifestsError> {
+    let crate::Service {
+        attach: _,
+        build,
+        blkio_config,
+        cpu_count,
+        cpu_percent,
+        cpu_shares,
+        cpu_period,
+        cpu_quota,
+        cpu_rt_runtime,
+        cpu_rt_period,
+        cpus,
+        cpuset,
+        cap_add,
+        cap_drop,
+        cgroup,
+        cgroup_parent,
+        command,
+        configs,
+        container_name,
+        credential_spec,
+        depends_on,
+        deploy,
+        develop,
+        device_cgroup_rules,
+        devices,
+        dns,
+        dns_opt,
+        dns_search,
+        domain_name,
+        entrypoint,
+        env_file,
+        environment,
+        expose,
+        extends,
+        annotations,
+        external_links,
+        extra_hosts,
+        group_add,
+        healthcheck,
+        hostname,
+        image,
+        init,
+        ipc,
+        uts,
+        isolation,
+        labels,
+        label_file,
+        links,
+        logging,
+        network_config,
+        mac_address,
+        mem_limit,
+        mem_reservation,
+        mem_swappiness,
+        memswap_limit,
+        oom_kill_disable,
+        oom_score_adj,
+        pid,
+        pids_limit,
+        platform: _,
+        ports,
+        privileged,
+        profiles: _,
+        pull_policy: _,
+        read_only,
+        restart,
+        runtime,
+        scale,
+        secrets,
+        security_opt,
+        shm_size,
+        stdin_open,
+        stop_grace_period,
+        stop_signal,
+        storage_opt,
+        sysctls,
+        tmpfs,
+        tty,
+        ulimits,
+        user,
+        userns_mode,
+        volumes,
+        volumes_from,
+        working_dir,
+        extensions: _,
+    } = service;
+
+    let mut converter = Converter {
+        project,
+        name,
+        unsupported_fields,
+        volumes: Vec::new(),
+        volume_mounts: Vec::new(),
+        volume_claim_templates: Vec::new(),
+    };
+
+    for (field, unsupported) in [
+        ("blkio_config", blkio_config.is_some()),
+        ("cpu_count", cpu_count.is_some()),
+        ("cpu_percent", cpu_percent.is_some()),
+        ("cpu_shares", cpu_shares.is_some()),
+        ("cpu_period", cpu_period.is_some()),
+        ("cpu_quota", cpu_quota.is_some()),
+        ("cpu_rt_runtime", cpu_rt_runtime.is_some()),
+        ("cpu_rt_period", cpu_rt_period.is_some()),
+        ("cpuset", !cpuset.is_empty()),
+        ("cgroup", cgroup.is_some()),
+        ("cgroup_parent", cgroup_parent.is_some()),
+        ("container_name", container_name.is_some()),
+        ("credential_spec", credential_spec.is_some()),
+        (
+            "depends_on",
+            !crate::service::depends_on_is_empty(depends_on),
+        ),
+        ("develop", develop.is_some()),
+        ("device_cgroup_rules", !device_cgroup_rules.is_empty()),
+        ("devices", !devices.is_empty()),
+        ("domain_name", domain_name.is_some()),
+        ("env_file", env_file.is_some()),
+        ("extends", extends.is_some()),
+        ("external_links", !external_links.is_empty()),
+        ("group_add", !group_add.is_empty()),
+        ("init", *init),
+        ("ipc", ipc.is_some()),
+        ("uts", uts.is_some()),
+        ("isolation", isolation.is_some()),
+        ("label_file", label_file.is_some()),
+        ("links", !links.is_empty()),
+        ("logging", logging.is_some()),
+        ("mac_address", mac_address.is_some()),
+        ("mem_swappiness", mem_swappiness.is_some()),
+        ("memswap_limit", memswap_limit.is_some()),
+        ("oom_kill_disable", *oom_kill_disable),
+        ("oom_score_adj", oom_score_adj.is_some()),
+        ("pid", pid.is_some()),
+        ("pids_limit", pids_limit.is_some()),
+        ("runtime", runtime.is_some()),
+        ("security_opt", !security_opt.is_empty()),
+        ("shm_size", shm_size.is_some()),
+        ("stop_signal", stop_signal.is_some()),
+        ("storage_opt", !storage_opt.is_empty()),
+        ("ulimits", !ulimits.is_empty()),
+        ("userns_mode", userns_mode.is_some()),
+        ("volumes_from", !volumes_from.is_empty()),
+    ] {
+        if unsupported {
+            converter.unsupported(field);
+        }
+    }
+    if let Some(NetworkConfig::NetworkMode(_)) = network_config {
+        converter.unsupported("network_mode");
+    }
+    if matches!(restart, Some(Restart::No | Restart::OnFailure { .. })) {
+        // Pods of deployments and stateful sets are always restarted.
+        converter.unsupported("restart");
+    }
+
+    let mut image = image.as_ref().map(ToString::to_string);
+    if build.is_some() {
+        converter.unsupported("build");
+        image = image.or_else(|| Some(format!("{}-{name}", project.name)));
+    }
+
+    let mut env = Vec::new();
+    for item in environment.clone().into_list() {
+        if let Some((name, value)) = item.split_once('=') {
+            env.push(EnvVar {
+                name: name.to_owned(),
+                value: Some(value.to_owned()),
+                value_from: None,
+            });
+        } else {
+            converter.unsupported(format!("environment.{item}"));
+        }
+    }
+
+    let mut container_ports = Vec::new();
+    let mut service_ports = Vec::new();
+    for port in ports::into_long_iter(ports.clone()) {
+        if port.host_ip.is_some() {
+            converter.unsupported("ports.host_ip");
+        }
+        let protocol = protocol(port.protocol.as_ref());
+        let published = port
+            .published
+            .map_or(port.target, |published| published.start());
+        push_port(
+            &mut container_ports,
+            &mut service_ports,
+            published,
+            port.target,
+            protocol,
+        );
+    }
+    for expose in expose {
+        let protocol = protocol(expose.protocol.as_ref());
+        for port in expose.range {
+            push_port(
+                &mut container_ports,
+                &mut service_ports,
+                port,
+                port,
+                protocol.clone(),
+            );
+        }
+    }
+
+    for mount in volumes::into_long_iter(volumes.clone()) {
+        converter.mount(mount);
+    }
+    for entry in tmpfs.iter().flat_map(|tmpfs| tmpfs.clone().into_list()) {
+        let mut size = None;
+        for option in &entry.options {
+            if let TmpfsOption::Size(option) = option {
+                size = Some(*option);
+            } else {
+                converter.unsupported(format!("tmpfs.{option}"));
+            }
+        }
+        converter.empty_dir(
+            Some("Memory"),
+            size,
+            entry.path.as_path().display().to_string(),
+            false,
+        );
+    }
+    for config in configs {
+        converter.config_or_secret(ResourceKind::Config, config);
+    }
+    for secret in secrets {
+        converter.config_or_secret(ResourceKind::Secret, secret);
+    }
+
+    let (replicas, resources, workload_annotations) = converter.deploy(deploy.as_ref());
+    let replicas = replicas.or(*scale);
+    let resources = ResourceRequirements::new(
+        cpus.or(resources.limits_cpus),
+        mem_limit.or(resources.limits_memory),
+        resources.reservations_cpus,
+        mem_reservation.or(resources.reservations_memory),
+    );
+
+    let security_context = SecurityContext::new(*privileged, *read_only, cap_add, cap_drop);
+    let security_context = match user {
+        Some(User {
+            user: IdOrName::Id(user),
+            group,
+        }) => {
+            let group = match group {
+                Some(IdOrName::Id(group)) => Some(*group),
+                Some(IdOrName::Name(_)) => {
+                    converter.unsupported("user");
+                    None
+                }
+                None => None,
+            };
+            Some(SecurityContext {
+                run_as_user: Some(*user),
+                run_as_group: group,
+                ..security_context.unwrap_or_default()
+            })
+        }
+        Some(User {
+            user: IdOrName::Name(_),
+            ..
+        }) => {
+            converter.unsupported("user");
+            security_context
+        }
+        None => security_context,
+    };
+
+    let probe = healthcheck
+        .as_ref()
+        .and_then(|healthcheck| converter.probe(healthcheck));
+
+    let container = Container {
+        name: object_name(name.as_str()),
+        image,
+        command: entrypoint
+            .as_ref()
+            .map(|entrypoint| split_command(name, "entrypoint", entrypoint))
+            .transpose()?
+            .unwrap_or_default(),
+        args: command
+            .as_ref()
+            .map(|command| split_command(name, "command", command))
+            .transpose()?
+            .unwrap_or_default(),
+        working_dir: working_dir
+            .as_ref()
+            .map(|working_dir| working_dir.as_path().display().to_string()),
+        env,
+        env_from: Vec::new(),
+        ports: container_ports,
+        resources,
+        volume_mounts: converter.volume_mounts,
+        liveness_probe: probe.clone(),
+        readiness_probe: probe,
+        security_context,
+        stdin: *stdin_open,
+        tty: *tty,
+    };
+
+    let mut host_aliases: Vec<HostAlias> = Vec::new();
+    for (hostname, ip) in extra_hosts {
+        let ip = ip.to_string();
+        let hostname = hostname.as_str().to_owned();
+        if let Some(alias) = host_aliases.iter_mut().find(|alias| alias.ip == ip) {
+            alias.hostnames.push(hostname);
+        } else {
+            host_aliases.push(HostAlias {
+                ip,
+                hostnames: vec![hostname],
+            });
+        }
+    }
+
+    let dns_config = PodDnsConfig {
+        nameservers: dns
+            .iter()
+            .flat_map(|dns| dns.clone().into_list())
+            .map(|dns| dns.to_string())
+            .collect(),
+        searches: dns_search
+            .iter()
+            .flat_map(|dns_search| dns_search.clone().into_list())
+            .map(|dns_search| dns_search.as_str().to_owned())
+            .collect(),
+        options: dns_opt
+            .iter()
+            .map(|option| match option.split_once(':') {
+                Some((name, value)) => PodDnsConfigOption {
+                    name: name.to_owned(),
+                    value: Some(value.to_owned()),
+                },
+                None => PodDnsConfigOption {
+                    name: option.clone(),
+                    value: None,
+                },
+            })
+            .collect(),
+    };
+
+    let sysctls: Vec<Sysctl> = string_map(sysctls)
+        .into_iter()
+        .map(|(name, value)| Sysctl { name, value })
+        .collect();
+
+    let object_name = object_name(name.as_str());
+    let selector: IndexMap<String, String> =
+        iter::once((NAME_LABEL.to_owned(), object_name.clone())).collect();
+    let object_labels: IndexMap<String, String> = [
+        (PART_OF_LABEL.to_owned(), project.name.to_string()),
+        (NAME_LABEL.to_owned(), object_name.clone()),
+    ]
+    .into_iter()
+    .collect();
+
+    let mut pod_annotations = string_map(labels);
+    pod_annotations.extend(string_map(annotations));
+
+    let stateful = !converter.volume_claim_templates.is_empty();
+    let workload = Workload {
+        metadata: ObjectMeta {
+            name: Some(object_name.clone()),
+            labels: object_labels.clone(),
+            annotations: workload_annotations,
+        },
+        spec: WorkloadSpec {
+            replicas,
+            service_name: stateful.then(|| object_name.clone()),
+            selector: LabelSelector {
+                match_labels: selector.clone(),
+            },
+            template: PodTemplateSpec {
+                metadata: ObjectMeta {
+                    name: None,
+                    labels: object_labels.clone(),
+                    annotations: pod_annotations,
+                },
+                spec: PodSpec {
+                    containers: vec![container],
+                    volumes: converter.volumes,
+                    hostname: hostname
+                        .as_ref()
+                        .map(|hostname| hostname.as_str().to_owned()),
+                    host_aliases,
+                    dns_config: (!dns_config.is_empty()).then_some(dns_config),
+                    security_context: (!sysctls.is_empty())
+                        .then_some(PodSecurityContext { sysctls }),
+                    termination_grace_period_seconds: stop_grace_period.map(duration::seconds),
+                },
+            },
+            volume_claim_templates: converter.volume_claim_templates,
+        },
+    };
+
+    let mut objects = Vec::with_capacity(2);
+    if stateful || !service_ports.is_empty() {
+        objects.push(Object::Service(Service {
+            metadata: ObjectMeta {
+                name: Some(object_name),
+                labels: object_labels,
+                annotations: IndexMap::new(),
+            },
+            spec: ServiceSpec {
+                // Stateful sets require a service, which is headless if there are no ports.
+                cluster_ip: service_ports.is_empty().then(|| "None".to_owned()),
+                selector,
+                ports: service_ports,
+            },
+        }));
+    }
+    objects.push(if stateful {
+        Object::StatefulSet(workload)
+    } else {
+        Object::Deployment(workload)
+    });
+
+    Ok(objects)
+}
+
+/// State for converting a [`Service`](crate::Service) into a [`Workload`].
+struct Converter<'a> {
+    /// The project the service is from, used to resolve volumes, configs, and secrets.
+    project: &'a Project<'a>,
+
+    /// Name of the service.
+    name: &'a Identifier,
+
+    /// Fields which could not be converted.
+    unsupported_fields: &'a mut Vec<Unsupported>,
+
+    /// Volumes of the pod.
+    volumes: Vec<Volume>,
+
+    /// Volume mounts of the container.
+    volume_mounts: Vec<VolumeMount>,
+
+    /// Claims created for each pod of a stateful set, one for each volume managed by Compose.
+    volume_claim_templates: Vec<PersistentVolumeClaim>,
+}
+
+impl Converter<'_> {
+    /// Add an [`Unsupported`] field.
+    fn unsupported<T: Into<String>>(&mut self, field: T) {
+        self.unsupported_fields.push(Unsupported {
+            kind: ResourceKind::Service,
+            name: self.name.clone(),
+            field: field.into(),
+        });
+    }
+
+    /// Add a pod [`Volume`] if one with the same name does not already exist.
+    fn push_volume(&mut self, name: &str, source: VolumeSource) {
+        if !self.volumes.iter().any(|volume| volume.name == name) {
+            self.volumes.push(Volume {
+                name: name.to_owned(),
+                source: Some(source),
+            });
+        }
+    }
+
+    /// Add a pod [`Volume`] for the [`PersistentVolumeClaim`] of a top-level volume, returning the
+    /// name of the pod volume.
+    fn push_claim_volume(&mut self, volume: &Identifier, claim_name: String) -> String {
+        let name = object_name(volume.as_str());
+        self.push_volume(
+            &name,
+            VolumeSource::PersistentVolumeClaim(PersistentVolumeClaimVolumeSource {
+                claim_name,
+                read_only: false,
+            }),
+        );
+        name
+    }
+
+    /// Convert a volume [`Mount`] into a [`VolumeMount`].
+    ///
+    /// Named volumes managed by Compose which only this service uses become
+    /// [`PersistentVolumeClaim`] templates, so each pod of the stateful set gets its own claim.
+    /// Volumes shared with other services are mounted from the standalone claim created by
+    /// [`Manifests::from_project()`](super::Manifests::from_project()), and are unsupported if
+    /// not defined in the top-level `volumes` field. External volumes are existing claims mounted
+    /// as pod volumes. Anonymous volumes and tmpfs mounts become `emptyDir` volumes.
+    fn mount(&mut self, mount: Mount) {
+        match mount {
+            Mount::Volume(mount::Volume {
+                source: Some(source),
+                volume,
+                common,
+            }) => {
+                let sub_path = volume.and_then(
+                    |VolumeOptions {
+                         nocopy, subpath, ..
+                     }| {
+                        if nocopy {
+                            self.unsupported("volumes.volume.nocopy");
+                        }
+                        subpath.map(|subpath| subpath.display().to_string())
+                    },
+                );
+                let compose = self.project.compose;
+                let claim_name = claim_name(compose, &source);
+                let name = match compose.volumes.get(&source) {
+                    // Existing claims and claims shared by multiple services are pod volumes.
+                    Some(Some(Resource::External { .. })) => {
+                        self.push_claim_volume(&source, claim_name)
+                    }
+                    Some(_) if is_shared(compose, &source) => {
+                        self.push_claim_volume(&source, claim_name)
+                    }
+                    None if is_shared(compose, &source) => {
+                        self.unsupported(format!("volumes.{source}"));
+                        return;
+                    }
+                    _ => {
+                        if !self
+                            .volume_claim_templates
+                            .iter()
+                            .any(|claim| claim.metadata.name.as_ref() == Some(&claim_name))
+                        {
+                            self.volume_claim_templates.push(persistent_volume_claim(
+                                self.project,
+                                &source,
+                                false,
+                            ));
+                        }
+                        claim_name
+                    }
+                };
+                self.volume_mounts.push(VolumeMount {
+                    name,
+                    mount_path: common.target.as_path().display().to_string(),
+                    sub_path,
+                    read_only: common.read_only,
+                });
+            }
+            Mount::Volume(mount::Volume {
+                source: None,
+                common,
+                ..
+            }) => {
+                self.empty_dir(
+                    None,
+                    None,
+                    common.target.as_path().display().to_string(),
+                    common.read_only,
+                );
+            }
+            Mount::Tmpfs(Tmpfs { tmpfs, common }) => {
+                let size = tmpfs.and_then(|TmpfsOptions { size, mode, .. }| {
+                    if mode.is_some() {
+                        self.unsupported("volumes.tmpfs.mode");
+                    }
+                    size
+                });
+                self.empty_dir(
+                    Some("Memory"),
+                    size,
+                    common.target.as_path().display().to_string(),
+                    common.read_only,
+                );
+            }
+            mount => self.unsupported(format!("volumes.{}", mount.kind())),
+        }
+    }
+
+    /// Add an `emptyDir` volume mounted at `mount_path`.
+    fn empty_dir(
+        &mut self,
+        medium: Option<&str>,
+        size: Option<ByteValue>,
+        mount_path: String,
+        read_only: bool,
+    ) {
+        let empty_dirs = self
+            .volumes
+            .iter()
+            .filter(|volume| matches!(volume.source, Some(VolumeSource::EmptyDir(_))))
+            .count();
+        let name = format!("empty-dir-{}", empty_dirs + 1);
+        self.push_volume(
+            &name,
+            VolumeSource::EmptyDir(EmptyDirVolumeSource {
+                medium: medium.map(ToOwned::to_owned),
+                size_limit: size.map(quantity),
+            }),
+        );
+        self.volume_mounts.push(VolumeMount {
+            name,
+            mount_path,
+            sub_path: None,
+            read_only,
+        });
+    }
+
+    /// Mount a config or secret into the container.
+    ///
+    /// Configs are mounted at `/{source}` and secrets at `/run/secrets/{source}` by default.
+    fn config_or_secret(
+        &mut self,
+        kind: ResourceKind,
+        config_or_secret: &ShortOrLong<Identifier, ConfigOrSecret>,
+    ) {
+        let (source, target) = match config_or_secret {
+            ShortOrLong::Short(source) => (source, None),
+            ShortOrLong::Long(ConfigOrSecret {
+                source,
+                target,
+                uid,
+                gid,
+                mode,
+                extensions: _,
+            }) => {
+                if uid.is_some() || gid.is_some() || mode.is_some() {
+                    self.unsupported(format!("{kind}s.{source}"));
+                }
+                (source, target.as_ref())
+            }
+        };
+
+        let compose = self.project.compose;
+        let (directory, object, volume_source) = if kind == ResourceKind::Secret {
+            let object = match compose.secrets.get(source) {
+                Some(Resource::External { name: Some(name) }) => name.clone(),
+                _ => object_name(source.as_str()),
+            };
+            let volume_source = VolumeSource::Secret(SecretVolumeSource {
+                secret_name: object.clone(),
+            });
+            ("/run/secrets", object, volume_source)
+        } else {
+            let object = match compose.configs.get(source) {
+                Some(Resource::External { name: Some(name) }) => name.clone(),
+                _ => object_name(source.as_str()),
+            };
+            let volume_source = VolumeSource::ConfigMap(ConfigMapVolumeSource {
+                name: object.clone(),
+            });
+            ("/", object, volume_source)
+        };
+
+        let name = format!("{kind}-{object}");
+        self.push_volume(&name, volume_source);
+        let target = std::path::Path::new(directory)
+            .join(target.map_or_else(|| std::path::Path::new(source.as_str()), AsRef::as_ref));
+        self.volume_mounts.push(VolumeMount {
+            name,
+            mount_path: target.display().to_string(),
+            sub_path: Some(source.to_string()),
+            read_only: true,
+        });
+    }
+
+    /// Convert [`Deploy`] into replicas, resources, and annotations of the workload.
+    fn deploy(
+        &mut self,
+        deploy: Option<&Deploy>,
+    ) -> (Option<u64>, DeployResources, IndexMap<String, String>) {
+        let Some(Deploy {
+            endpoint_mode,
+            labels,
+            mode,
+            placement,
+            replicas,
+            resources,
+            restart_policy,
+            rollback_config,
+            update_config,
+            extensions: _,
+        }) = deploy
+        else {
+            return (None, DeployResources::default(), IndexMap::new());
+        };
+
+        for (field, unsupported) in [
+            ("deploy.endpoint_mode", endpoint_mode.is_some()),
+            ("deploy.mode", mode.is_some()),
+            ("deploy.placement", placement.is_some()),
+            ("deploy.restart_policy", restart_policy.is_some()),
+            ("deploy.rollback_config", rollback_config.is_some()),
+            ("deploy.update_config", update_config.is_some()),
+        ] {
+            if unsupported {
+                self.unsupported(field);
+            }
+        }
+
+        let mut deploy_resources = DeployResources::default();
+        if let Some(Resources {
+            limits,
+            reservations,
+            extensions: _,
+        }) = resources
+        {
+            if let Some(Limits {
+                cpus,
+                memory,
+                pids,
+                extensions: _,
+            }) = limits
+            {
+                if pids.is_some() {
+                    self.unsupported("deploy.resources.limits.pids");
+                }
+                deploy_resources.limits_cpus = *cpus;
+                deploy_resources.limits_memory = *memory;
+            }
+            if let Some(Reservations {
+                cpus,
+                memory,
+                devices,
+                generic_resources,
+                extensions: _,
+            }) = reservations
+            {
+                if !devices.is_empty() {
+                    self.unsupported("deploy.resources.reservations.devices");
+                }
+                if !generic_resources.is_empty() {
+                    self.unsupported("deploy.resources.reservations.generic_resources");
+                }
+                deploy_resources.reservations_cpus = *cpus;
+                deploy_resources.reservations_memory = *memory;
+            }
+        }
+
+        (*replicas, deploy_resources, string_map(labels))
+    }
+
+    /// Convert a [`Healthcheck`] into a [`Probe`].
+    ///
+    /// Returns [`None`] if the healthcheck is disabled or does not set a test, as Kubernetes
+    /// ignores the healthcheck of the image.
+    fn probe(&mut self, healthcheck: &Healthcheck) -> Option<Probe> {
+        let Healthcheck::Command(healthcheck::Command {
+            test,
+            interval,
+            timeout,
+            retries,
+            start_period,
+            start_interval,
+            extensions: _,
+        }) = healthcheck
+        else {
+            return None;
+        };
+
+        if start_interval.is_some() {
+            self.unsupported("healthcheck.start_interval");
+        }
+        let command = match test.as_ref()? {
+            Test::Command(command) => command.clone(),
+            Test::ShellCommand(command) => {
+                vec!["/bin/sh".to_owned(), "-c".to_owned(), command.clone()]
+            }
+        };
+
+        Some(Probe {
+            exec: Some(ExecAction { command }),
+            http_get: None,
+            tcp_socket: None,
+            initial_delay_seconds: start_period.map(duration::seconds),
+            period_seconds: interval.map(duration::seconds),
+            timeout_seconds: timeout.map(duration::seconds),
+            failure_threshold: *retries,
+        })
+    }
+}
+
+/// Resources from [`Deploy`], used if the equivalent service fields are not set.
+#[derive(Debug, Default, Clone, Copy)]
+struct DeployResources {
+    /// `deploy.resources.limits.cpus`
+    limits_cpus: Option<Cpus>,
+
+    /// `deploy.resources.limits.memory`
+    limits_memory: Option<ByteValue>,
+
+    /// `deploy.resources.reservations.cpus`
+    reservations_cpus: Option<Cpus>,
+
+    /// `deploy.resources.reservations.memory`
+    reservations_memory: Option<ByteValue>,
+}
+
+/// Split a [`Command`] into words.
+fn split_command(
+    service: &Identifier,
+    field: &'static str,
+    command: &Command,
+) -> Result<Vec<String>, ManifestsError> {
+    match command {
+        Command::String(command) => {
+            crate::shell::split(command).ok_or_else(|| ManifestsError::SplitCommand {
+                service: service.clone(),
+                field,
+            })
+        }
+        Command::List(command) => Ok(command.clone()),
+    }
+}
+
+/// Kubernetes name of a port [`Protocol`], e.g. `TCP`.
+fn protocol(protocol: Option<&Protocol>) -> String {
+    protocol
+        .map_or("tcp", Protocol::as_str)
+        .to_ascii_uppercase()
+}
+
+/// Add a port to the container and service, if not already added.
+fn push_port(
+    container_ports: &mut Vec<ContainerPort>,
+    service_ports: &mut Vec<ServicePort>,
+    port: u16,
+    target_port: u16,
+    protocol: String,
+) {
+    let container_port = ContainerPort {
+        name: None,
+        container_port: target_port,
+        host_port: None,
+        protocol: protocol.clone(),
+    };
+    if !container_ports.contains(&container_port) {
+        container_ports.push(container_port);
+    }
+    if !service_ports
+        .iter()
+        .any(|service_port| service_port.port == port && service_port.protocol == protocol)
+    {
+        service_ports.push(ServicePort {
+            name: format!("{port}-{}", protocol.to_ascii_lowercase()),
+            port,
+            target_port: Some(TargetPort::Number(target_port)),
+            protocol,
+        });
+    }
+}
+
+/// A pod, which runs a group of containers sharing a network namespace.
+///
+/// Only read by [`Manifests::from_yaml()`](super::Manifests::from_yaml()), pods are not created
+/// from services.
+///
+/// [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/workload-resources/pod-v1/)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Pod {
+    /// Metadata of the pod.
+    #[serde(default)]
+    pub metadata: ObjectMeta,
+
+    /// Specification of the pod.
+    pub spec: PodSpec,
+}
+
+/// A [`Deployment`](Object::Deployment) or [`StatefulSet`](Object::StatefulSet), which runs
+/// replicas of a service's pod.
+///
+/// [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/workload-resources/deployment-v1/)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Workload {
+    /// Metadata of the workload.
+    pub metadata: ObjectMeta,
+
+    /// Specification of the workload.
+    pub spec: WorkloadSpec,
+}
+
+/// Specification of a [`Workload`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct WorkloadSpec {
+    /// Number of pods to run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub replicas: Option<u64>,
+
+    /// Name of the [`Service`] governing a [`StatefulSet`](Object::StatefulSet).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub service_name: Option<String>,
+
+    /// Selects the pods of the workload.
+    #[serde(default)]
+    pub selector: LabelSelector,
+
+    /// Template pods are created from.
+    pub template: PodTemplateSpec,
+
+    /// Claims created for each pod of a [`StatefulSet`](Object::StatefulSet), which the pods
+    /// mount by the claim's name.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub volume_claim_templates: Vec<PersistentVolumeClaim>,
+}
+
+/// Selects objects by their labels.
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct LabelSelector {
+    /// Labels the objects must have.
+    #[serde(default)]
+    pub match_labels: IndexMap<String, String>,
+}
+
+/// Template for the pods of a [`Workload`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PodTemplateSpec {
+    /// Metadata of the pods.
+    pub metadata: ObjectMeta,
+
+    /// Specification of the pods.
+    pub spec: PodSpec,
+}
+
+/// Specification of a pod.
+///
+/// [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/workload-resources/pod-v1/#PodSpec)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PodSpec {
+    /// Containers of the pod.
+    pub containers: Vec<Container>,
+
+    /// Volumes which may be mounted by the containers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub volumes: Vec<Volume>,
+
+    /// Hostname of the pod.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub hostname: Option<String>,
+
+    /// Entries added to the pod's hosts file.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub host_aliases: Vec<HostAlias>,
+
+    /// DNS configuration of the pod.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub dns_config: Option<PodDnsConfig>,
+
+    /// Security attributes of the pod.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_context: Option<PodSecurityContext>,
+
+    /// Seconds the pod is given to stop gracefully before it is killed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub termination_grace_period_seconds: Option<u64>,
+}
+
+/// A container of a pod.
+///
+/// [Kubernetes API](https://kubernetes.io/docs/reference/kubernetes-api/workload-resources/pod-v1/#Container)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Container {
+    /// Name of the container.
+    pub name: String,
+
+    /// Image the container is run from.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<String>,
+
+    /// Overrides the entrypoint of the image.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub command: Vec<String>,
+
+    /// Overrides the command of the image.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub args: Vec<String>,
+
+    /// Working directory of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<String>,
+
+    /// Environment variables of the container.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env: Vec<EnvVar>,
+
+    /// Sources of environment variables of the container.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub env_from: Vec<EnvFromSource>,
+
+    /// Ports exposed by the container.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub ports: Vec<ContainerPort>,
+
+    /// Compute resources of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub resources: Option<ResourceRequirements>,
+
+    /// Pod volumes mounted into the container.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub volume_mounts: Vec<VolumeMount>,
+
+    /// Probe which restarts the container on failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub liveness_probe: Option<Probe>,
+
+    /// Probe which removes the container from service endpoints on failure.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub readiness_probe: Option<Probe>,
+
+    /// Security attributes of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub security_context: Option<SecurityContext>,
+
+    /// Whether to allocate a buffer for stdin.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub stdin: bool,
+
+    /// Whether to allocate a TTY.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub tty: bool,
+}
+
+/// An environment variable of a [`Container`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvVar {
+    /// Name of the variable.
+    pub name: String,
+
+    /// Value of the variable.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+
+    /// Source of the variable's value, if [`value`](Self::value) is not set.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value_from: Option<EnvVarSource>,
+}
+
+/// Source of the value of an [`EnvVar`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvVarSource {
+    /// Key of a [`ConfigMap`](super::ConfigMap).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_map_key_ref: Option<KeySelector>,
+
+    /// Key of a [`Secret`](super::Secret).
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_key_ref: Option<KeySelector>,
+}
+
+/// Selects a key of a [`ConfigMap`](super::ConfigMap) or [`Secret`](super::Secret).
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct KeySelector {
+    /// Name of the object.
+    pub name: String,
+
+    /// Key within the object's data.
+    pub key: String,
+}
+
+/// Source of a set of environment variables of a [`Container`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EnvFromSource {
+    /// Prefix added to each key.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub prefix: Option<String>,
+
+    /// [`ConfigMap`](super::ConfigMap) whose keys become variables.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub config_map_ref: Option<ObjectReference>,
+
+    /// [`Secret`](super::Secret) whose keys become variables.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub secret_ref: Option<ObjectReference>,
+}
+
+/// Reference to an object by name.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ObjectReference {
+    /// Name of the object.
+    pub name: String,
+}
+
+/// A port exposed by a [`Container`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct ContainerPort {
+    /// Name of the port, which may be referred to by a [`ServicePort`].
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub name: Option<String>,
+
+    /// Port number within the container.
+    pub container_port: u16,
+
+    /// Port number on the host the port is published to.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host_port: Option<u16>,
+
+    /// Protocol of the port, `TCP`, `UDP`, or `SCTP`.
+    #[serde(default = "default_protocol")]
+    pub protocol: String,
+}
+
+/// Compute resources of a [`Container`].
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+pub struct ResourceRequirements {
+    /// Maximum resources the container may use, e.g. `cpu: 0.5` and `memory: 512M`.
+    #[serde(
+        default,
+        deserialize_with = "quantities",
+        skip_serializing_if = "IndexMap::is_empty"
+    )]
+    pub limits: IndexMap<String, String>,
+
+    /// Minimum resources reserved for the container.
+    #[serde(
+        default,
+        deserialize_with = "quantities",
+        skip_serializing_if = "IndexMap::is_empty"
+    )]
+    pub requests: IndexMap<String, String>,
+}
+
+impl ResourceRequirements {
+    /// Create [`ResourceRequirements`] from CPU and memory limits and reservations.
+    ///
+    /// Returns [`None`] if none are set.
+    fn new(
+        limits_cpus: Option<Cpus>,
+        limits_memory: Option<ByteValue>,
+        requests_cpus: Option<Cpus>,
+        requests_memory: Option<ByteValue>,
+    ) -> Option<Self> {
+        /// Map of CPU and memory quantities.
+        fn resources(cpus: Option<Cpus>, memory: Option<ByteValue>) -> IndexMap<String, String> {
+            cpus.map(|cpus| ("cpu".to_owned(), cpus.into_inner().to_string()))
+                .into_iter()
+                .chain(memory.map(|memory| ("memory".to_owned(), quantity(memory))))
+                .collect()
+        }
+
+        let requirements = Self {
+            limits: resources(limits_cpus, limits_memory),
+            requests: resources(requests_cpus, requests_memory),
+        };
+        (!requirements.limits.is_empty() || !requirements.requests.is_empty())
+            .then_some(requirements)
+    }
+}
+
+/// Mount of a pod [`Volume`] into a [`Container`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct VolumeMount {
+    /// Name of the pod volume.
+    pub name: String,
+
+    /// Path within the container the volume is mounted at.
+    pub mount_path: String,
+
+    /// Path within the volume to mount instead of its root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub sub_path: Option<String>,
+
+    /// Whether the volume is mounted read-only.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+}
+
+/// A health check of a [`Container`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct Probe {
+    /// Command run to check the health of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec: Option<ExecAction>,
+
+    /// HTTP request made to check the health of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub http_get: Option<HttpGetAction>,
+
+    /// TCP connection opened to check the health of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub tcp_socket: Option<TcpSocketAction>,
+
+    /// Seconds after the container started before the probe is run.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub initial_delay_seconds: Option<u64>,
+
+    /// Seconds between probes.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub period_seconds: Option<u64>,
+
+    /// Seconds after which the probe times out.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub timeout_seconds: Option<u64>,
+
+    /// Consecutive failures for the probe to be considered failed.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub failure_threshold: Option<u64>,
+}
+
+/// Command run by a [`Probe`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ExecAction {
+    /// Command and its arguments, not run in a shell.
+    #[serde(default)]
+    pub command: Vec<String>,
+}
+
+/// HTTP request made by a [`Probe`], which succeeds if the response status is below 400.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HttpGetAction {
+    /// Path of the request, defaults to `/`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub path: Option<String>,
+
+    /// Port number or name of a [`ContainerPort`] to connect to.
+    pub port: TargetPort,
+
+    /// Host to connect to, defaults to the pod's IP address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+
+    /// Scheme of the request, `HTTP` or `HTTPS`.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub scheme: Option<String>,
+}
+
+/// TCP connection opened by a [`Probe`], which succeeds if the connection is established.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct TcpSocketAction {
+    /// Port number or name of a [`ContainerPort`] to connect to.
+    pub port: TargetPort,
+
+    /// Host to connect to, defaults to the pod's IP address.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub host: Option<String>,
+}
+
+/// Security attributes of a [`Container`].
+#[allow(clippy::struct_field_names)]
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecurityContext {
+    /// Whether the container is privileged.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub privileged: bool,
+
+    /// Whether the container's root filesystem is read-only.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only_root_filesystem: bool,
+
+    /// UID the container is run as.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_as_user: Option<u32>,
+
+    /// GID the container is run as.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub run_as_group: Option<u32>,
+
+    /// Capabilities added to or dropped from the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub capabilities: Option<Capabilities>,
+}
+
+impl SecurityContext {
+    /// Create a [`SecurityContext`] from privileged, read-only, and capabilities.
+    ///
+    /// Returns [`None`] if none are set.
+    fn new<T: ToString>(
+        privileged: bool,
+        read_only_root_filesystem: bool,
+        add: &indexmap::IndexSet<T>,
+        drop: &indexmap::IndexSet<T>,
+    ) -> Option<Self> {
+        let capabilities = (!add.is_empty() || !drop.is_empty()).then(|| Capabilities {
+            add: add.iter().map(ToString::to_string).collect(),
+            drop: drop.iter().map(ToString::to_string).collect(),
+        });
+        (privileged || read_only_root_filesystem || capabilities.is_some()).then_some(Self {
+            privileged,
+            read_only_root_filesystem,
+            run_as_user: None,
+            run_as_group: None,
+            capabilities,
+        })
+    }
+}
+
+/// Capabilities added to or dropped from a [`Container`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Capabilities {
+    /// Added capabilities.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub add: Vec<String>,
+
+    /// Dropped capabilities.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub drop: Vec<String>,
+}
+
+/// An entry of a pod's hosts file.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HostAlias {
+    /// IP address the hostnames resolve to.
+    pub ip: String,
+
+    /// Hostnames of the IP address.
+    #[serde(default)]
+    pub hostnames: Vec<String>,
+}
+
+/// DNS configuration of a pod.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PodDnsConfig {
+    /// IP addresses of DNS servers.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub nameservers: Vec<String>,
+
+    /// DNS search domains.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub searches: Vec<String>,
+
+    /// DNS resolver options.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub options: Vec<PodDnsConfigOption>,
+}
+
+impl PodDnsConfig {
+    /// Returns `true` if no DNS configuration is set.
+    fn is_empty(&self) -> bool {
+        self.nameservers.is_empty() && self.searches.is_empty() && self.options.is_empty()
+    }
+}
+
+/// A DNS resolver option of a pod, e.g. `ndots: 2`.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PodDnsConfigOption {
+    /// Name of the option.
+    pub name: String,
+
+    /// Value of the option.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub value: Option<String>,
+}
+
+/// Security attributes of a pod.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct PodSecurityContext {
+    /// Kernel parameters set for the pod.
+    #[serde(default, skip_serializing_if = "Vec::is_empty")]
+    pub sysctls: Vec<Sysctl>,
+}
+
+/// A kernel parameter set for a pod.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Sysctl {
+    /// Name of the kernel parameter, e.g. `net.core.somaxconn`.
+    pub name: String,
+
+    /// Value of the kernel parameter.
+    pub value: String,
+}
+
+/// A volume of a pod.
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Volume {
+    /// Name of the volume, referenced by [`VolumeMount`]s.
+    pub name: String,
+
+    /// Source of the volume.
+    ///
+    /// [`None`] if the volume has a kind of source which is not supported.
+    #[serde(flatten)]
+    pub source: Option<VolumeSource>,
+}
+
+/// Source of a pod [`Volume`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub enum VolumeSource {
+    /// Storage of a [`PersistentVolumeClaim`](super::PersistentVolumeClaim).
+    PersistentVolumeClaim(PersistentVolumeClaimVolumeSource),
+
+    /// Empty directory which exists as long as the pod.
+    EmptyDir(EmptyDirVolumeSource),
+
+    /// Keys of a [`ConfigMap`](super::ConfigMap) as files.
+    ConfigMap(ConfigMapVolumeSource),
+
+    /// Keys of a [`Secret`](super::Secret) as files.
+    Secret(SecretVolumeSource),
+
+    /// File or directory on the host.
+    HostPath(HostPathVolumeSource),
+}
+
+/// A [`PersistentVolumeClaim`](super::PersistentVolumeClaim) used as a pod [`Volume`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct PersistentVolumeClaimVolumeSource {
+    /// Name of the claim.
+    pub claim_name: String,
+
+    /// Whether the volume is read-only for all mounts.
+    #[serde(default, skip_serializing_if = "std::ops::Not::not")]
+    pub read_only: bool,
+}
+
+/// An empty directory used as a pod [`Volume`].
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct EmptyDirVolumeSource {
+    /// Storage medium of the directory, `Memory` for a tmpfs.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub medium: Option<String>,
+
+    /// Maximum size of the directory.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub size_limit: Option<String>,
+}
+
+/// A [`ConfigMap`](super::ConfigMap) used as a pod [`Volume`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct ConfigMapVolumeSource {
+    /// Name of the config map.
+    pub name: String,
+}
+
+/// A [`Secret`](super::Secret) used as a pod [`Volume`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "camelCase")]
+pub struct SecretVolumeSource {
+    /// Name of the secret.
+    pub secret_name: String,
+}
+
+/// A file or directory on the host used as a pod [`Volume`].
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct HostPathVolumeSource {
+    /// Path on the host.
+    pub path: String,
+}
@@ -95,16 +95,23 @@
 //! [`Short`]: ShortOrLong::Short
 //! [`Long`]: ShortOrLong::Long
 
+pub mod bake;
 mod common;
 pub mod config;
 pub mod duration;
+pub mod engine;
 mod include;
+pub mod kubernetes;
 mod name;
 pub mod network;
 mod options;
+pub mod quadlet;
+pub mod run;
 pub mod secret;
 mod serde;
 pub mod service;
+mod shell;
+pub mod systemd;
 mod volume;
 
 use std::{
@@ -247,23 +254,62 @@ impl Compose {
         Options::default()
     }
 
-    /// Ensure that all [`Resource`]s ([`Network`]s, [`Volume`]s, [`Config`]s, and [`Secret`]s) used
-    /// in each [`Service`] are defined in the appropriate top-level field.
+    /// Ensure that all [`Service`]s and [`Resource`]s ([`Network`]s, [`Volume`]s, [`Config`]s, and
+    /// [`Secret`]s) used in each [`Service`] are defined in the appropriate top-level field.
     ///
-    /// Runs, in order, [`validate_networks()`](Self::validate_networks()),
+    /// Runs, in order, [`validate_services()`](Self::validate_services()),
+    /// [`validate_networks()`](Self::validate_networks()),
     /// [`validate_volumes()`](Self::validate_volumes()),
-    /// [`validate_configs()`](Self::validate_configs()), and
-    /// [`validate_secrets()`](Self::validate_secrets()).
+    /// [`validate_configs()`](Self::validate_configs()),
+    /// [`validate_secrets()`](Self::validate_secrets()),
+    /// [`validate_sysctls()`](Self::validate_sysctls()),
+    /// [`validate_network_attachments()`](Self::validate_network_attachments()), and
+    /// [`validate_develop()`](Self::validate_develop()).
     ///
     /// # Errors
     ///
-    /// Returns the first error encountered, meaning an [`Identifier`] for a [`Resource`] was used
-    /// in a [`Service`] which is not defined in the appropriate top-level field.
+    /// Returns the first error encountered, meaning an [`Identifier`] for a [`Service`] or
+    /// [`Resource`] was used in a [`Service`] which is not defined in the appropriate top-level
+    /// field, or a [`Service`] has invalid `sysctls`, network attachments, or watch rules.
     pub fn validate_all(&self) -> Result<(), ValidationError> {
+        self.validate_services()?;
         self.validate_networks()?;
         self.validate_volumes()?;
         self.validate_configs()?;
         self.validate_secrets()?;
+        self.validate_sysctls()?;
+        self.validate_network_attachments()?;
+        self.validate_develop()?;
+        Ok(())
+    }
+
+    /// Ensure that the other services each [`Service`] depends on are defined in the `services`
+    /// field.
+    ///
+    /// See [`Service::service_dependencies()`] for the fields which are checked.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a [`Service`] depends on a service not defined in the `services` field.
+    ///
+    /// Only the first undefined service is listed in the error's [`Display`] output.
+    pub fn validate_services(&self) -> Result<(), ValidationError> {
+        for (name, service) in &self.services {
+            if let Some(dependency) = service
+                .service_dependencies()
+                .into_iter()
+                .find(|dependency| !self.services.contains_key(*dependency))
+            {
+                return Err(ValidationError {
+                    service: Some(name.clone()),
+                    kind: ValidationErrorKind::Undefined {
+                        resource: dependency.clone(),
+                        kind: ResourceKind::Service,
+                    },
+                });
+            }
+        }
+
         Ok(())
     }
 
@@ -281,8 +327,10 @@ impl Compose {
                 .validate_networks(&self.networks)
                 .map_err(|resource| ValidationError {
                     service: Some(name.clone()),
-                    resource,
-                    kind: ResourceKind::Network,
+                    kind: ValidationErrorKind::Undefined {
+                        resource,
+                        kind: ResourceKind::Network,
+                    },
                 })?;
         }
 
@@ -311,8 +359,10 @@ impl Compose {
                     if !entry.get() && !self.volumes.contains_key(volume) {
                         return Err(ValidationError {
                             service: None,
-                            resource: volume.clone(),
-                            kind: ResourceKind::Volume,
+                            kind: ValidationErrorKind::Undefined {
+                                resource: volume.clone(),
+                                kind: ResourceKind::Volume,
+                            },
                         });
                     }
                     *entry.get_mut() = true;
@@ -340,8 +390,10 @@ impl Compose {
                 .validate_configs(&self.configs)
                 .map_err(|resource| ValidationError {
                     service: Some(name.clone()),
-                    resource,
-                    kind: ResourceKind::Config,
+                    kind: ValidationErrorKind::Undefined {
+                        resource,
+                        kind: ResourceKind::Config,
+                    },
                 })?;
         }
 
@@ -362,56 +414,171 @@ impl Compose {
                 .validate_secrets(&self.secrets)
                 .map_err(|resource| ValidationError {
                     service: Some(name.clone()),
-                    resource,
-                    kind: ResourceKind::Secret,
+                    kind: ValidationErrorKind::Undefined {
+                        resource,
+                        kind: ResourceKind::Secret,
+                    },
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Ensure that the `sysctls` of each [`Service`] are valid.
+    ///
+    /// See [`Service::validate_sysctls()`] for the checks which are performed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a [`Service`] has a sysctl which is invalid, is not namespaced, or is in a
+    /// namespace the container shares with the host.
+    pub fn validate_sysctls(&self) -> Result<(), ValidationError> {
+        for (name, service) in &self.services {
+            service
+                .validate_sysctls()
+                .map_err(|source| ValidationError {
+                    service: Some(name.clone()),
+                    kind: ValidationErrorKind::Sysctl(source),
                 })?;
         }
 
         Ok(())
     }
+
+    /// Ensure that the network attachments of each [`Service`] are valid.
+    ///
+    /// See [`Service::validate_network_attachments()`] for the checks which are performed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if multiple networks of a [`Service`] have the highest `gw_priority`, or an
+    /// IPv4 address is set for a network with IPv4 disabled.
+    pub fn validate_network_attachments(&self) -> Result<(), ValidationError> {
+        for (name, service) in &self.services {
+            service
+                .validate_network_attachments(&self.networks)
+                .map_err(|source| ValidationError {
+                    service: Some(name.clone()),
+                    kind: ValidationErrorKind::NetworkAttachment(source),
+                })?;
+        }
+
+        Ok(())
+    }
+
+    /// Ensure that the `develop` watch rules of each [`Service`] are valid.
+    ///
+    /// See [`WatchRule::validate()`](service::develop::WatchRule::validate()) for the checks which
+    /// are performed.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a watch rule of a [`Service`] is missing a field required by, or has a
+    /// field not allowed for, its action.
+    pub fn validate_develop(&self) -> Result<(), ValidationError> {
+        for (name, service) in &self.services {
+            if let Some(develop) = &service.develop {
+                develop.validate().map_err(|source| ValidationError {
+                    service: Some(name.clone()),
+                    kind: ValidationErrorKind::WatchRule(source),
+                })?;
+            }
+        }
+
+        Ok(())
+    }
 }
 
 /// Error returned when validation of a [`Compose`] file fails.
 ///
-/// Occurs when a [`Service`] uses a [`Resource`] which is not defined in the corresponding
-/// field in the [`Compose`].
+/// Occurs when a [`Service`] uses another [`Service`] or a [`Resource`] which is not defined in
+/// the corresponding field in the [`Compose`], or when a [`Service`] has invalid `sysctls`,
+/// network attachments, or watch rules.
 #[derive(Debug, Clone, PartialEq, Eq)]
 pub struct ValidationError {
-    /// Name of the [`Service`] which uses the invalid `resource`.
+    /// Name of the [`Service`] which is invalid.
     service: Option<Identifier>,
-    /// Name of the resource which is not defined by the [`Compose`] file.
-    resource: Identifier,
-    /// The kind of the `resource`.
-    kind: ResourceKind,
+    /// The kind of validation error.
+    kind: ValidationErrorKind,
 }
 
 impl Display for ValidationError {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        let Self {
-            service,
-            resource,
-            kind,
-        } = self;
+        let Self { service, kind } = self;
 
-        write!(f, "{kind} `{resource}` ")?;
+        match kind {
+            ValidationErrorKind::Undefined { resource, kind } => {
+                write!(f, "{kind} `{resource}` ")?;
 
-        if let Some(service) = service {
-            write!(f, "(used in the `{service}` service) ")?;
-        }
+                if let Some(service) = service {
+                    write!(f, "(used in the `{service}` service) ")?;
+                }
+
+                if matches!(kind, ResourceKind::Volume) {
+                    write!(f, "is used across multiple services and ")?;
+                }
 
-        if matches!(kind, ResourceKind::Volume) {
-            write!(f, "is used across multiple services and ")?;
+                write!(f, "is not defined in the top-level `{kind}s` field")
+            }
+            ValidationErrorKind::Sysctl(_) => {
+                f.write_str("invalid `sysctls`")?;
+                if let Some(service) = service {
+                    write!(f, " in the `{service}` service")?;
+                }
+                Ok(())
+            }
+            ValidationErrorKind::NetworkAttachment(_) => {
+                f.write_str("invalid network attachments")?;
+                if let Some(service) = service {
+                    write!(f, " in the `{service}` service")?;
+                }
+                Ok(())
+            }
+            ValidationErrorKind::WatchRule(_) => {
+                f.write_str("invalid `develop`")?;
+                if let Some(service) = service {
+                    write!(f, " in the `{service}` service")?;
+                }
+                Ok(())
+            }
         }
+    }
+}
 
-        write!(f, "is not defined in the top-level `{kind}s` field")
+impl Error for ValidationError {
+    fn source(&self) -> Option<&(dyn Error + 'static)> {
+        match &self.kind {
+            ValidationErrorKind::Undefined { .. } => None,
+            ValidationErrorKind::Sysctl(source) => Some(source),
+            ValidationErrorKind::NetworkAttachment(source) => Some(source),
+            ValidationErrorKind::WatchRule(source) => Some(source),
+        }
     }
 }
 
-impl Error for ValidationError {}
+/// Kinds of [`ValidationError`]s.
+#[derive(Debug, Clone, PartialEq, Eq)]
+enum ValidationErrorKind {
+    /// A [`Service`] or [`Resource`] is not defined in the appropriate top-level field.
+    Undefined {
+        /// Name of the resource which is not defined by the [`Compose`] file.
+        resource: Identifier,
+        /// The kind of the `resource`.
+        kind: ResourceKind,
+    },
+    /// The `sysctls` of a [`Service`] are invalid.
+    Sysctl(service::SysctlError),
+    /// The network attachments of a [`Service`] are invalid.
+    NetworkAttachment(service::NetworkAttachmentError),
+    /// A `develop` watch rule of a [`Service`] is invalid.
+    WatchRule(service::develop::WatchRuleError),
+}
 
 /// Kinds of [`Resource`]s that may be used in a [`ValidationError`].
 #[derive(Debug, Clone, Copy, PartialEq, Eq)]
 enum ResourceKind {
+    /// [`Service`] resource kind.
+    Service,
     /// [`Network`] resource kind.
     Network,
     /// [`Volume`] resource kind.
@@ -427,6 +594,7 @@ impl ResourceKind {
     #[must_use]
     const fn as_str(self) -> &'static str {
         match self {
+            Self::Service => "service",
             Self::Network => "network",
             Self::Volume => "volume",
             Self::Config => "config",
@@ -573,8 +741,10 @@ mod tests {
             compose.validate_networks(),
             Err(ValidationError {
                 service: Some(test),
-                resource: network.clone(),
-                kind: ResourceKind::Network
+                kind: ValidationErrorKind::Undefined {
+                    resource: network.clone(),
+                    kind: ResourceKind::Network,
+                }
             })
         );
 
@@ -584,6 +754,39 @@ mod tests {
         Ok(())
     }
 
+    #[test]
+    fn validate_services() -> Result<(), InvalidIdentifierError> {
+        let test = Identifier::new("test")?;
+        let dependency = Identifier::new("dependency")?;
+
+        let service = Service {
+            pid: Some(service::Pid::Service(dependency.clone())),
+            ..Service::default()
+        };
+
+        let mut compose = Compose {
+            services: indexmap! {
+                test.clone() => service,
+            },
+            ..Compose::default()
+        };
+        assert_eq!(
+            compose.validate_services(),
+            Err(ValidationError {
+                service: Some(test),
+                kind: ValidationErrorKind::Undefined {
+                    resource: dependency.clone(),
+                    kind: ResourceKind::Service,
+                }
+            })
+        );
+
+        compose.services.insert(dependency, Service::default());
+        assert_eq!(compose.validate_services(), Ok(()));
+
+        Ok(())
+    }
+
     #[test]
     #[allow(clippy::unwrap_used, clippy::indexing_slicing)]
     fn validate_volumes() {
@@ -611,8 +814,10 @@ mod tests {
             .insert(Identifier::new("two").unwrap(), service);
         let error = Err(ValidationError {
             service: None,
-            resource: volume_id.clone(),
-            kind: ResourceKind::Volume,
+            kind: ValidationErrorKind::Undefined {
+                resource: volume_id.clone(),
+                kind: ResourceKind::Volume,
+            },
         });
         assert_eq!(compose.validate_volumes(), error);
 
@@ -644,8 +849,10 @@ mod tests {
             compose.validate_configs(),
             Err(ValidationError {
                 service: Some(service),
-                resource: config.clone(),
-                kind: ResourceKind::Config
+                kind: ValidationErrorKind::Undefined {
+                    resource: config.clone(),
+                    kind: ResourceKind::Config,
+                }
             })
         );
 
@@ -675,8 +882,10 @@ mod tests {
             compose.validate_secrets(),
             Err(ValidationError {
                 service: Some(service),
-                resource: secret.clone(),
-                kind: ResourceKind::Secret
+                kind: ValidationErrorKind::Undefined {
+                    resource: secret.clone(),
+                    kind: ResourceKind::Secret,
+                }
             })
         );
 
@@ -687,4 +896,79 @@ mod tests {
 
         Ok(())
     }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::indexing_slicing)]
+    fn validate_sysctls() {
+        let mut compose: Compose = serde_yaml::from_str(
+            "services:
+              test:
+                network_mode: host
+                sysctls:
+                  net.core.somaxconn: 1024",
+        )
+        .unwrap();
+
+        let error = compose.validate_all().unwrap_err();
+        assert!(matches!(error.kind, ValidationErrorKind::Sysctl(_)));
+        assert_eq!(error.to_string(), "invalid `sysctls` in the `test` service");
+
+        compose.services[0].network_config = None;
+        assert_eq!(compose.validate_all(), Ok(()));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::indexing_slicing)]
+    fn validate_network_attachments() {
+        let mut compose: Compose = serde_yaml::from_str(
+            "
+            services:
+              test:
+                networks:
+                  one:
+                  two:
+                    gw_priority: 0
+            networks:
+              one:
+              two:
+            ",
+        )
+        .unwrap();
+
+        let error = compose.validate_all().unwrap_err();
+        assert!(matches!(
+            error.kind,
+            ValidationErrorKind::NetworkAttachment(_)
+        ));
+        assert_eq!(
+            error.to_string(),
+            "invalid network attachments in the `test` service"
+        );
+
+        compose.services[0].network_config = None;
+        assert_eq!(compose.validate_all(), Ok(()));
+    }
+
+    #[test]
+    #[allow(clippy::unwrap_used, clippy::indexing_slicing)]
+    fn validate_develop() {
+        let mut compose: Compose = serde_yaml::from_str(
+            "
+            services:
+              test:
+                develop:
+                  watch:
+                    - action: sync
+                      path: ./src
+            ",
+        )
+        .unwrap();
+
+        let error = compose.validate_all().unwrap_err();
+        assert!(matches!(error.kind, ValidationErrorKind::WatchRule(_)));
+        assert_eq!(error.to_string(), "invalid `develop` in the `test` service");
+
+        compose.services[0].develop = None;
+        assert_eq!(compose.validate_all(), Ok(()));
+    }
 }
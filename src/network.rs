@@ -59,6 +59,14 @@ pub struct Network {
     #[serde(default, skip_serializing_if = "Not::not")]
     pub attachable: bool,
 
+    /// Whether to enable IPv4 networking.
+    ///
+    /// If [`None`], IPv4 is enabled.
+    ///
+    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/06-networks.md#enable_ipv4)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub enable_ipv4: Option<bool>,
+
     /// Whether to enable IPv6 networking.
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/06-networks.md#enable_ipv6)
@@ -124,6 +132,7 @@ impl Network {
             driver,
             driver_opts,
             attachable,
+            enable_ipv4,
             enable_ipv6,
             ipam,
             internal,
@@ -135,6 +144,7 @@ impl Network {
         driver.is_none()
             && driver_opts.is_empty()
             && !attachable
+            && enable_ipv4.is_none()
             && !enable_ipv6
             && !ipam.as_ref().is_some_and(|ipam| !ipam.is_empty())
             && !internal
@@ -0,0 +1,1016 @@
+//! Provides [`Quadlet`] for converting a [`Compose`] project into Podman Quadlet files and back.
+//!
+//! Each [`Service`](crate::Service) becomes a `.container` file, each top-level [`Network`] and
+//! [`Volume`] managed by Compose becomes a `.network` or `.volume` file, and, optionally, all
+//! containers are put into a `.pod`.
+//!
+//! Existing Quadlet files can be parsed with [`File::parse()`] or [`File::read()`] and converted
+//! into a [`Compose`] file with [`Quadlet::to_compose()`].
+//!
+//! [podman-systemd.unit](https://docs.podman.io/en/latest/markdown/podman-systemd.unit.5.html)
+
+mod container;
+mod import;
+
+use std::{
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use indexmap::IndexMap;
+use thiserror::Error;
+
+use crate::{
+    network::Ipam,
+    service::{network_config::NetworkMode, Condition},
+    systemd::{escape_specifiers, quote, ParseUnitError, Section, Unit},
+    Compose, Identifier, InvalidIdentifierError, ListOrMap, MapKey, Network, Resource,
+    StringOrNumber, Volume,
+};
+
+/// Podman Quadlet files converted from a [`Compose`] file.
+///
+/// Created with [`Quadlet::from_compose()`], or from parsed [`File`]s to convert them with
+/// [`Quadlet::to_compose()`].
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Quadlet {
+    /// Quadlet files, in order: containers, pod, networks, and volumes.
+    pub files: Vec<File>,
+
+    /// Fields which could not be converted as they have no Quadlet equivalent.
+    pub unsupported: Vec<Unsupported>,
+}
+
+impl Quadlet {
+    /// Convert a [`Compose`] file into Podman Quadlet files.
+    ///
+    /// Creates a `.container` file for each [`Service`](crate::Service), and a `.network` or
+    /// `.volume` file for each top-level [`Network`] or [`Volume`] which is not external. If any
+    /// service does not set `networks` or `network_mode` and a `default` network is not defined, a
+    /// `default.network` file is created to mirror the default network Compose creates.
+    ///
+    /// If `pod` is [`Some`], a `.pod` file with that name is also created and all containers are
+    /// put into it. Published ports and networks are then set on the pod instead of the
+    /// containers.
+    ///
+    /// Fields which have no Quadlet equivalent are not silently dropped, they are listed in
+    /// [`Quadlet::unsupported`].
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compose_spec::{quadlet::Quadlet, Compose};
+    ///
+    /// let yaml = "\
+    /// services:
+    ///   caddy:
+    ///     image: docker.io/library/caddy:latest
+    ///     ports:
+    ///       - 8000:80
+    ///     volumes:
+    ///       - caddy-data:/data
+    ///     restart: always
+    /// volumes:
+    ///   caddy-data:
+    /// ";
+    /// let compose: Compose = serde_yaml::from_str(yaml)?;
+    ///
+    /// let quadlet = Quadlet::from_compose(&compose, None);
+    /// assert!(quadlet.unsupported.is_empty());
+    ///
+    /// let caddy = &quadlet.files[0];
+    /// assert_eq!(caddy.file_name(), "caddy.container");
+    /// assert_eq!(
+    ///     caddy.unit.to_string(),
+    ///     "\
+    /// [Container]
+    /// Image=docker.io/library/caddy:latest
+    /// PublishPort=8000:80
+    /// Volume=caddy-data.volume:/data
+    /// Network=default.network
+    ///
+    /// [Service]
+    /// Restart=always
+    ///
+    /// [Install]
+    /// WantedBy=default.target
+    /// ",
+    /// );
+    /// # Ok::<(), serde_yaml::Error>(())
+    /// ```
+    #[must_use]
+    pub fn from_compose(compose: &Compose, pod: Option<Identifier>) -> Self {
+        let mut quadlet = Self::default();
+        quadlet.push_containers(compose, pod);
+
+        if let Some(default_network) = compose
+            .services
+            .values()
+            .any(|service| service.network_config.is_none())
+            .then(|| Identifier::new("default").ok())
+            .flatten()
+            .filter(|default_network| !compose.networks.contains_key(default_network))
+        {
+            quadlet.push_empty(default_network, Kind::Network);
+        }
+
+        for (name, network) in &compose.networks {
+            match network {
+                Some(Resource::External { .. }) => {}
+                Some(Resource::Compose(network)) => {
+                    let unit = convert_network(name, network, &mut quadlet.unsupported);
+                    quadlet.files.push(File {
+                        name: name.clone(),
+                        kind: Kind::Network,
+                        unit,
+                    });
+                }
+                None => quadlet.push_empty(name.clone(), Kind::Network),
+            }
+        }
+
+        for (name, volume) in &compose.volumes {
+            match volume {
+                Some(Resource::External { .. }) => {}
+                Some(Resource::Compose(volume)) => quadlet.files.push(File {
+                    name: name.clone(),
+                    kind: Kind::Volume,
+                    unit: convert_volume(volume),
+                }),
+                None => quadlet.push_empty(name.clone(), Kind::Volume),
+            }
+        }
+
+        quadlet
+    }
+
+    /// Convert the Quadlet [`files`](Self::files) into a [`Compose`] file.
+    ///
+    /// Each `.container` file becomes a [`Service`](crate::Service), and each `.network` or
+    /// `.volume` file becomes a top-level [`Network`] or [`Volume`]. Networks, volumes, and
+    /// secrets used by containers without a corresponding file are added as well. Compose has no
+    /// pods, so the published ports and networks of a `.pod` file are moved to its first container
+    /// which does not depend on another container in the pod, and the other containers use its
+    /// network with `network_mode: service:{name}`.
+    ///
+    /// `Requires=` and `Wants=` on other containers become `depends_on`, with the
+    /// `service_healthy` condition if the dependency has `Notify=healthy`.
+    ///
+    /// Entries which could not be converted are kept, by section and key, in the `x-quadlet`
+    /// extension of the service, network, or volume. Remaining entries of `.pod` files are kept in
+    /// the top-level `x-quadlet` extension by file name.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compose_spec::quadlet::{File, Quadlet};
+    ///
+    /// let caddy = File::parse(
+    ///     "caddy.container",
+    ///     "\
+    /// [Container]
+    /// Image=docker.io/library/caddy:latest
+    /// PublishPort=8000:80
+    /// Volume=caddy-data.volume:/data
+    /// AutoUpdate=registry
+    ///
+    /// [Service]
+    /// Restart=always
+    /// ",
+    /// )?;
+    /// let caddy_data = File::parse("caddy-data.volume", "[Volume]\n")?;
+    ///
+    /// let quadlet = Quadlet {
+    ///     files: vec![caddy, caddy_data],
+    ///     unsupported: Vec::new(),
+    /// };
+    /// let compose = quadlet.to_compose();
+    ///
+    /// assert_eq!(
+    ///     serde_yaml::to_string(&compose)?,
+    ///     "\
+    /// services:
+    ///   caddy:
+    ///     image: docker.io/library/caddy:latest
+    ///     ports:
+    ///     - 8000:80
+    ///     restart: always
+    ///     volumes:
+    ///     - caddy-data:/data
+    ///     x-quadlet:
+    ///       Container:
+    ///         AutoUpdate: registry
+    /// volumes:
+    ///   caddy-data: {}
+    /// ",
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn to_compose(&self) -> Compose {
+        import::convert(&self.files)
+    }
+
+    /// Convert each [`Service`](crate::Service) into a `.container` file, and add the `.pod` file
+    /// if `pod` is [`Some`].
+    fn push_containers(&mut self, compose: &Compose, pod: Option<Identifier>) {
+        let mut pod_section = Section::default();
+        for (name, service) in &compose.services {
+            let (unit, pod_entries) =
+                container::convert(compose, name, service, pod.as_ref(), &mut self.unsupported);
+            for (key, value) in pod_entries {
+                if !pod_section
+                    .entries
+                    .iter()
+                    .any(|(entry_key, entry_value)| *entry_key == key && *entry_value == value)
+                {
+                    pod_section.push(key, value);
+                }
+            }
+            self.files.push(File {
+                name: name.clone(),
+                kind: Kind::Container,
+                unit,
+            });
+        }
+
+        self.push_notify_healthy(compose);
+
+        if let Some(pod) = pod {
+            let mut unit = Unit::new();
+            unit.sections.insert("Pod".to_owned(), pod_section);
+            self.files.push(File {
+                name: pod,
+                kind: Kind::Pod,
+                unit,
+            });
+        }
+    }
+
+    /// Add `Notify=healthy` to each container which another service depends on with the
+    /// `service_healthy` condition.
+    fn push_notify_healthy(&mut self, compose: &Compose) {
+        // Dependencies with the `service_healthy` condition are only considered started once
+        // healthy, which requires the dependency to have a healthcheck command.
+        let mut healthy: Vec<&Identifier> = Vec::new();
+        for (name, service) in &compose.services {
+            let Some(depends_on) = service.depends_on.as_long() else {
+                continue;
+            };
+            for (dependency, _) in depends_on
+                .iter()
+                .filter(|(_, dependency)| matches!(dependency.condition, Condition::ServiceHealthy))
+            {
+                let has_health_cmd = self
+                    .files
+                    .iter()
+                    .find(|file| file.kind == Kind::Container && file.name == *dependency)
+                    .and_then(|file| file.unit.section("Container"))
+                    .and_then(|section| section.get("HealthCmd"))
+                    .is_some_and(|health_cmd| health_cmd != "none");
+                if has_health_cmd {
+                    healthy.push(dependency);
+                } else {
+                    self.unsupported.push(Unsupported {
+                        kind: Kind::Container,
+                        name: name.clone(),
+                        field: format!("depends_on.{dependency}.condition"),
+                    });
+                }
+            }
+        }
+        for file in &mut self.files {
+            if healthy.contains(&&file.name) {
+                let section = file.unit.section_mut("Container");
+                if !section.contains_key("Notify") {
+                    section.push("Notify", "healthy");
+                }
+            }
+        }
+    }
+
+    /// Add a file with only an empty Quadlet specific section.
+    fn push_empty(&mut self, name: Identifier, kind: Kind) {
+        let mut unit = Unit::new();
+        unit.section_mut(match kind {
+            Kind::Container => "Container",
+            Kind::Pod => "Pod",
+            Kind::Network => "Network",
+            Kind::Volume => "Volume",
+        });
+        self.files.push(File { name, kind, unit });
+    }
+}
+
+/// Reference to a [`Network`] for use in a Quadlet `Network=` value.
+///
+/// External networks are referenced by name, all others by their `.network` file.
+fn network_reference(compose: &Compose, name: &str) -> String {
+    match compose.networks.get(name) {
+        Some(Some(Resource::External { name: custom })) => {
+            custom.clone().unwrap_or_else(|| name.to_owned())
+        }
+        _ => format!("{name}.{}", Kind::Network),
+    }
+}
+
+/// Reference to a named [`Volume`] for use in a Quadlet `Volume=` or `Mount=` value.
+///
+/// Volumes defined in the top-level `volumes` field, which are not external, are referenced by
+/// their `.volume` file, all others by name.
+fn volume_reference(compose: &Compose, name: &str) -> String {
+    match compose.volumes.get(name) {
+        Some(Some(Resource::External { name: custom })) => {
+            custom.clone().unwrap_or_else(|| name.to_owned())
+        }
+        Some(_) => format!("{name}.{}", Kind::Volume),
+        None => name.to_owned(),
+    }
+}
+
+/// Network mode for use in a Quadlet `Network=` value.
+///
+/// Other services are referenced by their `.container` file.
+fn network_mode_reference(network_mode: &NetworkMode) -> String {
+    match network_mode {
+        NetworkMode::Service(service) => format!("{service}.{}", Kind::Container),
+        network_mode => network_mode.to_string(),
+    }
+}
+
+/// A Podman Quadlet file.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct File {
+    /// Name of the file without its extension.
+    pub name: Identifier,
+
+    /// Kind of Quadlet file, determines the file extension.
+    pub kind: Kind,
+
+    /// Contents of the file.
+    pub unit: Unit,
+}
+
+impl File {
+    /// Parse a Quadlet file from its `file_name`, e.g. `caddy.container`, and `contents`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file name does not have a supported extension, the name without the
+    /// extension is not a valid [`Identifier`], or the contents are not a valid systemd unit.
+    pub fn parse(file_name: &str, contents: &str) -> Result<Self, ParseFileError> {
+        let (name, kind) = file_name
+            .rsplit_once('.')
+            .and_then(|(name, extension)| Some((name, Kind::from_extension(extension)?)))
+            .ok_or_else(|| ParseFileError::Extension(file_name.to_owned()))?;
+
+        Ok(Self {
+            name: Identifier::new(name)?,
+            kind,
+            unit: contents.parse()?,
+        })
+    }
+
+    /// Read and [parse](Self::parse()) a Quadlet file.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the file could not be read or parsed.
+    pub fn read<P: AsRef<Path>>(path: P) -> Result<Self, ReadFileError> {
+        let path = path.as_ref();
+        let contents = fs::read_to_string(path).map_err(|source| ReadFileError::Read {
+            path: path.to_owned(),
+            source,
+        })?;
+        let file_name = path
+            .file_name()
+            .map(|file_name| file_name.to_string_lossy())
+            .unwrap_or_default();
+
+        Self::parse(&file_name, &contents).map_err(|source| ReadFileError::Parse {
+            path: path.to_owned(),
+            source,
+        })
+    }
+
+    /// Name of the file including its extension, e.g. `caddy.container`.
+    #[must_use]
+    pub fn file_name(&self) -> String {
+        format!("{}.{}", self.name, self.kind)
+    }
+}
+
+/// Error returned when [parsing](File::parse()) a Quadlet [`File`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseFileError {
+    /// The file name did not have a supported extension.
+    #[error("file name `{0}` does not have a supported Quadlet file extension")]
+    Extension(String),
+
+    /// The file name without its extension was not a valid [`Identifier`].
+    #[error("invalid Quadlet file name")]
+    Name(#[from] InvalidIdentifierError),
+
+    /// Error parsing the file's contents.
+    #[error("error parsing Quadlet file contents")]
+    Unit(#[from] ParseUnitError),
+}
+
+/// Error returned when [reading](File::read()) a Quadlet [`File`].
+#[derive(Error, Debug)]
+pub enum ReadFileError {
+    /// Error reading the file.
+    #[error("error reading Quadlet file `{}`", path.display())]
+    Read {
+        /// Path of the file.
+        path: PathBuf,
+
+        /// Source of the error.
+        source: io::Error,
+    },
+
+    /// Error parsing the file.
+    #[error("error parsing Quadlet file `{}`", path.display())]
+    Parse {
+        /// Path of the file.
+        path: PathBuf,
+
+        /// Source of the error.
+        source: ParseFileError,
+    },
+}
+
+/// Kind of Podman Quadlet [`File`].
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Kind {
+    /// `.container` file, converted from a [`Service`](crate::Service).
+    Container,
+
+    /// `.pod` file, grouping all containers.
+    Pod,
+
+    /// `.network` file, converted from a [`Network`].
+    Network,
+
+    /// `.volume` file, converted from a [`Volume`].
+    Volume,
+}
+
+impl Kind {
+    /// File extension (without the leading `.`) as a static string slice.
+    ///
+    /// Also the name of the Quadlet specific section of the file, in lowercase.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Container => "container",
+            Self::Pod => "pod",
+            Self::Network => "network",
+            Self::Volume => "volume",
+        }
+    }
+
+    /// Kind of Quadlet file from its extension (without the leading `.`).
+    ///
+    /// Returns [`None`] if the extension is not one of the supported kinds.
+    #[must_use]
+    pub fn from_extension(extension: &str) -> Option<Self> {
+        match extension {
+            "container" => Some(Self::Container),
+            "pod" => Some(Self::Pod),
+            "network" => Some(Self::Network),
+            "volume" => Some(Self::Volume),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Kind {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A field which has no Quadlet equivalent and was not converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+    /// Kind of the [`File`] the field would have been converted into.
+    pub kind: Kind,
+
+    /// Name of the service, network, or volume containing the field.
+    pub name: Identifier,
+
+    /// Name of the unsupported field, e.g. `deploy.replicas` or `depends_on.condition`.
+    pub field: String,
+}
+
+impl Display for Unsupported {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self { kind, name, field } = self;
+        let resource = match kind {
+            Kind::Container | Kind::Pod => "service",
+            Kind::Network => "network",
+            Kind::Volume => "volume",
+        };
+        write!(
+            f,
+            "`{field}` of {resource} `{name}` has no Quadlet equivalent"
+        )
+    }
+}
+
+/// Convert a top-level [`Network`] into a `.network` file [`Unit`].
+fn convert_network(
+    name: &Identifier,
+    network: &Network,
+    unsupported_fields: &mut Vec<Unsupported>,
+) -> Unit {
+    let Network {
+        driver,
+        driver_opts,
+        attachable,
+        enable_ipv4,
+        enable_ipv6,
+        ipam,
+        internal,
+        labels,
+        name: network_name,
+        extensions: _,
+    } = network;
+
+    let mut unsupported = |field: &str| {
+        unsupported_fields.push(Unsupported {
+            kind: Kind::Network,
+            name: name.clone(),
+            field: field.to_owned(),
+        });
+    };
+
+    let mut unit = Unit::new();
+    let section = unit.section_mut("Network");
+
+    if let Some(network_name) = network_name {
+        section.push("NetworkName", escape_specifiers(network_name));
+    }
+    if let Some(driver) = driver {
+        section.push("Driver", escape_specifiers(driver.as_str()));
+    }
+    push_options(section, "Options", driver_opts);
+    if *attachable {
+        unsupported("attachable");
+    }
+    if *enable_ipv4 == Some(false) {
+        unsupported("enable_ipv4");
+    }
+    if *enable_ipv6 {
+        section.push("IPv6", "true");
+    }
+    if let Some(Ipam {
+        driver,
+        config,
+        options,
+        extensions: _,
+    }) = ipam
+    {
+        if let Some(driver) = driver {
+            section.push("IPAMDriver", escape_specifiers(driver));
+        }
+        for config in config {
+            if let Some(subnet) = config.subnet {
+                section.push("Subnet", subnet.to_string());
+            }
+            if let Some(gateway) = config.gateway {
+                section.push("Gateway", gateway.to_string());
+            }
+            if let Some(ip_range) = config.ip_range {
+                section.push("IPRange", ip_range.to_string());
+            }
+            if !config.aux_addresses.is_empty() {
+                unsupported("ipam.config.aux_addresses");
+            }
+        }
+        if !options.is_empty() {
+            unsupported("ipam.options");
+        }
+    }
+    if *internal {
+        section.push("Internal", "true");
+    }
+    push_list(section, "Label", labels);
+
+    unit
+}
+
+/// Convert a top-level [`Volume`] into a `.volume` file [`Unit`].
+fn convert_volume(volume: &Volume) -> Unit {
+    let Volume {
+        driver,
+        driver_opts,
+        labels,
+        name: volume_name,
+        extensions: _,
+    } = volume;
+
+    let mut unit = Unit::new();
+    let section = unit.section_mut("Volume");
+
+    if let Some(volume_name) = volume_name {
+        section.push("VolumeName", escape_specifiers(volume_name));
+    }
+    if let Some(driver) = driver {
+        section.push("Driver", escape_specifiers(driver));
+    }
+    for (key, value) in driver_opts {
+        // The local driver's options have dedicated keys.
+        let key = match key.as_str() {
+            "type" => "Type",
+            "device" => "Device",
+            "o" => "Options",
+            _ => {
+                section.push("PodmanArgs", quote(&format!("--opt={key}={value}")));
+                continue;
+            }
+        };
+        section.push(key, escape_specifiers(&value.to_string()));
+    }
+    push_list(section, "Label", labels);
+
+    unit
+}
+
+/// Push each item of a [`ListOrMap`] as a `key` entry of the `section`.
+fn push_list(section: &mut Section, key: &str, list: &ListOrMap) {
+    for item in list.clone().into_list() {
+        section.push(key, quote(&item));
+    }
+}
+
+/// Push each driver option as a `key` entry in the `{key}={value}` format.
+fn push_options(section: &mut Section, key: &str, options: &IndexMap<MapKey, StringOrNumber>) {
+    for (option, value) in options {
+        section.push(key, quote(&format!("{option}={value}")));
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    /// File names and contents of the `quadlet` files.
+    fn files(quadlet: &Quadlet) -> Vec<(String, String)> {
+        quadlet
+            .files
+            .iter()
+            .map(|file| (file.file_name(), file.unit.to_string()))
+            .collect()
+    }
+
+    /// Owned file names and contents.
+    fn owned(files: &[(&str, &str)]) -> Vec<(String, String)> {
+        files
+            .iter()
+            .map(|&(name, contents)| (name.to_owned(), contents.to_owned()))
+            .collect()
+    }
+
+    #[test]
+    fn full() {
+        let yaml = "\
+services:
+  app:
+    image: app
+    command: [\"serve\", \"--listen\", \"0.0.0.0:80\"]
+    ports:
+      - 127.0.0.1:8080:80
+    volumes:
+      - data:/data:ro
+      - type: bind
+        source: ./config
+        target: /config
+        read_only: true
+    networks:
+      backend:
+        aliases:
+          - api
+    healthcheck:
+      test: curl -f http://localhost
+      interval: 10s
+    depends_on:
+      db:
+        condition: service_healthy
+    restart: unless-stopped
+    privileged: true
+  db:
+    image: db
+    networks:
+      - backend
+    healthcheck: { test: pg_isready }
+    deploy:
+      replicas: 2
+networks:
+  backend:
+    internal: true
+volumes:
+  data:
+    name: app-data
+";
+        let compose: Compose = serde_yaml::from_str(yaml).unwrap();
+        let quadlet = Quadlet::from_compose(&compose, None);
+
+        assert_eq!(
+            files(&quadlet),
+            owned(&[
+                (
+                    "app.container",
+                    "\
+[Container]
+Image=app
+Exec=serve --listen 0.0.0.0:80
+PublishPort=127.0.0.1:8080:80
+Volume=data.volume:/data:ro
+Mount=type=bind,source=./config,destination=/config,ro=true
+Network=backend.network:alias=api
+HealthCmd=curl -f http://localhost
+HealthInterval=10s
+PodmanArgs=--privileged
+
+[Unit]
+Requires=db.service
+After=db.service
+
+[Service]
+Restart=always
+
+[Install]
+WantedBy=default.target
+"
+                ),
+                (
+                    "db.container",
+                    "\
+[Container]
+Image=db
+Network=backend.network
+HealthCmd=pg_isready
+Notify=healthy
+"
+                ),
+                (
+                    "backend.network",
+                    "\
+[Network]
+Internal=true
+"
+                ),
+                (
+                    "data.volume",
+                    "\
+[Volume]
+VolumeName=app-data
+"
+                ),
+            ])
+        );
+
+        assert_eq!(
+            quadlet.unsupported,
+            [Unsupported {
+                kind: Kind::Container,
+                name: Identifier::new("db").unwrap(),
+                field: "deploy.replicas".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn deploy_resources() {
+        let yaml = "\
+services:
+  app:
+    image: app
+    stop_grace_period: 1500ms
+    pids_limit: 50
+    deploy:
+      placement:
+        constraints:
+          - node.role==worker
+      resources:
+        limits:
+          cpus: '0.5'
+          memory: 256mb
+          pids: 100
+        reservations:
+          memory: 64mb
+          cpus: '0.25'
+";
+        let compose: Compose = serde_yaml::from_str(yaml).unwrap();
+        let quadlet = Quadlet::from_compose(&compose, None);
+
+        assert_eq!(
+            files(&quadlet),
+            owned(&[
+                (
+                    "app.container",
+                    "\
+[Container]
+Image=app
+Network=default.network
+PidsLimit=50
+Memory=256mb
+StopTimeout=2
+PodmanArgs=--cpus=0.5
+PodmanArgs=--memory-reservation=64mb
+"
+                ),
+                ("default.network", "[Network]\n"),
+            ])
+        );
+
+        let unsupported: Vec<_> = quadlet
+            .unsupported
+            .iter()
+            .map(|unsupported| unsupported.field.as_str())
+            .collect();
+        assert_eq!(
+            unsupported,
+            ["deploy.placement", "deploy.resources.reservations.cpus"]
+        );
+    }
+
+    #[test]
+    fn healthy_without_healthcheck() {
+        let yaml = "\
+services:
+  app:
+    image: app
+    depends_on:
+      db:
+        condition: service_healthy
+  db:
+    image: db
+";
+        let compose: Compose = serde_yaml::from_str(yaml).unwrap();
+        let quadlet = Quadlet::from_compose(&compose, None);
+
+        assert!(quadlet.files.iter().all(|file| !file
+            .unit
+            .section("Container")
+            .is_some_and(|section| section.contains_key("Notify"))));
+        assert_eq!(
+            quadlet.unsupported,
+            [Unsupported {
+                kind: Kind::Container,
+                name: Identifier::new("app").unwrap(),
+                field: "depends_on.db.condition".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn namespace_services() {
+        let yaml = "\
+services:
+  one:
+    image: one
+    container_name: first
+    ipc: shareable
+  two:
+    image: two
+    ipc: service:one
+    pid: service:three
+  three:
+    image: three
+";
+        let compose: Compose = serde_yaml::from_str(yaml).unwrap();
+        let quadlet = Quadlet::from_compose(&compose, None);
+
+        let two = quadlet
+            .files
+            .iter()
+            .find(|file| file.name.as_str() == "two")
+            .and_then(|file| file.unit.section("Container"))
+            .unwrap();
+        assert_eq!(
+            two.get_all("PodmanArgs").collect::<Vec<_>>(),
+            ["--ipc=container:first", "--pid=container:systemd-three"]
+        );
+    }
+
+    #[test]
+    fn pod() {
+        let yaml = "\
+services:
+  one:
+    image: one
+    ports:
+      - 8080:80
+  two:
+    image: two
+    ports:
+      - 8081:81
+    networks:
+      default:
+        aliases:
+          - alias
+";
+        let compose: Compose = serde_yaml::from_str(yaml).unwrap();
+        let quadlet = Quadlet::from_compose(&compose, Some(Identifier::new("app").unwrap()));
+
+        assert_eq!(
+            files(&quadlet),
+            owned(&[
+                (
+                    "one.container",
+                    "\
+[Container]
+Image=one
+Pod=app.pod
+"
+                ),
+                (
+                    "two.container",
+                    "\
+[Container]
+Image=two
+Pod=app.pod
+"
+                ),
+                (
+                    "app.pod",
+                    "\
+[Pod]
+PublishPort=8080:80
+Network=default.network
+PublishPort=8081:81
+"
+                ),
+                ("default.network", "[Network]\n"),
+            ])
+        );
+
+        assert_eq!(
+            quadlet.unsupported,
+            [Unsupported {
+                kind: Kind::Container,
+                name: Identifier::new("two").unwrap(),
+                field: "networks.default.aliases".to_owned(),
+            }]
+        );
+    }
+
+    #[test]
+    fn external_resources() {
+        let yaml = "\
+services:
+  app:
+    image: app
+    network_mode: host
+    volumes:
+      - data:/data
+      - type: volume
+        source: other
+        target: /other
+    secrets:
+      - token
+      - source: key
+        target: /run/key
+volumes:
+  data:
+    external: true
+    name: existing
+secrets:
+  token:
+    external: true
+    name: api-token
+  key:
+    file: ./key.pem
+";
+        let compose: Compose = serde_yaml::from_str(yaml).unwrap();
+        let quadlet = Quadlet::from_compose(&compose, None);
+
+        assert_eq!(
+            files(&quadlet),
+            owned(&[(
+                "app.container",
+                "\
+[Container]
+Image=app
+Volume=existing:/data
+Volume=other:/other
+Network=host
+Secret=api-token
+"
+            )])
+        );
+        assert_eq!(
+            quadlet.unsupported,
+            [Unsupported {
+                kind: Kind::Container,
+                name: Identifier::new("app").unwrap(),
+                field: "secrets.key".to_owned(),
+            }]
+        );
+    }
+}
@@ -0,0 +1,1126 @@
+//! Conversion of a [`Service`] into a Quadlet `.container` file.
+
+use std::{fmt::Write, time::Duration};
+
+use crate::{
+    duration,
+    service::{
+        deploy::resources::Resources,
+        healthcheck::{self, Test},
+        network_config::Network,
+        ports::{Port, ShortPort},
+        volumes::{
+            mount::{self, Bind, BindOptions, BindRecursive, Tmpfs, TmpfsOptions, VolumeOptions},
+            Mount, SELinux, ShortOptions, ShortVolume, Source,
+        },
+        BlkioConfig, Command, Condition, ConfigOrSecret, Dependency, Deploy, Healthcheck, Ipc,
+        Logging, NetworkConfig, Pid, PullPolicy, Restart,
+    },
+    systemd::{escape_specifiers, quote, Section, Unit},
+    Compose, Extensions, Identifier, Resource, Service, ShortOrLong,
+};
+
+use super::{network_mode_reference, network_reference, volume_reference, Kind, Unsupported};
+
+/// Convert a [`Service`] into a `.container` file [`Unit`].
+///
+/// If `pod` is [`Some`], the container is put into the pod and the entries which must be set on
+/// the pod instead, published ports and networks, are returned separately.
+#[allow(clippy::too_many_lines)]
+pub(super) fn convert(
+    compose: &Compose,
+    name: &Identifier,
+    service: &Service,
+    pod: Option<&Identifier>,
+    unsupported_fields: &mut Vec<Unsupported>,
+) -> (Unit, Vec<(String, String)>) {
+    let Service {
+        attach,
+        build,
+        blkio_config,
+        cpu_count,
+        cpu_percent,
+        cpu_shares,
+        cpu_period,
+        cpu_quota,
+        cpu_rt_runtime,
+        cpu_rt_period,
+        cpus,
+        cpuset,
+        cap_add,
+        cap_drop,
+        cgroup,
+        cgroup_parent,
+        command,
+        configs,
+        container_name,
+        credential_spec,
+        depends_on,
+        deploy,
+        develop,
+        device_cgroup_rules,
+        devices,
+        dns,
+        dns_opt,
+        dns_search,
+        domain_name,
+        entrypoint,
+        env_file,
+        environment,
+        expose,
+        extends,
+        annotations,
+        external_links,
+        extra_hosts,
+        group_add,
+        healthcheck,
+        hostname,
+        image,
+        init,
+        ipc,
+        uts,
+        isolation,
+        labels,
+        label_file,
+        links,
+        logging,
+        network_config,
+        mac_address,
+        mem_limit,
+        mem_reservation,
+        mem_swappiness,
+        memswap_limit,
+        oom_kill_disable,
+        oom_score_adj,
+        pid,
+        pids_limit,
+        platform,
+        ports,
+        privileged,
+        profiles,
+        pull_policy,
+        read_only,
+        restart,
+        runtime,
+        scale,
+        secrets,
+        security_opt,
+        shm_size,
+        stdin_open,
+        stop_grace_period,
+        stop_signal,
+        storage_opt,
+        sysctls,
+        tmpfs,
+        tty,
+        ulimits,
+        user,
+        userns_mode,
+        volumes,
+        volumes_from,
+        working_dir,
+        extensions: _,
+    } = service;
+
+    let (limits, reservations) = deploy
+        .as_ref()
+        .and_then(|Deploy { resources, .. }| resources.as_ref())
+        .map_or(
+            (None, None),
+            |Resources {
+                 limits,
+                 reservations,
+                 ..
+             }| { (limits.as_ref(), reservations.as_ref()) },
+        );
+
+    let mut converter = Converter {
+        compose,
+        name,
+        pod: pod.is_some(),
+        unsupported_fields,
+        container: Section::default(),
+        pod_entries: Vec::new(),
+        podman_args: Vec::new(),
+    };
+
+    if !attach {
+        converter.unsupported("attach");
+    }
+    if build.is_some() {
+        converter.unsupported("build");
+    }
+    if let Some(image) = image {
+        converter.push("Image", escape_specifiers(image.as_str()));
+    }
+    if let Some(container_name) = container_name {
+        converter.push("ContainerName", container_name.as_str());
+    }
+    if let Some(hostname) = hostname {
+        converter.push("HostName", hostname.as_str());
+    }
+    if domain_name.is_some() {
+        converter.unsupported("domain_name");
+    }
+    if let Some(pod) = pod {
+        converter.push("Pod", format!("{pod}.{}", Kind::Pod));
+    }
+
+    match entrypoint {
+        Some(Command::String(entrypoint)) => {
+            converter.push("Entrypoint", escape_specifiers(entrypoint));
+        }
+        Some(Command::List(entrypoint)) => {
+            let entrypoint = match entrypoint.as_slice() {
+                [entrypoint] => escape_specifiers(entrypoint),
+                entrypoint => escape_specifiers(&json_array(entrypoint)),
+            };
+            converter.push("Entrypoint", entrypoint);
+        }
+        None => {}
+    }
+    match command {
+        Some(Command::String(command)) => converter.push("Exec", escape_specifiers(command)),
+        Some(Command::List(command)) => {
+            let command: Vec<String> = command.iter().map(|arg| quote(arg)).collect();
+            converter.push("Exec", command.join(" "));
+        }
+        None => {}
+    }
+    if let Some(user) = user {
+        converter.push("User", escape_specifiers(&user.to_string()));
+    }
+    if let Some(working_dir) = working_dir {
+        converter.push(
+            "WorkingDir",
+            quote(&working_dir.as_path().display().to_string()),
+        );
+    }
+
+    for item in environment.clone().into_list() {
+        if item.contains('=') {
+            converter.push("Environment", quote(&item));
+        } else {
+            // The value is taken from the environment Compose is run in, which has no equivalent.
+            converter.unsupported(format!("environment.{item}"));
+        }
+    }
+    if let Some(env_file) = env_file {
+        for env_file in env_file.clone().into_list() {
+            let env_file = env_file.into_long();
+            if !env_file.required {
+                converter.unsupported("env_file.required");
+            }
+            if env_file.format.is_raw() {
+                converter.unsupported("env_file.format");
+            }
+            converter.push(
+                "EnvironmentFile",
+                quote(&env_file.path.display().to_string()),
+            );
+        }
+    }
+    for label in labels.clone().into_list() {
+        converter.push("Label", quote(&label));
+    }
+    if let Some(label_file) = label_file {
+        for path in label_file.clone().into_list() {
+            converter
+                .podman_args
+                .push(format!("--label-file={}", path.display()));
+        }
+    }
+    for annotation in annotations.clone().into_list() {
+        converter.push("Annotation", quote(&annotation));
+    }
+
+    for port in ports {
+        converter.port(port);
+    }
+    for expose in expose {
+        converter.push("ExposeHostPort", expose.to_string());
+    }
+    for volume in volumes {
+        match volume {
+            ShortOrLong::Short(volume) => converter.short_volume(volume),
+            ShortOrLong::Long(mount) => converter.mount(mount),
+        }
+    }
+    if let Some(tmpfs) = tmpfs {
+        for tmpfs in tmpfs.clone().into_list() {
+            converter.push("Tmpfs", quote(&tmpfs.to_string()));
+        }
+    }
+    if !volumes_from.is_empty() {
+        converter.unsupported("volumes_from");
+    }
+
+    converter.network_config(network_config.as_ref());
+    if let Some(mac_address) = mac_address {
+        converter
+            .podman_args
+            .push(format!("--mac-address={mac_address}"));
+    }
+    if let Some(dns) = dns {
+        for dns in dns.clone().into_list() {
+            converter.push("DNS", dns.to_string());
+        }
+    }
+    for dns_opt in dns_opt {
+        converter.push("DNSOption", quote(dns_opt));
+    }
+    if let Some(dns_search) = dns_search {
+        for dns_search in dns_search.clone().into_list() {
+            converter.push("DNSSearch", dns_search.as_str());
+        }
+    }
+    for (host, ip) in extra_hosts {
+        converter.push("AddHost", format!("{host}:{ip}"));
+    }
+    if !links.is_empty() {
+        converter.unsupported("links");
+    }
+    if !external_links.is_empty() {
+        converter.unsupported("external_links");
+    }
+
+    if let Some(healthcheck) = healthcheck {
+        converter.healthcheck(healthcheck);
+    }
+
+    for capability in cap_add {
+        converter.push("AddCapability", capability.to_string());
+    }
+    for capability in cap_drop {
+        converter.push("DropCapability", capability.to_string());
+    }
+    for device in devices {
+        converter.push("AddDevice", quote(&device.to_string()));
+    }
+    for rule in device_cgroup_rules {
+        converter
+            .podman_args
+            .push(format!("--device-cgroup-rule={rule}"));
+    }
+    for group in group_add {
+        converter.push("GroupAdd", group.to_string());
+    }
+    if *read_only {
+        converter.push("ReadOnly", "true");
+    }
+    if *init {
+        converter.push("RunInit", "true");
+    }
+    if let Some(userns_mode) = userns_mode {
+        converter.push("UserNS", userns_mode.to_string());
+    }
+    for sysctl in sysctls.clone().into_list() {
+        converter.push("Sysctl", quote(&sysctl));
+    }
+    for (resource, ulimit) in ulimits {
+        let (soft, hard) = match ulimit {
+            ShortOrLong::Short(limit) => (limit, limit),
+            ShortOrLong::Long(ulimit) => (&ulimit.soft, &ulimit.hard),
+        };
+        converter.push("Ulimit", format!("{resource}={soft}:{hard}"));
+    }
+    if let Some(shm_size) = shm_size {
+        converter.push("ShmSize", shm_size.to_string());
+    }
+    if let Some(pids_limit) = pids_limit.or_else(|| limits.and_then(|limits| limits.pids)) {
+        converter.push("PidsLimit", pids_limit.to_string());
+    }
+    if let Some(mem_limit) = mem_limit.or_else(|| limits.and_then(|limits| limits.memory)) {
+        converter.push("Memory", mem_limit.to_string());
+    }
+    if let Some(stop_signal) = stop_signal {
+        converter.push("StopSignal", stop_signal.to_string());
+    }
+    if let Some(stop_grace_period) = stop_grace_period {
+        converter.push(
+            "StopTimeout",
+            duration::seconds(*stop_grace_period).to_string(),
+        );
+    }
+    if let Some(pull_policy) = pull_policy {
+        match pull_policy {
+            PullPolicy::Always => converter.push("Pull", "always"),
+            PullPolicy::Never => converter.push("Pull", "never"),
+            PullPolicy::Missing => converter.push("Pull", "missing"),
+            PullPolicy::Refresh => converter.push("Pull", "newer"),
+            PullPolicy::Build | PullPolicy::Daily | PullPolicy::Weekly | PullPolicy::Every(_) => {
+                converter.unsupported("pull_policy");
+            }
+        }
+    }
+    if let Some(Logging {
+        driver,
+        options,
+        extensions: _,
+    }) = logging
+    {
+        if let Some(driver) = driver {
+            converter.push("LogDriver", escape_specifiers(driver));
+        }
+        for (key, value) in options {
+            let option = value
+                .as_ref()
+                .map_or_else(|| key.to_string(), |value| format!("{key}={value}"));
+            converter.push("LogOpt", quote(&option));
+        }
+    }
+    for secret in secrets {
+        converter.secret(secret);
+    }
+    if !configs.is_empty() {
+        converter.unsupported("configs");
+    }
+
+    // Options without a dedicated Quadlet key, passed directly to `podman run`.
+    if *privileged {
+        converter.podman_args.push("--privileged".to_owned());
+    }
+    if *tty {
+        converter.podman_args.push("--tty".to_owned());
+    }
+    if *stdin_open {
+        converter.podman_args.push("--interactive".to_owned());
+    }
+    if let Some(ipc) = ipc {
+        let ipc = match ipc {
+            Ipc::Service(service) => converter.container_reference(service),
+            ipc => ipc.to_string(),
+        };
+        converter.podman_args.push(format!("--ipc={ipc}"));
+    }
+    if let Some(pid) = pid {
+        let pid = match pid {
+            Pid::Service(service) => converter.container_reference(service),
+            pid => pid.to_string(),
+        };
+        converter.podman_args.push(format!("--pid={pid}"));
+    }
+    if let Some(uts) = uts {
+        converter.podman_args.push(format!("--uts={uts}"));
+    }
+    if let Some(cgroup) = cgroup {
+        converter.podman_args.push(format!("--cgroupns={cgroup}"));
+    }
+    if let Some(cgroup_parent) = cgroup_parent {
+        converter
+            .podman_args
+            .push(format!("--cgroup-parent={cgroup_parent}"));
+    }
+    for security_opt in security_opt {
+        converter
+            .podman_args
+            .push(format!("--security-opt={security_opt}"));
+    }
+    if let Some(runtime) = runtime {
+        converter.podman_args.push(format!("--runtime={runtime}"));
+    }
+    if let Some(platform) = platform {
+        converter.podman_args.push(format!("--platform={platform}"));
+    }
+    if let Some(cpus) = cpus.or_else(|| limits.and_then(|limits| limits.cpus)) {
+        converter
+            .podman_args
+            .push(format!("--cpus={}", cpus.into_inner()));
+    }
+    if let Some(cpu_shares) = cpu_shares {
+        converter
+            .podman_args
+            .push(format!("--cpu-shares={cpu_shares}"));
+    }
+    for (flag, duration) in [
+        ("--cpu-period", cpu_period),
+        ("--cpu-quota", cpu_quota),
+        ("--cpu-rt-period", cpu_rt_period),
+        ("--cpu-rt-runtime", cpu_rt_runtime),
+    ] {
+        if let Some(duration) = duration {
+            converter
+                .podman_args
+                .push(format!("{flag}={}", duration.as_micros()));
+        }
+    }
+    if !cpuset.is_empty() {
+        converter
+            .podman_args
+            .push(format!("--cpuset-cpus={cpuset}"));
+    }
+    if let Some(mem_reservation) =
+        mem_reservation.or_else(|| reservations.and_then(|reservations| reservations.memory))
+    {
+        converter
+            .podman_args
+            .push(format!("--memory-reservation={mem_reservation}"));
+    }
+    if let Some(memswap_limit) = memswap_limit {
+        converter
+            .podman_args
+            .push(format!("--memory-swap={memswap_limit}"));
+    }
+    if let Some(mem_swappiness) = mem_swappiness {
+        converter
+            .podman_args
+            .push(format!("--memory-swappiness={}", u8::from(*mem_swappiness)));
+    }
+    if *oom_kill_disable {
+        converter.podman_args.push("--oom-kill-disable".to_owned());
+    }
+    if let Some(oom_score_adj) = oom_score_adj {
+        converter
+            .podman_args
+            .push(format!("--oom-score-adj={}", i16::from(*oom_score_adj)));
+    }
+    for (key, value) in storage_opt {
+        let option = value
+            .as_ref()
+            .map_or_else(|| key.to_string(), |value| format!("{key}={value}"));
+        converter
+            .podman_args
+            .push(format!("--storage-opt={option}"));
+    }
+
+    if blkio_config
+        .as_ref()
+        .is_some_and(|blkio_config| !BlkioConfig::is_empty(blkio_config))
+    {
+        converter.unsupported("blkio_config");
+    }
+    if cpu_count.is_some() {
+        converter.unsupported("cpu_count");
+    }
+    if cpu_percent.is_some() {
+        converter.unsupported("cpu_percent");
+    }
+    if credential_spec.is_some() {
+        converter.unsupported("credential_spec");
+    }
+    if let Some(deploy) = deploy {
+        converter.deploy(deploy);
+    }
+    if develop.is_some() {
+        converter.unsupported("develop");
+    }
+    if extends.is_some() {
+        converter.unsupported("extends");
+    }
+    if isolation.is_some() {
+        converter.unsupported("isolation");
+    }
+    if !profiles.is_empty() {
+        converter.unsupported("profiles");
+    }
+    if scale.is_some_and(|scale| scale != 1) {
+        converter.unsupported("scale");
+    }
+
+    let Converter {
+        mut container,
+        pod_entries,
+        podman_args,
+        ..
+    } = converter;
+    for arg in podman_args {
+        container.push("PodmanArgs", quote(&arg));
+    }
+
+    let mut unit = Unit::new();
+    unit.sections.insert("Container".to_owned(), container);
+
+    let dependencies: Vec<(&Identifier, Dependency)> = match depends_on {
+        ShortOrLong::Short(depends_on) => depends_on
+            .iter()
+            .map(|service| (service, Dependency::default()))
+            .collect(),
+        ShortOrLong::Long(depends_on) => depends_on
+            .iter()
+            .map(|(service, dependency)| (service, *dependency))
+            .collect(),
+    };
+    for (
+        service,
+        Dependency {
+            condition,
+            restart,
+            required,
+        },
+    ) in dependencies
+    {
+        let service_unit = format!("{service}.service");
+        let section = unit.section_mut("Unit");
+        section.push(if required { "Requires" } else { "Wants" }, &service_unit);
+        section.push("After", service_unit);
+        if matches!(condition, Condition::ServiceCompletedSuccessfully) {
+            unsupported_fields.push(Unsupported {
+                kind: Kind::Container,
+                name: name.clone(),
+                field: format!("depends_on.{service}.condition"),
+            });
+        }
+        if restart {
+            unsupported_fields.push(Unsupported {
+                kind: Kind::Container,
+                name: name.clone(),
+                field: format!("depends_on.{service}.restart"),
+            });
+        }
+    }
+
+    if let Some(restart) = restart {
+        let value = match restart {
+            Restart::No => "no",
+            // Stopping the systemd service stops restarts.
+            Restart::Always | Restart::UnlessStopped => "always",
+            Restart::OnFailure { max_retries } => {
+                if max_retries.is_some() {
+                    unsupported_fields.push(Unsupported {
+                        kind: Kind::Container,
+                        name: name.clone(),
+                        field: "restart.max_retries".to_owned(),
+                    });
+                }
+                "on-failure"
+            }
+        };
+        unit.section_mut("Service").push("Restart", value);
+
+        // Containers which always restart are also started on boot.
+        if matches!(restart, Restart::Always | Restart::UnlessStopped) {
+            unit.section_mut("Install")
+                .push("WantedBy", "default.target");
+        }
+    }
+
+    (unit, pod_entries)
+}
+
+/// State for converting a [`Service`] into a `.container` file.
+struct Converter<'a> {
+    /// The [`Compose`] file the service is from, used to resolve networks and volumes.
+    compose: &'a Compose,
+
+    /// Name of the service.
+    name: &'a Identifier,
+
+    /// Whether the container is put into a pod.
+    pod: bool,
+
+    /// Fields which could not be converted.
+    unsupported_fields: &'a mut Vec<Unsupported>,
+
+    /// `[Container]` section entries.
+    container: Section,
+
+    /// Entries which must be set on the pod.
+    pod_entries: Vec<(String, String)>,
+
+    /// Arguments passed directly to `podman run`, without a dedicated Quadlet key.
+    podman_args: Vec<String>,
+}
+
+impl Converter<'_> {
+    /// Push an entry to the `[Container]` section.
+    fn push<V: Into<String>>(&mut self, key: &str, value: V) {
+        self.container.push(key, value);
+    }
+
+    /// Push an entry to the pod if the container is in one, otherwise to the `[Container]`
+    /// section.
+    fn push_pod<V: Into<String>>(&mut self, key: &str, value: V) {
+        if self.pod {
+            self.pod_entries.push((key.to_owned(), value.into()));
+        } else {
+            self.container.push(key, value);
+        }
+    }
+
+    /// Add an [`Unsupported`] field.
+    fn unsupported<T: Into<String>>(&mut self, field: T) {
+        self.unsupported_fields.push(Unsupported {
+            kind: Kind::Container,
+            name: self.name.clone(),
+            field: field.into(),
+        });
+    }
+
+    /// Report the fields of `deploy` which have no Quadlet equivalent.
+    ///
+    /// Resource limits and memory reservations are converted together with the equivalent service
+    /// fields.
+    fn deploy(&mut self, deploy: &Deploy) {
+        let Deploy {
+            endpoint_mode,
+            labels,
+            mode,
+            placement,
+            replicas,
+            resources,
+            restart_policy,
+            rollback_config,
+            update_config,
+            extensions: _,
+        } = deploy;
+
+        let reservations = resources
+            .as_ref()
+            .and_then(|resources| resources.reservations.as_ref());
+        for (field, set) in [
+            ("deploy.endpoint_mode", endpoint_mode.is_some()),
+            ("deploy.labels", !labels.is_empty()),
+            ("deploy.mode", mode.is_some()),
+            ("deploy.placement", placement.is_some()),
+            ("deploy.replicas", replicas.is_some()),
+            (
+                "deploy.resources.reservations.cpus",
+                reservations.is_some_and(|reservations| reservations.cpus.is_some()),
+            ),
+            (
+                "deploy.resources.reservations.devices",
+                reservations.is_some_and(|reservations| !reservations.devices.is_empty()),
+            ),
+            (
+                "deploy.resources.reservations.generic_resources",
+                reservations.is_some_and(|reservations| !reservations.generic_resources.is_empty()),
+            ),
+            ("deploy.restart_policy", restart_policy.is_some()),
+            ("deploy.rollback_config", rollback_config.is_some()),
+            ("deploy.update_config", update_config.is_some()),
+        ] {
+            if set {
+                self.unsupported(field);
+            }
+        }
+    }
+
+    /// Reference to the container of another service in the format `container:{name}`, for use
+    /// in `--ipc` and `--pid` values.
+    ///
+    /// Quadlet names containers `systemd-{service}` unless the service sets a `container_name`.
+    fn container_reference(&self, service: &Identifier) -> String {
+        let container_name = self
+            .compose
+            .services
+            .get(service)
+            .and_then(|service| service.container_name.as_ref())
+            .map_or_else(|| format!("systemd-{service}"), ToString::to_string);
+        format!("container:{container_name}")
+    }
+
+    /// Convert a port into a `PublishPort=` entry.
+    fn port(&mut self, port: &ShortOrLong<ShortPort, Port>) {
+        let port = match port {
+            ShortOrLong::Short(port) => Ok(port.clone()),
+            // The name and application protocol are informational only, the mode only applies to
+            // swarm.
+            ShortOrLong::Long(port) => Port {
+                name: None,
+                app_protocol: None,
+                mode: None,
+                extensions: Extensions::new(),
+                ..port.clone()
+            }
+            .into_short(),
+        };
+        match port {
+            Ok(port) => self.push_pod("PublishPort", port.to_string()),
+            Err(_) => self.unsupported("ports.published"),
+        }
+    }
+
+    /// Convert a [`ShortVolume`] into a `Volume=` entry.
+    fn short_volume(&mut self, volume: &ShortVolume) {
+        let ShortVolume {
+            container_path,
+            options,
+        } = volume;
+
+        let Some(ShortOptions {
+            source,
+            read_only,
+            selinux,
+        }) = options
+        else {
+            self.push(
+                "Volume",
+                quote(&container_path.as_path().display().to_string()),
+            );
+            return;
+        };
+
+        let mut options = Vec::new();
+        if *read_only {
+            options.push("ro".to_owned());
+        }
+        options.extend(selinux.map(|selinux| selinux.to_string()));
+
+        let source = self.source(source);
+        self.push_volume(&source, container_path.as_path(), &options);
+    }
+
+    /// Push a `Volume=` entry in the format `{source}:{target}[:{options}]`.
+    fn push_volume(&mut self, source: &str, target: &std::path::Path, options: &[String]) {
+        let mut volume = format!("{source}:{}", target.display());
+        if !options.is_empty() {
+            volume.push(':');
+            volume.push_str(&options.join(","));
+        }
+        self.push("Volume", quote(&volume));
+    }
+
+    /// Source of a short volume for use in a `Volume=` entry.
+    fn source(&self, source: &Source) -> String {
+        match source {
+            Source::HostPath(path) => path.as_path().display().to_string(),
+            Source::Volume(volume) => volume_reference(self.compose, volume.as_str()),
+        }
+    }
+
+    /// Convert a [`Mount`] into a `Volume=` or `Mount=` entry.
+    fn mount(&mut self, mount: &Mount) {
+        let common = mount.common();
+        if common.consistency.is_some() {
+            self.unsupported("volumes.consistency");
+        }
+        let target = common.target.as_path();
+
+        let (source, options) = match mount {
+            Mount::Volume(volume) => match self.volume_mount(volume) {
+                Some(mount) => mount,
+                None => return,
+            },
+            Mount::Bind(Bind {
+                source,
+                bind,
+                common: _,
+            }) => (
+                Some(source.as_path().display().to_string()),
+                bind.as_ref()
+                    .map(|bind| self.bind_options(bind))
+                    .unwrap_or_default(),
+            ),
+            Mount::Tmpfs(Tmpfs { tmpfs, common: _ }) => {
+                let mut options = Vec::new();
+                if let Some(TmpfsOptions {
+                    size,
+                    mode,
+                    extensions: _,
+                }) = tmpfs
+                {
+                    options.extend(size.map(|size| format!("tmpfs-size={size}")));
+                    options.extend(mode.map(|mode| format!("tmpfs-mode={mode:o}")));
+                }
+                (None, options)
+            }
+            Mount::Image(mount::Image {
+                source,
+                image,
+                common: _,
+            }) => {
+                if image.as_ref().is_some_and(|image| image.subpath.is_some()) {
+                    self.unsupported("volumes.image.subpath");
+                }
+                (Some(source.to_string()), Vec::new())
+            }
+            Mount::NamedPipe(_) | Mount::Cluster(_) => {
+                self.unsupported(format!("volumes.{}", mount.kind()));
+                return;
+            }
+        };
+
+        let mut mount_options = vec![format!("type={}", mount.kind())];
+        mount_options.extend(source.map(|source| format!("source={source}")));
+        mount_options.push(format!("destination={}", target.display()));
+        if common.read_only {
+            mount_options.push("ro=true".to_owned());
+        }
+        mount_options.extend(options);
+        self.push("Mount", quote(&mount_options.join(",")));
+    }
+
+    /// Convert a volume [`Mount`] into a `Volume=` entry if possible.
+    ///
+    /// Returns the source and additional options for a `Mount=` entry if the volume's `subpath`
+    /// is set, as it can only be set with `Mount=`.
+    fn volume_mount(&mut self, volume: &mount::Volume) -> Option<(Option<String>, Vec<String>)> {
+        let mount::Volume {
+            source,
+            volume,
+            common,
+        } = volume;
+        let VolumeOptions {
+            nocopy,
+            subpath,
+            extensions: _,
+        } = volume.clone().unwrap_or_default();
+        let source = source
+            .as_ref()
+            .map(|source| volume_reference(self.compose, source.as_str()));
+
+        if let Some(subpath) = subpath {
+            if nocopy {
+                self.unsupported("volumes.volume.nocopy");
+            }
+            return Some((source, vec![format!("subpath={}", subpath.display())]));
+        }
+
+        let target = common.target.as_path();
+        let mut options = Vec::new();
+        if common.read_only {
+            options.push("ro".to_owned());
+        }
+        if nocopy {
+            options.push("nocopy".to_owned());
+        }
+        match source {
+            Some(source) => self.push_volume(&source, target, &options),
+            None => self.push("Volume", quote(&target.display().to_string())),
+        }
+        None
+    }
+
+    /// Additional options for a bind `Mount=` entry.
+    fn bind_options(&mut self, bind: &BindOptions) -> Vec<String> {
+        let mut options = Vec::new();
+        if let Some(propagation) = bind.propagation {
+            options.push(format!("bind-propagation={propagation}"));
+        }
+        match bind.selinux {
+            Some(SELinux::Shared) => options.push("relabel=shared".to_owned()),
+            Some(SELinux::Private) => options.push("relabel=private".to_owned()),
+            None => {}
+        }
+        match bind.recursive {
+            Some(BindRecursive::Disabled) => options.push("bind-nonrecursive=true".to_owned()),
+            Some(_) => self.unsupported("volumes.bind.recursive"),
+            None => {}
+        }
+        if bind.create_host_path {
+            self.unsupported("volumes.bind.create_host_path");
+        }
+        options
+    }
+
+    /// Convert the network config into `Network=` entries.
+    fn network_config(&mut self, network_config: Option<&NetworkConfig>) {
+        match network_config {
+            None => {
+                let network = network_reference(self.compose, "default");
+                self.push_pod("Network", network);
+            }
+            Some(NetworkConfig::NetworkMode(network_mode)) => {
+                if self.pod {
+                    self.unsupported("network_mode");
+                } else {
+                    self.push("Network", network_mode_reference(network_mode));
+                }
+            }
+            Some(NetworkConfig::Networks(ShortOrLong::Short(networks))) => {
+                for network in networks {
+                    let network = network_reference(self.compose, network.as_str());
+                    self.push_pod("Network", network);
+                }
+            }
+            Some(NetworkConfig::Networks(ShortOrLong::Long(networks))) => {
+                for (name, network) in networks {
+                    let options = network
+                        .as_ref()
+                        .map(|network| self.network_options(name, network))
+                        .unwrap_or_default();
+                    let mut network = network_reference(self.compose, name.as_str());
+                    if !options.is_empty() {
+                        network.push(':');
+                        network.push_str(&options.join(","));
+                    }
+                    self.push_pod("Network", network);
+                }
+            }
+        }
+    }
+
+    /// Options for a network attachment in the `Network=` entry.
+    ///
+    /// Containers in a pod cannot set any options.
+    fn network_options(&mut self, name: &Identifier, network: &Network) -> Vec<String> {
+        let Network {
+            aliases,
+            ipv4_address,
+            ipv6_address,
+            link_local_ips,
+            mac_address,
+            driver_opts,
+            priority,
+            interface_name,
+            gw_priority,
+            extensions: _,
+        } = network;
+
+        let mut options = Vec::new();
+        let mut unsupported = Vec::new();
+        if !aliases.is_empty() {
+            options.extend(aliases.iter().map(|alias| format!("alias={alias}")));
+            unsupported.push("aliases");
+        }
+        if let Some(ip) = ipv4_address {
+            options.push(format!("ip={ip}"));
+            unsupported.push("ipv4_address");
+        }
+        if let Some(ip) = ipv6_address {
+            options.push(format!("ip6={ip}"));
+            unsupported.push("ipv6_address");
+        }
+        if let Some(mac_address) = mac_address {
+            options.push(format!("mac={mac_address}"));
+            unsupported.push("mac_address");
+        }
+        if let Some(interface_name) = interface_name {
+            options.push(format!("interface_name={interface_name}"));
+            unsupported.push("interface_name");
+        }
+
+        if self.pod {
+            for field in unsupported {
+                self.unsupported(format!("networks.{name}.{field}"));
+            }
+            options.clear();
+        }
+
+        for (field, set) in [
+            ("link_local_ips", !link_local_ips.is_empty()),
+            ("driver_opts", !driver_opts.is_empty()),
+            ("priority", priority.is_some()),
+            ("gw_priority", gw_priority.is_some()),
+        ] {
+            if set {
+                self.unsupported(format!("networks.{name}.{field}"));
+            }
+        }
+
+        options
+    }
+
+    /// Convert a [`Healthcheck`] into `Health*` entries.
+    fn healthcheck(&mut self, healthcheck: &Healthcheck) {
+        let healthcheck::Command {
+            test,
+            interval,
+            timeout,
+            retries,
+            start_period,
+            start_interval,
+            extensions: _,
+        } = match healthcheck {
+            Healthcheck::Command(command) => command,
+            Healthcheck::Disable => {
+                self.push("HealthCmd", "none");
+                return;
+            }
+        };
+
+        match test {
+            Some(Test::Command(test)) => {
+                self.push("HealthCmd", escape_specifiers(&json_array(test)));
+            }
+            Some(Test::ShellCommand(test)) => self.push("HealthCmd", escape_specifiers(test)),
+            None => {}
+        }
+        for (key, duration) in [
+            ("HealthInterval", interval),
+            ("HealthTimeout", timeout),
+            ("HealthStartPeriod", start_period),
+        ] {
+            if let Some(duration) = duration {
+                self.push(key, duration_string(*duration));
+            }
+        }
+        if let Some(retries) = retries {
+            self.push("HealthRetries", retries.to_string());
+        }
+        if start_interval.is_some() {
+            self.unsupported("healthcheck.start_interval");
+        }
+    }
+
+    /// Convert an external secret into a `Secret=` entry.
+    fn secret(&mut self, secret: &ShortOrLong<Identifier, ConfigOrSecret>) {
+        let (source, options) = match secret {
+            ShortOrLong::Short(source) => (source, Vec::new()),
+            ShortOrLong::Long(ConfigOrSecret {
+                source,
+                target,
+                uid,
+                gid,
+                mode,
+                extensions: _,
+            }) => {
+                let mut options = vec!["type=mount".to_owned()];
+                options.extend(
+                    target
+                        .as_ref()
+                        .map(|target| format!("target={}", target.display())),
+                );
+                options.extend(uid.map(|uid| format!("uid={uid}")));
+                options.extend(gid.map(|gid| format!("gid={gid}")));
+                options.extend(mode.map(|mode| format!("mode={mode:o}")));
+                (source, options)
+            }
+        };
+
+        // Secrets managed by Compose have no Quadlet equivalent, their contents would have to be
+        // added with `podman secret create` first.
+        let name = match self.compose.secrets.get(source) {
+            Some(Resource::External { name }) => name.clone().unwrap_or_else(|| source.to_string()),
+            Some(Resource::Compose(_)) => {
+                self.unsupported(format!("secrets.{source}"));
+                return;
+            }
+            None => source.to_string(),
+        };
+
+        let mut value = name;
+        for option in options {
+            value.push(',');
+            value.push_str(&option);
+        }
+        self.push("Secret", quote(&value));
+    }
+}
+
+/// Format a [`Duration`] for Podman, which accepts Go duration strings.
+fn duration_string(duration: Duration) -> String {
+    if duration.is_zero() {
+        "0s".to_owned()
+    } else {
+        duration::to_string(duration)
+    }
+}
+
+/// Format a list of strings as a JSON array.
+fn json_array(items: &[String]) -> String {
+    let mut json = String::from("[");
+    for (index, item) in items.iter().enumerate() {
+        if index > 0 {
+            json.push_str(", ");
+        }
+        json.push('"');
+        for char in item.chars() {
+            match char {
+                '"' => json.push_str("\\\""),
+                '\\' => json.push_str("\\\\"),
+                '\n' => json.push_str("\\n"),
+                '\r' => json.push_str("\\r"),
+                '\t' => json.push_str("\\t"),
+                char if char.is_control() => {
+                    // Writing to a `String` cannot fail.
+                    let _ = write!(json, "\\u{:04x}", u32::from(char));
+                }
+                char => json.push(char),
+            }
+        }
+        json.push('"');
+    }
+    json.push(']');
+    json
+}
@@ -0,0 +1,635 @@
+//! Conversion of Quadlet [`File`]s into a [`Compose`] file.
+
+mod container;
+
+use std::{mem, net::IpAddr};
+
+use indexmap::{IndexMap, IndexSet};
+use ipnet::IpNet;
+use serde_yaml::Mapping;
+
+use crate::{
+    network::{Driver, Ipam, IpamConfig},
+    service::{network_config::NetworkMode, Condition, Dependency},
+    systemd::{split_words, unescape_specifiers, Section, Unit},
+    Compose, ExtensionKey, Extensions, Identifier, ListOrMap, Map, MapKey, Network, Resource,
+    Service, ShortOrLong, StringOrNumber, Value, Volume, YamlValue,
+};
+
+use super::{File, Kind};
+
+/// Name of the extension which keeps Quadlet options that could not be converted.
+const EXTENSION: &str = "x-quadlet";
+
+/// Convert Quadlet [`File`]s into a [`Compose`] file.
+pub(super) fn convert(files: &[File]) -> Compose {
+    let mut compose = Compose::default();
+
+    for file in files {
+        match file.kind {
+            Kind::Network => {
+                let (mut network, remaining) = network(effective(&file.unit));
+                network.extensions = extensions(&remaining);
+                compose
+                    .networks
+                    .insert(file.name.clone(), Some(Resource::Compose(network)));
+            }
+            Kind::Volume => {
+                let (mut volume, remaining) = volume(effective(&file.unit));
+                volume.extensions = extensions(&remaining);
+                compose
+                    .volumes
+                    .insert(file.name.clone(), Some(Resource::Compose(volume)));
+            }
+            Kind::Container | Kind::Pod => {}
+        }
+    }
+
+    let units: Vec<(&Identifier, Unit)> = files
+        .iter()
+        .filter(|file| file.kind == Kind::Container)
+        .map(|file| (&file.name, effective(&file.unit)))
+        .collect();
+    let containers: Vec<&Identifier> = units.iter().map(|(name, _)| *name).collect();
+    let mut pods: IndexMap<Identifier, (Unit, Option<Identifier>)> = files
+        .iter()
+        .filter(|file| file.kind == Kind::Pod)
+        .map(|file| {
+            let owner = pod_owner(&file.name, &units);
+            (file.name.clone(), (effective(&file.unit), owner))
+        })
+        .collect();
+
+    let mut services = IndexMap::new();
+    for (name, mut unit) in units {
+        let network_mode = join_pod(name, &mut unit, &mut pods);
+        let (mut service, remaining) = container::convert(&mut compose, &containers, unit);
+        if let Some(network_mode) = network_mode {
+            service
+                .network_config
+                .get_or_insert_with(|| network_mode.into());
+        }
+        services.insert(name.clone(), (service, remaining));
+    }
+    healthy_dependencies(&mut services);
+
+    for (name, (mut service, remaining)) in services {
+        service.extensions = extensions(&remaining);
+        compose.services.insert(name, service);
+    }
+
+    let pods: Mapping = pods
+        .into_iter()
+        .filter_map(|(name, (unit, _))| {
+            unit_value(&unit).map(|unit| (format!("{name}.{}", Kind::Pod).into(), unit))
+        })
+        .collect();
+    if !pods.is_empty() {
+        if let Ok(key) = ExtensionKey::new(EXTENSION) {
+            compose.extensions.insert(key, pods.into());
+        }
+    }
+
+    compose
+}
+
+/// Apply empty entries, which reset all previous entries with the same key, to a copy of the
+/// `unit`.
+fn effective(unit: &Unit) -> Unit {
+    let mut unit = unit.clone();
+    for section in unit.sections.values_mut() {
+        let mut entries: Vec<(String, String)> = Vec::with_capacity(section.entries.len());
+        for (key, value) in mem::take(&mut section.entries) {
+            if value.is_empty() {
+                entries.retain(|(entry_key, _)| *entry_key != key);
+            } else {
+                entries.push((key, value));
+            }
+        }
+        section.entries = entries;
+    }
+    unit
+}
+
+/// Remove the `Pod=` entry from a container `unit` if the pod is one of the `pods`.
+///
+/// Compose has no pods, the containers of a pod share the network of its owner instead, see
+/// [`pod_owner()`]. The pod's published ports and networks are moved to the owner. The network
+/// mode of the other containers is returned.
+fn join_pod(
+    name: &Identifier,
+    unit: &mut Unit,
+    pods: &mut IndexMap<Identifier, (Unit, Option<Identifier>)>,
+) -> Option<NetworkMode> {
+    let container = unit.sections.get_mut("Container")?;
+    let (pod_unit, owner) = pod_name(container).and_then(|pod| pods.get_mut(pod))?;
+    container.entries.retain(|(key, _)| key != "Pod");
+
+    match owner {
+        Some(owner) if owner != name => return Some(NetworkMode::Service(owner.clone())),
+        _ => {}
+    }
+
+    if let Some(pod) = pod_unit.sections.get_mut("Pod") {
+        let (shared, entries) = mem::take(&mut pod.entries)
+            .into_iter()
+            .partition(|(key, _)| matches!(key.as_str(), "PublishPort" | "Network"));
+        pod.entries = entries;
+        container.entries.extend(shared);
+    }
+    None
+}
+
+/// Choose the container of the `pod` whose network the other containers in the pod share.
+///
+/// The owner is the first container in the pod which does not depend on another container in the
+/// pod, as sharing its network makes all other containers depend on it. If there is no such
+/// container, the first container in the pod is used.
+fn pod_owner(pod: &Identifier, units: &[(&Identifier, Unit)]) -> Option<Identifier> {
+    let members: Vec<(&Identifier, &Unit)> = units
+        .iter()
+        .filter(|(_, unit)| {
+            unit.section("Container")
+                .and_then(pod_name)
+                .is_some_and(|name| name == pod.as_str())
+        })
+        .map(|(name, unit)| (*name, unit))
+        .collect();
+
+    let depends_on_member = |unit: &Unit| {
+        unit.section("Unit").is_some_and(|section| {
+            section
+                .entries
+                .iter()
+                .filter(|(key, _)| matches!(key.as_str(), "Requires" | "Wants"))
+                .flat_map(|(_, value)| split_words(value).unwrap_or_default())
+                .filter_map(|unit| unit.strip_suffix(".service").map(ToOwned::to_owned))
+                .any(|dependency| {
+                    members
+                        .iter()
+                        .any(|(member, _)| member.as_str() == dependency)
+                })
+        })
+    };
+
+    members
+        .iter()
+        .find(|(_, unit)| !depends_on_member(unit))
+        .or_else(|| members.first())
+        .map(|(name, _)| (*name).clone())
+}
+
+/// Name of the pod from the `Pod=` entry of a `[Container]` section, if it is a `.pod` file.
+fn pod_name(container: &Section) -> Option<&str> {
+    container.get("Pod")?.strip_suffix(".pod")
+}
+
+/// Set the condition of dependencies on containers which notify systemd once they are healthy to
+/// [`Condition::ServiceHealthy`].
+///
+/// The `Notify=healthy` entry of those containers is then removed.
+fn healthy_dependencies(services: &mut IndexMap<Identifier, (Service, Unit)>) {
+    let healthy: Vec<Identifier> = services
+        .iter()
+        .filter(|(_, (_, remaining))| {
+            remaining
+                .section("Container")
+                .and_then(|section| section.get("Notify"))
+                == Some("healthy")
+        })
+        .map(|(name, _)| name.clone())
+        .collect();
+
+    let mut notified = IndexSet::new();
+    for (service, _) in services.values_mut() {
+        if let ShortOrLong::Long(depends_on) = &mut service.depends_on {
+            for (name, dependency) in depends_on.iter_mut() {
+                if healthy.contains(name) {
+                    dependency.condition = Condition::ServiceHealthy;
+                    notified.insert(name.clone());
+                }
+            }
+            if depends_on
+                .values()
+                .all(|dependency| *dependency == Dependency::default())
+            {
+                service.depends_on = ShortOrLong::Short(depends_on.keys().cloned().collect());
+            }
+        }
+    }
+
+    for name in notified {
+        if let Some(section) = services
+            .get_mut(&name)
+            .and_then(|(_, remaining)| remaining.sections.get_mut("Container"))
+        {
+            section.entries.retain(|(key, _)| key != "Notify");
+        }
+    }
+}
+
+/// Convert a `.network` file [`Unit`] into a [`Network`].
+///
+/// Returns the network and the remaining entries which could not be converted.
+fn network(mut unit: Unit) -> (Network, Unit) {
+    let section = unit.section_mut("Network");
+    let entries = mem::take(&mut section.entries);
+
+    let mut network = Network::default();
+    let mut ipam = Ipam::default();
+    let mut subnets = Vec::new();
+    let mut gateways = Vec::new();
+    let mut ip_ranges = Vec::new();
+    let mut labels = Map::new();
+    for (key, value) in entries {
+        let converted = match key.as_str() {
+            "NetworkName" => unescape_specifiers(&value).map(|name| network.name = Some(name)),
+            "Driver" => unescape_specifiers(&value)
+                .map(|driver| network.driver = Some(Driver::parse(driver))),
+            "Options" => options(&value).map(|options| network.driver_opts.extend(options)),
+            "IPv6" => parse_bool(&value).map(|ipv6| network.enable_ipv6 = ipv6),
+            "Internal" => parse_bool(&value).map(|internal| network.internal = internal),
+            "IPAMDriver" => unescape_specifiers(&value).map(|driver| ipam.driver = Some(driver)),
+            "Subnet" => value
+                .parse::<IpNet>()
+                .ok()
+                .map(|subnet| subnets.push(subnet)),
+            "Gateway" => value
+                .parse::<IpAddr>()
+                .ok()
+                .map(|gateway| gateways.push(gateway)),
+            "IPRange" => value
+                .parse::<IpNet>()
+                .ok()
+                .map(|range| ip_ranges.push(range)),
+            "Label" => extend_map(&mut labels, &value),
+            _ => None,
+        };
+        if converted.is_none() {
+            section.push(key, value);
+        }
+    }
+
+    // Subnets, gateways, and IP ranges are matched up in order.
+    let configs = subnets.len().max(gateways.len()).max(ip_ranges.len());
+    let mut subnets = subnets.into_iter();
+    let mut gateways = gateways.into_iter();
+    let mut ip_ranges = ip_ranges.into_iter();
+    ipam.config = (0..configs)
+        .map(|_| IpamConfig {
+            subnet: subnets.next(),
+            ip_range: ip_ranges.next(),
+            gateway: gateways.next(),
+            ..IpamConfig::default()
+        })
+        .collect();
+    if ipam.driver.is_some() || !ipam.config.is_empty() {
+        network.ipam = Some(ipam);
+    }
+    if !labels.is_empty() {
+        network.labels = ListOrMap::Map(labels);
+    }
+
+    (network, unit)
+}
+
+/// Convert a `.volume` file [`Unit`] into a [`Volume`].
+///
+/// Returns the volume and the remaining entries which could not be converted.
+fn volume(mut unit: Unit) -> (Volume, Unit) {
+    let section = unit.section_mut("Volume");
+    let entries = mem::take(&mut section.entries);
+
+    let mut volume = Volume::default();
+    let mut labels = Map::new();
+    for (key, value) in entries {
+        let converted = match key.as_str() {
+            "VolumeName" => unescape_specifiers(&value).map(|name| volume.name = Some(name)),
+            "Driver" => unescape_specifiers(&value).map(|driver| volume.driver = Some(driver)),
+            // The local driver's options have dedicated keys.
+            "Type" | "Device" | "Options" => unescape_specifiers(&value).and_then(|value| {
+                let option = match key.as_str() {
+                    "Type" => "type",
+                    "Device" => "device",
+                    _ => "o",
+                };
+                let option = MapKey::new(option).ok()?;
+                volume.driver_opts.insert(option, value.into());
+                Some(())
+            }),
+            "PodmanArgs" => {
+                volume_options(&value).map(|options| volume.driver_opts.extend(options))
+            }
+            "Label" => extend_map(&mut labels, &value),
+            _ => None,
+        };
+        if converted.is_none() {
+            section.push(key, value);
+        }
+    }
+    if !labels.is_empty() {
+        volume.labels = ListOrMap::Map(labels);
+    }
+
+    (volume, unit)
+}
+
+/// Parse `PodmanArgs=` of a `.volume` file, if they are all `--opt` driver options.
+fn volume_options(value: &str) -> Option<Vec<(MapKey, StringOrNumber)>> {
+    let mut args = split_words(value)?.into_iter();
+    let mut options = Vec::new();
+    while let Some(arg) = args.next() {
+        let option = match arg.strip_prefix("--opt") {
+            Some("") => args.next()?,
+            Some(option) => option.strip_prefix('=')?.to_owned(),
+            None => return None,
+        };
+        let (key, value) = option.split_once('=')?;
+        options.push((MapKey::new(key).ok()?, value.into()));
+    }
+    Some(options)
+}
+
+/// Parse a value of words in the `{key}={value}` format into driver options.
+fn options(value: &str) -> Option<Vec<(MapKey, StringOrNumber)>> {
+    key_values(value).map(|options| {
+        options
+            .into_iter()
+            .map(|(key, value)| (key, value.into()))
+            .collect()
+    })
+}
+
+/// Parse a value of words in the `{key}={value}` format and add them to the `map`.
+fn extend_map(map: &mut Map, value: &str) -> Option<()> {
+    let items = key_values(value)?;
+    map.extend(
+        items
+            .into_iter()
+            .map(|(key, value)| (key, Some(Value::String(value)))),
+    );
+    Some(())
+}
+
+/// Parse a value of words in the `{key}={value}` format.
+///
+/// Returns [`None`] if any word is not in that format or has an invalid key.
+fn key_values(value: &str) -> Option<Vec<(MapKey, String)>> {
+    split_words(value)?
+        .into_iter()
+        .map(|word| {
+            let (key, value) = word.split_once('=')?;
+            Some((MapKey::new(key).ok()?, value.to_owned()))
+        })
+        .collect()
+}
+
+/// Parse a systemd boolean.
+fn parse_bool(value: &str) -> Option<bool> {
+    match value {
+        "1" | "yes" | "y" | "true" | "t" | "on" => Some(true),
+        "0" | "no" | "n" | "false" | "f" | "off" => Some(false),
+        _ => None,
+    }
+}
+
+/// [`Extensions`] keeping the remaining entries of a [`Unit`] under the `x-quadlet` key.
+fn extensions(remaining: &Unit) -> Extensions {
+    let mut extensions = Extensions::new();
+    if let Some((key, value)) = ExtensionKey::new(EXTENSION).ok().zip(unit_value(remaining)) {
+        extensions.insert(key, value);
+    }
+    extensions
+}
+
+/// Convert the non-empty sections of a [`Unit`] into a YAML mapping of sections to entries.
+///
+/// Keys which are repeated within a section have a sequence of values.
+///
+/// Returns [`None`] if the unit is empty.
+fn unit_value(unit: &Unit) -> Option<YamlValue> {
+    let sections: Mapping = unit
+        .sections
+        .iter()
+        .filter(|(_, section)| !section.is_empty())
+        .map(|(name, section)| (name.as_str().into(), section_value(section)))
+        .collect();
+    (!sections.is_empty()).then(|| sections.into())
+}
+
+/// Convert a [`Section`] into a YAML mapping of keys to values.
+fn section_value(section: &Section) -> YamlValue {
+    let mut entries = Mapping::new();
+    for (key, value) in &section.entries {
+        match entries.get_mut(key.as_str()) {
+            Some(YamlValue::Sequence(values)) => values.push(value.as_str().into()),
+            Some(existing) => {
+                let first = mem::take(existing);
+                *existing = YamlValue::Sequence(vec![first, value.as_str().into()]);
+            }
+            None => {
+                entries.insert(key.as_str().into(), value.as_str().into());
+            }
+        }
+    }
+    entries.into()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::quadlet::Quadlet;
+
+    use super::*;
+
+    #[test]
+    fn round_trip() {
+        let yaml = "\
+services:
+  app:
+    command:
+    - serve
+    - --listen
+    - 0.0.0.0:80
+    depends_on:
+      db:
+        condition: service_healthy
+    environment:
+      GREETING: hello world
+    healthcheck:
+      test: curl -f http://localhost
+      interval: 10s
+      retries: 5
+    image: app
+    networks:
+      backend:
+        aliases:
+        - api
+    ports:
+    - 127.0.0.1:8080:80
+    privileged: true
+    restart: always
+    secrets:
+    - source: token
+      target: /run/token
+      mode: 0o400
+    volumes:
+    - data:/data:ro
+    - type: bind
+      source: ./config
+      target: /config
+      read_only: true
+  db:
+    cap_add:
+    - NET_ADMIN
+    healthcheck:
+      test:
+      - CMD
+      - pg_isready
+    image: db
+    network_mode: host
+    ulimits:
+      nofile:
+        soft: 1024
+        hard: 2048
+networks:
+  backend:
+    internal: true
+    ipam:
+      config:
+      - subnet: 10.0.0.0/24
+        gateway: 10.0.0.1
+volumes:
+  data:
+    name: app-data
+secrets:
+  token:
+    external: true
+";
+        let compose: Compose = serde_yaml::from_str(yaml).unwrap();
+        let quadlet = Quadlet::from_compose(&compose, None);
+        assert!(quadlet.unsupported.is_empty());
+        assert_eq!(quadlet.to_compose(), compose);
+    }
+
+    #[test]
+    fn handwritten() {
+        let files = [
+            (
+                "web.container",
+                "\
+[Unit]
+Description=Web server
+Requires=api.service network-online.target
+After=api.service network-online.target
+
+[Container]
+Image=docker.io/library/nginx:latest
+Pod=app.pod
+Volume=%h/site:/usr/share/nginx/html:ro,Z
+Environment=A=1 \"B=two words\"
+PodmanArgs=--tty --cpus 2 --log-level debug
+
+[Install]
+WantedBy=multi-user.target
+",
+            ),
+            (
+                "api.container",
+                "\
+[Container]
+Image=api
+Pod=app.pod
+Network=
+Notify=healthy
+",
+            ),
+            (
+                "app.pod",
+                "\
+[Pod]
+PodName=app
+PublishPort=8080:80
+Network=frontend.network
+",
+            ),
+            ("frontend.network", "[Network]\nDisableDNS=true\n"),
+        ];
+        let files = files
+            .into_iter()
+            .map(|(name, contents)| File::parse(name, contents).unwrap())
+            .collect();
+        let quadlet = Quadlet {
+            files,
+            unsupported: Vec::new(),
+        };
+
+        let expected: Compose = serde_yaml::from_str(
+            "\
+services:
+  web:
+    image: docker.io/library/nginx:latest
+    environment:
+      A: '1'
+      B: two words
+    network_mode: service:api
+    depends_on:
+      api:
+        condition: service_healthy
+    tty: true
+    cpus: 2.0
+    x-quadlet:
+      Unit:
+        Description: Web server
+        Requires: network-online.target
+        After: network-online.target
+      Container:
+        Volume: '%h/site:/usr/share/nginx/html:ro,Z'
+        PodmanArgs: --log-level debug
+      Install:
+        WantedBy: multi-user.target
+  api:
+    image: api
+    ports:
+    - 8080:80
+    networks:
+    - frontend
+networks:
+  frontend:
+    x-quadlet:
+      Network:
+        DisableDNS: 'true'
+x-quadlet:
+  app.pod:
+    Pod:
+      PodName: app
+",
+        )
+        .unwrap();
+        let compose = quadlet.to_compose();
+        assert_eq!(compose, expected);
+        assert_no_dependency_cycle(&compose);
+    }
+
+    /// Assert that no service in the `compose` file depends on itself, directly or through other
+    /// services.
+    fn assert_no_dependency_cycle(compose: &Compose) {
+        for name in compose.services.keys() {
+            let mut visited = IndexSet::new();
+            let mut stack = vec![name];
+            while let Some(service) = stack.pop() {
+                let dependencies = compose
+                    .services
+                    .get(service)
+                    .map(Service::service_dependencies)
+                    .unwrap_or_default();
+                for dependency in dependencies {
+                    assert_ne!(dependency, name, "dependency cycle through `{name}`");
+                    if visited.insert(dependency) {
+                        stack.push(dependency);
+                    }
+                }
+            }
+        }
+    }
+}
@@ -0,0 +1,711 @@
+//! Conversion of a Quadlet `.container` file into a [`Service`].
+
+use std::{mem, net::IpAddr, path::PathBuf, str::FromStr, time::Duration};
+
+use indexmap::IndexMap;
+
+use crate::{
+    common::item_or_list,
+    duration,
+    service::{
+        device::CgroupRule,
+        healthcheck::{self, Test},
+        network_config::{Network, NetworkMode},
+        parse_ulimit,
+        ports::ShortPort,
+        volumes::{mount::parse_mount_flag, Mount, ShortOptions, ShortVolume, Source},
+        AbsolutePath, ByteValue, Capability, Cgroup, Command, ConfigOrSecret, CpuSet, Cpus,
+        Dependency, Device, EnvFile, Expose, Healthcheck, Hostname, IdOrName, Image, Ipc, Limit,
+        Logging, MacAddress, NetworkConfig, OomScoreAdj, Percent, Pid, PullPolicy, Restart,
+        SecurityOpt, Signal, TmpfsEntry, User, UsernsMode, Uts,
+    },
+    systemd::{quote, split_words, unescape_specifiers, Section, Unit},
+    Compose, Extensions, Identifier, ListOrMap, Map, MapKey, Resource, Service, ShortOrLong,
+};
+
+use super::{extend_map, key_values, parse_bool};
+
+/// Convert a `.container` file [`Unit`] into a [`Service`].
+///
+/// `containers` are the names of all `.container` files, used to resolve dependencies. Networks,
+/// volumes, and secrets the container uses which are not yet defined in the `compose` file are
+/// added to it.
+///
+/// Returns the service and the remaining entries which could not be converted.
+pub(super) fn convert(
+    compose: &mut Compose,
+    containers: &[&Identifier],
+    mut unit: Unit,
+) -> (Service, Unit) {
+    let mut importer = Importer {
+        compose,
+        containers,
+        service: Service::default(),
+        environment: Map::new(),
+        labels: Map::new(),
+        annotations: Map::new(),
+        sysctls: Map::new(),
+        env_files: Vec::new(),
+        label_files: Vec::new(),
+        tmpfs: Vec::new(),
+        dns: Vec::new(),
+        dns_search: Vec::new(),
+        networks: IndexMap::new(),
+        network_mode: None,
+        podman_args: Vec::new(),
+    };
+
+    let section = unit.section_mut("Container");
+    for (key, value) in mem::take(&mut section.entries) {
+        if importer.entry(&key, &value).is_none() {
+            section.push(key, value);
+        }
+    }
+    if !importer.podman_args.is_empty() {
+        let args: Vec<String> = importer.podman_args.iter().map(|arg| quote(arg)).collect();
+        section.push("PodmanArgs", args.join(" "));
+    }
+    if let Some(section) = unit.sections.get_mut("Unit") {
+        importer.dependencies(section);
+    }
+    if let Some(section) = unit.sections.get_mut("Service") {
+        importer.restart(section);
+    }
+    if let Some(section) = unit.sections.get_mut("Install") {
+        importer.install(section);
+    }
+
+    (importer.finish(), unit)
+}
+
+/// State for converting a `.container` file into a [`Service`].
+struct Importer<'a> {
+    /// Compose file the service is added to.
+    compose: &'a mut Compose,
+
+    /// Names of all `.container` files.
+    containers: &'a [&'a Identifier],
+
+    /// Service being converted into.
+    service: Service,
+
+    /// `Environment=` entries.
+    environment: Map,
+
+    /// `Label=` entries.
+    labels: Map,
+
+    /// `Annotation=` entries.
+    annotations: Map,
+
+    /// `Sysctl=` entries.
+    sysctls: Map,
+
+    /// `EnvironmentFile=` entries.
+    env_files: Vec<PathBuf>,
+
+    /// `--label-file` Podman arguments.
+    label_files: Vec<PathBuf>,
+
+    /// `Tmpfs=` entries.
+    tmpfs: Vec<TmpfsEntry>,
+
+    /// `DNS=` entries.
+    dns: Vec<IpAddr>,
+
+    /// `DNSSearch=` entries.
+    dns_search: Vec<Hostname>,
+
+    /// `Network=` entries which attach the container to a network.
+    networks: IndexMap<Identifier, Option<Network>>,
+
+    /// `Network=` entry which sets the network mode.
+    network_mode: Option<NetworkMode>,
+
+    /// `PodmanArgs=` arguments which could not be converted.
+    podman_args: Vec<String>,
+}
+
+impl Importer<'_> {
+    /// Convert a `[Container]` entry.
+    ///
+    /// Returns [`None`] if the entry could not be converted, in which case nothing is changed.
+    fn entry(&mut self, key: &str, value: &str) -> Option<()> {
+        let service = &mut self.service;
+        match key {
+            "Image" => {
+                // Images built or pulled by other Quadlet files.
+                let image = parse::<Image>(value).filter(|image| {
+                    !image
+                        .as_str()
+                        .rsplit_once('.')
+                        .is_some_and(|(_, extension)| matches!(extension, "image" | "build"))
+                })?;
+                service.image = Some(image);
+            }
+            "ContainerName" => service.container_name = Some(identifier(value)?),
+            "HostName" => service.hostname = Some(Hostname::new(unescape_specifiers(value)?).ok()?),
+            "Entrypoint" => {
+                let entrypoint = unescape_specifiers(value)?;
+                let entrypoint = if entrypoint.starts_with('[') {
+                    serde_yaml::from_str(&entrypoint).ok()?
+                } else {
+                    vec![entrypoint]
+                };
+                service.entrypoint = Some(Command::List(entrypoint));
+            }
+            "Exec" => service.command = Some(Command::List(split_words(value)?)),
+            "User" => service.user = Some(parse::<User>(value)?),
+            "WorkingDir" => service.working_dir = Some(AbsolutePath::new(word(value)?).ok()?),
+            "Environment" => extend_map(&mut self.environment, value)?,
+            "EnvironmentFile" => self.env_files.push(word(value)?.into()),
+            "Label" => extend_map(&mut self.labels, value)?,
+            "Annotation" => extend_map(&mut self.annotations, value)?,
+            "PublishPort" => {
+                let port = parse::<ShortPort>(value)?;
+                service.ports.insert(ShortOrLong::Short(port));
+            }
+            "ExposeHostPort" => {
+                service.expose.insert(parse::<Expose>(value)?);
+            }
+            "Volume" => self.volume(value)?,
+            "Mount" => self.mount(value)?,
+            "Tmpfs" => self.tmpfs.push(word(value)?.parse().ok()?),
+            "Network" => self.network(value)?,
+            "DNS" => self.dns.push(value.parse().ok()?),
+            "DNSOption" => service.dns_opt.extend(split_words(value)?),
+            "DNSSearch" => self.dns_search.push(Hostname::new(value).ok()?),
+            "AddHost" => {
+                let (host, ip) = value.split_once(':')?;
+                let ip = ip.parse().ok()?;
+                service.extra_hosts.insert(Hostname::new(host).ok()?, ip);
+            }
+            "HealthCmd" | "HealthInterval" | "HealthTimeout" | "HealthStartPeriod"
+            | "HealthRetries" => self.healthcheck(key, value)?,
+            _ => self.resource_entry(key, value)?,
+        }
+        Some(())
+    }
+
+    /// Convert a `[Container]` entry for container resources, security, or lifecycle.
+    ///
+    /// Returns [`None`] if the entry could not be converted, in which case nothing is changed.
+    fn resource_entry(&mut self, key: &str, value: &str) -> Option<()> {
+        let service = &mut self.service;
+        match key {
+            "AddCapability" | "DropCapability" => {
+                let capabilities = split_words(value)?.into_iter().map(Capability::parse);
+                if key == "AddCapability" {
+                    service.cap_add.extend(capabilities);
+                } else {
+                    service.cap_drop.extend(capabilities);
+                }
+            }
+            "AddDevice" => {
+                service.devices.insert(word(value)?.parse::<Device>().ok()?);
+            }
+            "GroupAdd" => {
+                service.group_add.insert(IdOrName::parse(value).ok()?);
+            }
+            "ReadOnly" => service.read_only = parse_bool(value)?,
+            "RunInit" => service.init = parse_bool(value)?,
+            "UserNS" => service.userns_mode = Some(parse::<UsernsMode>(value)?),
+            "Sysctl" => extend_map(&mut self.sysctls, value)?,
+            "Ulimit" => self.ulimit(value)?,
+            "ShmSize" => service.shm_size = Some(parse::<ByteValue>(value)?),
+            "PidsLimit" => service.pids_limit = Some(parse::<Limit<u32>>(value)?),
+            "Memory" => service.mem_limit = Some(parse::<ByteValue>(value)?),
+            "StopSignal" => service.stop_signal = Some(parse::<Signal>(value)?),
+            "StopTimeout" => {
+                service.stop_grace_period = Some(Duration::from_secs(value.parse().ok()?));
+            }
+            "Pull" => {
+                service.pull_policy = Some(match value {
+                    "always" => PullPolicy::Always,
+                    "never" => PullPolicy::Never,
+                    "missing" => PullPolicy::Missing,
+                    "newer" => PullPolicy::Refresh,
+                    _ => return None,
+                });
+            }
+            "LogDriver" => {
+                let driver = unescape_specifiers(value)?;
+                service.logging.get_or_insert_with(Logging::default).driver = Some(driver);
+            }
+            "LogOpt" => {
+                let options = key_values(value)?
+                    .into_iter()
+                    .map(|(key, value)| (key, Some(value.into())));
+                service
+                    .logging
+                    .get_or_insert_with(Logging::default)
+                    .options
+                    .extend(options);
+            }
+            "Secret" => self.secret(value)?,
+            "PodmanArgs" => self.podman_args(value)?,
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Convert a `Volume=` entry.
+    fn volume(&mut self, value: &str) -> Option<()> {
+        let volume = word(value)?;
+        // Volumes defined by `.volume` files.
+        let volume = match volume.split_once(':') {
+            Some((source, rest)) if source.ends_with(".volume") => {
+                format!("{}:{rest}", source.trim_end_matches(".volume"))
+            }
+            _ => volume,
+        };
+        let volume: ShortVolume = volume.parse().ok()?;
+
+        if let Some(ShortOptions {
+            source: Source::Volume(source),
+            ..
+        }) = &volume.options
+        {
+            // Podman creates missing volumes.
+            self.compose.volumes.entry(source.clone()).or_insert(None);
+        }
+        self.service.volumes.insert(ShortOrLong::Short(volume));
+        Some(())
+    }
+
+    /// Convert a `Mount=` entry of the `bind`, `volume`, `tmpfs`, or `image` type.
+    fn mount(&mut self, value: &str) -> Option<()> {
+        // Volumes are referenced by their `.volume` file.
+        let value = word(value)?;
+        let options = value.split(',').map(|option| match option.split_once('=') {
+            Some((key @ ("source" | "src"), source)) => {
+                format!("{key}={}", source.trim_end_matches(".volume"))
+            }
+            _ => option.to_owned(),
+        });
+        let mount = parse_mount_flag(options)?;
+        if let Mount::Volume(volume) = &mount {
+            if let Some(source) = &volume.source {
+                self.compose.volumes.entry(source.clone()).or_insert(None);
+            }
+        }
+        self.service.volumes.insert(ShortOrLong::Long(mount));
+        Some(())
+    }
+
+    /// Convert a `Network=` entry.
+    fn network(&mut self, value: &str) -> Option<()> {
+        let value = unescape_specifiers(value)?;
+        let (name, options) = value
+            .split_once(':')
+            .map_or((value.as_str(), None), |(name, options)| {
+                (name, Some(options))
+            });
+
+        if let Some(service) = name.strip_suffix(".container") {
+            if options.is_some() {
+                return None;
+            }
+            return self.network_mode(NetworkMode::Service(Identifier::new(service).ok()?));
+        }
+        if matches!(
+            name,
+            "host" | "none" | "bridge" | "private" | "slirp4netns" | "pasta" | "container" | "ns"
+        ) {
+            return self.network_mode(NetworkMode::parse(value.clone()).ok()?);
+        }
+
+        let (network, resource) = match name.strip_suffix(".network") {
+            Some(network) => (Identifier::new(network).ok()?, None),
+            // Podman does not create missing networks.
+            None => (
+                Identifier::new(name).ok()?,
+                Some(Resource::External { name: None }),
+            ),
+        };
+        let options = match options {
+            Some(options) => Some(network_options(options)?),
+            None => None,
+        };
+        if self.network_mode.is_some() || self.networks.contains_key(&network) {
+            return None;
+        }
+        self.compose
+            .networks
+            .entry(network.clone())
+            .or_insert(resource);
+        self.networks.insert(network, options);
+        Some(())
+    }
+
+    /// Set the network mode, unless the container is already attached to a network.
+    fn network_mode(&mut self, network_mode: NetworkMode) -> Option<()> {
+        if self.network_mode.is_some() || !self.networks.is_empty() {
+            return None;
+        }
+        self.network_mode = Some(network_mode);
+        Some(())
+    }
+
+    /// Convert a `Health*=` entry.
+    fn healthcheck(&mut self, key: &str, value: &str) -> Option<()> {
+        if key == "HealthCmd" && value == "none" {
+            if self.service.healthcheck.is_some() {
+                return None;
+            }
+            self.service.healthcheck = Some(Healthcheck::Disable);
+            return Some(());
+        }
+
+        let Healthcheck::Command(command) = self
+            .service
+            .healthcheck
+            .get_or_insert_with(Healthcheck::default)
+        else {
+            return None;
+        };
+        let healthcheck::Command {
+            test,
+            interval,
+            timeout,
+            retries,
+            start_period,
+            ..
+        } = command;
+        match key {
+            "HealthCmd" => {
+                let value = unescape_specifiers(value)?;
+                *test = Some(if value.starts_with('[') {
+                    Test::Command(serde_yaml::from_str(&value).ok()?)
+                } else {
+                    Test::ShellCommand(value)
+                });
+            }
+            "HealthInterval" => *interval = Some(duration::parse(value).ok()?),
+            "HealthTimeout" => *timeout = Some(duration::parse(value).ok()?),
+            "HealthStartPeriod" => *start_period = Some(duration::parse(value).ok()?),
+            "HealthRetries" => *retries = Some(value.parse().ok()?),
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Convert an `Ulimit=` entry in the `{resource}={soft}[:{hard}]` format.
+    fn ulimit(&mut self, value: &str) -> Option<()> {
+        let (resource, ulimit) = parse_ulimit(value)?;
+        self.service.ulimits.insert(resource, ulimit);
+        Some(())
+    }
+
+    /// Convert a `Secret=` entry in the `{secret}[,{option}=value]...` format.
+    fn secret(&mut self, value: &str) -> Option<()> {
+        let value = word(value)?;
+        let mut options = value.split(',');
+        let source = Identifier::new(options.next()?).ok()?;
+
+        let mut secret = ConfigOrSecret {
+            source: source.clone(),
+            target: None,
+            uid: None,
+            gid: None,
+            mode: None,
+            extensions: Extensions::new(),
+        };
+        for option in options {
+            let (key, value) = option.split_once('=')?;
+            match key {
+                "type" if value == "mount" => {}
+                "target" => secret.target = Some(value.into()),
+                "uid" => secret.uid = Some(value.parse().ok()?),
+                "gid" => secret.gid = Some(value.parse().ok()?),
+                "mode" => secret.mode = Some(u32::from_str_radix(value, 8).ok()?),
+                _ => return None,
+            }
+        }
+
+        // Podman secrets are created outside of Quadlet.
+        self.compose
+            .secrets
+            .entry(source.clone())
+            .or_insert(Resource::External { name: None });
+        self.service.secrets.push(
+            if secret.target.is_none()
+                && secret.uid.is_none()
+                && secret.gid.is_none()
+                && secret.mode.is_none()
+            {
+                ShortOrLong::Short(source)
+            } else {
+                ShortOrLong::Long(secret)
+            },
+        );
+        Some(())
+    }
+
+    /// Convert a `PodmanArgs=` entry.
+    ///
+    /// Arguments which could not be converted are kept to be added back as a single `PodmanArgs=`
+    /// entry.
+    fn podman_args(&mut self, value: &str) -> Option<()> {
+        let mut args = split_words(value)?.into_iter().peekable();
+        while let Some(arg) = args.next() {
+            let (flag, value) = match arg.split_once('=') {
+                Some((flag, value)) => (flag, Some(value.to_owned())),
+                None if !is_bool_flag(&arg)
+                    && args.peek().is_some_and(|next| !next.starts_with('-')) =>
+                {
+                    (arg.as_str(), args.next())
+                }
+                None => (arg.as_str(), None),
+            };
+            if self.podman_arg(flag, value.as_deref()).is_none() {
+                self.podman_args.push(arg.clone());
+                if !arg.contains('=') {
+                    self.podman_args.extend(value);
+                }
+            }
+        }
+        Some(())
+    }
+
+    /// Convert a single Podman argument, with its value if it has one.
+    ///
+    /// Returns [`None`] if the argument could not be converted, in which case nothing is changed.
+    fn podman_arg(&mut self, flag: &str, value: Option<&str>) -> Option<()> {
+        let service = &mut self.service;
+        let Some(value) = value else {
+            match flag {
+                "--privileged" => service.privileged = true,
+                "--tty" | "-t" => service.tty = true,
+                "--interactive" | "-i" => service.stdin_open = true,
+                "--oom-kill-disable" => service.oom_kill_disable = true,
+                _ => return None,
+            }
+            return Some(());
+        };
+
+        match flag {
+            "--ipc" => service.ipc = Some(value.parse::<Ipc>().ok()?),
+            "--pid" => service.pid = Some(value.parse::<Pid>().ok()?),
+            "--uts" if value == Uts::Host.as_str() => service.uts = Some(Uts::Host),
+            "--cgroupns" => {
+                service.cgroup = Some(match value {
+                    "host" => Cgroup::Host,
+                    "private" => Cgroup::Private,
+                    _ => return None,
+                });
+            }
+            "--cgroup-parent" => service.cgroup_parent = Some(value.parse().ok()?),
+            "--security-opt" => {
+                service.security_opt.insert(SecurityOpt::parse(value));
+            }
+            "--runtime" => service.runtime = Some(value.to_owned()),
+            "--cpus" => service.cpus = Some(Cpus::new(value.parse::<f64>().ok()?).ok()?),
+            "--cpu-shares" => service.cpu_shares = Some(value.parse().ok()?),
+            "--cpu-period" => service.cpu_period = Some(micros(value)?),
+            "--cpu-quota" => service.cpu_quota = Some(micros(value)?),
+            "--cpu-rt-period" => service.cpu_rt_period = Some(micros(value)?),
+            "--cpu-rt-runtime" => service.cpu_rt_runtime = Some(micros(value)?),
+            "--cpuset-cpus" => service.cpuset = value.parse::<CpuSet>().ok()?,
+            "--memory-reservation" => service.mem_reservation = Some(value.parse().ok()?),
+            "--memory-swap" => service.memswap_limit = Some(value.parse().ok()?),
+            "--memory-swappiness" => {
+                service.mem_swappiness = Some(Percent::new(value.parse().ok()?).ok()?);
+            }
+            "--oom-score-adj" => {
+                service.oom_score_adj = Some(OomScoreAdj::new(value.parse().ok()?).ok()?);
+            }
+            "--storage-opt" => {
+                let (key, value) = value.split_once('=')?;
+                service
+                    .storage_opt
+                    .insert(MapKey::new(key).ok()?, Some(value.into()));
+            }
+            "--mac-address" => service.mac_address = Some(value.parse::<MacAddress>().ok()?),
+            "--device-cgroup-rule" => {
+                service
+                    .device_cgroup_rules
+                    .insert(value.parse::<CgroupRule>().ok()?);
+            }
+            "--label-file" => self.label_files.push(value.into()),
+            _ => return None,
+        }
+        Some(())
+    }
+
+    /// Convert `Requires=` and `Wants=` entries of the `[Unit]` section which refer to other
+    /// containers into dependencies.
+    ///
+    /// The dependency units are removed from those entries and from `After=` entries, as
+    /// dependencies are also started first.
+    fn dependencies(&mut self, section: &mut Section) {
+        let mut depends_on = IndexMap::new();
+        for (key, value) in &section.entries {
+            let required = match key.as_str() {
+                "Requires" => true,
+                "Wants" => false,
+                _ => continue,
+            };
+            for unit in split_words(value).unwrap_or_default() {
+                if let Some(service) = self.container(&unit) {
+                    depends_on.entry(service).or_insert(Dependency {
+                        required,
+                        ..Dependency::default()
+                    });
+                }
+            }
+        }
+        if depends_on.is_empty() {
+            return;
+        }
+
+        let entries = mem::take(&mut section.entries);
+        for (key, value) in entries {
+            let units = split_words(&value)
+                .filter(|_| matches!(key.as_str(), "Requires" | "Wants" | "After"));
+            let Some(units) = units else {
+                section.push(key, value);
+                continue;
+            };
+            let count = units.len();
+            let units: Vec<String> = units
+                .into_iter()
+                .filter(|unit| {
+                    !self
+                        .container(unit)
+                        .is_some_and(|service| depends_on.contains_key(&service))
+                })
+                .collect();
+            if units.len() == count {
+                section.push(key, value);
+            } else if !units.is_empty() {
+                let units: Vec<String> = units.iter().map(|unit| quote(unit)).collect();
+                section.push(key, units.join(" "));
+            }
+        }
+        self.service.depends_on = ShortOrLong::Long(depends_on);
+    }
+
+    /// Name of the container for a `{container}.service` unit, if it is one of the containers.
+    fn container(&self, unit: &str) -> Option<Identifier> {
+        let name = unit.strip_suffix(".service")?;
+        self.containers
+            .iter()
+            .find(|container| container.as_str() == name)
+            .map(|container| (*container).clone())
+    }
+
+    /// Convert the `Restart=` entry of the `[Service]` section.
+    fn restart(&mut self, section: &mut Section) {
+        let restart = section.get("Restart").and_then(|restart| match restart {
+            "no" => Some(Restart::No),
+            "always" => Some(Restart::Always),
+            "on-failure" => Some(Restart::OnFailure { max_retries: None }),
+            _ => None,
+        });
+        if restart.is_some() {
+            section.entries.retain(|(key, _)| key != "Restart");
+            self.service.restart = restart;
+        }
+    }
+
+    /// Remove `default.target` from `WantedBy=` entries of the `[Install]` section if the container
+    /// is always restarted, as Compose also starts those containers when the engine starts.
+    fn install(&self, section: &mut Section) {
+        if self.service.restart != Some(Restart::Always) {
+            return;
+        }
+        section.entries.retain(|(key, value)| {
+            key != "WantedBy" || value.split_whitespace().ne(["default.target"])
+        });
+    }
+
+    /// Set the remaining collected fields of the service and return it.
+    fn finish(self) -> Service {
+        let Self {
+            compose: _,
+            containers: _,
+            mut service,
+            environment,
+            labels,
+            annotations,
+            sysctls,
+            env_files,
+            label_files,
+            tmpfs,
+            dns,
+            dns_search,
+            networks,
+            network_mode,
+            podman_args: _,
+        } = self;
+
+        for (field, map) in [
+            (&mut service.environment, environment),
+            (&mut service.labels, labels),
+            (&mut service.annotations, annotations),
+            (&mut service.sysctls, sysctls),
+        ] {
+            if !map.is_empty() {
+                *field = ListOrMap::Map(map);
+            }
+        }
+        service.env_file = EnvFile::from_paths(env_files);
+        service.label_file = item_or_list(label_files);
+        service.tmpfs = item_or_list(tmpfs);
+        service.dns = item_or_list(dns);
+        service.dns_search = item_or_list(dns_search);
+
+        service.network_config = NetworkConfig::from_parts(network_mode, networks);
+
+        service
+    }
+}
+
+/// Returns `true` if the Podman flag does not take a value.
+fn is_bool_flag(flag: &str) -> bool {
+    matches!(
+        flag,
+        "--privileged" | "--tty" | "-t" | "--interactive" | "-i" | "--oom-kill-disable"
+    ) || !flag.starts_with('-')
+}
+
+/// Parse a value after unescaping specifiers.
+fn parse<T: FromStr>(value: &str) -> Option<T> {
+    unescape_specifiers(value)?.parse().ok()
+}
+
+/// Split a value which must be a single word.
+fn word(value: &str) -> Option<String> {
+    let [word] = <[String; 1]>::try_from(split_words(value)?).ok()?;
+    Some(word)
+}
+
+/// Parse an [`Identifier`] after unescaping specifiers.
+fn identifier(value: &str) -> Option<Identifier> {
+    Identifier::new(unescape_specifiers(value)?).ok()
+}
+
+/// Parse a duration in microseconds.
+fn micros(value: &str) -> Option<Duration> {
+    value.parse().ok().map(Duration::from_micros)
+}
+
+/// Parse the `{option}={value}[,...]` options of a `Network=` entry.
+fn network_options(options: &str) -> Option<Network> {
+    let mut network = Network::default();
+    for option in options.split(',') {
+        let (key, value) = option.split_once('=')?;
+        match key {
+            "alias" => {
+                network.aliases.insert(Hostname::new(value).ok()?);
+            }
+            "ip" => network.ipv4_address = Some(value.parse().ok()?),
+            "ip6" => network.ipv6_address = Some(value.parse().ok()?),
+            "mac" => network.mac_address = Some(value.parse().ok()?),
+            "interface_name" => network.interface_name = Some(value.to_owned()),
+            _ => return None,
+        }
+    }
+    Some(network)
+}
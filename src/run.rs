@@ -0,0 +1,1562 @@
+//! Conversion of a [`Service`] into an equivalent `docker run` or `podman run` command line and
+//! back.
+//!
+//! [`RunCommand`] is useful for reproducing a single service outside of a Compose implementation,
+//! e.g. for debugging. [`RunService`] turns a command line, such as one from a README, into a
+//! service. [`SystemdUnits`] runs the services of a project with systemd.
+
+mod parse;
+mod units;
+
+use std::{
+    fmt::{self, Display, Formatter},
+    path::Path,
+    time::Duration,
+};
+
+use thiserror::Error;
+
+use crate::{
+    duration,
+    engine::{mac_address_string, service_networks, Project},
+    service::{
+        blkio_config::{BpsLimit, IopsLimit, WeightDevice},
+        depends_on_is_empty,
+        deploy::resources::{Capability, Count, Device, Reservations, Resources},
+        healthcheck::{self, Test},
+        image::InvalidImageError,
+        network_config,
+        ports::{Port, ShortPort},
+        volumes::{
+            mount::{self, Bind, BindOptions, BindRecursive},
+            Mount, ShortOptions, ShortVolume, Source,
+        },
+        BlkioConfig, Command, Deploy, Healthcheck, Ipc, Logging, NetworkConfig, Pid, PullPolicy,
+        UsernsMode, VolumesFromSource,
+    },
+    shell, Extensions, Identifier, Service, ShortOrLong,
+};
+
+pub use self::units::{SystemdUnit, SystemdUnits, Unsupported};
+
+/// Container engine whose command line interface is used.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Engine {
+    /// `docker run`
+    #[default]
+    Docker,
+
+    /// `podman run`
+    Podman,
+}
+
+impl Engine {
+    /// Name of the engine's program.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Docker => "docker",
+            Self::Podman => "podman",
+        }
+    }
+}
+
+impl Display for Engine {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// A `docker run` or `podman run` command line for a [`Service`].
+///
+/// [`Display`] joins the arguments with spaces, quoting them for use in a POSIX shell.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct RunCommand {
+    /// Program and its arguments, e.g. `["docker", "run", "--name", "app-web-1", ...]`.
+    pub args: Vec<String>,
+
+    /// Fields of the service which have no equivalent `run` flag and were not converted, e.g.
+    /// `depends_on` or `volumes.bind.recursive`.
+    pub unsupported: Vec<String>,
+}
+
+impl RunCommand {
+    /// Render the [`Service`] named `name` of the `project` as a `run` command line for the
+    /// `engine`.
+    ///
+    /// Names of containers, networks, and volumes are resolved with the `project`, relative host
+    /// paths are resolved against its working directory. Configs and secrets with a file source
+    /// are bind mounted. Resource limits and memory reservations from `deploy` are used when the
+    /// equivalent service field is not set, and GPU device reservations become `--gpus` flags. The
+    /// rest of `deploy` has no equivalent and is listed in [`unsupported`](Self::unsupported). The
+    /// service's networks and volumes must already exist.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use compose_spec::{
+    ///     engine::Project,
+    ///     run::{Engine, RunCommand},
+    ///     Compose, Identifier, Name,
+    /// };
+    ///
+    /// let yaml = "\
+    /// services:
+    ///   caddy:
+    ///     image: docker.io/library/caddy:latest
+    ///     command: caddy file-server --root '/srv/my site'
+    ///     ports:
+    ///       - 8080:80
+    ///     depends_on:
+    ///       - php
+    ///   php:
+    ///     image: docker.io/library/php:fpm
+    /// ";
+    /// let compose: Compose = serde_yaml::from_str(yaml)?;
+    /// let name = Name::new("app")?;
+    /// let project = Project::new(&name, &compose, Path::new("/srv/app"));
+    ///
+    /// let caddy = Identifier::new("caddy")?;
+    /// let run =
+    ///     RunCommand::from_service(&project, &caddy, &compose.services[&caddy], Engine::Docker);
+    /// assert_eq!(
+    ///     run.to_string(),
+    ///     "docker run --name app-caddy-1 --publish 8080:80 \
+    ///     --network name=app_default,alias=caddy docker.io/library/caddy:latest \
+    ///     caddy file-server --root '/srv/my site'",
+    /// );
+    /// assert_eq!(run.unsupported, ["depends_on"]);
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[allow(clippy::too_many_lines)]
+    #[must_use]
+    pub fn from_service(
+        project: &Project,
+        name: &Identifier,
+        service: &Service,
+        engine: Engine,
+    ) -> Self {
+        let Service {
+            attach,
+            build,
+            blkio_config,
+            cpu_count,
+            cpu_percent,
+            cpu_shares,
+            cpu_period,
+            cpu_quota,
+            cpu_rt_runtime,
+            cpu_rt_period,
+            cpus,
+            cpuset,
+            cap_add,
+            cap_drop,
+            cgroup,
+            cgroup_parent,
+            command,
+            configs,
+            container_name: _,
+            credential_spec,
+            depends_on,
+            deploy,
+            develop,
+            device_cgroup_rules,
+            devices,
+            dns,
+            dns_opt,
+            dns_search,
+            domain_name,
+            entrypoint,
+            env_file,
+            environment,
+            expose,
+            extends,
+            annotations,
+            external_links,
+            extra_hosts,
+            group_add,
+            healthcheck,
+            hostname,
+            image,
+            init,
+            ipc,
+            uts,
+            isolation,
+            labels,
+            label_file,
+            links,
+            logging,
+            network_config,
+            mac_address,
+            mem_limit,
+            mem_reservation,
+            mem_swappiness,
+            memswap_limit,
+            oom_kill_disable,
+            oom_score_adj,
+            pid,
+            pids_limit,
+            platform,
+            ports,
+            privileged,
+            profiles,
+            pull_policy,
+            read_only,
+            restart,
+            runtime,
+            scale,
+            secrets,
+            security_opt,
+            shm_size,
+            stdin_open,
+            stop_grace_period,
+            stop_signal,
+            storage_opt,
+            sysctls,
+            tmpfs,
+            tty,
+            ulimits,
+            user,
+            userns_mode,
+            volumes,
+            volumes_from,
+            working_dir,
+            extensions: _,
+        } = service;
+
+        let (limits, reservations) = deploy
+            .as_ref()
+            .and_then(|Deploy { resources, .. }| resources.as_ref())
+            .map_or(
+                (None, None),
+                |Resources {
+                     limits,
+                     reservations,
+                     ..
+                 }| { (limits.as_ref(), reservations.as_ref()) },
+            );
+
+        let mut renderer = Renderer {
+            project,
+            engine,
+            args: vec![engine.as_str().to_owned(), "run".to_owned()],
+            unsupported: Vec::new(),
+        };
+
+        renderer.flag("--name", project.container_name(name.as_str()));
+        if let Some(hostname) = hostname {
+            renderer.flag("--hostname", hostname.as_str());
+        }
+        if let Some(domain_name) = domain_name {
+            match engine {
+                Engine::Docker => renderer.flag("--domainname", domain_name.as_str()),
+                Engine::Podman => renderer.unsupported("domain_name"),
+            }
+        }
+        if let Some(user) = user {
+            renderer.flag("--user", user.to_string());
+        }
+        if let Some(working_dir) = working_dir {
+            renderer.flag("--workdir", working_dir.as_path().display().to_string());
+        }
+        renderer.switch("--init", *init);
+        renderer.switch("--read-only", *read_only);
+        renderer.switch("--privileged", *privileged);
+        renderer.switch("--tty", *tty);
+        renderer.switch("--interactive", *stdin_open);
+
+        for item in environment.clone().into_list() {
+            // Variables without a value are taken from the environment `run` is called in.
+            renderer.flag("--env", item);
+        }
+        if let Some(env_file) = env_file {
+            for env_file in env_file.clone().into_list() {
+                let env_file = env_file.into_long();
+                if !env_file.required {
+                    renderer.unsupported("env_file.required");
+                }
+                if env_file.format.is_raw() {
+                    renderer.unsupported("env_file.format");
+                }
+                renderer.path_flag("--env-file", &env_file.path);
+            }
+        }
+        for label in labels.clone().into_list() {
+            renderer.flag("--label", label);
+        }
+        if let Some(label_file) = label_file {
+            for path in label_file.clone().into_list() {
+                renderer.path_flag("--label-file", &path);
+            }
+        }
+        for annotation in annotations.clone().into_list() {
+            renderer.flag("--annotation", annotation);
+        }
+
+        for port in ports {
+            renderer.port(port);
+        }
+        for expose in expose {
+            renderer.flag("--expose", expose.to_string());
+        }
+        for volume in volumes {
+            match volume {
+                ShortOrLong::Short(volume) => renderer.short_volume(volume),
+                ShortOrLong::Long(mount) => renderer.mount(mount),
+            }
+        }
+        if let Some(tmpfs) = tmpfs {
+            for tmpfs in tmpfs.clone().into_list() {
+                renderer.flag("--tmpfs", tmpfs.to_string());
+            }
+        }
+        for volumes_from in volumes_from {
+            let mut container = match &volumes_from.source {
+                VolumesFromSource::Service(service) => project.container_name(service.as_str()),
+                VolumesFromSource::Container(container) => container.to_string(),
+            };
+            if volumes_from.read_only {
+                container.push_str(":ro");
+            }
+            renderer.flag("--volumes-from", container);
+        }
+        for config in configs {
+            match project.config_file(config) {
+                Some((source, target)) => renderer.bind_file(&source, &target),
+                None => renderer.unsupported("configs"),
+            }
+        }
+        for secret in secrets {
+            match project.secret_file(secret) {
+                Some((source, target)) => renderer.bind_file(&source, &target),
+                None => renderer.unsupported("secrets"),
+            }
+        }
+
+        renderer.networks(name, network_config.as_ref());
+        if let Some(mac_address) = mac_address {
+            renderer.flag("--mac-address", mac_address_string(*mac_address));
+        }
+        if let Some(dns) = dns {
+            for dns in dns.clone().into_list() {
+                renderer.flag("--dns", dns.to_string());
+            }
+        }
+        for dns_opt in dns_opt {
+            renderer.flag("--dns-option", dns_opt.as_str());
+        }
+        if let Some(dns_search) = dns_search {
+            for dns_search in dns_search.clone().into_list() {
+                renderer.flag("--dns-search", dns_search.as_str());
+            }
+        }
+        for (host, ip) in extra_hosts {
+            renderer.flag("--add-host", format!("{host}:{ip}"));
+        }
+        for link in links {
+            let container = project.container_name(link.service.as_str());
+            let alias = link.alias.as_deref().unwrap_or(link.service.as_str());
+            renderer.flag("--link", format!("{container}:{alias}"));
+        }
+        for link in external_links {
+            renderer.flag("--link", link.to_string());
+        }
+
+        if let Some(healthcheck) = healthcheck {
+            renderer.healthcheck(healthcheck);
+        }
+
+        for capability in cap_add {
+            renderer.flag("--cap-add", capability.to_string());
+        }
+        for capability in cap_drop {
+            renderer.flag("--cap-drop", capability.to_string());
+        }
+        for device in devices {
+            renderer.flag("--device", device.to_string());
+        }
+        for rule in device_cgroup_rules {
+            renderer.flag("--device-cgroup-rule", rule.to_string());
+        }
+        for group in group_add {
+            renderer.flag("--group-add", group.to_string());
+        }
+        for security_opt in security_opt {
+            renderer.flag("--security-opt", security_opt.to_string());
+        }
+        if let Some(ipc) = ipc {
+            let ipc = match ipc {
+                Ipc::Service(service) => {
+                    format!("container:{}", project.container_name(service.as_str()))
+                }
+                ipc => ipc.to_string(),
+            };
+            renderer.flag("--ipc", ipc);
+        }
+        if let Some(pid) = pid {
+            let pid = match pid {
+                Pid::Service(service) => {
+                    format!("container:{}", project.container_name(service.as_str()))
+                }
+                pid => pid.to_string(),
+            };
+            renderer.flag("--pid", pid);
+        }
+        if let Some(uts) = uts {
+            renderer.flag("--uts", uts.as_str());
+        }
+        if let Some(cgroup) = cgroup {
+            renderer.flag("--cgroupns", cgroup.as_str());
+        }
+        if let Some(cgroup_parent) = cgroup_parent {
+            renderer.flag("--cgroup-parent", cgroup_parent.to_string());
+        }
+        if let Some(userns_mode) = userns_mode {
+            if engine == Engine::Podman || matches!(userns_mode, UsernsMode::Host) {
+                renderer.flag("--userns", userns_mode.to_string());
+            } else {
+                renderer.unsupported("userns_mode");
+            }
+        }
+        for sysctl in sysctls.clone().into_list() {
+            renderer.flag("--sysctl", sysctl);
+        }
+        for (key, value) in storage_opt {
+            let option = value
+                .as_ref()
+                .map_or_else(|| key.to_string(), |value| format!("{key}={value}"));
+            renderer.flag("--storage-opt", option);
+        }
+        for (resource, ulimit) in ulimits {
+            let (soft, hard) = match ulimit {
+                ShortOrLong::Short(limit) => (limit, limit),
+                ShortOrLong::Long(ulimit) => (&ulimit.soft, &ulimit.hard),
+            };
+            renderer.flag("--ulimit", format!("{resource}={soft}:{hard}"));
+        }
+
+        if let Some(cpus) = cpus.or_else(|| limits.and_then(|limits| limits.cpus)) {
+            renderer.flag("--cpus", cpus.into_inner().to_string());
+        }
+        if let Some(cpu_shares) = cpu_shares {
+            renderer.flag("--cpu-shares", cpu_shares.to_string());
+        }
+        for (flag, duration) in [
+            ("--cpu-period", cpu_period),
+            ("--cpu-quota", cpu_quota),
+            ("--cpu-rt-period", cpu_rt_period),
+            ("--cpu-rt-runtime", cpu_rt_runtime),
+        ] {
+            if let Some(duration) = duration {
+                renderer.flag(flag, duration.as_micros().to_string());
+            }
+        }
+        if !cpuset.is_empty() {
+            renderer.flag("--cpuset-cpus", cpuset.to_string());
+        }
+        for (field, flag, value) in [
+            (
+                "cpu_count",
+                "--cpu-count",
+                cpu_count.map(|count| count.to_string()),
+            ),
+            (
+                "cpu_percent",
+                "--cpu-percent",
+                cpu_percent.map(|percent| u8::from(percent).to_string()),
+            ),
+            ("isolation", "--isolation", isolation.clone()),
+        ] {
+            // Windows only options.
+            if let Some(value) = value {
+                match engine {
+                    Engine::Docker => renderer.flag(flag, value),
+                    Engine::Podman => renderer.unsupported(field),
+                }
+            }
+        }
+        if let Some(mem_limit) = mem_limit.or_else(|| limits.and_then(|limits| limits.memory)) {
+            renderer.flag("--memory", mem_limit.to_string());
+        }
+        if let Some(mem_reservation) =
+            mem_reservation.or_else(|| reservations.and_then(|reservations| reservations.memory))
+        {
+            renderer.flag("--memory-reservation", mem_reservation.to_string());
+        }
+        if let Some(memswap_limit) = memswap_limit {
+            renderer.flag("--memory-swap", memswap_limit.to_string());
+        }
+        if let Some(mem_swappiness) = mem_swappiness {
+            renderer.flag("--memory-swappiness", u8::from(*mem_swappiness).to_string());
+        }
+        renderer.switch("--oom-kill-disable", *oom_kill_disable);
+        if let Some(oom_score_adj) = oom_score_adj {
+            renderer.flag("--oom-score-adj", i16::from(*oom_score_adj).to_string());
+        }
+        if let Some(pids_limit) = pids_limit.or_else(|| limits.and_then(|limits| limits.pids)) {
+            renderer.flag("--pids-limit", pids_limit.to_string());
+        }
+        if let Some(deploy) = deploy {
+            renderer.deploy(deploy);
+        }
+        if let Some(shm_size) = shm_size {
+            renderer.flag("--shm-size", shm_size.to_string());
+        }
+        if let Some(blkio_config) = blkio_config {
+            renderer.blkio_config(blkio_config);
+        }
+
+        if let Some(restart) = restart {
+            renderer.flag("--restart", restart.to_string());
+        }
+        if let Some(stop_signal) = stop_signal {
+            renderer.flag("--stop-signal", stop_signal.to_string());
+        }
+        if let Some(stop_grace_period) = stop_grace_period {
+            renderer.flag(
+                "--stop-timeout",
+                duration::seconds(*stop_grace_period).to_string(),
+            );
+        }
+        if let Some(Logging {
+            driver,
+            options,
+            extensions: _,
+        }) = logging
+        {
+            if let Some(driver) = driver {
+                renderer.flag("--log-driver", driver.as_str());
+            }
+            for (key, value) in options {
+                let option = value
+                    .as_ref()
+                    .map_or_else(|| key.to_string(), |value| format!("{key}={value}"));
+                renderer.flag("--log-opt", option);
+            }
+        }
+        if let Some(pull_policy) = pull_policy {
+            match (pull_policy, engine) {
+                (PullPolicy::Always, _) => renderer.flag("--pull", "always"),
+                (PullPolicy::Never, _) => renderer.flag("--pull", "never"),
+                (PullPolicy::Missing, _) => renderer.flag("--pull", "missing"),
+                (PullPolicy::Refresh, Engine::Podman) => renderer.flag("--pull", "newer"),
+                _ => renderer.unsupported("pull_policy"),
+            }
+        }
+        if let Some(platform) = platform {
+            renderer.flag("--platform", platform.to_string());
+        }
+        if let Some(runtime) = runtime {
+            renderer.flag("--runtime", runtime.as_str());
+        }
+
+        for (field, unsupported) in [
+            ("attach", !attach),
+            ("build", build.is_some()),
+            ("credential_spec", credential_spec.is_some()),
+            ("depends_on", !depends_on_is_empty(depends_on)),
+            ("develop", develop.is_some()),
+            ("extends", extends.is_some()),
+            ("profiles", !profiles.is_empty()),
+            ("scale", scale.is_some_and(|scale| scale != 1)),
+        ] {
+            if unsupported {
+                renderer.unsupported(field);
+            }
+        }
+
+        // The image name Compose would build, for services with only `build`.
+        let image = image
+            .as_ref()
+            .map_or_else(|| format!("{}-{name}", project.name), ToString::to_string);
+        renderer.command(entrypoint.as_ref(), image, command.as_ref());
+
+        let Renderer {
+            args, unsupported, ..
+        } = renderer;
+        Self { args, unsupported }
+    }
+}
+
+impl Display for RunCommand {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut args = self.args.iter();
+        if let Some(arg) = args.next() {
+            f.write_str(&shell::quote(arg))?;
+        }
+        for arg in args {
+            write!(f, " {}", shell::quote(arg))?;
+        }
+        Ok(())
+    }
+}
+
+/// A [`Service`] parsed from a `docker run` or `podman run` command line.
+#[derive(Debug, Clone, PartialEq)]
+pub struct RunService {
+    /// Service converted from the flags, image, and command of the command line.
+    pub service: Service,
+
+    /// Flags which could not be converted into a field of the service.
+    pub unconverted: Vec<Unconverted>,
+}
+
+impl RunService {
+    /// Parse a command line, splitting it into words like a POSIX shell would.
+    ///
+    /// There are no expansions, so variables like `$HOME` are kept as is. See
+    /// [`from_args()`](Self::from_args()) for details on the conversion.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the command line contains an unterminated quote or ends with a
+    /// backslash, or if it could not be parsed.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compose_spec::run::RunService;
+    ///
+    /// let run = RunService::parse(
+    ///     "docker run -d --rm -p 8080:80 -e TZ=UTC \\
+    ///     --health-cmd 'curl -f http://localhost' docker.io/library/nginx:latest",
+    /// )?;
+    /// let service = run.service;
+    /// assert_eq!(service.image.unwrap(), "docker.io/library/nginx:latest");
+    /// assert_eq!(service.ports.len(), 1);
+    /// assert_eq!(run.unconverted.len(), 2);
+    /// assert_eq!(run.unconverted[0].to_string(), "flag `--detach` has no service equivalent");
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    pub fn parse(command: &str) -> Result<Self, ParseRunError> {
+        let args = shell::split(command).ok_or(ParseRunError::Split)?;
+        Self::from_args(args)
+    }
+
+    /// Parse the arguments of a command line.
+    ///
+    /// The arguments may start with `docker run`, `podman run`, `docker container run`, or just
+    /// `run`, or be only the arguments to `run`. Flags are converted into the equivalent
+    /// [`Service`] fields, the first argument which is not a flag is the `image`, and the
+    /// remaining arguments are the `command`.
+    ///
+    /// Flags which are unknown, have no equivalent field (e.g. `--rm`), or have an invalid value are
+    /// added to [`unconverted`](Self::unconverted). Unknown flags are assumed to take a value,
+    /// unless the next argument starts with a dash (-).
+    ///
+    /// Networks and named volumes the service uses are not created by `run`, they must be added
+    /// to the [`Compose`](crate::Compose) file the service is used in, e.g. as external resources.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the arguments are for a command other than `run`, a flag is missing its
+    /// value, or the image is missing or invalid.
+    pub fn from_args<I>(args: I) -> Result<Self, ParseRunError>
+    where
+        I: IntoIterator,
+        I::Item: Into<String>,
+    {
+        parse::convert(args.into_iter().map(Into::into).collect())
+    }
+}
+
+/// A flag of a `run` command line which was not converted into a [`Service`] field.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unconverted {
+    /// The flag, e.g. `--rm`.
+    pub flag: String,
+
+    /// Value of the flag, if it has one.
+    pub value: Option<String>,
+
+    /// Why the flag was not converted.
+    pub reason: Reason,
+}
+
+impl Display for Unconverted {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self {
+            flag,
+            value,
+            reason,
+        } = self;
+        match (reason, value) {
+            (Reason::Unknown, _) => write!(f, "unknown flag `{flag}`"),
+            (Reason::Unsupported, _) => write!(f, "flag `{flag}` has no service equivalent"),
+            (Reason::Invalid, Some(value)) => {
+                write!(f, "invalid value `{value}` for flag `{flag}`")
+            }
+            (Reason::Invalid, None) => write!(f, "invalid use of flag `{flag}`"),
+        }
+    }
+}
+
+/// Reason a flag of a `run` command line was not converted.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum Reason {
+    /// The flag is not known.
+    Unknown,
+
+    /// The flag has no equivalent [`Service`] field.
+    Unsupported,
+
+    /// The flag's value could not be parsed, or the flag cannot be used with other flags.
+    Invalid,
+}
+
+/// Error returned when parsing a `run` command line into a [`RunService`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseRunError {
+    /// The command line could not be split into words as it contains an unterminated quote or
+    /// ends with a backslash.
+    #[error("command line could not be split into words")]
+    Split,
+
+    /// The command line was for a command other than `run`.
+    #[error("`{0}` is not a run command")]
+    NotRun(String),
+
+    /// A flag which requires a value was the last argument.
+    #[error("flag `{0}` requires a value")]
+    MissingValue(String),
+
+    /// There was no image after the flags.
+    #[error("command line does not include an image")]
+    MissingImage,
+
+    /// The image was invalid.
+    #[error("invalid image")]
+    Image(#[from] InvalidImageError),
+}
+
+/// State for rendering a [`Service`] as a [`RunCommand`].
+struct Renderer<'a> {
+    /// The project the service is from, used to resolve names and paths.
+    project: &'a Project<'a>,
+
+    /// Engine the command line is for.
+    engine: Engine,
+
+    /// Program and its arguments.
+    args: Vec<String>,
+
+    /// Fields which could not be converted.
+    unsupported: Vec<String>,
+}
+
+/// Value of a `--gpus` flag for a reserved [`Device`].
+///
+/// Returns [`None`] if the device does not only have the `gpu` capability, or it sets a driver
+/// other than `nvidia` or driver specific options.
+fn gpus(device: &Device) -> Option<String> {
+    let Device {
+        capabilities,
+        driver,
+        count,
+        device_ids,
+        options,
+        extensions: _,
+    } = device;
+
+    if !capabilities
+        .iter()
+        .all(|capability| *capability == Capability::Gpu)
+        || capabilities.is_empty()
+        || driver.as_ref().is_some_and(|driver| driver != "nvidia")
+        || !options.is_empty()
+    {
+        return None;
+    }
+
+    if device_ids.is_empty() {
+        Some(match count.unwrap_or_default() {
+            Count::All => "all".to_owned(),
+            Count::Integer(count) => count.to_string(),
+        })
+    } else {
+        // The value is parsed as CSV, so the comma separated device IDs must be quoted.
+        let device_ids: Vec<&str> = device_ids.iter().map(String::as_str).collect();
+        Some(format!("\"device={}\"", device_ids.join(",")))
+    }
+}
+
+impl Renderer<'_> {
+    /// Push a flag and its value.
+    fn flag<V: Into<String>>(&mut self, flag: &str, value: V) {
+        self.args.push(flag.to_owned());
+        self.args.push(value.into());
+    }
+
+    /// Push a flag with a host path value, resolved against the project's working directory.
+    fn path_flag(&mut self, flag: &str, path: &Path) {
+        let path = self.project.host_path(path);
+        self.flag(flag, path.display().to_string());
+    }
+
+    /// Push a flag without a value if `enabled`.
+    fn switch(&mut self, flag: &str, enabled: bool) {
+        if enabled {
+            self.args.push(flag.to_owned());
+        }
+    }
+
+    /// Add an unsupported field, if it has not already been added.
+    fn unsupported<T: AsRef<str> + Into<String>>(&mut self, field: T) {
+        if !self.unsupported.iter().any(|added| added == field.as_ref()) {
+            self.unsupported.push(field.into());
+        }
+    }
+
+    /// Convert a port into a `--publish` flag.
+    fn port(&mut self, port: &ShortOrLong<ShortPort, Port>) {
+        let port = match port {
+            ShortOrLong::Short(port) => Ok(port.clone()),
+            // The name and application protocol are informational only, the mode only applies to
+            // swarm.
+            ShortOrLong::Long(port) => Port {
+                name: None,
+                app_protocol: None,
+                mode: None,
+                extensions: Extensions::new(),
+                ..port.clone()
+            }
+            .into_short(),
+        };
+        match port {
+            Ok(port) => self.flag("--publish", port.to_string()),
+            Err(_) => self.unsupported("ports"),
+        }
+    }
+
+    /// Convert a [`ShortVolume`] into a `--volume` flag.
+    fn short_volume(&mut self, volume: &ShortVolume) {
+        let ShortVolume {
+            container_path,
+            options,
+        } = volume;
+        let target = container_path.as_path().display();
+
+        let Some(ShortOptions {
+            source,
+            read_only,
+            selinux,
+        }) = options
+        else {
+            self.flag("--volume", target.to_string());
+            return;
+        };
+
+        let source = match source {
+            Source::HostPath(path) => self.project.host_path(path.as_path()).display().to_string(),
+            Source::Volume(volume) => self.project.volume_name(volume.as_str()),
+        };
+        let mut options = Vec::new();
+        if *read_only {
+            options.push("ro".to_owned());
+        }
+        options.extend(selinux.map(|selinux| selinux.to_string()));
+        self.volume(&source, &target.to_string(), &options);
+    }
+
+    /// Push a `--volume` flag in the format `{source}:{target}[:{options}]`.
+    fn volume(&mut self, source: &str, target: &str, options: &[String]) {
+        let mut volume = format!("{source}:{target}");
+        if !options.is_empty() {
+            volume.push(':');
+            volume.push_str(&options.join(","));
+        }
+        self.flag("--volume", volume);
+    }
+
+    /// Convert a [`Mount`] into a `--mount` or `--volume` flag.
+    fn mount(&mut self, mount: &Mount) {
+        let common = mount.common();
+        let target = common.target.as_path().display().to_string();
+
+        let mut options = vec![("type", mount.kind().to_owned())];
+        match mount {
+            Mount::Volume(mount::Volume {
+                source,
+                volume,
+                common: _,
+            }) => {
+                options.extend(
+                    source
+                        .as_ref()
+                        .map(|source| ("source", self.project.volume_name(source.as_str()))),
+                );
+                if let Some(mount::VolumeOptions {
+                    nocopy,
+                    subpath,
+                    extensions: _,
+                }) = volume
+                {
+                    if *nocopy {
+                        options.push(("volume-nocopy", "true".to_owned()));
+                    }
+                    options.extend(
+                        subpath
+                            .as_ref()
+                            .map(|subpath| ("volume-subpath", subpath.display().to_string())),
+                    );
+                }
+            }
+            Mount::Bind(bind) => {
+                if !self.bind(bind, &mut options) {
+                    return;
+                }
+            }
+            Mount::Tmpfs(mount::Tmpfs { tmpfs, common: _ }) => {
+                if let Some(mount::TmpfsOptions {
+                    size,
+                    mode,
+                    extensions: _,
+                }) = tmpfs
+                {
+                    options.extend(size.map(|size| ("tmpfs-size", size.to_string())));
+                    options.extend(mode.map(|mode| ("tmpfs-mode", format!("{mode:o}"))));
+                }
+            }
+            Mount::NamedPipe(mount::NamedPipe { source, common: _ }) => {
+                options.push(("source", source.as_path().display().to_string()));
+            }
+            Mount::Image(mount::Image {
+                source,
+                image,
+                common: _,
+            }) => {
+                options.push(("source", source.to_string()));
+                options.extend(image.as_ref().and_then(|image| {
+                    image
+                        .subpath
+                        .as_ref()
+                        .map(|subpath| ("image-subpath", subpath.display().to_string()))
+                }));
+            }
+            Mount::Cluster(_) => {
+                self.unsupported("volumes.cluster");
+                return;
+            }
+        }
+
+        options.push(("target", target));
+        if common.read_only {
+            options.push(("readonly", "true".to_owned()));
+        }
+        options.extend(
+            common
+                .consistency
+                .as_ref()
+                .map(|consistency| ("consistency", consistency.clone())),
+        );
+        self.push_mount(&options);
+    }
+
+    /// Add the source and options of a bind [`Mount`] to the `--mount` `options`.
+    ///
+    /// Bind mounts which create the host path or use SELinux relabeling are converted into a
+    /// `--volume` flag instead, as `--mount` does not support either. Returns `false` in that
+    /// case.
+    fn bind(
+        &mut self,
+        Bind {
+            source,
+            bind,
+            common,
+        }: &Bind,
+        options: &mut Vec<(&str, String)>,
+    ) -> bool {
+        let source = self
+            .project
+            .host_path(source.as_path())
+            .display()
+            .to_string();
+        let BindOptions {
+            propagation,
+            create_host_path,
+            selinux,
+            recursive,
+            extensions: _,
+        } = bind.clone().unwrap_or_default();
+
+        if create_host_path || selinux.is_some() {
+            if recursive.is_some() {
+                self.unsupported("volumes.bind.recursive");
+            }
+            let mut volume_options = Vec::new();
+            if common.read_only {
+                volume_options.push("ro".to_owned());
+            }
+            volume_options.extend(propagation.map(|propagation| propagation.to_string()));
+            volume_options.extend(selinux.map(|selinux| selinux.to_string()));
+            if common.consistency.is_some() {
+                self.unsupported("volumes.consistency");
+            }
+            let target = common.target.as_path().display().to_string();
+            self.volume(&source, &target, &volume_options);
+            return false;
+        }
+
+        options.push(("source", source));
+        options
+            .extend(propagation.map(|propagation| ("bind-propagation", propagation.to_string())));
+        if let Some(recursive) = recursive.filter(|recursive| *recursive != BindRecursive::Enabled)
+        {
+            options.push(("bind-recursive", recursive.as_str().to_owned()));
+        }
+        true
+    }
+
+    /// Push a `--mount` flag with comma (,) separated `{key}={value}` options.
+    ///
+    /// Options containing a comma or quote are quoted, as the value is parsed as CSV.
+    fn push_mount(&mut self, options: &[(&str, String)]) {
+        let options: Vec<String> = options
+            .iter()
+            .map(|(key, value)| csv_field(&format!("{key}={value}")))
+            .collect();
+        self.flag("--mount", options.join(","));
+    }
+
+    /// Push a `--mount` flag for a read-only bind mount of a file.
+    fn bind_file(&mut self, source: &Path, target: &Path) {
+        self.push_mount(&[
+            ("type", "bind".to_owned()),
+            ("source", source.display().to_string()),
+            ("target", target.display().to_string()),
+            ("readonly", "true".to_owned()),
+        ]);
+    }
+
+    /// Push `--network` flags for the service's networks or network mode.
+    fn networks(&mut self, service: &Identifier, network_config: Option<&NetworkConfig>) {
+        let networks = match service_networks(network_config) {
+            Ok(networks) => networks,
+            Err(network_mode) => {
+                let network_mode = self.project.network_mode(network_mode);
+                self.flag("--network", network_mode);
+                return;
+            }
+        };
+
+        for (network, config) in networks {
+            let name = self.project.network_name(network);
+            let network = self.network(service, &name, config);
+            self.flag("--network", network);
+        }
+    }
+
+    /// Value of a `--network` flag with the network's aliases and options.
+    ///
+    /// Docker's format is `name={name},{key}={value},...`, Podman's is
+    /// `{name}:{key}={value},...`.
+    fn network(
+        &mut self,
+        service: &Identifier,
+        name: &str,
+        config: Option<&network_config::Network>,
+    ) -> String {
+        let mut options = vec![("alias", service.to_string())];
+        if let Some(network_config::Network {
+            aliases,
+            ipv4_address,
+            ipv6_address,
+            link_local_ips,
+            mac_address,
+            driver_opts,
+            priority: _,
+            interface_name,
+            gw_priority,
+            extensions: _,
+        }) = config
+        {
+            options.extend(aliases.iter().map(|alias| ("alias", alias.to_string())));
+            let (ip6, mac) = match self.engine {
+                Engine::Docker => ("ip6", "mac-address"),
+                Engine::Podman => ("ip", "mac"),
+            };
+            options.extend(ipv4_address.map(|address| ("ip", address.to_string())));
+            options.extend(ipv6_address.map(|address| (ip6, address.to_string())));
+            options.extend(mac_address.map(|address| (mac, mac_address_string(address))));
+
+            match self.engine {
+                Engine::Docker => {
+                    options.extend(
+                        link_local_ips
+                            .iter()
+                            .map(|address| ("link-local-ip", address.to_string())),
+                    );
+                    options.extend(
+                        driver_opts
+                            .iter()
+                            .map(|(key, value)| ("driver-opt", format!("{key}={value}"))),
+                    );
+                    options.extend(interface_name.as_ref().map(|interface_name| {
+                        (
+                            "driver-opt",
+                            format!("com.docker.network.endpoint.ifname={interface_name}"),
+                        )
+                    }));
+                    options.extend(
+                        gw_priority.map(|gw_priority| ("gw-priority", gw_priority.to_string())),
+                    );
+                }
+                Engine::Podman => {
+                    options.extend(
+                        interface_name
+                            .as_ref()
+                            .map(|interface_name| ("interface_name", interface_name.clone())),
+                    );
+                    for (field, unsupported) in [
+                        ("link_local_ips", !link_local_ips.is_empty()),
+                        ("driver_opts", !driver_opts.is_empty()),
+                        ("gw_priority", gw_priority.is_some()),
+                    ] {
+                        if unsupported {
+                            self.unsupported(format!("networks.{field}"));
+                        }
+                    }
+                }
+            }
+        }
+
+        let options: Vec<String> = options
+            .into_iter()
+            .map(|(key, value)| csv_field(&format!("{key}={value}")))
+            .collect();
+        let options = options.join(",");
+        match self.engine {
+            Engine::Docker => format!("{},{options}", csv_field(&format!("name={name}"))),
+            Engine::Podman => format!("{name}:{options}"),
+        }
+    }
+
+    /// Push `--health-*` flags or `--no-healthcheck`.
+    fn healthcheck(&mut self, healthcheck: &Healthcheck) {
+        let Healthcheck::Command(healthcheck::Command {
+            test,
+            interval,
+            timeout,
+            retries,
+            start_period,
+            start_interval,
+            extensions: _,
+        }) = healthcheck
+        else {
+            self.args.push("--no-healthcheck".to_owned());
+            return;
+        };
+
+        match test {
+            Some(Test::Command(command)) => {
+                // `--health-cmd` is run with the container's shell.
+                let command: Vec<String> = command.iter().map(|arg| shell::quote(arg)).collect();
+                self.flag("--health-cmd", command.join(" "));
+            }
+            Some(Test::ShellCommand(command)) => self.flag("--health-cmd", command.as_str()),
+            None => {}
+        }
+        for (flag, duration) in [
+            ("--health-interval", interval),
+            ("--health-timeout", timeout),
+            ("--health-start-period", start_period),
+            ("--health-start-interval", start_interval),
+        ] {
+            if let Some(duration) = duration {
+                self.duration_flag(flag, *duration);
+            }
+        }
+        if let Some(retries) = retries {
+            self.flag("--health-retries", retries.to_string());
+        }
+    }
+
+    /// Push a flag with a duration value, e.g. `1m30s`.
+    fn duration_flag(&mut self, flag: &str, duration: Duration) {
+        self.flag(flag, duration::to_string(duration));
+    }
+
+    /// Push a `--gpus` flag for each device reservation with the `gpu` capability, and add the
+    /// other fields of `deploy` as unsupported.
+    ///
+    /// Resource limits and memory reservations are converted together with the equivalent service
+    /// fields.
+    fn deploy(&mut self, deploy: &Deploy) {
+        let Deploy {
+            endpoint_mode,
+            labels,
+            mode,
+            placement,
+            replicas,
+            resources,
+            restart_policy,
+            rollback_config,
+            update_config,
+            extensions: _,
+        } = deploy;
+
+        for (field, set) in [
+            ("deploy.endpoint_mode", endpoint_mode.is_some()),
+            ("deploy.labels", !labels.is_empty()),
+            ("deploy.mode", mode.is_some()),
+            ("deploy.placement", placement.is_some()),
+            ("deploy.replicas", replicas.is_some()),
+            ("deploy.restart_policy", restart_policy.is_some()),
+            ("deploy.rollback_config", rollback_config.is_some()),
+            ("deploy.update_config", update_config.is_some()),
+        ] {
+            if set {
+                self.unsupported(field);
+            }
+        }
+
+        let Some(Reservations {
+            cpus,
+            memory: _,
+            devices,
+            generic_resources,
+            extensions: _,
+        }) = resources
+            .as_ref()
+            .and_then(|resources| resources.reservations.as_ref())
+        else {
+            return;
+        };
+        if cpus.is_some() {
+            self.unsupported("deploy.resources.reservations.cpus");
+        }
+        for device in devices {
+            match gpus(device) {
+                Some(gpus) => self.flag("--gpus", gpus),
+                None => self.unsupported("deploy.resources.reservations.devices"),
+            }
+        }
+        if !generic_resources.is_empty() {
+            self.unsupported("deploy.resources.reservations.generic_resources");
+        }
+    }
+
+    /// Push `--blkio-weight` and `--device-*` flags.
+    fn blkio_config(&mut self, blkio_config: &BlkioConfig) {
+        let BlkioConfig {
+            device_read_bps,
+            device_read_iops,
+            device_write_bps,
+            device_write_iops,
+            weight,
+            weight_device,
+        } = blkio_config;
+
+        if let Some(weight) = weight {
+            self.flag("--blkio-weight", weight.to_string());
+        }
+        for WeightDevice { path, weight } in weight_device {
+            self.flag(
+                "--blkio-weight-device",
+                format!("{}:{weight}", path.as_path().display()),
+            );
+        }
+        for (flag, limits) in [
+            ("--device-read-bps", device_read_bps),
+            ("--device-write-bps", device_write_bps),
+        ] {
+            for BpsLimit { path, rate } in limits {
+                self.flag(flag, format!("{}:{rate}", path.as_path().display()));
+            }
+        }
+        for (flag, limits) in [
+            ("--device-read-iops", device_read_iops),
+            ("--device-write-iops", device_write_iops),
+        ] {
+            for IopsLimit { path, rate } in limits {
+                self.flag(flag, format!("{}:{rate}", path.as_path().display()));
+            }
+        }
+    }
+
+    /// Push the `--entrypoint` flag, the `image`, and the command.
+    ///
+    /// `--entrypoint` only takes a single program, the rest of the entrypoint is prepended to the
+    /// command.
+    fn command(&mut self, entrypoint: Option<&Command>, image: String, command: Option<&Command>) {
+        let mut args = Vec::new();
+        if let Some(entrypoint) = entrypoint {
+            match self.words("entrypoint", entrypoint) {
+                Some(entrypoint) => {
+                    let mut entrypoint = entrypoint.into_iter();
+                    self.flag("--entrypoint", entrypoint.next().unwrap_or_default());
+                    args.extend(entrypoint);
+                }
+                None => self.unsupported("entrypoint"),
+            }
+        }
+
+        if let Some(command) = command.and_then(|command| self.words("command", command)) {
+            args.extend(command);
+        }
+
+        self.args.push(image);
+        self.args.extend(args);
+    }
+
+    /// Split a [`Command`] into words, the `field` is unsupported if it could not be split.
+    fn words(&mut self, field: &str, command: &Command) -> Option<Vec<String>> {
+        match command {
+            Command::String(command) => {
+                let words = shell::split(command);
+                if words.is_none() {
+                    self.unsupported(field);
+                }
+                words
+            }
+            Command::List(command) => Some(command.clone()),
+        }
+    }
+}
+
+/// Quote a CSV field if it contains a comma (,) or quote (").
+fn csv_field(field: &str) -> String {
+    if field.contains([',', '"']) {
+        format!("\"{}\"", field.replace('"', "\"\""))
+    } else {
+        field.to_owned()
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use indexmap::IndexMap;
+
+    use crate::{service::network_config::NetworkMode, Compose, Name};
+
+    use super::*;
+
+    #[test]
+    fn full() {
+        let compose: Compose = serde_yaml::from_str(include_str!("engine/test-full.yaml")).unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+
+        let mut expected = include_str!("run/test-full.txt").lines();
+        for engine in [Engine::Docker, Engine::Podman] {
+            for (name, service) in &compose.services {
+                let run = RunCommand::from_service(&project, name, service, engine);
+                assert_eq!(run.to_string(), expected.next().unwrap());
+
+                let unsupported: &[&str] = match name.as_str() {
+                    "web" => &["volumes.bind.recursive"],
+                    _ => &["build"],
+                };
+                assert_eq!(run.unsupported, unsupported);
+            }
+        }
+        assert_eq!(expected.next(), None);
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn deploy() {
+        let yaml = "\
+services:
+  app:
+    image: app
+    deploy:
+      mode: replicated
+      replicas: 2
+      placement:
+        constraints:
+          - node.role==worker
+      resources:
+        limits:
+          cpus: '1'
+        reservations:
+          cpus: '0.5'
+          memory: 64mb
+          devices:
+            - capabilities: [gpu]
+              count: 2
+            - capabilities: [gpu]
+              driver: nvidia
+              device_ids: ['0', '1']
+            - capabilities: [tpu]
+";
+        let compose: Compose = serde_yaml::from_str(yaml).unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+        let app = Identifier::new("app").unwrap();
+
+        let run = RunCommand::from_service(&project, &app, &compose.services[&app], Engine::Docker);
+        assert_eq!(
+            run.to_string(),
+            "docker run --name app-app-1 --network name=app_default,alias=app --cpus 1 \
+            --memory-reservation 64mb --gpus 2 --gpus '\"device=0,1\"' app",
+        );
+        assert_eq!(
+            run.unsupported,
+            [
+                "deploy.mode",
+                "deploy.placement",
+                "deploy.replicas",
+                "deploy.resources.reservations.cpus",
+                "deploy.resources.reservations.devices",
+            ]
+        );
+    }
+
+    #[test]
+    #[allow(clippy::indexing_slicing)]
+    fn stop_timeout_rounds_up() {
+        let yaml = "\
+services:
+  app:
+    image: app
+    stop_grace_period: 1500ms
+";
+        let compose: Compose = serde_yaml::from_str(yaml).unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+        let app = Identifier::new("app").unwrap();
+
+        let run = RunCommand::from_service(&project, &app, &compose.services[&app], Engine::Podman);
+        assert_eq!(
+            run.to_string(),
+            "podman run --name app-app-1 --network app_default:alias=app --stop-timeout 2 app",
+        );
+    }
+
+    #[test]
+    fn systemd_units() {
+        let compose: Compose = serde_yaml::from_str(include_str!("engine/test-full.yaml")).unwrap();
+        let name = Name::new("app").unwrap();
+        let project = Project::new(&name, &compose, Path::new("/srv/app"));
+
+        let systemd = SystemdUnits::from_project(&project, Engine::Docker);
+        let units: Vec<String> = systemd
+            .units
+            .iter()
+            .map(|SystemdUnit { name, unit }| format!("# {name}\n{unit}"))
+            .collect();
+        assert_eq!(units.join("\n"), include_str!("run/test-full.units.txt"));
+
+        let unsupported: Vec<String> = systemd
+            .unsupported
+            .iter()
+            .map(ToString::to_string)
+            .collect();
+        assert_eq!(
+            unsupported,
+            [
+                "`volumes.bind.recursive` of service `web` has no systemd unit equivalent",
+                "`networks.back.attachable` of service `web` has no systemd unit equivalent",
+                "`restart.max_retries` of service `web` has no systemd unit equivalent",
+                "`build` of service `worker` has no systemd unit equivalent",
+            ],
+        );
+    }
+
+    #[test]
+    fn parse_rendered() {
+        let mut services = IndexMap::new();
+        for line in include_str!("run/test-full.txt").lines() {
+            let run = RunService::parse(line).unwrap();
+            assert!(run.unconverted.is_empty(), "{:?}", run.unconverted);
+            let name = run.service.container_name.clone().unwrap();
+            // Docker and Podman command lines are parsed into the same service.
+            if let Some(service) = services.get(&name) {
+                assert_eq!(*service, run.service);
+            } else {
+                services.insert(name, run.service);
+            }
+        }
+
+        let expected: serde_yaml::Value =
+            serde_yaml::from_str(include_str!("run/test-parse.yaml")).unwrap();
+        assert_eq!(serde_yaml::to_value(&services).unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_flags() {
+        let run = RunService::from_args([
+            "podman",
+            "container",
+            "run",
+            "-dit",
+            "-p8080:80",
+            "-e=FOO=1",
+            "-i=false",
+            "--rm=false",
+            "--sig-proxy=false",
+            "--entrypoint=",
+            "--memory=1g",
+            "--unknown",
+            "--cpus",
+            "many",
+            "--network",
+            "host",
+            "--network-alias",
+            "web",
+            "--pod",
+            "app",
+            "alpine",
+            "sh",
+            "-c",
+            "echo hi",
+        ])
+        .unwrap();
+        let service = run.service;
+        assert!(service.tty);
+        assert!(!service.stdin_open);
+        assert_eq!(service.ports.len(), 1);
+        assert_eq!(
+            service
+                .environment
+                .into_list()
+                .into_iter()
+                .collect::<Vec<_>>(),
+            ["FOO=1"],
+        );
+        assert_eq!(service.mem_limit.unwrap().to_string(), "1gb");
+        assert_eq!(service.entrypoint, Some(Command::List(Vec::new())));
+        assert_eq!(
+            service.network_config,
+            Some(NetworkConfig::NetworkMode(NetworkMode::Host)),
+        );
+        assert_eq!(
+            service.command,
+            Some(Command::List(vec![
+                "sh".to_owned(),
+                "-c".to_owned(),
+                "echo hi".to_owned()
+            ])),
+        );
+        let unconverted: Vec<String> = run.unconverted.iter().map(ToString::to_string).collect();
+        assert_eq!(
+            unconverted,
+            [
+                "flag `--detach` has no service equivalent",
+                "flag `--sig-proxy` has no service equivalent",
+                "unknown flag `--unknown`",
+                "invalid value `many` for flag `--cpus`",
+                "flag `--pod` has no service equivalent",
+                "invalid value `web` for flag `--network-alias`",
+            ],
+        );
+
+        assert_eq!(
+            RunService::parse("docker ps -a"),
+            Err(ParseRunError::NotRun("docker".to_owned())),
+        );
+        assert_eq!(
+            RunService::parse("run -it --name"),
+            Err(ParseRunError::MissingValue("--name".to_owned())),
+        );
+        assert_eq!(
+            RunService::parse("run -it"),
+            Err(ParseRunError::MissingImage)
+        );
+        assert_eq!(RunService::parse("run 'alpine"), Err(ParseRunError::Split));
+    }
+}
@@ -0,0 +1,831 @@
+//! Parsing of `docker run` and `podman run` command lines into a [`Service`].
+
+use std::{iter::Peekable, mem, net::IpAddr, path::PathBuf, str::FromStr, time::Duration, vec};
+
+use indexmap::IndexMap;
+
+use crate::{
+    common::{item_or_list, parse_bool},
+    duration,
+    service::{
+        blkio_config::{BpsLimit, IopsLimit, Weight, WeightDevice},
+        device::CgroupRule,
+        healthcheck::{self, Test},
+        network_config::{Network, NetworkMode},
+        parse_ulimit,
+        ports::ShortPort,
+        volumes::{mount::parse_mount_flag, ShortVolume},
+        AbsolutePath, BlkioConfig, ByteValue, Capability, Cgroup, CgroupParent, Command, CpuSet,
+        Cpus, Device, EnvFile, Expose, Healthcheck, Hostname, IdOrName, Image, Ipc, Limit, Link,
+        Logging, MacAddress, NetworkConfig, OomScoreAdj, Percent, Pid, Platform, PullPolicy,
+        Restart, SecurityOpt, Signal, TmpfsEntry, User, UsernsMode, Uts, VolumesFrom,
+        VolumesFromSource,
+    },
+    Identifier, ListOrMap, Map, MapKey, Service, ShortOrLong, Value,
+};
+
+use super::{ParseRunError, Reason, RunService, Unconverted};
+
+/// Long flags which do not take a value, though they may be set with `--{flag}={bool}`.
+const SWITCHES: &[&str] = &[
+    "detach",
+    "disable-content-trust",
+    "env-host",
+    "help",
+    "http-proxy",
+    "init",
+    "interactive",
+    "no-healthcheck",
+    "no-hosts",
+    "oom-kill-disable",
+    "privileged",
+    "publish-all",
+    "quiet",
+    "read-only",
+    "read-only-tmpfs",
+    "replace",
+    "rm",
+    "rootfs",
+    "sig-proxy",
+    "tls-verify",
+    "tty",
+    "use-api-socket",
+];
+
+/// Known flags which have no equivalent [`Service`] field.
+const UNSUPPORTED: &[&str] = &[
+    "attach",
+    "cidfile",
+    "detach",
+    "detach-keys",
+    "disable-content-trust",
+    "env-host",
+    "gpus",
+    "help",
+    "http-proxy",
+    "no-hosts",
+    "pod",
+    "publish-all",
+    "quiet",
+    "read-only-tmpfs",
+    "replace",
+    "rm",
+    "rootfs",
+    "sig-proxy",
+    "tls-verify",
+    "use-api-socket",
+    "volume-driver",
+];
+
+/// [`SWITCHES`] which are enabled by default, so only `--{flag}=false` has an effect.
+const ENABLED_BY_DEFAULT: &[&str] = &["http-proxy", "sig-proxy", "tls-verify"];
+
+/// Driver option Docker uses for the name of a network interface.
+const IFNAME_DRIVER_OPT: &str = "com.docker.network.endpoint.ifname";
+
+/// Parse the arguments of a `run` command line into a [`RunService`].
+pub(super) fn convert(args: Vec<String>) -> Result<RunService, ParseRunError> {
+    let mut args = args.into_iter().peekable();
+    skip_program(&mut args)?;
+
+    let mut parser = Parser {
+        service: Service::default(),
+        environment: Map::new(),
+        labels: Map::new(),
+        annotations: Map::new(),
+        sysctls: Map::new(),
+        env_files: Vec::new(),
+        label_files: Vec::new(),
+        tmpfs: Vec::new(),
+        dns: Vec::new(),
+        dns_search: Vec::new(),
+        networks: IndexMap::new(),
+        network_mode: None,
+        endpoint_flags: Vec::new(),
+        unconverted: Vec::new(),
+    };
+
+    let mut image = None;
+    while let Some(arg) = args.next() {
+        if arg == "--" {
+            image = args.next();
+            break;
+        } else if let Some(flag) = arg.strip_prefix("--") {
+            let (flag, value) = flag
+                .split_once('=')
+                .map_or((flag, None), |(flag, value)| (flag, Some(value.to_owned())));
+            parser.flag(flag, value, &mut args)?;
+        } else if let Some(flags) = arg.strip_prefix('-').filter(|flags| !flags.is_empty()) {
+            parser.short_flags(flags, &mut args)?;
+        } else {
+            image = Some(arg);
+            break;
+        }
+    }
+
+    let image = Image::parse(image.ok_or(ParseRunError::MissingImage)?)?;
+    let command: Vec<String> = args.collect();
+
+    let mut run = parser.finish();
+    run.service.image = Some(image);
+    if !command.is_empty() {
+        run.service.command = Some(Command::List(command));
+    }
+    Ok(run)
+}
+
+/// Skip the program and `run` subcommand at the start of the arguments, if present.
+fn skip_program(args: &mut Peekable<vec::IntoIter<String>>) -> Result<(), ParseRunError> {
+    let is_program = args.peek().is_some_and(|program| {
+        let name = program.rsplit('/').next().unwrap_or(program);
+        matches!(name, "docker" | "podman")
+    });
+    if is_program {
+        let program = args.next().unwrap_or_default();
+        args.next_if_eq("container");
+        if args.next_if_eq("run").is_none() {
+            return Err(ParseRunError::NotRun(program));
+        }
+    } else {
+        args.next_if_eq("run");
+    }
+    Ok(())
+}
+
+/// State for converting the flags of a `run` command line into a [`Service`].
+struct Parser {
+    /// Service being converted into.
+    service: Service,
+
+    /// `--env` flags.
+    environment: Map,
+
+    /// `--label` flags.
+    labels: Map,
+
+    /// `--annotation` flags.
+    annotations: Map,
+
+    /// `--sysctl` flags.
+    sysctls: Map,
+
+    /// `--env-file` flags.
+    env_files: Vec<PathBuf>,
+
+    /// `--label-file` flags.
+    label_files: Vec<PathBuf>,
+
+    /// `--tmpfs` flags.
+    tmpfs: Vec<TmpfsEntry>,
+
+    /// `--dns` flags.
+    dns: Vec<IpAddr>,
+
+    /// `--dns-search` flags.
+    dns_search: Vec<Hostname>,
+
+    /// `--network` flags which connect the container to a network.
+    networks: IndexMap<Identifier, Option<Network>>,
+
+    /// `--network` flag which sets the network mode.
+    network_mode: Option<NetworkMode>,
+
+    /// `--network-alias`, `--ip`, `--ip6`, and `--link-local-ip` flags, which apply to the first
+    /// network.
+    endpoint_flags: Vec<(String, String)>,
+
+    /// Flags which could not be converted.
+    unconverted: Vec<Unconverted>,
+}
+
+impl Parser {
+    /// Convert a long flag, taking its value from the `args` if it needs one and it was not set
+    /// with `--{flag}={value}`.
+    fn flag(
+        &mut self,
+        flag: &str,
+        value: Option<String>,
+        args: &mut Peekable<vec::IntoIter<String>>,
+    ) -> Result<(), ParseRunError> {
+        if SWITCHES.contains(&flag) {
+            let result = value
+                .as_deref()
+                .map_or(Some(true), parse_bool)
+                .map_or(Err(Reason::Invalid), |enabled| self.switch(flag, enabled));
+            if let Err(reason) = result {
+                self.unconverted(flag, value, reason);
+            }
+            return Ok(());
+        }
+
+        let inline = value.is_some();
+        let Some(value) = value.or_else(|| args.peek().cloned()) else {
+            return Err(ParseRunError::MissingValue(format!("--{flag}")));
+        };
+        match self.value_flag(flag, &value) {
+            Ok(()) => {}
+            // Unknown flags are assumed to not take a value if the next argument is a flag.
+            Err(Reason::Unknown) if !inline && value.starts_with('-') => {
+                self.unconverted(flag, None, Reason::Unknown);
+                return Ok(());
+            }
+            Err(reason) => self.unconverted(flag, Some(value), reason),
+        }
+        if !inline {
+            args.next();
+        }
+        Ok(())
+    }
+
+    /// Convert grouped single character flags, e.g. `-it`, `-p8080:80`, or `-e=KEY=value`.
+    ///
+    /// The first flag which takes a value uses the rest of the group as its value, or the next
+    /// argument if it is the last flag of the group. Like the `docker` and `podman` flag parser, a
+    /// single `=` directly after a flag sets its value, for switches too, e.g. `-d=false`.
+    fn short_flags(
+        &mut self,
+        flags: &str,
+        args: &mut Peekable<vec::IntoIter<String>>,
+    ) -> Result<(), ParseRunError> {
+        for (index, short) in flags.char_indices() {
+            let Some(flag) = long_flag(short) else {
+                self.unconverted(&short.to_string(), None, Reason::Unknown);
+                continue;
+            };
+            let rest = flags.get(index + short.len_utf8()..).unwrap_or_default();
+            if let Some(value) = rest.strip_prefix('=') {
+                return self.flag(flag, Some(value.to_owned()), args);
+            }
+            if SWITCHES.contains(&flag) {
+                self.flag(flag, None, args)?;
+                continue;
+            }
+            let value = (!rest.is_empty()).then(|| rest.to_owned());
+            return self.flag(flag, value, args);
+        }
+        Ok(())
+    }
+
+    /// Add a flag which could not be converted.
+    fn unconverted(&mut self, flag: &str, value: Option<String>, reason: Reason) {
+        let flag = if flag.chars().count() == 1 {
+            format!("-{flag}")
+        } else {
+            format!("--{flag}")
+        };
+        self.unconverted.push(Unconverted {
+            flag,
+            value,
+            reason,
+        });
+    }
+
+    /// Convert a flag which does not take a value.
+    fn switch(&mut self, flag: &str, enabled: bool) -> Result<(), Reason> {
+        let service = &mut self.service;
+        match flag {
+            "init" => service.init = enabled,
+            "interactive" => service.stdin_open = enabled,
+            "tty" => service.tty = enabled,
+            "privileged" => service.privileged = enabled,
+            "read-only" => service.read_only = enabled,
+            "oom-kill-disable" => service.oom_kill_disable = enabled,
+            "no-healthcheck" if enabled => {
+                if service.healthcheck.is_some() {
+                    return Err(Reason::Invalid);
+                }
+                service.healthcheck = Some(Healthcheck::Disable);
+            }
+            // Flags set to their default have no effect.
+            "no-healthcheck" => {}
+            flag if UNSUPPORTED.contains(&flag) => {
+                if enabled != ENABLED_BY_DEFAULT.contains(&flag) {
+                    return Err(Reason::Unsupported);
+                }
+            }
+            _ => return Err(Reason::Unknown),
+        }
+        Ok(())
+    }
+
+    /// Convert a flag which takes a value.
+    ///
+    /// Returns an error if the flag could not be converted, in which case nothing is changed.
+    fn value_flag(&mut self, flag: &str, value: &str) -> Result<(), Reason> {
+        let service = &mut self.service;
+        match flag {
+            "name" => service.container_name = Some(parse(value)?),
+            "hostname" => service.hostname = Some(valid(Hostname::new(value))?),
+            "domainname" => service.domain_name = Some(valid(Hostname::new(value))?),
+            "user" => service.user = Some(parse::<User>(value)?),
+            "workdir" => service.working_dir = Some(valid(AbsolutePath::new(value))?),
+            "entrypoint" => {
+                // An empty entrypoint resets the image's entrypoint.
+                let entrypoint = (!value.is_empty()).then(|| value.to_owned());
+                service.entrypoint = Some(Command::List(entrypoint.into_iter().collect()));
+            }
+            "env" => insert_item(&mut self.environment, value)?,
+            "env-file" => self.env_files.push(value.into()),
+            "label" => insert_item(&mut self.labels, value)?,
+            "label-file" => self.label_files.push(value.into()),
+            "annotation" => insert_item(&mut self.annotations, value)?,
+            "publish" => {
+                let port = parse::<ShortPort>(value)?;
+                service.ports.insert(ShortOrLong::Short(port));
+            }
+            "expose" => {
+                service.expose.insert(parse::<Expose>(value)?);
+            }
+            "volume" => {
+                let volume = parse::<ShortVolume>(value)?;
+                service.volumes.insert(ShortOrLong::Short(volume));
+            }
+            "mount" => {
+                let mount = parse_mount_flag(csv_fields(value).ok_or(Reason::Invalid)?)
+                    .ok_or(Reason::Invalid)?;
+                service.volumes.insert(ShortOrLong::Long(mount));
+            }
+            "tmpfs" => self.tmpfs.push(parse(value)?),
+            "volumes-from" => {
+                let (container, read_only) = match value.rsplit_once(':') {
+                    Some((container, "ro")) => (container, true),
+                    Some((container, "rw")) => (container, false),
+                    _ => (value, false),
+                };
+                service.volumes_from.insert(VolumesFrom {
+                    source: VolumesFromSource::Container(parse(container)?),
+                    read_only,
+                });
+            }
+            "link" => {
+                service.external_links.insert(valid(Link::parse(value))?);
+            }
+            "network" | "net" => self.network(value)?,
+            "network-alias" | "net-alias" | "ip" | "ip6" | "link-local-ip" => {
+                self.endpoint_flags
+                    .push((flag.to_owned(), value.to_owned()));
+            }
+            "mac-address" => service.mac_address = Some(parse::<MacAddress>(value)?),
+            "dns" => self.dns.push(parse(value)?),
+            "dns-option" | "dns-opt" => {
+                service.dns_opt.insert(value.to_owned());
+            }
+            "dns-search" => self.dns_search.push(valid(Hostname::new(value))?),
+            "add-host" => {
+                let (host, ip) = value
+                    .split_once('=')
+                    .or_else(|| value.split_once(':'))
+                    .ok_or(Reason::Invalid)?;
+                let host = valid(Hostname::new(host))?;
+                service.extra_hosts.insert(host, parse(ip)?);
+            }
+            "health-cmd"
+            | "health-interval"
+            | "health-timeout"
+            | "health-retries"
+            | "health-start-period"
+            | "health-start-interval" => self.healthcheck(flag, value)?,
+            _ => return self.resource_flag(flag, value),
+        }
+        Ok(())
+    }
+
+    /// Convert a flag for container security, namespaces, or lifecycle which takes a value.
+    ///
+    /// Returns an error if the flag could not be converted, in which case nothing is changed.
+    fn resource_flag(&mut self, flag: &str, value: &str) -> Result<(), Reason> {
+        let service = &mut self.service;
+        match flag {
+            "cap-add" => {
+                service.cap_add.insert(Capability::parse(value));
+            }
+            "cap-drop" => {
+                service.cap_drop.insert(Capability::parse(value));
+            }
+            "device" => {
+                service.devices.insert(parse::<Device>(value)?);
+            }
+            "device-cgroup-rule" => {
+                service
+                    .device_cgroup_rules
+                    .insert(parse::<CgroupRule>(value)?);
+            }
+            "group-add" => {
+                service.group_add.insert(valid(IdOrName::parse(value))?);
+            }
+            "security-opt" => {
+                service.security_opt.insert(SecurityOpt::parse(value));
+            }
+            "ulimit" => {
+                let (resource, ulimit) = parse_ulimit(value).ok_or(Reason::Invalid)?;
+                service.ulimits.insert(resource, ulimit);
+            }
+            "sysctl" => insert_item(&mut self.sysctls, value)?,
+            "storage-opt" => insert_item(&mut service.storage_opt, value)?,
+            "ipc" => service.ipc = Some(parse::<Ipc>(value)?),
+            "pid" => service.pid = Some(parse::<Pid>(value)?),
+            "uts" if value == Uts::Host.as_str() => service.uts = Some(Uts::Host),
+            "cgroupns" => {
+                service.cgroup = Some(match value {
+                    "host" => Cgroup::Host,
+                    "private" => Cgroup::Private,
+                    _ => return Err(Reason::Invalid),
+                });
+            }
+            "cgroup-parent" => service.cgroup_parent = Some(parse::<CgroupParent>(value)?),
+            "userns" => service.userns_mode = Some(parse::<UsernsMode>(value)?),
+            "runtime" => service.runtime = Some(value.to_owned()),
+            "platform" => service.platform = Some(parse::<Platform>(value)?),
+            "isolation" => service.isolation = Some(value.to_owned()),
+            "pull" => {
+                service.pull_policy = Some(match value {
+                    "always" => PullPolicy::Always,
+                    "never" => PullPolicy::Never,
+                    "missing" => PullPolicy::Missing,
+                    "newer" => PullPolicy::Refresh,
+                    _ => return Err(Reason::Invalid),
+                });
+            }
+            "restart" => service.restart = Some(parse::<Restart>(value)?),
+            "stop-signal" => service.stop_signal = Some(parse::<Signal>(value)?),
+            "stop-timeout" => service.stop_grace_period = Some(Duration::from_secs(parse(value)?)),
+            "log-driver" => {
+                service.logging.get_or_insert_with(Logging::default).driver =
+                    Some(value.to_owned());
+            }
+            "log-opt" => {
+                let (key, value) = value.split_once('=').ok_or(Reason::Invalid)?;
+                service
+                    .logging
+                    .get_or_insert_with(Logging::default)
+                    .options
+                    .insert(valid(MapKey::new(key))?, Some(value.into()));
+            }
+            _ => return self.limit_flag(flag, value),
+        }
+        Ok(())
+    }
+
+    /// Convert a flag for container resource limits which takes a value.
+    ///
+    /// Returns an error if the flag could not be converted, in which case nothing is changed.
+    fn limit_flag(&mut self, flag: &str, value: &str) -> Result<(), Reason> {
+        let service = &mut self.service;
+        match flag {
+            "cpus" => service.cpus = Some(valid(Cpus::new(parse::<f64>(value)?))?),
+            "cpu-shares" => service.cpu_shares = Some(parse(value)?),
+            "cpu-period" => service.cpu_period = Some(Duration::from_micros(parse(value)?)),
+            "cpu-quota" => service.cpu_quota = Some(Duration::from_micros(parse(value)?)),
+            "cpu-rt-period" => service.cpu_rt_period = Some(Duration::from_micros(parse(value)?)),
+            "cpu-rt-runtime" => service.cpu_rt_runtime = Some(Duration::from_micros(parse(value)?)),
+            "cpuset-cpus" => service.cpuset = parse::<CpuSet>(value)?,
+            "cpu-count" => service.cpu_count = Some(parse(value)?),
+            "cpu-percent" => service.cpu_percent = Some(valid(Percent::new(parse(value)?))?),
+            "memory" => service.mem_limit = Some(parse::<ByteValue>(value)?),
+            "memory-reservation" => service.mem_reservation = Some(parse(value)?),
+            "memory-swap" => service.memswap_limit = Some(parse::<Limit<ByteValue>>(value)?),
+            "memory-swappiness" => {
+                service.mem_swappiness = Some(valid(Percent::new(parse(value)?))?);
+            }
+            "oom-score-adj" => {
+                service.oom_score_adj = Some(valid(OomScoreAdj::new(parse(value)?))?);
+            }
+            "pids-limit" => service.pids_limit = Some(parse::<Limit<u32>>(value)?),
+            "shm-size" => service.shm_size = Some(parse(value)?),
+            "blkio-weight"
+            | "blkio-weight-device"
+            | "device-read-bps"
+            | "device-write-bps"
+            | "device-read-iops"
+            | "device-write-iops" => {
+                blkio_config(
+                    service
+                        .blkio_config
+                        .get_or_insert_with(BlkioConfig::default),
+                    flag,
+                    value,
+                )?;
+            }
+            flag if UNSUPPORTED.contains(&flag) => return Err(Reason::Unsupported),
+            _ => return Err(Reason::Unknown),
+        }
+        Ok(())
+    }
+
+    /// Convert a `--network` flag.
+    ///
+    /// Supports network modes, network names, Docker's `name={network},{option}={value},...`
+    /// syntax, and Podman's `{network}:{option}={value},...` syntax.
+    fn network(&mut self, value: &str) -> Result<(), Reason> {
+        let mode = value.split_once(':').map_or(value, |(mode, _)| mode);
+        if matches!(
+            mode,
+            "host"
+                | "none"
+                | "bridge"
+                | "default"
+                | "private"
+                | "slirp4netns"
+                | "pasta"
+                | "container"
+                | "ns"
+        ) {
+            if self.network_mode.is_some() || !self.networks.is_empty() {
+                return Err(Reason::Invalid);
+            }
+            self.network_mode = Some(valid(NetworkMode::parse(value))?);
+            return Ok(());
+        }
+
+        let (name, options) = if value.starts_with("name=") {
+            let mut fields = csv_fields(value).ok_or(Reason::Invalid)?.into_iter();
+            let name = fields.next().unwrap_or_default();
+            let name = name.strip_prefix("name=").unwrap_or(&name).to_owned();
+            (name, network_options(fields, docker_network_option)?)
+        } else if let Some((name, options)) = value.split_once(':') {
+            let options = options.split(',').map(str::to_owned);
+            (
+                name.to_owned(),
+                network_options(options, podman_network_option)?,
+            )
+        } else {
+            (value.to_owned(), None)
+        };
+
+        let name = parse::<Identifier>(&name)?;
+        if self.network_mode.is_some() || self.networks.contains_key(&name) {
+            return Err(Reason::Invalid);
+        }
+        self.networks.insert(name, options);
+        Ok(())
+    }
+
+    /// Convert a `--health-*` flag.
+    fn healthcheck(&mut self, flag: &str, value: &str) -> Result<(), Reason> {
+        let Healthcheck::Command(command) = self
+            .service
+            .healthcheck
+            .get_or_insert_with(Healthcheck::default)
+        else {
+            return Err(Reason::Invalid);
+        };
+        let healthcheck::Command {
+            test,
+            interval,
+            timeout,
+            retries,
+            start_period,
+            start_interval,
+            ..
+        } = command;
+        match flag {
+            // The command is run with the container's shell.
+            "health-cmd" => *test = Some(Test::ShellCommand(value.to_owned())),
+            "health-interval" => *interval = Some(valid(duration::parse(value))?),
+            "health-timeout" => *timeout = Some(valid(duration::parse(value))?),
+            "health-start-period" => *start_period = Some(valid(duration::parse(value))?),
+            "health-start-interval" => *start_interval = Some(valid(duration::parse(value))?),
+            "health-retries" => *retries = Some(parse(value)?),
+            _ => return Err(Reason::Unknown),
+        }
+        Ok(())
+    }
+
+    /// Apply the endpoint flags to the first network and set the remaining collected fields of the
+    /// service.
+    fn finish(mut self) -> RunService {
+        let endpoint_flags = mem::take(&mut self.endpoint_flags);
+        for (flag, value) in endpoint_flags {
+            let result = match self.networks.first_mut() {
+                Some((_, network)) => {
+                    endpoint_option(network.get_or_insert_with(Network::default), &flag, &value)
+                }
+                // Endpoint options are only supported for user-defined networks.
+                None => Err(Reason::Invalid),
+            };
+            if let Err(reason) = result {
+                self.unconverted(&flag, Some(value), reason);
+            }
+        }
+
+        let Self {
+            mut service,
+            environment,
+            labels,
+            annotations,
+            sysctls,
+            env_files,
+            label_files,
+            tmpfs,
+            dns,
+            dns_search,
+            networks,
+            network_mode,
+            endpoint_flags: _,
+            unconverted,
+        } = self;
+
+        for (field, map) in [
+            (&mut service.environment, environment),
+            (&mut service.labels, labels),
+            (&mut service.annotations, annotations),
+            (&mut service.sysctls, sysctls),
+        ] {
+            if !map.is_empty() {
+                *field = ListOrMap::Map(map);
+            }
+        }
+        service.env_file = EnvFile::from_paths(env_files);
+        service.label_file = item_or_list(label_files);
+        service.tmpfs = item_or_list(tmpfs);
+        service.dns = item_or_list(dns);
+        service.dns_search = item_or_list(dns_search);
+
+        service.network_config = NetworkConfig::from_parts(network_mode, networks);
+
+        RunService {
+            service,
+            unconverted,
+        }
+    }
+}
+
+/// Long name of a single character flag.
+const fn long_flag(short: char) -> Option<&'static str> {
+    Some(match short {
+        'a' => "attach",
+        'c' => "cpu-shares",
+        'd' => "detach",
+        'e' => "env",
+        'h' => "hostname",
+        'i' => "interactive",
+        'l' => "label",
+        'm' => "memory",
+        'p' => "publish",
+        'P' => "publish-all",
+        'q' => "quiet",
+        't' => "tty",
+        'u' => "user",
+        'v' => "volume",
+        'w' => "workdir",
+        _ => return None,
+    })
+}
+
+/// Parse a value, which is invalid if it could not be parsed.
+fn parse<T: FromStr>(value: &str) -> Result<T, Reason> {
+    #[allow(clippy::map_err_ignore)]
+    value.parse().map_err(|_| Reason::Invalid)
+}
+
+/// Map the error of a [`Result`] to [`Reason::Invalid`].
+fn valid<T, E>(result: Result<T, E>) -> Result<T, Reason> {
+    #[allow(clippy::map_err_ignore)]
+    result.map_err(|_| Reason::Invalid)
+}
+
+/// Insert an item in the `{key}[={value}]` format into the `map`.
+fn insert_item(map: &mut Map, item: &str) -> Result<(), Reason> {
+    let (key, value) = item
+        .split_once('=')
+        .map_or((item, None), |(key, value)| (key, Some(value)));
+    let key = valid(MapKey::new(key))?;
+    map.insert(key, value.map(|value| Value::String(value.to_owned())));
+    Ok(())
+}
+
+/// Split a CSV record into its fields.
+///
+/// Returns [`None`] if a quoted field is not terminated.
+fn csv_fields(record: &str) -> Option<Vec<String>> {
+    let mut fields = Vec::new();
+    let mut field = String::new();
+    let mut chars = record.chars().peekable();
+    while let Some(char) = chars.next() {
+        match char {
+            '"' if field.is_empty() => loop {
+                match chars.next()? {
+                    '"' if chars.next_if_eq(&'"').is_some() => field.push('"'),
+                    '"' => break,
+                    char => field.push(char),
+                }
+            },
+            ',' => fields.push(mem::take(&mut field)),
+            char => field.push(char),
+        }
+    }
+    fields.push(field);
+    Some(fields)
+}
+
+/// Parse the options of a `--network` flag with the `option` function.
+///
+/// Returns [`None`] if there are no options.
+fn network_options<I>(
+    options: I,
+    option: fn(&mut Network, &str, &str) -> Result<(), Reason>,
+) -> Result<Option<Network>, Reason>
+where
+    I: IntoIterator<Item = String>,
+{
+    let mut network = None;
+    for field in options {
+        let (key, value) = field.split_once('=').ok_or(Reason::Invalid)?;
+        option(network.get_or_insert_with(Network::default), key, value)?;
+    }
+    Ok(network)
+}
+
+/// Parse an option of Docker's `--network name={network},{option}={value},...` syntax.
+fn docker_network_option(network: &mut Network, key: &str, value: &str) -> Result<(), Reason> {
+    match key {
+        "alias" | "ip" | "ip6" | "link-local-ip" => endpoint_option(network, key, value)?,
+        "mac-address" => network.mac_address = Some(parse(value)?),
+        "gw-priority" => network.gw_priority = Some(parse(value)?),
+        "driver-opt" => {
+            let (key, value) = value.split_once('=').ok_or(Reason::Invalid)?;
+            if key == IFNAME_DRIVER_OPT {
+                network.interface_name = Some(value.to_owned());
+            } else {
+                network
+                    .driver_opts
+                    .insert(valid(MapKey::new(key))?, value.into());
+            }
+        }
+        _ => return Err(Reason::Invalid),
+    }
+    Ok(())
+}
+
+/// Parse an option of Podman's `--network {network}:{option}={value},...` syntax.
+fn podman_network_option(network: &mut Network, key: &str, value: &str) -> Result<(), Reason> {
+    match key {
+        "alias" => endpoint_option(network, key, value)?,
+        // Podman uses `ip` for both IPv4 and IPv6 addresses.
+        "ip" => match parse(value)? {
+            IpAddr::V4(address) => network.ipv4_address = Some(address),
+            IpAddr::V6(address) => network.ipv6_address = Some(address),
+        },
+        "mac" => network.mac_address = Some(parse(value)?),
+        "interface_name" => network.interface_name = Some(value.to_owned()),
+        _ => return Err(Reason::Invalid),
+    }
+    Ok(())
+}
+
+/// Parse a network endpoint option, from a flag like `--ip` or Docker's `--network` syntax.
+fn endpoint_option(network: &mut Network, option: &str, value: &str) -> Result<(), Reason> {
+    match option {
+        "alias" | "network-alias" | "net-alias" => {
+            network.aliases.insert(valid(Hostname::new(value))?);
+        }
+        "ip" => network.ipv4_address = Some(parse(value)?),
+        "ip6" => network.ipv6_address = Some(parse(value)?),
+        "link-local-ip" => {
+            network.link_local_ips.insert(parse(value)?);
+        }
+        _ => return Err(Reason::Invalid),
+    }
+    Ok(())
+}
+
+/// Convert a `--blkio-weight` or `--device-*` flag into the `blkio_config`.
+fn blkio_config(blkio_config: &mut BlkioConfig, flag: &str, value: &str) -> Result<(), Reason> {
+    if flag == "blkio-weight" {
+        blkio_config.weight = Some(valid(Weight::new(parse::<u16>(value)?))?);
+        return Ok(());
+    }
+
+    let (path, rate) = value.rsplit_once(':').ok_or(Reason::Invalid)?;
+    let path = valid(AbsolutePath::new(path))?;
+    match flag {
+        "blkio-weight-device" => blkio_config.weight_device.push(WeightDevice {
+            path,
+            weight: valid(Weight::new(parse::<u16>(rate)?))?,
+        }),
+        "device-read-bps" | "device-write-bps" => {
+            let limit = BpsLimit {
+                path,
+                rate: parse(rate)?,
+            };
+            if flag == "device-read-bps" {
+                blkio_config.device_read_bps.push(limit);
+            } else {
+                blkio_config.device_write_bps.push(limit);
+            }
+        }
+        "device-read-iops" | "device-write-iops" => {
+            let limit = IopsLimit {
+                path,
+                rate: parse(rate)?,
+            };
+            if flag == "device-read-iops" {
+                blkio_config.device_read_iops.push(limit);
+            } else {
+                blkio_config.device_write_iops.push(limit);
+            }
+        }
+        _ => return Err(Reason::Unknown),
+    }
+    Ok(())
+}
@@ -0,0 +1,476 @@
+//! Conversion of a [`Project`] into systemd units which run its services with [`RunCommand`]s.
+
+use std::fmt::{self, Display, Formatter};
+
+use indexmap::IndexMap;
+
+use crate::{
+    duration,
+    engine::{service_networks, Project},
+    network::Ipam,
+    service::{volumes::named_volumes_iter, Condition, Dependency, Restart},
+    shell,
+    systemd::{quote, Unit},
+    Identifier, ListOrMap, MapKey, Network, Resource, Service, ShortOrLong, StringOrNumber, Volume,
+};
+
+use super::{Engine, RunCommand};
+
+/// Seconds added to a service's stop timeout for `TimeoutStopSec=`, so that systemd does not
+/// kill the unit while `stop` is still waiting for the container to exit.
+const STOP_TIMEOUT_HEADROOM: u64 = 10;
+
+/// systemd units which run the services of a [`Project`] with `docker run` or `podman run`.
+///
+/// Useful for hosts without a Compose implementation.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemdUnits {
+    /// A `.service` unit for each service, followed by a `.target` unit for the project.
+    pub units: Vec<SystemdUnit>,
+
+    /// Fields of the services which have no equivalent and were not converted.
+    pub unsupported: Vec<Unsupported>,
+}
+
+impl SystemdUnits {
+    /// Convert the services of a `project` into systemd `.service` units named
+    /// `{project}-{service}.service`, and a `{project}.target` unit which wants them all.
+    ///
+    /// Each service unit runs the service's [`RunCommand`] in the foreground with `--rm`, after
+    /// removing any leftover container and creating the service's networks and named volumes if
+    /// they do not exist. The container is stopped with `stop`.
+    ///
+    /// Networks and volumes are created with their `driver`, `driver_opts`, and `labels`, and
+    /// networks also with their `enable_ipv6`, `internal`, and `ipam` settings. Other network
+    /// fields are reported as unsupported fields of the service, e.g. `networks.{network}.attachable`.
+    ///
+    /// - `depends_on` and other references to services, e.g. `network_mode: service:{service}`,
+    ///   become `Requires=` (or `Wants=` if not `required`) and `After=`. Dependencies with
+    ///   `restart: true` which are not required become `PartOf=`.
+    /// - `restart` becomes `Restart=`, with `unless-stopped` becoming `always`.
+    /// - `stop_grace_period` becomes `TimeoutStopSec=`, 10 seconds longer than the
+    ///   `--stop-timeout` which `stop` waits for before killing the container.
+    ///
+    /// Units of the Docker engine require `docker.service`. Service units are part of the
+    /// project's target, so stopping or restarting it does the same to all services.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::path::Path;
+    ///
+    /// use compose_spec::{
+    ///     engine::Project,
+    ///     run::{Engine, SystemdUnits},
+    ///     Compose, Name,
+    /// };
+    ///
+    /// let yaml = "\
+    /// services:
+    ///   caddy:
+    ///     image: docker.io/library/caddy:latest
+    ///     depends_on:
+    ///       - php
+    ///     restart: always
+    ///     stop_grace_period: 30s
+    ///   php:
+    ///     image: docker.io/library/php:fpm
+    /// ";
+    /// let compose: Compose = serde_yaml::from_str(yaml)?;
+    /// let name = Name::new("app")?;
+    /// let project = Project::new(&name, &compose, Path::new("/srv/app"));
+    ///
+    /// let systemd = SystemdUnits::from_project(&project, Engine::Podman);
+    /// assert!(systemd.unsupported.is_empty());
+    ///
+    /// let caddy = &systemd.units[0];
+    /// assert_eq!(caddy.name, "app-caddy.service");
+    /// assert_eq!(
+    ///     caddy.unit.to_string(),
+    ///     "\
+    /// [Unit]
+    /// Description=Service caddy of Compose project app
+    /// PartOf=app.target
+    /// Requires=app-php.service
+    /// After=app-php.service
+    ///
+    /// [Service]
+    /// ExecStartPre=/bin/sh -c \"podman network inspect app_default >/dev/null 2>&1 \
+    /// || podman network create app_default\"
+    /// ExecStartPre=-podman rm --force app-caddy-1
+    /// ExecStart=podman run --rm --name app-caddy-1 --network app_default:alias=caddy \
+    /// --stop-timeout 30 docker.io/library/caddy:latest
+    /// ExecStop=podman stop app-caddy-1
+    /// Restart=always
+    /// TimeoutStopSec=40s
+    /// ",
+    /// );
+    ///
+    /// let target = &systemd.units[2];
+    /// assert_eq!(target.name, "app.target");
+    /// assert_eq!(
+    ///     target.unit.to_string(),
+    ///     "\
+    /// [Unit]
+    /// Description=Compose project app
+    /// Wants=app-caddy.service
+    /// Wants=app-php.service
+    ///
+    /// [Install]
+    /// WantedBy=multi-user.target
+    /// ",
+    /// );
+    /// # Ok::<(), Box<dyn std::error::Error>>(())
+    /// ```
+    #[must_use]
+    pub fn from_project(project: &Project, engine: Engine) -> Self {
+        let mut systemd = Self {
+            units: Vec::new(),
+            unsupported: Vec::new(),
+        };
+
+        let mut target = Unit::new();
+        let section = target.section_mut("Unit");
+        section.push("Description", format!("Compose project {}", project.name));
+        for (name, service) in &project.compose.services {
+            let unit = systemd.service_unit(project, name, service, engine);
+            target.section_mut("Unit").push("Wants", &unit.name);
+            systemd.units.push(unit);
+        }
+        target
+            .section_mut("Install")
+            .push("WantedBy", "multi-user.target");
+        systemd.units.push(SystemdUnit {
+            name: target_name(project),
+            unit: target,
+        });
+
+        systemd
+    }
+
+    /// Convert a [`Service`] into a `.service` unit.
+    fn service_unit(
+        &mut self,
+        project: &Project,
+        name: &Identifier,
+        service: &Service,
+        engine: Engine,
+    ) -> SystemdUnit {
+        let mut unit = Unit::new();
+        let section = unit.section_mut("Unit");
+        section.push(
+            "Description",
+            format!("Service {name} of Compose project {}", project.name),
+        );
+        section.push("PartOf", target_name(project));
+        if engine == Engine::Docker {
+            section.push("Requires", "docker.service");
+            section.push("After", "docker.service");
+        }
+
+        for dependency in service.service_dependencies() {
+            let Dependency {
+                condition,
+                restart,
+                required,
+            } = match &service.depends_on {
+                ShortOrLong::Long(depends_on) => {
+                    depends_on.get(dependency).copied().unwrap_or_default()
+                }
+                ShortOrLong::Short(_) => Dependency::default(),
+            };
+            let dependency_unit = service_name(project, dependency);
+            let section = unit.section_mut("Unit");
+            section.push(
+                if required { "Requires" } else { "Wants" },
+                &dependency_unit,
+            );
+            if restart && !required {
+                // `Requires=` already restarts this unit with its dependency.
+                section.push("PartOf", &dependency_unit);
+            }
+            section.push("After", dependency_unit);
+            if !matches!(condition, Condition::ServiceStarted) {
+                self.unsupported(name, format!("depends_on.{dependency}.condition"));
+            }
+        }
+
+        // Dependencies and restarts are managed by systemd instead.
+        let run_service = Service {
+            depends_on: ShortOrLong::default(),
+            restart: None,
+            ..service.clone()
+        };
+        let mut run = RunCommand::from_service(project, name, &run_service, engine);
+        for field in run.unsupported {
+            self.unsupported(name, field);
+        }
+        // Remove the container when it exits, the flag goes right after `{engine} run`.
+        run.args.insert(2, "--rm".to_owned());
+
+        let container_name = project.container_name(name.as_str());
+        for command in self.create_commands(project, name, service, engine) {
+            unit.section_mut("Service").push("ExecStartPre", command);
+        }
+        let section = unit.section_mut("Service");
+        section.push(
+            "ExecStartPre",
+            format!("-{engine} rm --force {}", quote(&container_name)),
+        );
+        section.push("ExecStart", command_line(&run.args));
+        section.push(
+            "ExecStop",
+            format!("{engine} stop {}", quote(&container_name)),
+        );
+
+        if let Some(restart) = &service.restart {
+            let value = match restart {
+                Restart::No => "no",
+                // Stopping the systemd unit stops restarts.
+                Restart::Always | Restart::UnlessStopped => "always",
+                Restart::OnFailure { max_retries } => {
+                    if max_retries.is_some() {
+                        self.unsupported(name, "restart.max_retries");
+                    }
+                    "on-failure"
+                }
+            };
+            unit.section_mut("Service").push("Restart", value);
+        }
+        if let Some(stop_grace_period) = service.stop_grace_period {
+            let timeout =
+                duration::seconds(stop_grace_period).saturating_add(STOP_TIMEOUT_HEADROOM);
+            unit.section_mut("Service")
+                .push("TimeoutStopSec", format!("{timeout}s"));
+        }
+
+        SystemdUnit {
+            name: service_name(project, name),
+            unit,
+        }
+    }
+
+    /// Commands which create the networks and named volumes of a `service`, if they do not exist.
+    ///
+    /// External networks and volumes are expected to exist already.
+    fn create_commands(
+        &mut self,
+        project: &Project,
+        service_name: &Identifier,
+        service: &Service,
+        engine: Engine,
+    ) -> Vec<String> {
+        let mut commands = Vec::new();
+        if let Ok(networks) = service_networks(service.network_config.as_ref()) {
+            for (network, _) in networks {
+                let args = match project.compose.networks.get(network) {
+                    Some(Some(Resource::External { .. })) => continue,
+                    Some(Some(Resource::Compose(config))) => {
+                        self.network_args(service_name, network, config)
+                    }
+                    _ => Vec::new(),
+                };
+                let network = project.network_name(network);
+                commands.push(create_command(engine, "network", &network, args));
+            }
+        }
+        let mut volumes: Vec<&Identifier> = Vec::new();
+        for volume in named_volumes_iter(&service.volumes) {
+            if !volumes.contains(&volume) {
+                volumes.push(volume);
+            }
+        }
+        for volume in volumes {
+            let args = match project.compose.volumes.get(volume) {
+                Some(Some(Resource::External { .. })) => continue,
+                Some(Some(Resource::Compose(config))) => volume_args(config),
+                _ => Vec::new(),
+            };
+            let volume = project.volume_name(volume.as_str());
+            commands.push(create_command(engine, "volume", &volume, args));
+        }
+
+        commands
+    }
+
+    /// Arguments for `network create` from a top-level [`Network`] used by the `service`.
+    fn network_args(&mut self, service: &Identifier, name: &str, network: &Network) -> Vec<String> {
+        let Network {
+            driver,
+            driver_opts,
+            attachable,
+            enable_ipv4,
+            enable_ipv6,
+            ipam,
+            internal,
+            labels,
+            name: _,
+            extensions: _,
+        } = network;
+
+        let mut args = Vec::new();
+        if let Some(driver) = driver {
+            args.extend(["--driver".to_owned(), driver.as_str().to_owned()]);
+        }
+        push_options(&mut args, driver_opts);
+        if *attachable {
+            self.unsupported(service, format!("networks.{name}.attachable"));
+        }
+        if *enable_ipv4 == Some(false) {
+            self.unsupported(service, format!("networks.{name}.enable_ipv4"));
+        }
+        if *enable_ipv6 {
+            args.push("--ipv6".to_owned());
+        }
+        if let Some(Ipam {
+            driver,
+            config,
+            options,
+            extensions: _,
+        }) = ipam
+        {
+            if let Some(driver) = driver {
+                args.extend(["--ipam-driver".to_owned(), driver.clone()]);
+            }
+            for config in config {
+                if let Some(subnet) = config.subnet {
+                    args.extend(["--subnet".to_owned(), subnet.to_string()]);
+                }
+                if let Some(gateway) = config.gateway {
+                    args.extend(["--gateway".to_owned(), gateway.to_string()]);
+                }
+                if let Some(ip_range) = config.ip_range {
+                    args.extend(["--ip-range".to_owned(), ip_range.to_string()]);
+                }
+                if !config.aux_addresses.is_empty() {
+                    self.unsupported(
+                        service,
+                        format!("networks.{name}.ipam.config.aux_addresses"),
+                    );
+                }
+            }
+            if !options.is_empty() {
+                self.unsupported(service, format!("networks.{name}.ipam.options"));
+            }
+        }
+        if *internal {
+            args.push("--internal".to_owned());
+        }
+        push_labels(&mut args, labels);
+
+        args
+    }
+
+    /// Add an unsupported field of a service, if it has not already been added.
+    fn unsupported<T: AsRef<str> + Into<String>>(&mut self, service: &Identifier, field: T) {
+        if !self.unsupported.iter().any(|unsupported| {
+            unsupported.service == *service && unsupported.field == field.as_ref()
+        }) {
+            self.unsupported.push(Unsupported {
+                service: service.clone(),
+                field: field.into(),
+            });
+        }
+    }
+}
+
+/// Name of the `.service` unit of a service.
+fn service_name(project: &Project, service: &Identifier) -> String {
+    format!("{}-{service}.service", project.name)
+}
+
+/// Name of the `.target` unit of a project.
+fn target_name(project: &Project) -> String {
+    format!("{}.target", project.name)
+}
+
+/// Command which creates a network or volume named `name` with `args`, if it does not exist.
+///
+/// `kind` is `network` or `volume`. The command fails if the network or volume could not be
+/// created, which stops the unit from starting.
+fn create_command(engine: Engine, kind: &str, name: &str, args: Vec<String>) -> String {
+    let mut create = vec![engine.to_string(), kind.to_owned(), "create".to_owned()];
+    create.extend(args);
+    create.push(name.to_owned());
+    let create: Vec<String> = create.iter().map(|arg| shell::quote(arg)).collect();
+
+    let script = format!(
+        "{engine} {kind} inspect {} >/dev/null 2>&1 || {}",
+        shell::quote(name),
+        create.join(" "),
+    );
+    command_line(&["/bin/sh".to_owned(), "-c".to_owned(), script])
+}
+
+/// Arguments for `volume create` from a top-level [`Volume`].
+fn volume_args(volume: &Volume) -> Vec<String> {
+    let Volume {
+        driver,
+        driver_opts,
+        labels,
+        name: _,
+        extensions: _,
+    } = volume;
+
+    let mut args = Vec::new();
+    if let Some(driver) = driver {
+        args.extend(["--driver".to_owned(), driver.clone()]);
+    }
+    push_options(&mut args, driver_opts);
+    push_labels(&mut args, labels);
+    args
+}
+
+/// Push an `--opt` argument for each driver option.
+fn push_options(args: &mut Vec<String>, options: &IndexMap<MapKey, StringOrNumber>) {
+    for (option, value) in options {
+        args.extend(["--opt".to_owned(), format!("{option}={value}")]);
+    }
+}
+
+/// Push a `--label` argument for each label.
+fn push_labels(args: &mut Vec<String>, labels: &ListOrMap) {
+    for label in labels.clone().into_list() {
+        args.extend(["--label".to_owned(), label]);
+    }
+}
+
+/// Join arguments into a systemd command line, quoting them as needed.
+///
+/// Dollar signs (`$`) are escaped, as systemd substitutes environment variables in commands.
+fn command_line(args: &[String]) -> String {
+    args.iter()
+        .map(|arg| quote(arg).replace('$', "$$"))
+        .collect::<Vec<_>>()
+        .join(" ")
+}
+
+/// A systemd unit and its name.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct SystemdUnit {
+    /// Name of the unit, including its type suffix, e.g. `app-web.service` or `app.target`.
+    pub name: String,
+
+    /// Contents of the unit.
+    pub unit: Unit,
+}
+
+/// A field of a service which has no equivalent in its systemd unit and was not converted.
+#[derive(Debug, Clone, PartialEq, Eq)]
+pub struct Unsupported {
+    /// Name of the service containing the field.
+    pub service: Identifier,
+
+    /// Name of the unsupported field, e.g. `build` or `restart.max_retries`.
+    pub field: String,
+}
+
+impl Display for Unsupported {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self { service, field } = self;
+        write!(
+            f,
+            "`{field}` of service `{service}` has no systemd unit equivalent"
+        )
+    }
+}
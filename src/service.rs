@@ -3,6 +3,7 @@
 pub mod blkio_config;
 pub mod build;
 mod byte_value;
+mod capability;
 mod config_or_secret;
 mod cpuset;
 mod credential_spec;
@@ -14,21 +15,29 @@ mod expose;
 pub mod healthcheck;
 mod hostname;
 pub mod image;
+mod label_file;
 mod limit;
+pub mod logging;
 pub mod network_config;
 pub mod platform;
 pub mod ports;
+mod security_opt;
+mod signal;
+mod sysctl;
+mod tmpfs;
 mod ulimit;
 pub mod user;
+mod userns_mode;
 pub mod volumes;
 
 use std::{
     borrow::Cow,
     fmt::{self, Display, Formatter},
     net::IpAddr,
+    num::ParseIntError,
     ops::Not,
-    path::PathBuf,
-    time::Duration,
+    path::{Path, PathBuf},
+    time::{Duration, SystemTime},
 };
 
 use compose_spec_macros::{DeserializeTryFromString, SerializeDisplay};
@@ -37,17 +46,19 @@ use serde::{de, Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
 use crate::{
+    duration::{self, ParseDurationError},
     impl_from_str,
     serde::{default_true, duration_option, duration_us_option, skip_true, ItemOrListVisitor},
     AsShortIter, Configs, Extensions, Identifier, InvalidIdentifierError, ItemOrList, ListOrMap,
-    Map, MapKey, Networks, Secrets, ShortOrLong, StringOrNumber, Value,
+    Map, Networks, Secrets, ShortOrLong, Value,
 };
 
-use self::build::Context;
+pub(crate) use self::ulimit::parse_ulimit;
 pub use self::{
     blkio_config::BlkioConfig,
     build::Build,
     byte_value::{ByteValue, ParseByteValueError},
+    capability::Capability,
     config_or_secret::ConfigOrSecret,
     cpuset::{CpuSet, ParseCpuSetError},
     credential_spec::{CredentialSpec, Kind as CredentialSpecKind},
@@ -59,14 +70,25 @@ pub use self::{
     healthcheck::Healthcheck,
     hostname::{Hostname, InvalidHostnameError},
     image::Image,
+    label_file::LabelFileError,
     limit::Limit,
-    network_config::{MacAddress, NetworkConfig},
+    logging::Logging,
+    network_config::{MacAddress, NetworkAttachmentError, NetworkConfig},
     platform::Platform,
     ports::Ports,
-    ulimit::{InvalidResourceError, Resource, Ulimit, Ulimits},
+    security_opt::{Label as SecurityOptLabel, Seccomp, SecurityOpt},
+    signal::{ParseSignalError, RealTimeOffset, Signal},
+    sysctl::{InvalidSysctlError, Sysctl, SysctlError, SysctlNamespace},
+    tmpfs::{ParseTmpfsEntryError, ParseTmpfsOptionError, TmpfsEntry, TmpfsOption},
+    ulimit::{
+        EngineUlimit, InvalidResourceError, KnownResource, Resource, SoftExceedsHardError, Ulimit,
+        Ulimits,
+    },
     user::{IdOrName, User},
+    userns_mode::{AutoOptions, KeepIdOptions, ParseUsernsModeError, UsernsMode},
     volumes::{AbsolutePath, Volumes},
 };
+use self::{build::Context, network_config::NetworkMode};
 
 /// A service is an abstract definition of a computing resource within an application which can be
 /// scaled or replaced independently from other components.
@@ -181,13 +203,13 @@ pub struct Service {
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#cap_add)
     #[serde(default, skip_serializing_if = "IndexSet::is_empty")]
-    pub cap_add: IndexSet<String>,
+    pub cap_add: IndexSet<Capability>,
 
     /// Drop container [**capabilities**(7)](https://man7.org/linux/man-pages/man7/capabilities.7.html).
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#cap_drop)
     #[serde(default, skip_serializing_if = "IndexSet::is_empty")]
-    pub cap_drop: IndexSet<String>,
+    pub cap_drop: IndexSet<Capability>,
 
     /// [Cgroup](https://man7.org/linux/man-pages/man7/cgroups.7.html) namespace to join.
     ///
@@ -203,7 +225,7 @@ pub struct Service {
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#cgroup_parent)
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub cgroup_parent: Option<String>,
+    pub cgroup_parent: Option<CgroupParent>,
 
     /// Overrides the default command declared by the container image.
     ///
@@ -402,6 +424,14 @@ pub struct Service {
     #[serde(default, skip_serializing_if = "ListOrMap::is_empty")]
     pub labels: ListOrMap,
 
+    /// Files to read labels from, in the format `{key}={value}`.
+    ///
+    /// Use [`Service::resolve_labels()`] to merge them with [`labels`](Self::labels).
+    ///
+    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#label_file)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub label_file: Option<ItemOrList<PathBuf>>,
+
     /// Network links to containers in another service.
     ///
     /// Note: Availability of the `links` field is implementation specific.
@@ -487,7 +517,7 @@ pub struct Service {
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#pid)
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub pid: Option<String>,
+    pub pid: Option<Pid>,
 
     /// Tune the container's PIDs limit.
     ///
@@ -574,11 +604,12 @@ pub struct Service {
 
     /// Container security options.
     ///
-    /// Available values are platform specific.
+    /// Available values are platform specific, unknown values are kept as
+    /// [`SecurityOpt::Other`].
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#security_opt)
     #[serde(default, skip_serializing_if = "IndexSet::is_empty")]
-    pub security_opt: IndexSet<String>,
+    pub security_opt: IndexSet<SecurityOpt>,
 
     /// Size of the shared memory (`/dev/shm` on Linux) allowed for the container.
     ///
@@ -608,7 +639,7 @@ pub struct Service {
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#stop_signal)
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub stop_signal: Option<String>,
+    pub stop_signal: Option<Signal>,
 
     /// Storage driver options.
     ///
@@ -618,6 +649,8 @@ pub struct Service {
 
     /// Kernel parameters to set in the container.
     ///
+    /// Use [`Service::validate_sysctls()`] to ensure the [`Sysctl`]s can be set.
+    ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#sysctls)
     #[serde(default, skip_serializing_if = "ListOrMap::is_empty")]
     pub sysctls: ListOrMap,
@@ -626,7 +659,7 @@ pub struct Service {
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#tmpfs)
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub tmpfs: Option<ItemOrList<AbsolutePath>>,
+    pub tmpfs: Option<ItemOrList<TmpfsEntry>>,
 
     /// Whether to run the container with a TTY.
     ///
@@ -654,7 +687,7 @@ pub struct Service {
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#userns_mode)
     #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub userns_mode: Option<String>,
+    pub userns_mode: Option<UsernsMode>,
 
     /// [`Volume`](crate::Volume)s to mount within the container.
     ///
@@ -682,6 +715,200 @@ pub struct Service {
 }
 
 impl Service {
+    /// Effective set of capabilities of the service container.
+    ///
+    /// Computed from the Docker Engine's [default](Capability::DEFAULT) set of capabilities
+    /// combined with `cap_add` and `cap_drop`. See [`Capability::effective_set()`] for details.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compose_spec::service::{Capability, Service};
+    /// use indexmap::indexset;
+    ///
+    /// let service = Service {
+    ///     cap_add: indexset![Capability::NetAdmin],
+    ///     cap_drop: indexset![Capability::All],
+    ///     ..Service::default()
+    /// };
+    ///
+    /// assert_eq!(service.effective_capabilities(), indexset![Capability::NetAdmin]);
+    /// ```
+    #[must_use]
+    pub fn effective_capabilities(&self) -> IndexSet<Capability> {
+        Capability::effective_set(Capability::DEFAULT, &self.cap_add, &self.cap_drop)
+    }
+
+    /// Names of the other [`Service`]s this service depends on.
+    ///
+    /// Includes services from `depends_on`, `links`, `volumes_from`, and the service references of
+    /// the `network_mode`, `ipc`, and `pid` namespace modes.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compose_spec::{service::Pid, Identifier, Service};
+    ///
+    /// let database = Identifier::new("database")?;
+    /// let service = Service {
+    ///     pid: Some(Pid::Service(database.clone())),
+    ///     ..Service::default()
+    /// };
+    ///
+    /// assert!(service.service_dependencies().contains(&database));
+    /// # Ok::<(), compose_spec::InvalidIdentifierError>(())
+    /// ```
+    #[must_use]
+    pub fn service_dependencies(&self) -> IndexSet<&Identifier> {
+        let mut dependencies = IndexSet::new();
+
+        match &self.depends_on {
+            ShortOrLong::Short(depends_on) => dependencies.extend(depends_on),
+            ShortOrLong::Long(depends_on) => dependencies.extend(depends_on.keys()),
+        }
+
+        dependencies.extend(self.links.iter().map(|link| &link.service));
+
+        dependencies.extend(self.volumes_from.iter().filter_map(|volumes_from| {
+            match &volumes_from.source {
+                VolumesFromSource::Service(service) => Some(service),
+                VolumesFromSource::Container(_) => None,
+            }
+        }));
+
+        if let Some(NetworkConfig::NetworkMode(NetworkMode::Service(service))) =
+            &self.network_config
+        {
+            dependencies.insert(service);
+        }
+
+        dependencies.extend(self.ipc.as_ref().and_then(Ipc::as_service));
+        dependencies.extend(self.pid.as_ref().and_then(Pid::as_service));
+
+        dependencies
+    }
+
+    /// Effective labels of the service container.
+    ///
+    /// Reads each file in `label_file`, resolving relative paths against `base_dir`, and merges
+    /// them with the inline `labels`. Labels from later files take precedence over earlier ones,
+    /// and inline `labels` take precedence over all files.
+    ///
+    /// Label files contain lines in the format `{key}={value}` and are parsed the same as
+    /// [`Format::Raw`](env_file::Format::Raw) environment files. Empty lines and lines starting
+    /// with `#` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a label file could not be read or a label key is invalid.
+    pub fn resolve_labels<P: AsRef<Path>>(&self, base_dir: P) -> Result<ListOrMap, LabelFileError> {
+        label_file::resolve(self.label_file.as_ref(), &self.labels, base_dir.as_ref())
+            .map(ListOrMap::Map)
+    }
+
+    /// Ensure that all `sysctls` can be set in the service container.
+    ///
+    /// Only namespaced sysctls can be set, see [`Sysctl`] for details. Additionally, `net.*`
+    /// sysctls cannot be set when the `network_mode` is `host`, and IPC sysctls (e.g.
+    /// `kernel.shmmax`) cannot be set when `ipc` is `host`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compose_spec::{
+    ///     service::{network_config::NetworkMode, NetworkConfig, SysctlError},
+    ///     ListOrMap, Service,
+    /// };
+    ///
+    /// let mut service = Service {
+    ///     sysctls: ListOrMap::List(["net.core.somaxconn=1024".to_owned()].into()),
+    ///     ..Service::default()
+    /// };
+    /// assert!(service.validate_sysctls().is_ok());
+    ///
+    /// service.network_config = Some(NetworkConfig::NetworkMode(NetworkMode::Host));
+    /// assert!(matches!(
+    ///     service.validate_sysctls(),
+    ///     Err(SysctlError::HostNamespace { .. }),
+    /// ));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a sysctl is invalid, is not namespaced, or is in a namespace the
+    /// container shares with the host.
+    pub fn validate_sysctls(&self) -> Result<(), SysctlError> {
+        let mut host_namespaces = Vec::new();
+        if matches!(
+            self.network_config,
+            Some(NetworkConfig::NetworkMode(NetworkMode::Host))
+        ) {
+            host_namespaces.push(SysctlNamespace::Net);
+        }
+        if self.ipc.as_ref().is_some_and(Ipc::is_host) {
+            host_namespaces.push(SysctlNamespace::Ipc);
+        }
+
+        sysctl::validate(&self.sysctls, &host_namespaces)
+    }
+
+    /// Ensure that the service's network attachments are consistent with each other and with the
+    /// top-level `networks` of the [`Compose`](crate::Compose) file.
+    ///
+    /// At most one attachment may have the highest `gw_priority`, as it determines the container's
+    /// default gateway. Attachments without a `gw_priority` have a priority of 0. Additionally, IPv4
+    /// addresses cannot be set for networks with `enable_ipv4: false`.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compose_spec::{
+    ///     service::{network_config::Network, NetworkAttachmentError, NetworkConfig},
+    ///     Compose, Identifier, Service, ShortOrLong,
+    /// };
+    /// use indexmap::indexmap;
+    ///
+    /// let one = Identifier::new("one")?;
+    /// let two = Identifier::new("two")?;
+    /// let network = Network {
+    ///     gw_priority: Some(1),
+    ///     ..Network::default()
+    /// };
+    /// let service = Service {
+    ///     network_config: Some(NetworkConfig::Networks(ShortOrLong::Long(indexmap! {
+    ///         one.clone() => Some(network.clone()),
+    ///         two.clone() => Some(network),
+    ///     }))),
+    ///     ..Service::default()
+    /// };
+    ///
+    /// assert_eq!(
+    ///     service.validate_network_attachments(&Compose::default().networks),
+    ///     Err(NetworkAttachmentError::GwPriority {
+    ///         first: one,
+    ///         second: two,
+    ///         gw_priority: 1,
+    ///     }),
+    /// );
+    /// # Ok::<(), compose_spec::InvalidIdentifierError>(())
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if multiple networks have the highest `gw_priority`, or an IPv4 address is
+    /// set for a network with IPv4 disabled.
+    pub fn validate_network_attachments(
+        &self,
+        networks: &Networks,
+    ) -> Result<(), NetworkAttachmentError> {
+        match &self.network_config {
+            Some(NetworkConfig::Networks(ShortOrLong::Long(attachments))) => {
+                network_config::validate_attachments(attachments, networks)
+            }
+            _ => Ok(()),
+        }
+    }
+
     /// Ensure that all networks used in the `network_config` of the service are defined in the
     /// top-level `networks` field of the [`Compose`](crate::Compose) file.
     ///
@@ -835,6 +1062,84 @@ impl Display for Cgroup {
     }
 }
 
+/// Parent [cgroup](https://man7.org/linux/man-pages/man7/cgroups.7.html) of a [`Service`]'s
+/// container.
+///
+/// (De)serializes from/to a string. Names ending with `.slice` which do not contain a slash (/)
+/// are systemd slices, used with the systemd cgroup driver. All other values are cgroupfs paths.
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#cgroup_parent)
+#[derive(SerializeDisplay, DeserializeTryFromString, Debug, Clone, PartialEq, Eq)]
+pub enum CgroupParent {
+    /// systemd slice unit to place the container's scope in, e.g. `user.slice`.
+    Slice(String),
+
+    /// cgroupfs path, absolute or relative to the container runtime's default parent cgroup,
+    /// e.g. `/docker`.
+    Path(PathBuf),
+}
+
+impl CgroupParent {
+    /// [`Self::Slice`] suffix.
+    const SLICE_SUFFIX: &'static str = ".slice";
+
+    /// Parse a [`CgroupParent`] from a string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the string is empty.
+    pub fn parse<T>(cgroup_parent: T) -> Result<Self, ParseCgroupParentError>
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        let s = cgroup_parent.as_ref();
+
+        if s.is_empty() {
+            Err(ParseCgroupParentError)
+        } else if s.ends_with(Self::SLICE_SUFFIX) && !s.contains('/') {
+            Ok(Self::Slice(cgroup_parent.into()))
+        } else {
+            Ok(Self::Path(cgroup_parent.into().into()))
+        }
+    }
+
+    /// Returns [`Some`] if a systemd [`Slice`](Self::Slice).
+    #[must_use]
+    pub fn as_slice(&self) -> Option<&str> {
+        if let Self::Slice(slice) = self {
+            Some(slice)
+        } else {
+            None
+        }
+    }
+
+    /// Returns [`Some`] if a cgroupfs [`Path`](Self::Path).
+    #[must_use]
+    pub fn as_path(&self) -> Option<&Path> {
+        if let Self::Path(path) = self {
+            Some(path)
+        } else {
+            None
+        }
+    }
+}
+
+impl_from_str!(CgroupParent => ParseCgroupParentError);
+
+/// Error returned when [parsing](CgroupParent::parse()) a [`CgroupParent`] from a string.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("cgroup parent cannot be empty")]
+pub struct ParseCgroupParentError;
+
+impl Display for CgroupParent {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Slice(slice) => f.write_str(slice),
+            Self::Path(path) => path.display().fmt(f),
+        }
+    }
+}
+
 /// Override the default command or entrypoint declared by the container image.
 ///
 /// [command compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#command)
@@ -950,7 +1255,7 @@ impl<'a> AsShortIter<'a> for IndexMap<Identifier, Dependency> {
 }
 
 /// Returns `true` if `depends_on` is empty.
-fn depends_on_is_empty(depends_on: &DependsOn) -> bool {
+pub(crate) fn depends_on_is_empty(depends_on: &DependsOn) -> bool {
     match depends_on {
         ShortOrLong::Short(short) => short.is_empty(),
         ShortOrLong::Long(long) => long.is_empty(),
@@ -1097,6 +1402,8 @@ fn strip_brackets(s: &str) -> &str {
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#ipc)
 #[derive(SerializeDisplay, DeserializeTryFromString, Debug, Clone, PartialEq, Eq)]
 pub enum Ipc {
+    /// Use the host's IPC namespace.
+    Host,
     /// Give the container its own private IPC namespace and allow it to be shared with other
     /// containers.
     Shareable,
@@ -1107,6 +1414,9 @@ pub enum Ipc {
 }
 
 impl Ipc {
+    /// [`Self::Host`] string value.
+    const HOST: &'static str = "host";
+
     /// [`Self::Shareable`] string value.
     const SHAREABLE: &'static str = "shareable";
 
@@ -1123,7 +1433,9 @@ impl Ipc {
     where
         T: AsRef<str> + Into<String>,
     {
-        if ipc.as_ref() == Self::SHAREABLE {
+        if ipc.as_ref() == Self::HOST {
+            Ok(Self::Host)
+        } else if ipc.as_ref() == Self::SHAREABLE {
             Ok(Self::Shareable)
         } else if let Some(service) = ipc.as_ref().strip_prefix(Self::SERVICE_PREFIX) {
             service.parse().map(Self::Service).map_err(Into::into)
@@ -1132,6 +1444,14 @@ impl Ipc {
         }
     }
 
+    /// Returns `true` if the IPC isolation mode is [`Host`].
+    ///
+    /// [`Host`]: Ipc::Host
+    #[must_use]
+    pub const fn is_host(&self) -> bool {
+        matches!(self, Self::Host)
+    }
+
     /// Returns `true` if the IPC isolation mode is [`Shareable`].
     ///
     /// [`Shareable`]: Ipc::Shareable
@@ -1191,6 +1511,7 @@ pub struct ParseIpcError(#[from] InvalidIdentifierError);
 impl Display for Ipc {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
         match self {
+            Self::Host => f.write_str(Self::HOST),
             Self::Shareable => f.write_str(Self::SHAREABLE),
             Self::Service(service) => write!(f, "{}{service}", Self::SERVICE_PREFIX),
             Self::Other(other) => f.write_str(other),
@@ -1210,10 +1531,170 @@ impl From<Ipc> for String {
 
 impl From<Ipc> for Cow<'static, str> {
     fn from(value: Ipc) -> Self {
-        if value.is_shareable() {
-            Ipc::SHAREABLE.into()
+        match value {
+            Ipc::Host => Ipc::HOST.into(),
+            Ipc::Shareable => Ipc::SHAREABLE.into(),
+            value => value.to_string().into(),
+        }
+    }
+}
+
+/// PID namespace mode for a [`Service`] container.
+///
+/// Available values are platform specific, but some options are defined in the Compose
+/// specification.
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#pid)
+#[derive(SerializeDisplay, DeserializeTryFromString, Debug, Clone, PartialEq, Eq)]
+pub enum Pid {
+    /// Use the host's PID namespace.
+    Host,
+
+    /// Join the PID namespace of the specified service.
+    Service(Identifier),
+
+    /// Join the PID namespace of the specified container.
+    Container(Identifier),
+
+    /// Other PID namespace mode.
+    Other(String),
+}
+
+impl Pid {
+    /// [`Self::Host`] string value.
+    const HOST: &'static str = "host";
+
+    /// [`Self::Service`] string prefix.
+    const SERVICE_PREFIX: &'static str = "service:";
+
+    /// [`Self::Container`] string prefix.
+    const CONTAINER_PREFIX: &'static str = "container:";
+
+    /// Parse a [`Pid`] namespace mode from a string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the service or container in the service or container PID namespace
+    /// mode is not a valid [`Identifier`].
+    pub fn parse<T>(pid: T) -> Result<Self, ParsePidError>
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        let s = pid.as_ref();
+
+        if s == Self::HOST {
+            Ok(Self::Host)
+        } else if let Some(service) = s.strip_prefix(Self::SERVICE_PREFIX) {
+            service.parse().map(Self::Service).map_err(Into::into)
+        } else if let Some(container) = s.strip_prefix(Self::CONTAINER_PREFIX) {
+            container.parse().map(Self::Container).map_err(Into::into)
+        } else {
+            Ok(Self::Other(pid.into()))
+        }
+    }
+
+    /// Returns `true` if the PID namespace mode is [`Host`].
+    ///
+    /// [`Host`]: Pid::Host
+    #[must_use]
+    pub const fn is_host(&self) -> bool {
+        matches!(self, Self::Host)
+    }
+
+    /// Returns `true` if the PID namespace mode is [`Service`].
+    ///
+    /// [`Service`]: Pid::Service
+    #[must_use]
+    pub const fn is_service(&self) -> bool {
+        matches!(self, Self::Service(..))
+    }
+
+    /// Returns [`Some`] if the PID namespace mode is [`Service`].
+    ///
+    /// [`Service`]: Pid::Service
+    #[must_use]
+    pub const fn as_service(&self) -> Option<&Identifier> {
+        if let Self::Service(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
+    /// Returns `true` if the PID namespace mode is [`Container`].
+    ///
+    /// [`Container`]: Pid::Container
+    #[must_use]
+    pub const fn is_container(&self) -> bool {
+        matches!(self, Self::Container(..))
+    }
+
+    /// Returns [`Some`] if the PID namespace mode is [`Container`].
+    ///
+    /// [`Container`]: Pid::Container
+    #[must_use]
+    pub const fn as_container(&self) -> Option<&Identifier> {
+        if let Self::Container(v) = self {
+            Some(v)
         } else {
-            value.to_string().into()
+            None
+        }
+    }
+
+    /// Returns `true` if the PID namespace mode is [`Other`].
+    ///
+    /// [`Other`]: Pid::Other
+    #[must_use]
+    pub const fn is_other(&self) -> bool {
+        matches!(self, Self::Other(..))
+    }
+
+    /// Returns [`Some`] if the PID namespace mode is [`Other`].
+    ///
+    /// [`Other`]: Pid::Other
+    #[must_use]
+    pub const fn as_other(&self) -> Option<&String> {
+        if let Self::Other(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+
+impl_from_str!(Pid => ParsePidError);
+
+/// Error returned when [parsing](Pid::parse()) a [`Pid`] namespace mode from a string.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("error parsing service or container PID namespace mode")]
+pub struct ParsePidError(#[from] InvalidIdentifierError);
+
+impl Display for Pid {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Host => f.write_str(Self::HOST),
+            Self::Service(service) => write!(f, "{}{service}", Self::SERVICE_PREFIX),
+            Self::Container(container) => write!(f, "{}{container}", Self::CONTAINER_PREFIX),
+            Self::Other(other) => f.write_str(other),
+        }
+    }
+}
+
+impl From<Pid> for String {
+    fn from(value: Pid) -> Self {
+        if let Pid::Other(other) = value {
+            other
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+impl From<Pid> for Cow<'static, str> {
+    fn from(value: Pid) -> Self {
+        match value {
+            Pid::Host => Cow::Borrowed(Pid::HOST),
+            value => Cow::Owned(value.to_string()),
         }
     }
 }
@@ -1249,42 +1730,6 @@ impl Display for Uts {
     }
 }
 
-/// Logging configuration for a [`Service`].
-///
-/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#logging)
-#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
-pub struct Logging {
-    /// Logging driver for the [`Service`] container.
-    ///
-    /// The default and available values are platform specific.
-    #[serde(default, skip_serializing_if = "Option::is_none")]
-    pub driver: Option<String>,
-
-    /// Driver specific options.
-    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
-    pub options: IndexMap<MapKey, Option<StringOrNumber>>,
-
-    /// Extension values, which are (de)serialized via flattening.
-    ///
-    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/11-extension.md)
-    #[serde(flatten)]
-    pub extensions: Extensions,
-}
-
-impl Logging {
-    /// Returns `true` if all fields are [`None`] or empty.
-    #[must_use]
-    pub fn is_empty(&self) -> bool {
-        let Self {
-            driver,
-            options,
-            extensions,
-        } = self;
-
-        driver.is_none() && options.is_empty() && extensions.is_empty()
-    }
-}
-
 /// Preference for a [`Service`] container to be killed by the platform in the case of memory
 /// starvation.
 ///
@@ -1335,9 +1780,13 @@ impl PartialEq<i16> for OomScoreAdj {
 
 /// When the platform should pull a [`Service`]'s [`Image`].
 ///
+/// (De)serializes from/to a string. Time-based pull policies use the format `every_{duration}`,
+/// where `duration` is in the [compose-spec duration format](crate::duration).
+///
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#pull_policy)
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
-#[serde(rename_all = "snake_case")]
+#[derive(
+    SerializeDisplay, DeserializeTryFromString, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
 pub enum PullPolicy {
     /// Always pull the image from the registry.
     Always,
@@ -1348,27 +1797,194 @@ pub enum PullPolicy {
     Never,
 
     /// Pull the image only if it's not available in the platform cache.
-    #[serde(alias = "if_not_present")]
+    ///
+    /// Also parsed from `if_not_present`.
     #[default]
     Missing,
 
     /// Build the image.
     Build,
+
+    /// Pull the image if it was last pulled more than a day ago.
+    Daily,
+
+    /// Pull the image if it was last pulled more than a week ago.
+    Weekly,
+
+    /// Pull the image if it was last pulled more than the given duration ago.
+    Every(Duration),
+
+    /// Check the registry for updates to the image and pull it if it has changed.
+    Refresh,
 }
 
 impl PullPolicy {
-    /// Pull policy as a static string slice.
+    /// [`Self::Always`] string value.
+    const ALWAYS: &'static str = "always";
+
+    /// [`Self::Never`] string value.
+    const NEVER: &'static str = "never";
+
+    /// [`Self::Missing`] string value.
+    const MISSING: &'static str = "missing";
+
+    /// Alias for [`Self::Missing`].
+    const IF_NOT_PRESENT: &'static str = "if_not_present";
+
+    /// [`Self::Build`] string value.
+    const BUILD: &'static str = "build";
+
+    /// [`Self::Daily`] string value.
+    const DAILY: &'static str = "daily";
+
+    /// [`Self::Weekly`] string value.
+    const WEEKLY: &'static str = "weekly";
+
+    /// [`Self::Every`] string prefix.
+    const EVERY_PREFIX: &'static str = "every_";
+
+    /// [`Self::Refresh`] string value.
+    const REFRESH: &'static str = "refresh";
+
+    /// Duration of [`Self::Daily`].
+    const DAY: Duration = Duration::from_secs(24 * 60 * 60);
+
+    /// Duration of [`Self::Weekly`].
+    const WEEK: Duration = Duration::from_secs(7 * 24 * 60 * 60);
+
+    /// Parse a [`PullPolicy`] from a string.
+    ///
+    /// `if_not_present` is parsed as [`Missing`](Self::Missing).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the pull policy is unknown or if the duration of an `every_{duration}`
+    /// pull policy could not be parsed.
+    pub fn parse<T>(pull_policy: T) -> Result<Self, ParsePullPolicyError>
+    where
+        T: AsRef<str>,
+    {
+        match pull_policy.as_ref() {
+            Self::ALWAYS => Ok(Self::Always),
+            Self::NEVER => Ok(Self::Never),
+            Self::MISSING | Self::IF_NOT_PRESENT => Ok(Self::Missing),
+            Self::BUILD => Ok(Self::Build),
+            Self::DAILY => Ok(Self::Daily),
+            Self::WEEKLY => Ok(Self::Weekly),
+            Self::REFRESH => Ok(Self::Refresh),
+            s => s
+                .strip_prefix(Self::EVERY_PREFIX)
+                .ok_or_else(|| ParsePullPolicyError::Unknown(s.to_owned()))
+                .and_then(|duration| {
+                    duration::parse(duration)
+                        .map(Self::Every)
+                        .map_err(|source| ParsePullPolicyError::Duration {
+                            source,
+                            value: duration.to_owned(),
+                        })
+                }),
+        }
+    }
+
+    /// Pull policy name as a static string slice.
+    ///
+    /// Does not include the duration of [`Every`](Self::Every), use the [`Display`]
+    /// implementation for the full value.
     #[must_use]
     pub const fn as_str(self) -> &'static str {
         match self {
-            Self::Always => "always",
-            Self::Never => "never",
-            Self::Missing => "missing",
-            Self::Build => "build",
+            Self::Always => Self::ALWAYS,
+            Self::Never => Self::NEVER,
+            Self::Missing => Self::MISSING,
+            Self::Build => Self::BUILD,
+            Self::Daily => Self::DAILY,
+            Self::Weekly => Self::WEEKLY,
+            Self::Every(_) => "every",
+            Self::Refresh => Self::REFRESH,
+        }
+    }
+
+    /// Interval between pulls for time-based pull policies.
+    ///
+    /// Returns [`Some`] for [`Daily`](Self::Daily), [`Weekly`](Self::Weekly), and
+    /// [`Every`](Self::Every), and [`None`] otherwise.
+    #[must_use]
+    pub const fn interval(self) -> Option<Duration> {
+        match self {
+            Self::Daily => Some(Self::DAY),
+            Self::Weekly => Some(Self::WEEK),
+            Self::Every(duration) => Some(duration),
+            Self::Always | Self::Never | Self::Missing | Self::Build | Self::Refresh => None,
+        }
+    }
+
+    /// Determine whether the image should be pulled according to the pull policy.
+    ///
+    /// `last_pulled` is the time the image was last pulled, or [`None`] if the image is not in the
+    /// platform cache. `now` is the current time.
+    ///
+    /// - [`Always`](Self::Always) and [`Refresh`](Self::Refresh) are always due, as the registry
+    ///   must be checked for updates.
+    /// - [`Never`](Self::Never) and [`Build`](Self::Build) are never due.
+    /// - [`Missing`](Self::Missing) is due if the image has not been pulled.
+    /// - Time-based policies are due if the image has not been pulled or if at least the
+    ///   [`interval()`](Self::interval()) has elapsed since `last_pulled`. If `last_pulled` is
+    ///   after `now`, the pull is not due.
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use std::time::{Duration, SystemTime};
+    ///
+    /// use compose_spec::service::PullPolicy;
+    ///
+    /// let pull_policy: PullPolicy = "every_12h".parse().unwrap();
+    /// let last_pulled = SystemTime::UNIX_EPOCH;
+    ///
+    /// let now = last_pulled + Duration::from_secs(60 * 60);
+    /// assert!(!pull_policy.is_pull_due(Some(last_pulled), now));
+    ///
+    /// let now = last_pulled + Duration::from_secs(12 * 60 * 60);
+    /// assert!(pull_policy.is_pull_due(Some(last_pulled), now));
+    /// ```
+    #[must_use]
+    pub fn is_pull_due(self, last_pulled: Option<SystemTime>, now: SystemTime) -> bool {
+        match self {
+            Self::Always | Self::Refresh => true,
+            Self::Never | Self::Build => false,
+            Self::Missing => last_pulled.is_none(),
+            Self::Daily | Self::Weekly | Self::Every(_) => match (last_pulled, self.interval()) {
+                (Some(last_pulled), Some(interval)) => now
+                    .duration_since(last_pulled)
+                    .is_ok_and(|elapsed| elapsed >= interval),
+                _ => true,
+            },
         }
     }
 }
 
+impl_from_str!(PullPolicy => ParsePullPolicyError);
+
+/// Error returned when [parsing](PullPolicy::parse()) a [`PullPolicy`] from a string.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParsePullPolicyError {
+    /// Unknown pull policy.
+    #[error(
+        "unknown pull policy `{0}`, must be `always`, `never`, `missing`, `if_not_present`, \
+        `build`, `daily`, `weekly`, `every_{{duration}}`, or `refresh`"
+    )]
+    Unknown(String),
+
+    /// Error parsing the duration of an `every_{duration}` pull policy.
+    #[error("error parsing pull policy duration `{value}`")]
+    Duration {
+        /// Source of the error.
+        source: ParseDurationError,
+        /// Value that was attempted to parse.
+        value: String,
+    },
+}
+
 impl AsRef<str> for PullPolicy {
     fn as_ref(&self) -> &str {
         self.as_str()
@@ -1377,15 +1993,28 @@ impl AsRef<str> for PullPolicy {
 
 impl Display for PullPolicy {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        f.write_str(self.as_str())
+        if let Self::Every(duration) = self {
+            write!(
+                f,
+                "{}{}",
+                Self::EVERY_PREFIX,
+                duration::to_string(*duration)
+            )
+        } else {
+            f.write_str(self.as_str())
+        }
     }
 }
 
 /// Restart policy that the platform applies on [`Service`] container termination.
 ///
+/// (De)serializes from/to a string in the format `{policy}[:{max_retries}]`, where `max_retries`
+/// may only be set for the `on-failure` policy.
+///
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#restart)
-#[derive(Serialize, Deserialize, Debug, Clone, Copy, Default, PartialEq, Eq)]
-#[serde(rename_all = "kebab-case")]
+#[derive(
+    SerializeDisplay, DeserializeTryFromString, Debug, Clone, Copy, Default, PartialEq, Eq,
+)]
 pub enum Restart {
     /// Do not restart the container under any circumstance.
     #[default]
@@ -1393,25 +2022,191 @@ pub enum Restart {
     /// Always restart the container until its removal.
     Always,
     /// Restart the container if the exit code indicates an error.
-    OnFailure,
+    OnFailure {
+        /// Maximum number of times to attempt to restart the container.
+        ///
+        /// If [`None`], restart attempts are unlimited.
+        max_retries: Option<u64>,
+    },
     /// Restart the container irrespective of the exit code, but stops restarting when the service
     /// is stopped or removed.
     UnlessStopped,
 }
 
 impl Restart {
-    /// Restart policy as a static string slice.
+    /// [`Self::No`] string value.
+    const NO: &'static str = "no";
+
+    /// [`Self::Always`] string value.
+    const ALWAYS: &'static str = "always";
+
+    /// [`Self::OnFailure`] string value.
+    const ON_FAILURE: &'static str = "on-failure";
+
+    /// [`Self::UnlessStopped`] string value.
+    const UNLESS_STOPPED: &'static str = "unless-stopped";
+
+    /// Parse a [`Restart`] policy from a string in the format `{policy}[:{max_retries}]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the policy is unknown, if `max_retries` is given for a policy other than
+    /// `on-failure`, or if `max_retries` is not an unsigned integer.
+    pub fn parse<T>(restart: T) -> Result<Self, ParseRestartError>
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        let restart_str = restart.as_ref();
+
+        // Format is "{policy}[:{max_retries}]"
+        let (policy, max_retries) = restart_str
+            .split_once(':')
+            .map_or((restart_str, None), |(policy, max_retries)| {
+                (policy, Some(max_retries))
+            });
+
+        match (policy, max_retries) {
+            (Self::NO, None) => Ok(Self::No),
+            (Self::ALWAYS, None) => Ok(Self::Always),
+            (Self::ON_FAILURE, max_retries) => max_retries
+                .map(|max_retries| {
+                    max_retries
+                        .parse()
+                        .map_err(|source| ParseRestartError::MaxRetries {
+                            source,
+                            value: max_retries.to_owned(),
+                        })
+                })
+                .transpose()
+                .map(|max_retries| Self::OnFailure { max_retries }),
+            (Self::UNLESS_STOPPED, None) => Ok(Self::UnlessStopped),
+            (Self::NO | Self::ALWAYS | Self::UNLESS_STOPPED, Some(_)) => {
+                Err(ParseRestartError::UnexpectedMaxRetries(policy.to_owned()))
+            }
+            _ => Err(ParseRestartError::Unknown(restart.into())),
+        }
+    }
+
+    /// Restart policy name as a static string slice.
+    ///
+    /// Does not include the maximum number of retries of [`OnFailure`](Self::OnFailure), use the
+    /// [`Display`] implementation for the full value.
     #[must_use]
     pub const fn as_str(self) -> &'static str {
         match self {
-            Self::No => "no",
-            Self::Always => "always",
-            Self::OnFailure => "on-failure",
-            Self::UnlessStopped => "unless-stopped",
+            Self::No => Self::NO,
+            Self::Always => Self::ALWAYS,
+            Self::OnFailure { .. } => Self::ON_FAILURE,
+            Self::UnlessStopped => Self::UNLESS_STOPPED,
+        }
+    }
+
+    /// Returns the maximum number of restart attempts if the restart policy is
+    /// [`OnFailure`](Self::OnFailure) and the maximum is set.
+    #[must_use]
+    pub const fn max_retries(self) -> Option<u64> {
+        if let Self::OnFailure { max_retries } = self {
+            max_retries
+        } else {
+            None
+        }
+    }
+
+    /// Convert into the equivalent [`deploy::RestartPolicy`].
+    ///
+    /// [`UnlessStopped`](Self::UnlessStopped) has no direct equivalent and is converted to the
+    /// [`Any`](deploy::RestartCondition::Any) restart condition, the same as
+    /// [`Always`](Self::Always).
+    ///
+    /// # Examples
+    ///
+    /// ```
+    /// use compose_spec::service::{
+    ///     deploy::{RestartCondition, RestartPolicy},
+    ///     Restart,
+    /// };
+    ///
+    /// let restart: Restart = "on-failure:5".parse().unwrap();
+    /// let policy = restart.into_restart_policy();
+    /// assert_eq!(policy.condition, Some(RestartCondition::OnFailure));
+    /// assert_eq!(policy.max_attempts, Some(5));
+    ///
+    /// assert_eq!(Restart::from_restart_policy(&policy), Some(restart));
+    /// ```
+    #[must_use]
+    pub fn into_restart_policy(self) -> deploy::RestartPolicy {
+        let (condition, max_attempts) = match self {
+            Self::No => (deploy::RestartCondition::None, None),
+            Self::Always | Self::UnlessStopped => (deploy::RestartCondition::Any, None),
+            Self::OnFailure { max_retries } => (deploy::RestartCondition::OnFailure, max_retries),
+        };
+
+        deploy::RestartPolicy {
+            condition: Some(condition),
+            max_attempts,
+            ..deploy::RestartPolicy::default()
+        }
+    }
+
+    /// Create a [`Restart`] policy from a [`deploy::RestartPolicy`].
+    ///
+    /// A `condition` of [`None`] is treated as the default,
+    /// [`Any`](deploy::RestartCondition::Any).
+    ///
+    /// Returns [`None`] if the restart policy cannot be represented as a [`Restart`] policy, i.e.
+    /// if `delay`, `window`, or `extensions` are set, or if `max_attempts` is set and the
+    /// `condition` is not [`OnFailure`](deploy::RestartCondition::OnFailure).
+    #[must_use]
+    pub fn from_restart_policy(restart_policy: &deploy::RestartPolicy) -> Option<Self> {
+        let deploy::RestartPolicy {
+            condition,
+            delay,
+            max_attempts,
+            window,
+            extensions,
+        } = restart_policy;
+
+        if delay.is_some() || window.is_some() || !extensions.is_empty() {
+            return None;
+        }
+
+        match (condition.unwrap_or_default(), *max_attempts) {
+            (deploy::RestartCondition::None, None) => Some(Self::No),
+            (deploy::RestartCondition::Any, None) => Some(Self::Always),
+            (deploy::RestartCondition::OnFailure, max_retries) => {
+                Some(Self::OnFailure { max_retries })
+            }
+            (deploy::RestartCondition::None | deploy::RestartCondition::Any, Some(_)) => None,
         }
     }
 }
 
+impl_from_str!(Restart => ParseRestartError);
+
+/// Error returned when [parsing](Restart::parse()) a [`Restart`] policy from a string.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseRestartError {
+    /// Unknown restart policy.
+    #[error(
+        "unknown restart policy `{0}`, \
+        must be `no`, `always`, `on-failure[:{{max_retries}}]`, or `unless-stopped`"
+    )]
+    Unknown(String),
+
+    /// Maximum number of retries given for a policy other than `on-failure`.
+    #[error("maximum retries can only be set for the `on-failure` restart policy, not `{0}`")]
+    UnexpectedMaxRetries(String),
+
+    /// Error parsing the maximum number of retries as an unsigned integer.
+    #[error("restart policy maximum retries `{value}` could not be parsed as an unsigned integer")]
+    MaxRetries {
+        /// Source of the error.
+        source: ParseIntError,
+        /// Value that was attempted to parse.
+        value: String,
+    },
+}
+
 impl AsRef<str> for Restart {
     fn as_ref(&self) -> &str {
         self.as_str()
@@ -1420,7 +2215,19 @@ impl AsRef<str> for Restart {
 
 impl Display for Restart {
     fn fmt(&self, f: &mut Formatter) -> fmt::Result {
-        f.write_str(self.as_str())
+        f.write_str(self.as_str())?;
+
+        if let Some(max_retries) = self.max_retries() {
+            write!(f, ":{max_retries}")?;
+        }
+
+        Ok(())
+    }
+}
+
+impl From<Restart> for deploy::RestartPolicy {
+    fn from(value: Restart) -> Self {
+        value.into_restart_policy()
     }
 }
 
@@ -1573,6 +2380,8 @@ mod tests {
         strategy::{Just, Strategy},
     };
 
+    use crate::duration::tests::duration_truncated;
+
     use super::*;
 
     /// [`Strategy`] for generating [`PathBuf`]s that do not contain colons.
@@ -1596,6 +2405,244 @@ mod tests {
         }
     }
 
+    mod pull_policy {
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn parse_no_panic(string: String) {
+                let _ = string.parse::<PullPolicy>();
+            }
+
+            #[test]
+            fn round_trip(pull_policy in pull_policy()) {
+                prop_assert_eq!(pull_policy, pull_policy.to_string().parse()?);
+            }
+        }
+
+        #[test]
+        fn parse() {
+            assert_eq!("if_not_present".parse(), Ok(PullPolicy::Missing));
+            assert_eq!(
+                "every_1h30m".parse(),
+                Ok(PullPolicy::Every(Duration::from_secs(90 * 60)))
+            );
+            assert!(matches!(
+                "every_".parse::<PullPolicy>(),
+                Err(ParsePullPolicyError::Duration { .. })
+            ));
+            assert!(matches!(
+                "hourly".parse::<PullPolicy>(),
+                Err(ParsePullPolicyError::Unknown(_))
+            ));
+        }
+
+        #[test]
+        fn is_pull_due() {
+            let last_pulled = SystemTime::UNIX_EPOCH + PullPolicy::WEEK;
+            let hour = Duration::from_secs(60 * 60);
+
+            assert!(PullPolicy::Always.is_pull_due(Some(last_pulled), last_pulled));
+            assert!(!PullPolicy::Never.is_pull_due(None, last_pulled));
+            assert!(PullPolicy::Missing.is_pull_due(None, last_pulled));
+            assert!(!PullPolicy::Missing.is_pull_due(Some(last_pulled), last_pulled));
+
+            assert!(PullPolicy::Daily.is_pull_due(None, last_pulled));
+            assert!(!PullPolicy::Daily.is_pull_due(Some(last_pulled), last_pulled + hour));
+            assert!(PullPolicy::Daily.is_pull_due(Some(last_pulled), last_pulled + PullPolicy::DAY));
+            assert!(
+                !PullPolicy::Weekly.is_pull_due(Some(last_pulled), last_pulled + PullPolicy::DAY)
+            );
+            assert!(PullPolicy::Every(hour).is_pull_due(Some(last_pulled), last_pulled + hour));
+            assert!(!PullPolicy::Every(hour).is_pull_due(Some(last_pulled), last_pulled - hour));
+        }
+    }
+
+    fn pull_policy() -> impl Strategy<Value = PullPolicy> {
+        prop_oneof![
+            Just(PullPolicy::Always),
+            Just(PullPolicy::Never),
+            Just(PullPolicy::Missing),
+            Just(PullPolicy::Build),
+            Just(PullPolicy::Daily),
+            Just(PullPolicy::Weekly),
+            duration_truncated().prop_map(PullPolicy::Every),
+            Just(PullPolicy::Refresh),
+        ]
+    }
+
+    mod cgroup_parent {
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn parse_no_panic(string: String) {
+                let _ = string.parse::<CgroupParent>();
+            }
+
+            #[test]
+            fn round_trip(cgroup_parent in cgroup_parent()) {
+                prop_assert_eq!(&cgroup_parent, &cgroup_parent.to_string().parse()?);
+            }
+        }
+
+        #[test]
+        fn parse() {
+            assert_eq!(
+                "user.slice".parse(),
+                Ok(CgroupParent::Slice("user.slice".to_owned()))
+            );
+            assert_eq!("/docker".parse(), Ok(CgroupParent::Path("/docker".into())));
+            assert_eq!(
+                "machine/user.slice".parse(),
+                Ok(CgroupParent::Path("machine/user.slice".into()))
+            );
+            assert_eq!("".parse::<CgroupParent>(), Err(ParseCgroupParentError));
+        }
+    }
+
+    fn cgroup_parent() -> impl Strategy<Value = CgroupParent> {
+        prop_oneof![
+            "[a-z-]+\\.slice".prop_map(CgroupParent::Slice),
+            "/?[a-z]+(/[a-z]+)*".prop_map(|path| CgroupParent::Path(path.into())),
+        ]
+    }
+
+    mod pid {
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn parse_no_panic(string: String) {
+                let _ = string.parse::<Pid>();
+            }
+
+            #[test]
+            fn round_trip(pid in pid()) {
+                prop_assert_eq!(&pid, &pid.to_string().parse()?);
+            }
+        }
+    }
+
+    fn pid() -> impl Strategy<Value = Pid> {
+        prop_oneof![
+            Just(Pid::Host),
+            any::<Identifier>().prop_map(Pid::Service),
+            any::<Identifier>().prop_map(Pid::Container),
+            "[a-z]+"
+                .prop_filter("must not be host", |other| other != Pid::HOST)
+                .prop_map(Pid::Other),
+        ]
+    }
+
+    #[test]
+    fn service_dependencies() -> Result<(), InvalidIdentifierError> {
+        let depends_on = Identifier::new("depends_on")?;
+        let ipc = Identifier::new("ipc")?;
+        let pid = Identifier::new("pid")?;
+        let network = Identifier::new("network")?;
+
+        let service = Service {
+            depends_on: ShortOrLong::Short(indexset![depends_on.clone(), pid.clone()]),
+            ipc: Some(Ipc::Service(ipc.clone())),
+            pid: Some(Pid::Service(pid.clone())),
+            network_config: Some(NetworkConfig::NetworkMode(NetworkMode::Service(
+                network.clone(),
+            ))),
+            ..Service::default()
+        };
+
+        assert_eq!(
+            service.service_dependencies(),
+            indexset![&depends_on, &pid, &network, &ipc]
+        );
+
+        Ok(())
+    }
+
+    mod restart {
+        use super::*;
+
+        proptest! {
+            #[test]
+            fn parse_no_panic(string: String) {
+                let _ = string.parse::<Restart>();
+            }
+
+            #[test]
+            fn round_trip(restart in restart()) {
+                prop_assert_eq!(restart, restart.to_string().parse()?);
+            }
+
+            #[test]
+            fn restart_policy_round_trip(restart in restart()) {
+                let restart_policy = restart.into_restart_policy();
+                let expected = if restart == Restart::UnlessStopped {
+                    Restart::Always
+                } else {
+                    restart
+                };
+                prop_assert_eq!(Restart::from_restart_policy(&restart_policy), Some(expected));
+            }
+        }
+
+        #[test]
+        fn parse() {
+            assert_eq!("no".parse(), Ok(Restart::No));
+            assert_eq!(
+                "on-failure".parse(),
+                Ok(Restart::OnFailure { max_retries: None })
+            );
+            assert_eq!(
+                "on-failure:3".parse(),
+                Ok(Restart::OnFailure {
+                    max_retries: Some(3)
+                })
+            );
+            assert!(matches!(
+                "always:3".parse::<Restart>(),
+                Err(ParseRestartError::UnexpectedMaxRetries(_))
+            ));
+            assert!(matches!(
+                "on-failure:".parse::<Restart>(),
+                Err(ParseRestartError::MaxRetries { .. })
+            ));
+            assert!(matches!(
+                "on-failure:-1".parse::<Restart>(),
+                Err(ParseRestartError::MaxRetries { .. })
+            ));
+            assert!(matches!(
+                "sometimes".parse::<Restart>(),
+                Err(ParseRestartError::Unknown(_))
+            ));
+        }
+
+        #[test]
+        fn from_restart_policy_lossy() {
+            let restart_policy = deploy::RestartPolicy {
+                condition: Some(deploy::RestartCondition::OnFailure),
+                delay: Some(Duration::from_secs(1)),
+                ..deploy::RestartPolicy::default()
+            };
+            assert_eq!(Restart::from_restart_policy(&restart_policy), None);
+
+            let restart_policy = deploy::RestartPolicy {
+                max_attempts: Some(1),
+                ..deploy::RestartPolicy::default()
+            };
+            assert_eq!(Restart::from_restart_policy(&restart_policy), None);
+        }
+    }
+
+    fn restart() -> impl Strategy<Value = Restart> {
+        prop_oneof![
+            Just(Restart::No),
+            Just(Restart::Always),
+            any::<Option<u64>>().prop_map(|max_retries| Restart::OnFailure { max_retries }),
+            Just(Restart::UnlessStopped),
+        ]
+    }
+
     fn volumes_from() -> impl Strategy<Value = VolumesFrom> {
         any::<(Identifier, bool)>()
             .prop_flat_map(|(ident, read_only)| {
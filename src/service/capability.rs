@@ -0,0 +1,320 @@
+//! Provides [`Capability`] for the `cap_add` and `cap_drop` fields of [`Service`](super::Service).
+
+use std::fmt::{self, Display, Formatter};
+
+use compose_spec_macros::{DeserializeTryFromString, SerializeDisplay};
+use indexmap::IndexSet;
+
+use crate::impl_from_str;
+
+/// Create the [`Capability`] enum and its associated items from a list of variants and their
+/// capability names without the `CAP_` prefix.
+macro_rules! capabilities {
+    ($($(#[doc = $doc:literal])* $Variant:ident => $name:literal,)*) => {
+        /// Linux [**capabilities**(7)](https://man7.org/linux/man-pages/man7/capabilities.7.html)
+        /// to add to or drop from a [`Service`](super::Service) container.
+        ///
+        /// When parsing, the `CAP_` prefix is optional and case is ignored, e.g. `CAP_NET_ADMIN`,
+        /// `net_admin`, and `NET_ADMIN` are all parsed as [`NetAdmin`](Self::NetAdmin). Unknown
+        /// capabilities are kept as [`Other`](Self::Other).
+        ///
+        /// (De)serializes from/to the capability name in upper case without the `CAP_` prefix,
+        /// e.g. `NET_ADMIN`.
+        ///
+        /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#cap_add)
+        #[derive(SerializeDisplay, DeserializeTryFromString, Debug, Clone, PartialEq, Eq, Hash)]
+        pub enum Capability {
+            $(
+                $(#[doc = $doc])*
+                $Variant,
+            )*
+            /// All capabilities.
+            All,
+            /// Other, unknown capability.
+            Other(String),
+        }
+
+        impl Capability {
+            /// All known Linux capabilities.
+            ///
+            /// Does not include [`All`](Self::All) or [`Other`](Self::Other).
+            pub const KNOWN: &'static [Self] = &[$(Self::$Variant,)*];
+
+            /// Capability name as a string slice, in upper case without the `CAP_` prefix.
+            #[must_use]
+            pub fn as_str(&self) -> &str {
+                match self {
+                    $(Self::$Variant => $name,)*
+                    Self::All => Self::ALL,
+                    Self::Other(other) => other,
+                }
+            }
+
+            /// Convert an upper case capability name without the `CAP_` prefix into a known
+            /// [`Capability`].
+            fn from_known(name: &str) -> Option<Self> {
+                match name {
+                    $($name => Some(Self::$Variant),)*
+                    Self::ALL => Some(Self::All),
+                    _ => None,
+                }
+            }
+        }
+    };
+}
+
+capabilities! {
+    /// `CAP_AUDIT_CONTROL`: Configure the kernel audit subsystem.
+    AuditControl => "AUDIT_CONTROL",
+    /// `CAP_AUDIT_READ`: Read the audit log via a multicast netlink socket.
+    AuditRead => "AUDIT_READ",
+    /// `CAP_AUDIT_WRITE`: Write records to the kernel audit log.
+    AuditWrite => "AUDIT_WRITE",
+    /// `CAP_BLOCK_SUSPEND`: Employ features that can block system suspend.
+    BlockSuspend => "BLOCK_SUSPEND",
+    /// `CAP_BPF`: Employ privileged BPF operations.
+    Bpf => "BPF",
+    /// `CAP_CHECKPOINT_RESTORE`: Employ checkpoint/restore related operations.
+    CheckpointRestore => "CHECKPOINT_RESTORE",
+    /// `CAP_CHOWN`: Make arbitrary changes to file UIDs and GIDs.
+    Chown => "CHOWN",
+    /// `CAP_DAC_OVERRIDE`: Bypass file read, write, and execute permission checks.
+    DacOverride => "DAC_OVERRIDE",
+    /// `CAP_DAC_READ_SEARCH`: Bypass file read and directory read and execute permission checks.
+    DacReadSearch => "DAC_READ_SEARCH",
+    /// `CAP_FOWNER`: Bypass permission checks on operations that require the file UID to match.
+    Fowner => "FOWNER",
+    /// `CAP_FSETID`: Don't clear set-user-ID and set-group-ID bits when a file is modified.
+    Fsetid => "FSETID",
+    /// `CAP_IPC_LOCK`: Lock memory.
+    IpcLock => "IPC_LOCK",
+    /// `CAP_IPC_OWNER`: Bypass permission checks for operations on System V IPC objects.
+    IpcOwner => "IPC_OWNER",
+    /// `CAP_KILL`: Bypass permission checks for sending signals.
+    Kill => "KILL",
+    /// `CAP_LEASE`: Establish leases on arbitrary files.
+    Lease => "LEASE",
+    /// `CAP_LINUX_IMMUTABLE`: Set the `FS_APPEND_FL` and `FS_IMMUTABLE_FL` inode flags.
+    LinuxImmutable => "LINUX_IMMUTABLE",
+    /// `CAP_MAC_ADMIN`: Allow MAC configuration or state changes.
+    MacAdmin => "MAC_ADMIN",
+    /// `CAP_MAC_OVERRIDE`: Override Mandatory Access Control (MAC).
+    MacOverride => "MAC_OVERRIDE",
+    /// `CAP_MKNOD`: Create special files using **mknod**(2).
+    Mknod => "MKNOD",
+    /// `CAP_NET_ADMIN`: Perform various network-related operations.
+    NetAdmin => "NET_ADMIN",
+    /// `CAP_NET_BIND_SERVICE`: Bind a socket to privileged ports (port numbers less than 1024).
+    NetBindService => "NET_BIND_SERVICE",
+    /// `CAP_NET_BROADCAST`: Make socket broadcasts and listen to multicasts.
+    NetBroadcast => "NET_BROADCAST",
+    /// `CAP_NET_RAW`: Use RAW and PACKET sockets.
+    NetRaw => "NET_RAW",
+    /// `CAP_PERFMON`: Employ various performance-monitoring mechanisms.
+    Perfmon => "PERFMON",
+    /// `CAP_SETFCAP`: Set arbitrary capabilities on a file.
+    Setfcap => "SETFCAP",
+    /// `CAP_SETGID`: Make arbitrary manipulations of process GIDs.
+    Setgid => "SETGID",
+    /// `CAP_SETPCAP`: Modify process capabilities.
+    Setpcap => "SETPCAP",
+    /// `CAP_SETUID`: Make arbitrary manipulations of process UIDs.
+    Setuid => "SETUID",
+    /// `CAP_SYS_ADMIN`: Perform a range of system administration operations.
+    SysAdmin => "SYS_ADMIN",
+    /// `CAP_SYS_BOOT`: Use **reboot**(2) and **`kexec_load`**(2).
+    SysBoot => "SYS_BOOT",
+    /// `CAP_SYS_CHROOT`: Use **chroot**(2).
+    SysChroot => "SYS_CHROOT",
+    /// `CAP_SYS_MODULE`: Load and unload kernel modules.
+    SysModule => "SYS_MODULE",
+    /// `CAP_SYS_NICE`: Raise process nice value and change scheduling policies and priorities.
+    SysNice => "SYS_NICE",
+    /// `CAP_SYS_PACCT`: Use **acct**(2).
+    SysPacct => "SYS_PACCT",
+    /// `CAP_SYS_PTRACE`: Trace arbitrary processes using **ptrace**(2).
+    SysPtrace => "SYS_PTRACE",
+    /// `CAP_SYS_RAWIO`: Perform I/O port operations.
+    SysRawio => "SYS_RAWIO",
+    /// `CAP_SYS_RESOURCE`: Override resource limits.
+    SysResource => "SYS_RESOURCE",
+    /// `CAP_SYS_TIME`: Set the system clock.
+    SysTime => "SYS_TIME",
+    /// `CAP_SYS_TTY_CONFIG`: Use **vhangup**(2) and employ privileged **ioctl**(2) operations on
+    /// virtual terminals.
+    SysTtyConfig => "SYS_TTY_CONFIG",
+    /// `CAP_SYSLOG`: Perform privileged **syslog**(2) operations.
+    Syslog => "SYSLOG",
+    /// `CAP_WAKE_ALARM`: Trigger something that will wake up the system.
+    WakeAlarm => "WAKE_ALARM",
+}
+
+impl Capability {
+    /// [`Self::All`] string value.
+    const ALL: &'static str = "ALL";
+
+    /// Optional capability name prefix.
+    const PREFIX: &'static str = "CAP_";
+
+    /// Default set of capabilities granted to containers by the Docker Engine.
+    pub const DEFAULT: &'static [Self] = &[
+        Self::AuditWrite,
+        Self::Chown,
+        Self::DacOverride,
+        Self::Fowner,
+        Self::Fsetid,
+        Self::Kill,
+        Self::Mknod,
+        Self::NetBindService,
+        Self::NetRaw,
+        Self::Setfcap,
+        Self::Setgid,
+        Self::Setpcap,
+        Self::Setuid,
+        Self::SysChroot,
+    ];
+
+    /// Parse a [`Capability`] from a string.
+    ///
+    /// The `CAP_` prefix is optional and case is ignored. Unknown capabilities are parsed as
+    /// [`Other`](Self::Other).
+    pub fn parse<T>(capability: T) -> Self
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        let name = capability.as_ref().to_ascii_uppercase();
+        let name = name.strip_prefix(Self::PREFIX).unwrap_or(&name);
+
+        Self::from_known(name).unwrap_or_else(|| Self::Other(capability.into()))
+    }
+
+    /// Returns `true` if the capability is [`Other`](Self::Other).
+    #[must_use]
+    pub const fn is_other(&self) -> bool {
+        matches!(self, Self::Other(..))
+    }
+
+    /// Returns [`Some`] if the capability is [`Other`](Self::Other).
+    #[must_use]
+    pub fn as_other(&self) -> Option<&str> {
+        if let Self::Other(other) = self {
+            Some(other)
+        } else {
+            None
+        }
+    }
+
+    /// Compute the effective set of capabilities from a `default` set of capabilities, combined
+    /// with the capabilities to `add` and `drop`.
+    ///
+    /// This follows the same rules as the Docker Engine:
+    ///
+    /// - If `add` contains [`All`](Self::All), the result is all [known](Self::KNOWN)
+    ///   capabilities except those in `drop`.
+    /// - Otherwise, if `drop` contains [`All`](Self::All), the result is only the capabilities in
+    ///   `add`.
+    /// - Otherwise, the result is the `default` capabilities not in `drop`, plus the capabilities
+    ///   in `add`.
+    #[must_use]
+    pub fn effective_set<'a, I>(
+        default: I,
+        add: &'a IndexSet<Self>,
+        drop: &IndexSet<Self>,
+    ) -> IndexSet<Self>
+    where
+        I: IntoIterator<Item = &'a Self>,
+    {
+        if add.contains(&Self::All) {
+            Self::KNOWN
+                .iter()
+                .filter(|capability| !drop.contains(*capability))
+                .cloned()
+                .collect()
+        } else if drop.contains(&Self::All) {
+            add.clone()
+        } else {
+            default
+                .into_iter()
+                .filter(|capability| !drop.contains(*capability))
+                .chain(add)
+                .cloned()
+                .collect()
+        }
+    }
+}
+
+impl_from_str!(Capability);
+
+impl AsRef<str> for Capability {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for Capability {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use indexmap::indexset;
+    use proptest::{prop_assert_eq, proptest, sample::select};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn parse_no_panic(string: String) {
+            let _ = Capability::parse(string);
+        }
+
+        #[test]
+        fn round_trip(capability in select(Capability::KNOWN)) {
+            prop_assert_eq!(&capability, &capability.to_string().parse()?);
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Capability::parse("NET_ADMIN"), Capability::NetAdmin);
+        assert_eq!(Capability::parse("CAP_NET_ADMIN"), Capability::NetAdmin);
+        assert_eq!(Capability::parse("cap_net_admin"), Capability::NetAdmin);
+        assert_eq!(Capability::parse("all"), Capability::All);
+        assert_eq!(
+            Capability::parse("cap_unknown"),
+            Capability::Other("cap_unknown".to_owned())
+        );
+    }
+
+    #[test]
+    fn effective_set() {
+        let default = [Capability::Chown, Capability::Kill];
+
+        assert_eq!(
+            Capability::effective_set(
+                &default,
+                &indexset![Capability::NetAdmin],
+                &indexset![Capability::Kill],
+            ),
+            indexset![Capability::Chown, Capability::NetAdmin]
+        );
+        assert_eq!(
+            Capability::effective_set(
+                &default,
+                &indexset![Capability::NetAdmin],
+                &indexset![Capability::All],
+            ),
+            indexset![Capability::NetAdmin]
+        );
+
+        let all = Capability::effective_set(
+            &default,
+            &indexset![Capability::All],
+            &indexset![Capability::SysAdmin],
+        );
+        assert_eq!(all.len(), Capability::KNOWN.len() - 1);
+        assert!(!all.contains(&Capability::SysAdmin));
+    }
+}
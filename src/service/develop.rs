@@ -2,14 +2,17 @@
 
 use std::{
     fmt::{self, Display, Formatter},
+    ops::Not,
     path::PathBuf,
 };
 
-use serde::{Deserialize, Serialize};
+use indexmap::IndexSet;
+use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
 
-use crate::Extensions;
+use crate::{Extensions, ListOrMap};
 
-use super::AbsolutePath;
+use super::{AbsolutePath, Command, User};
 
 /// Development constraints and workflows for maintaining a container in sync with source.
 ///
@@ -23,6 +26,17 @@ pub struct Develop {
     pub watch: Vec<WatchRule>,
 }
 
+impl Develop {
+    /// Ensure all [`WatchRule`]s are valid.
+    ///
+    /// # Errors
+    ///
+    /// Returns the first [`WatchRule`] validation error, see [`WatchRule::validate()`].
+    pub fn validate(&self) -> Result<(), WatchRuleError> {
+        self.watch.iter().try_for_each(WatchRule::validate)
+    }
+}
+
 /// Rule which controls automatic service updates based on local file changes.
 ///
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/develop.md#watch)
@@ -52,6 +66,138 @@ pub struct WatchRule {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub target: Option<AbsolutePath>,
 
+    /// Whether to synchronize files that differ between `path` and `target` before starting the
+    /// watch.
+    ///
+    /// Only used with sync actions.
+    ///
+    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/develop.md#initial_sync)
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub initial_sync: bool,
+
+    /// Command to run in the container after files are synchronized.
+    ///
+    /// Required when the `action` is [`SyncAndExec`](Action::SyncAndExec).
+    ///
+    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/develop.md#exec)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub exec: Option<Exec>,
+
+    /// Extension values, which are (de)serialized via flattening.
+    ///
+    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/11-extension.md)
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+impl WatchRule {
+    /// Ensure the fields used are valid for the [`Action`].
+    ///
+    /// Sync actions require a `target` and [`Rebuild`](Action::Rebuild) must not have one.
+    /// `exec` is required for [`SyncAndExec`](Action::SyncAndExec) and not allowed for other
+    /// actions. `initial_sync` is only allowed for sync actions.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a field is missing or not allowed for the `action`.
+    pub fn validate(&self) -> Result<(), WatchRuleError> {
+        let action = self.action;
+        let error = |kind| Err(WatchRuleError { action, kind });
+
+        if action.is_sync() {
+            if self.target.is_none() {
+                return error(WatchRuleErrorKind::MissingTarget);
+            }
+        } else {
+            if self.target.is_some() {
+                return error(WatchRuleErrorKind::UnexpectedTarget);
+            }
+            if self.initial_sync {
+                return error(WatchRuleErrorKind::UnexpectedInitialSync);
+            }
+        }
+
+        match (action, &self.exec) {
+            (Action::SyncAndExec, None) => error(WatchRuleErrorKind::MissingExec),
+            (Action::Rebuild | Action::Sync | Action::SyncAndRestart, Some(_)) => {
+                error(WatchRuleErrorKind::UnexpectedExec)
+            }
+            _ => Ok(()),
+        }
+    }
+}
+
+/// Error returned when [validating](WatchRule::validate()) a [`WatchRule`].
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("invalid `{action}` watch rule")]
+pub struct WatchRuleError {
+    /// Action of the invalid [`WatchRule`].
+    pub action: Action,
+
+    /// Reason the [`WatchRule`] is invalid.
+    #[source]
+    pub kind: WatchRuleErrorKind,
+}
+
+/// Reason a [`WatchRule`] is invalid.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum WatchRuleErrorKind {
+    /// `target` is required for sync actions.
+    #[error("`target` is required for sync actions")]
+    MissingTarget,
+
+    /// `target` is not allowed for the `rebuild` action.
+    #[error("`target` is not allowed for the `rebuild` action")]
+    UnexpectedTarget,
+
+    /// `initial_sync` is only allowed for sync actions.
+    #[error("`initial_sync` is only allowed for sync actions")]
+    UnexpectedInitialSync,
+
+    /// `exec` is required for the `sync+exec` action.
+    #[error("`exec` is required for the `sync+exec` action")]
+    MissingExec,
+
+    /// `exec` is only allowed for the `sync+exec` action.
+    #[error("`exec` is only allowed for the `sync+exec` action")]
+    UnexpectedExec,
+}
+
+/// Command to run in the [`Service`](super::Service) container after files are synchronized by
+/// a [`WatchRule`] with the [`SyncAndExec`](Action::SyncAndExec) action.
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/develop.md#exec)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+pub struct Exec {
+    /// Command to run.
+    pub command: Command,
+
+    /// User to run the command as.
+    ///
+    /// The default is the user of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub user: Option<User>,
+
+    /// Whether to run the command with elevated privileges.
+    #[serde(default, skip_serializing_if = "Not::not")]
+    pub privileged: bool,
+
+    /// Working directory in which to run the command.
+    ///
+    /// The default is the working directory of the container.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub working_dir: Option<AbsolutePath>,
+
+    /// Environment variables to set for the command, in the `{key}={value}` format.
+    ///
+    /// Deserializes from a list or a map, maps are converted with [`ListOrMap::into_list()`].
+    #[serde(
+        default,
+        skip_serializing_if = "IndexSet::is_empty",
+        deserialize_with = "environment"
+    )]
+    pub environment: IndexSet<String>,
+
     /// Extension values, which are (de)serialized via flattening.
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/11-extension.md)
@@ -59,6 +205,27 @@ pub struct WatchRule {
     pub extensions: Extensions,
 }
 
+impl From<Command> for Exec {
+    fn from(command: Command) -> Self {
+        Self {
+            command,
+            user: None,
+            privileged: false,
+            working_dir: None,
+            environment: IndexSet::new(),
+            extensions: Extensions::default(),
+        }
+    }
+}
+
+/// Deserialize [`Exec::environment`] from a list or a map.
+fn environment<'de, D>(deserializer: D) -> Result<IndexSet<String>, D::Error>
+where
+    D: Deserializer<'de>,
+{
+    ListOrMap::deserialize(deserializer).map(ListOrMap::into_list)
+}
+
 /// Action for a [`WatchRule`] to take when changes are detected.
 ///
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/develop.md#action)
@@ -77,6 +244,11 @@ pub enum Action {
     /// restart the container.
     #[serde(rename = "sync+restart")]
     SyncAndRestart,
+
+    /// Synchronize source files with container content according to the `target` field, and then
+    /// run the `exec` command in the container.
+    #[serde(rename = "sync+exec")]
+    SyncAndExec,
 }
 
 impl Action {
@@ -87,8 +259,18 @@ impl Action {
             Self::Rebuild => "rebuild",
             Self::Sync => "sync",
             Self::SyncAndRestart => "sync+restart",
+            Self::SyncAndExec => "sync+exec",
         }
     }
+
+    /// Returns `true` if the action synchronizes files into the container.
+    ///
+    /// That is, [`Sync`](Self::Sync), [`SyncAndRestart`](Self::SyncAndRestart), or
+    /// [`SyncAndExec`](Self::SyncAndExec).
+    #[must_use]
+    pub const fn is_sync(self) -> bool {
+        matches!(self, Self::Sync | Self::SyncAndRestart | Self::SyncAndExec)
+    }
 }
 
 impl AsRef<str> for Action {
@@ -102,3 +284,80 @@ impl Display for Action {
         f.write_str(self.as_str())
     }
 }
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use crate::service::volumes::AbsolutePathError;
+
+    use super::*;
+
+    fn watch_rule(action: Action) -> WatchRule {
+        WatchRule {
+            action,
+            ignore: Vec::new(),
+            path: "path".into(),
+            target: None,
+            initial_sync: false,
+            exec: None,
+            extensions: Extensions::default(),
+        }
+    }
+
+    #[test]
+    fn validate_target() -> Result<(), AbsolutePathError> {
+        let mut rule = watch_rule(Action::Sync);
+        assert_eq!(
+            rule.validate().map_err(|error| error.kind),
+            Err(WatchRuleErrorKind::MissingTarget)
+        );
+        rule.target = Some(AbsolutePath::new("/target")?);
+        assert_eq!(rule.validate(), Ok(()));
+
+        rule.action = Action::Rebuild;
+        assert_eq!(
+            rule.validate().map_err(|error| error.kind),
+            Err(WatchRuleErrorKind::UnexpectedTarget)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn validate_exec() -> Result<(), AbsolutePathError> {
+        let mut rule = watch_rule(Action::SyncAndExec);
+        rule.target = Some(AbsolutePath::new("/target")?);
+        assert_eq!(
+            rule.validate().map_err(|error| error.kind),
+            Err(WatchRuleErrorKind::MissingExec)
+        );
+
+        rule.exec = Some(Command::String("reload".to_owned()).into());
+        assert_eq!(rule.validate(), Ok(()));
+
+        rule.action = Action::Sync;
+        assert_eq!(
+            rule.validate().map_err(|error| error.kind),
+            Err(WatchRuleErrorKind::UnexpectedExec)
+        );
+
+        Ok(())
+    }
+
+    #[test]
+    fn exec_environment_map() {
+        let exec: Exec = serde_yaml::from_str(
+            "\
+command: reload
+environment:
+  DEBUG: 1
+  QUIET:
+",
+        )
+        .unwrap();
+        assert_eq!(
+            exec.environment,
+            IndexSet::from(["DEBUG=1".to_owned(), "QUIET".to_owned()])
+        );
+    }
+}
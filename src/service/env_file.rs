@@ -1,13 +1,18 @@
 //! Provides [`EnvFile`] for the `env_file` field of [`Service`](super::Service).
 
-use std::path::PathBuf;
+use std::{
+    fmt::{self, Display, Formatter},
+    fs, io,
+    path::{Path, PathBuf},
+};
 
 use compose_spec_macros::{AsShort, FromShort};
 use serde::{Deserialize, Deserializer, Serialize};
+use thiserror::Error;
 
 use crate::{
     serde::{default_true, skip_true, ItemOrListVisitor},
-    AsShort, ShortOrLong,
+    AsShort, InvalidMapKeyError, Map, MapKey, ShortOrLong, Value,
 };
 
 /// [`List`](EnvFile::List) of environment file paths.
@@ -48,6 +53,62 @@ impl EnvFile {
             Self::List(list) => list,
         }
     }
+
+    /// Create from environment file paths, as collected from `--env-file` flags.
+    ///
+    /// Returns [`None`] if there are no paths.
+    pub(crate) fn from_paths(paths: Vec<PathBuf>) -> Option<Self> {
+        match <[PathBuf; 1]>::try_from(paths) {
+            Ok([path]) => Some(Self::Single(path)),
+            Err(paths) => (!paths.is_empty())
+                .then(|| Self::List(paths.into_iter().map(ShortOrLong::Short).collect())),
+        }
+    }
+
+    /// Read each environment file, relative to `base_dir`, and merge them into a single
+    /// environment map.
+    ///
+    /// Files are read in order, with variables from later files taking precedence over earlier
+    /// ones. Files which are not [`required`](Config::required) are skipped if they do not exist.
+    /// Each file is parsed according to its [`Format`]. Lines without a `=` have a value of
+    /// [`None`], meaning the value is taken from the environment Compose is run in.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a required file could not be read or a file could not be parsed.
+    pub fn read<P: AsRef<Path>>(&self, base_dir: P) -> Result<Map, EnvFileError> {
+        let base_dir = base_dir.as_ref();
+        let mut environment = Map::new();
+
+        let files: Vec<(&PathBuf, bool, Format)> = match self {
+            Self::Single(path) => vec![(path, true, Format::Dotenv)],
+            Self::List(list) => list
+                .iter()
+                .map(|file| match file {
+                    ShortOrLong::Short(path) => (path, true, Format::Dotenv),
+                    ShortOrLong::Long(Config {
+                        path,
+                        required,
+                        format,
+                    }) => (path, *required, *format),
+                })
+                .collect(),
+        };
+
+        for (path, required, format) in files {
+            let path = base_dir.join(path);
+            let contents = match fs::read_to_string(&path) {
+                Ok(contents) => contents,
+                Err(error) if !required && error.kind() == io::ErrorKind::NotFound => continue,
+                Err(source) => return Err(EnvFileError::Read { path, source }),
+            };
+            format
+                .parse_into(&contents, &mut environment)
+                .map_err(|(line, kind)| EnvFileError::Parse { path, line, kind })?;
+        }
+
+        Ok(environment)
+    }
 }
 
 impl From<PathBuf> for EnvFile {
@@ -100,6 +161,13 @@ pub struct Config {
     #[serde(default = "default_true", skip_serializing_if = "skip_true")]
     #[as_short(default = default_true, if_fn = skip_true)]
     pub required: bool,
+
+    /// Format of the environment file.
+    ///
+    /// Default is [`Format::Dotenv`].
+    #[serde(default, skip_serializing_if = "Format::is_dotenv")]
+    #[as_short(default = Format::default, if_fn = Format::is_dotenv)]
+    pub format: Format,
 }
 
 impl From<String> for Config {
@@ -107,3 +175,308 @@ impl From<String> for Config {
         PathBuf::from(value).into()
     }
 }
+
+/// Format of an [`EnvFile`].
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#format)
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum Format {
+    /// Lines in the `{key}={value}` format. Values may be single or double quoted, with escape
+    /// sequences processed in double quoted values. Quoted values may span multiple lines.
+    #[default]
+    Dotenv,
+
+    /// Lines in the `{key}={value}` format. Values are used as is, without any quote or escape
+    /// processing.
+    Raw,
+}
+
+impl Format {
+    /// Returns `true` if the format is [`Dotenv`].
+    ///
+    /// [`Dotenv`]: Format::Dotenv
+    #[must_use]
+    pub const fn is_dotenv(&self) -> bool {
+        matches!(self, Self::Dotenv)
+    }
+
+    /// Returns `true` if the format is [`Raw`].
+    ///
+    /// [`Raw`]: Format::Raw
+    #[must_use]
+    pub const fn is_raw(&self) -> bool {
+        matches!(self, Self::Raw)
+    }
+
+    /// Format as a static string slice.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Dotenv => "dotenv",
+            Self::Raw => "raw",
+        }
+    }
+
+    /// Parse the `contents` of an environment file and insert the variables into `map`.
+    ///
+    /// Empty lines and lines starting with `#` are ignored.
+    ///
+    /// # Errors
+    ///
+    /// Returns the line number (starting at 1) and error if a line could not be parsed.
+    pub(super) fn parse_into(
+        self,
+        contents: &str,
+        map: &mut Map,
+    ) -> Result<(), (usize, ParseErrorKind)> {
+        let mut lines = contents.lines().enumerate();
+        while let Some((index, line)) = lines.next() {
+            let line_number = index + 1;
+            let line = line.trim_start();
+            if line.is_empty() || line.starts_with('#') {
+                continue;
+            }
+
+            let (key, value) = match self {
+                Self::Dotenv => {
+                    let line = line.strip_prefix("export ").unwrap_or(line);
+                    match line.split_once('=') {
+                        Some((key, value)) => (
+                            key.trim(),
+                            Some(
+                                parse_dotenv_value(value.trim_start(), &mut lines)
+                                    .map_err(|kind| (line_number, kind))?,
+                            ),
+                        ),
+                        None => (line.trim_end(), None),
+                    }
+                }
+                Self::Raw => line
+                    .split_once('=')
+                    .map_or((line, None), |(key, value)| (key, Some(value.to_owned()))),
+            };
+
+            let key = MapKey::new(key).map_err(|error| (line_number, error.into()))?;
+            map.insert(key, value.map(Value::String));
+        }
+
+        Ok(())
+    }
+}
+
+impl Display for Format {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Parse a value of a [`Format::Dotenv`] environment file.
+///
+/// Quoted values may continue onto subsequent `lines`. Unquoted values end at a `#` preceded by
+/// whitespace.
+fn parse_dotenv_value<'a, I>(value: &str, lines: &mut I) -> Result<String, ParseErrorKind>
+where
+    I: Iterator<Item = (usize, &'a str)>,
+{
+    let mut chars = value.chars();
+    let Some(quote) = chars.next().filter(|char| matches!(char, '"' | '\'')) else {
+        let end = value
+            .char_indices()
+            .find(|&(index, char)| {
+                char == '#'
+                    && value
+                        .get(..index)
+                        .and_then(|before| before.chars().next_back())
+                        .is_some_and(char::is_whitespace)
+            })
+            .map_or(value.len(), |(index, _)| index);
+        return Ok(value.get(..end).unwrap_or(value).trim_end().to_owned());
+    };
+
+    let mut parsed = String::new();
+    let mut line = chars.as_str();
+    loop {
+        let mut chars = line.chars();
+        while let Some(char) = chars.next() {
+            match char {
+                char if char == quote => return Ok(parsed),
+                '\\' if quote == '"' => match chars.next() {
+                    Some('n') => parsed.push('\n'),
+                    Some('r') => parsed.push('\r'),
+                    Some('t') => parsed.push('\t'),
+                    Some(char @ ('\\' | '"' | '$')) => parsed.push(char),
+                    Some(char) => {
+                        parsed.push('\\');
+                        parsed.push(char);
+                    }
+                    None => parsed.push('\\'),
+                },
+                char => parsed.push(char),
+            }
+        }
+
+        let Some((_, next)) = lines.next() else {
+            return Err(ParseErrorKind::UnterminatedQuote);
+        };
+        parsed.push('\n');
+        line = next;
+    }
+}
+
+/// Error returned when [reading](EnvFile::read()) an [`EnvFile`].
+#[derive(Error, Debug)]
+pub enum EnvFileError {
+    /// Error reading an environment file.
+    #[error("error reading environment file `{}`", path.display())]
+    Read {
+        /// Path of the environment file.
+        path: PathBuf,
+        /// Source of the error.
+        source: io::Error,
+    },
+
+    /// Error parsing an environment file.
+    #[error("error parsing line {line} of environment file `{}`", path.display())]
+    Parse {
+        /// Path of the environment file.
+        path: PathBuf,
+        /// Line number of the error, starting at 1.
+        line: usize,
+        /// Kind of parsing error.
+        #[source]
+        kind: ParseErrorKind,
+    },
+}
+
+/// Kind of [`EnvFileError::Parse`] error.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseErrorKind {
+    /// Invalid variable name.
+    #[error("invalid variable name")]
+    Key(#[from] InvalidMapKeyError),
+
+    /// Quoted value without a closing quote.
+    #[error("quoted value is missing a closing quote")]
+    UnterminatedQuote,
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    /// Parsed environment variables as strings.
+    type Parsed = Vec<(String, Option<String>)>;
+
+    fn parse(format: Format, contents: &str) -> Result<Parsed, (usize, ParseErrorKind)> {
+        let mut map = Map::new();
+        format.parse_into(contents, &mut map)?;
+        Ok(map
+            .into_iter()
+            .map(|(key, value)| (key.into(), value.map(String::from)))
+            .collect())
+    }
+
+    #[test]
+    fn dotenv() {
+        let contents = "# comment\n\
+            \n\
+            ONE=1\n\
+            export TWO = two # comment\n\
+            THREE='single #quoted $VAR'\n\
+            FOUR=\"double\\n\\\"quoted\\\"\" # comment\n\
+            FIVE=\"multi\n\
+            line\"\n\
+            SIX=\n\
+            SEVEN\n";
+
+        assert_eq!(
+            parse(Format::Dotenv, contents).unwrap(),
+            [
+                ("ONE".to_owned(), Some("1".to_owned())),
+                ("TWO".to_owned(), Some("two".to_owned())),
+                ("THREE".to_owned(), Some("single #quoted $VAR".to_owned())),
+                ("FOUR".to_owned(), Some("double\n\"quoted\"".to_owned())),
+                ("FIVE".to_owned(), Some("multi\nline".to_owned())),
+                ("SIX".to_owned(), Some(String::new())),
+                ("SEVEN".to_owned(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn dotenv_unterminated_quote() {
+        assert_eq!(
+            parse(Format::Dotenv, "ONE=1\nTWO=\"two\n"),
+            Err((2, ParseErrorKind::UnterminatedQuote))
+        );
+    }
+
+    #[test]
+    fn raw() {
+        assert_eq!(
+            parse(
+                Format::Raw,
+                "# comment\nONE=\"1\" # not a comment\nTWO='a\\nb'\nTHREE\n"
+            )
+            .unwrap(),
+            [
+                ("ONE".to_owned(), Some("\"1\" # not a comment".to_owned())),
+                ("TWO".to_owned(), Some("'a\\nb'".to_owned())),
+                ("THREE".to_owned(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn invalid_key() {
+        assert_eq!(
+            parse(Format::Raw, "ONE=1\n=value"),
+            Err((2, ParseErrorKind::Key(InvalidMapKeyError::Empty)))
+        );
+    }
+
+    #[test]
+    fn read() {
+        let dir =
+            std::env::temp_dir().join(format!("compose_spec-{}-env_file-read", process::id()));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("one.env"), "ONE=1\nTWO=\"1\"\n").unwrap();
+        fs::write(dir.join("two.env"), "TWO=\"2\"\n").unwrap();
+
+        let env_file: EnvFile = [
+            ShortOrLong::Short("one.env".into()),
+            ShortOrLong::Long(Config {
+                path: "missing.env".into(),
+                required: false,
+                format: Format::Dotenv,
+            }),
+            ShortOrLong::Long(Config {
+                path: "two.env".into(),
+                required: true,
+                format: Format::Raw,
+            }),
+        ]
+        .into_iter()
+        .collect();
+        let environment = env_file.read(&dir);
+        let missing = EnvFile::Single("missing.env".into()).read(&dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            environment
+                .unwrap()
+                .into_iter()
+                .map(|(key, value)| (String::from(key), value.map(String::from)))
+                .collect::<Vec<_>>(),
+            [
+                ("ONE".to_owned(), Some("1".to_owned())),
+                ("TWO".to_owned(), Some("\"2\"".to_owned())),
+            ]
+        );
+        assert!(matches!(missing, Err(EnvFileError::Read { .. })));
+    }
+}
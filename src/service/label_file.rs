@@ -0,0 +1,156 @@
+//! Provides [`resolve()`] for merging the `label_file` and `labels` fields of
+//! [`Service`](super::Service).
+
+use std::{
+    fs, io,
+    path::{Path, PathBuf},
+};
+
+use thiserror::Error;
+
+use crate::{InvalidMapKeyError, ItemOrList, ListOrMap, Map};
+
+use super::env_file::{Format, ParseErrorKind};
+
+/// Read each label file, relative to `base_dir`, and merge them with the inline `labels`.
+///
+/// Label files are parsed the same as [`Format::Raw`] environment files.
+///
+/// Labels from later files take precedence over earlier ones, and inline `labels` take precedence
+/// over all files.
+pub(super) fn resolve(
+    label_file: Option<&ItemOrList<PathBuf>>,
+    labels: &ListOrMap,
+    base_dir: &Path,
+) -> Result<Map, LabelFileError> {
+    let mut resolved = Map::new();
+
+    let files: Vec<&PathBuf> = match label_file {
+        None => Vec::new(),
+        Some(ItemOrList::Item(path)) => vec![path],
+        Some(ItemOrList::List(paths)) => paths.iter().collect(),
+    };
+
+    for path in files {
+        let path = base_dir.join(path);
+        let contents = fs::read_to_string(&path).map_err(|source| LabelFileError::Read {
+            path: path.clone(),
+            source,
+        })?;
+        Format::Raw
+            .parse_into(&contents, &mut resolved)
+            .map_err(|(line, kind)| LabelFileError::Parse { path, line, kind })?;
+    }
+
+    resolved.extend(labels.clone().into_map()?);
+
+    Ok(resolved)
+}
+
+/// Error returned when [resolving](super::Service::resolve_labels()) the labels of a
+/// [`Service`](super::Service).
+#[derive(Error, Debug)]
+pub enum LabelFileError {
+    /// Error reading a label file.
+    #[error("error reading label file `{}`", path.display())]
+    Read {
+        /// Path of the label file.
+        path: PathBuf,
+        /// Source of the error.
+        source: io::Error,
+    },
+
+    /// Error parsing a label file.
+    #[error("error parsing line {line} of label file `{}`", path.display())]
+    Parse {
+        /// Path of the label file.
+        path: PathBuf,
+        /// Line number of the error, starting at 1.
+        line: usize,
+        /// Kind of parsing error.
+        #[source]
+        kind: ParseErrorKind,
+    },
+
+    /// Invalid key in the inline `labels`.
+    #[error("invalid label key")]
+    Labels(#[from] InvalidMapKeyError),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use std::process;
+
+    use super::*;
+
+    #[test]
+    fn resolve_precedence() {
+        let dir = std::env::temp_dir().join(format!(
+            "compose_spec-{}-label_file-resolve_precedence",
+            process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(
+            dir.join("one.labels"),
+            "# comment\n\none=1\ntwo=1\nthree=1\nempty=\nkey\n",
+        )
+        .unwrap();
+        fs::write(dir.join("two.labels"), "two=\"2\"\nthree=2\n").unwrap();
+
+        let label_file = ItemOrList::List(["one.labels".into(), "two.labels".into()].into());
+        let labels = ListOrMap::List(["three=3".to_owned()].into());
+        let resolved = resolve(Some(&label_file), &labels, &dir).unwrap();
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert_eq!(
+            resolved
+                .into_iter()
+                .map(|(key, value)| (String::from(key), value.map(String::from)))
+                .collect::<Vec<_>>(),
+            [
+                ("one".to_owned(), Some("1".to_owned())),
+                ("two".to_owned(), Some("\"2\"".to_owned())),
+                ("three".to_owned(), Some("3".to_owned())),
+                ("empty".to_owned(), Some(String::new())),
+                ("key".to_owned(), None),
+            ]
+        );
+    }
+
+    #[test]
+    fn resolve_invalid_key() {
+        let dir = std::env::temp_dir().join(format!(
+            "compose_spec-{}-label_file-resolve_invalid_key",
+            process::id()
+        ));
+        fs::create_dir_all(&dir).unwrap();
+        fs::write(dir.join("invalid.labels"), "valid=1\n=value\n").unwrap();
+
+        let label_file = ItemOrList::Item("invalid.labels".into());
+        let resolved = resolve(Some(&label_file), &ListOrMap::default(), &dir);
+        fs::remove_dir_all(&dir).unwrap();
+
+        assert!(matches!(
+            resolved,
+            Err(LabelFileError::Parse {
+                line: 2,
+                kind: ParseErrorKind::Key(InvalidMapKeyError::Empty),
+                ..
+            })
+        ));
+    }
+
+    #[test]
+    fn resolve_missing_file() {
+        let label_file = ItemOrList::Item("missing.labels".into());
+        assert!(matches!(
+            resolve(
+                Some(&label_file),
+                &ListOrMap::default(),
+                Path::new("/nonexistent")
+            ),
+            Err(LabelFileError::Read { .. })
+        ));
+    }
+}
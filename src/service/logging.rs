@@ -0,0 +1,770 @@
+//! Provides [`Logging`] for the `logging` field of [`Service`](super::Service).
+
+use std::{
+    fmt::{self, Display, Formatter},
+    num::ParseIntError,
+    str::{FromStr, ParseBoolError},
+    time::Duration,
+};
+
+use indexmap::IndexMap;
+use serde::{Deserialize, Serialize};
+use thiserror::Error;
+use url::Url;
+
+use crate::{
+    duration::{self, ParseDurationError},
+    Extensions, MapKey, StringOrNumber,
+};
+
+use super::{ByteValue, ParseByteValueError, RangeError};
+
+/// Logging configuration for a [`Service`](super::Service).
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#logging)
+#[derive(Serialize, Deserialize, Debug, Default, Clone, PartialEq)]
+pub struct Logging {
+    /// Logging driver for the [`Service`](super::Service) container.
+    ///
+    /// The default and available values are platform specific.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub driver: Option<String>,
+
+    /// Driver specific options.
+    ///
+    /// Use [`driver_options()`](Self::driver_options()) to parse the options of common drivers.
+    #[serde(default, skip_serializing_if = "IndexMap::is_empty")]
+    pub options: IndexMap<MapKey, Option<StringOrNumber>>,
+
+    /// Extension values, which are (de)serialized via flattening.
+    ///
+    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/11-extension.md)
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+impl Logging {
+    /// Returns `true` if all fields are [`None`] or empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            driver,
+            options,
+            extensions,
+        } = self;
+
+        driver.is_none() && options.is_empty() && extensions.is_empty()
+    }
+
+    /// Parse [`options`](Self::options) according to the [`driver`](Self::driver).
+    ///
+    /// Returns [`None`] if the driver is not set or is not one of the drivers with typed options.
+    /// Options which are not modeled by the driver's type are kept in its `other` field.
+    /// Options with a `null` value are treated as not set.
+    ///
+    /// ```
+    /// use compose_spec::service::{logging::DriverOptions, ByteValue, Logging};
+    ///
+    /// let logging: Logging = serde_yaml::from_str(
+    ///     "
+    /// driver: json-file
+    /// options:
+    ///   max-size: 10m
+    ///   max-file: 3
+    /// ",
+    /// )
+    /// .unwrap();
+    ///
+    /// let Some(DriverOptions::JsonFile(options)) = logging.driver_options().unwrap() else {
+    ///     panic!("expected json-file options");
+    /// };
+    /// assert_eq!(options.max_size, Some(ByteValue::Megabytes(10)));
+    /// assert_eq!(options.max_file, Some(3));
+    /// ```
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if a known option of the driver has an invalid value, e.g. `max-size: 10x`
+    /// for the `json-file` driver.
+    pub fn driver_options(&self) -> Result<Option<DriverOptions>, InvalidOptionError> {
+        let Some(driver) = &self.driver else {
+            return Ok(None);
+        };
+
+        let options = &self.options;
+        Ok(Some(match driver.as_str() {
+            DriverOptions::JSON_FILE => DriverOptions::JsonFile(FileOptions::parse(options)?),
+            DriverOptions::LOCAL => DriverOptions::Local(FileOptions::parse(options)?),
+            DriverOptions::JOURNALD => DriverOptions::Journald(Journald::parse(options)),
+            DriverOptions::SYSLOG => DriverOptions::Syslog(Syslog::parse(options)?),
+            DriverOptions::FLUENTD => DriverOptions::Fluentd(Fluentd::parse(options)?),
+            DriverOptions::GELF => DriverOptions::Gelf(Gelf::parse(options)?),
+            _ => return Ok(None),
+        }))
+    }
+}
+
+/// Typed [`Logging`] options for common logging drivers.
+///
+/// Returned from [`Logging::driver_options()`].
+#[derive(Debug, Clone, PartialEq)]
+pub enum DriverOptions {
+    /// `json-file` driver options.
+    JsonFile(FileOptions),
+
+    /// `local` driver options.
+    Local(FileOptions),
+
+    /// `journald` driver options.
+    Journald(Journald),
+
+    /// `syslog` driver options.
+    Syslog(Syslog),
+
+    /// `fluentd` driver options.
+    Fluentd(Fluentd),
+
+    /// `gelf` driver options.
+    Gelf(Gelf),
+}
+
+impl DriverOptions {
+    /// [`Self::JsonFile`] driver name.
+    const JSON_FILE: &'static str = "json-file";
+
+    /// [`Self::Local`] driver name.
+    const LOCAL: &'static str = "local";
+
+    /// [`Self::Journald`] driver name.
+    const JOURNALD: &'static str = "journald";
+
+    /// [`Self::Syslog`] driver name.
+    const SYSLOG: &'static str = "syslog";
+
+    /// [`Self::Fluentd`] driver name.
+    const FLUENTD: &'static str = "fluentd";
+
+    /// [`Self::Gelf`] driver name.
+    const GELF: &'static str = "gelf";
+
+    /// Name of the logging driver.
+    #[must_use]
+    pub const fn driver(&self) -> &'static str {
+        match self {
+            Self::JsonFile(_) => Self::JSON_FILE,
+            Self::Local(_) => Self::LOCAL,
+            Self::Journald(_) => Self::JOURNALD,
+            Self::Syslog(_) => Self::SYSLOG,
+            Self::Fluentd(_) => Self::FLUENTD,
+            Self::Gelf(_) => Self::GELF,
+        }
+    }
+}
+
+/// Options for the `json-file` and `local` logging drivers, which write logs to files on the host.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct FileOptions {
+    /// `max-size`: Maximum size of a log file before it is rotated.
+    pub max_size: Option<ByteValue>,
+
+    /// `max-file`: Maximum number of log files that can be present.
+    ///
+    /// Must be at least 1.
+    pub max_file: Option<u64>,
+
+    /// `compress`: Whether rotated log files should be compressed.
+    pub compress: Option<bool>,
+
+    /// Other driver options.
+    pub other: IndexMap<MapKey, Option<StringOrNumber>>,
+}
+
+impl FileOptions {
+    /// Parse [`FileOptions`] from [`Logging`] options.
+    fn parse(
+        options: &IndexMap<MapKey, Option<StringOrNumber>>,
+    ) -> Result<Self, InvalidOptionError> {
+        let mut file_options = Self::default();
+        for (key, value) in options {
+            let Some(value) = value else { continue };
+            match key.as_str() {
+                "max-size" => file_options.max_size = Some(parse_option(key, value)?),
+                "max-file" => {
+                    file_options.max_file = Some(parse_option_with(key, value, |max_file| {
+                        let max_file: u64 = max_file.parse()?;
+                        if max_file == 0 {
+                            Err(RangeError {
+                                value: 0,
+                                start: 1,
+                                end: i64::MAX,
+                            }
+                            .into())
+                        } else {
+                            Ok(max_file)
+                        }
+                    })?);
+                }
+                "compress" => file_options.compress = Some(parse_option(key, value)?),
+                _ => {
+                    file_options.other.insert(key.clone(), Some(value.clone()));
+                }
+            }
+        }
+        Ok(file_options)
+    }
+}
+
+/// Options for the `journald` logging driver.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Journald {
+    /// `tag`: Template for the `CONTAINER_TAG` and `SYSLOG_IDENTIFIER` journal fields.
+    pub tag: Option<String>,
+
+    /// Other driver options.
+    pub other: IndexMap<MapKey, Option<StringOrNumber>>,
+}
+
+impl Journald {
+    /// Parse [`Journald`] options from [`Logging`] options.
+    fn parse(options: &IndexMap<MapKey, Option<StringOrNumber>>) -> Self {
+        let mut journald = Self::default();
+        for (key, value) in options {
+            let Some(value) = value else { continue };
+            if key.as_str() == "tag" {
+                journald.tag = Some(value.to_string());
+            } else {
+                journald.other.insert(key.clone(), Some(value.clone()));
+            }
+        }
+        journald
+    }
+}
+
+/// Options for the `syslog` logging driver.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Syslog {
+    /// `syslog-address`: Address of the syslog server.
+    ///
+    /// The URL scheme must be `tcp`, `udp`, `tcp+tls`, `unix`, or `unixgram`.
+    pub address: Option<Url>,
+
+    /// `syslog-facility`: Syslog facility to log with.
+    pub facility: Option<SyslogFacility>,
+
+    /// `syslog-format`: Format of the syslog messages.
+    pub format: Option<SyslogFormat>,
+
+    /// `tag`: Template for the syslog message tag.
+    pub tag: Option<String>,
+
+    /// Other driver options.
+    pub other: IndexMap<MapKey, Option<StringOrNumber>>,
+}
+
+impl Syslog {
+    /// Supported [`Self::address`] URL schemes.
+    const SCHEMES: &'static [&'static str] = &["tcp", "udp", "tcp+tls", "unix", "unixgram"];
+
+    /// Parse [`Syslog`] options from [`Logging`] options.
+    fn parse(
+        options: &IndexMap<MapKey, Option<StringOrNumber>>,
+    ) -> Result<Self, InvalidOptionError> {
+        let mut syslog = Self::default();
+        for (key, value) in options {
+            let Some(value) = value else { continue };
+            match key.as_str() {
+                "syslog-address" => {
+                    syslog.address = Some(parse_option_with(key, value, |address| {
+                        parse_url(address, Self::SCHEMES)
+                    })?);
+                }
+                "syslog-facility" => syslog.facility = Some(parse_option(key, value)?),
+                "syslog-format" => syslog.format = Some(parse_option(key, value)?),
+                "tag" => syslog.tag = Some(value.to_string()),
+                _ => {
+                    syslog.other.insert(key.clone(), Some(value.clone()));
+                }
+            }
+        }
+        Ok(syslog)
+    }
+}
+
+/// Options for the `fluentd` logging driver.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Fluentd {
+    /// `fluentd-address`: Address of the fluentd daemon, either `{host}:{port}` or
+    /// `unix://{path}`.
+    pub address: Option<String>,
+
+    /// `fluentd-async`: Whether to connect to fluentd in the background.
+    pub async_connect: Option<bool>,
+
+    /// `fluentd-buffer-limit`: Maximum number of events buffered in memory.
+    pub buffer_limit: Option<u64>,
+
+    /// `fluentd-retry-wait`: How long to wait between connection retries.
+    pub retry_wait: Option<Duration>,
+
+    /// `fluentd-max-retries`: Maximum number of connection retries.
+    pub max_retries: Option<u64>,
+
+    /// `fluentd-sub-second-precision`: Whether to generate event logs in nanosecond resolution.
+    pub sub_second_precision: Option<bool>,
+
+    /// `tag`: Template for the fluentd tag.
+    pub tag: Option<String>,
+
+    /// Other driver options.
+    pub other: IndexMap<MapKey, Option<StringOrNumber>>,
+}
+
+impl Fluentd {
+    /// Parse [`Fluentd`] options from [`Logging`] options.
+    fn parse(
+        options: &IndexMap<MapKey, Option<StringOrNumber>>,
+    ) -> Result<Self, InvalidOptionError> {
+        let mut fluentd = Self::default();
+        for (key, value) in options {
+            let Some(value) = value else { continue };
+            match key.as_str() {
+                "fluentd-address" => fluentd.address = Some(value.to_string()),
+                "fluentd-async" => fluentd.async_connect = Some(parse_option(key, value)?),
+                "fluentd-buffer-limit" => fluentd.buffer_limit = Some(parse_option(key, value)?),
+                "fluentd-retry-wait" => {
+                    fluentd.retry_wait = Some(parse_option_with(key, value, |retry_wait| {
+                        duration::parse(retry_wait).map_err(Into::into)
+                    })?);
+                }
+                "fluentd-max-retries" => fluentd.max_retries = Some(parse_option(key, value)?),
+                "fluentd-sub-second-precision" => {
+                    fluentd.sub_second_precision = Some(parse_option(key, value)?);
+                }
+                "tag" => fluentd.tag = Some(value.to_string()),
+                _ => {
+                    fluentd.other.insert(key.clone(), Some(value.clone()));
+                }
+            }
+        }
+        Ok(fluentd)
+    }
+}
+
+/// Options for the `gelf` (Graylog Extended Log Format) logging driver.
+#[derive(Debug, Default, Clone, PartialEq)]
+pub struct Gelf {
+    /// `gelf-address`: Address of the GELF server.
+    ///
+    /// The URL scheme must be `udp` or `tcp`.
+    pub address: Option<Url>,
+
+    /// `gelf-compression-type`: Compression used for GELF messages sent over UDP.
+    pub compression_type: Option<GelfCompressionType>,
+
+    /// `gelf-compression-level`: Compression level, between -1 and 9 inclusive.
+    pub compression_level: Option<i8>,
+
+    /// `gelf-tcp-max-reconnect`: Maximum number of reconnection attempts when using TCP.
+    pub tcp_max_reconnect: Option<u64>,
+
+    /// `gelf-tcp-reconnect-delay`: Number of seconds to wait between reconnection attempts when
+    /// using TCP.
+    pub tcp_reconnect_delay: Option<u64>,
+
+    /// `tag`: Template for the GELF `_container_tag` field.
+    pub tag: Option<String>,
+
+    /// Other driver options.
+    pub other: IndexMap<MapKey, Option<StringOrNumber>>,
+}
+
+impl Gelf {
+    /// Supported [`Self::address`] URL schemes.
+    const SCHEMES: &'static [&'static str] = &["udp", "tcp"];
+
+    /// Parse [`Gelf`] options from [`Logging`] options.
+    fn parse(
+        options: &IndexMap<MapKey, Option<StringOrNumber>>,
+    ) -> Result<Self, InvalidOptionError> {
+        let mut gelf = Self::default();
+        for (key, value) in options {
+            let Some(value) = value else { continue };
+            match key.as_str() {
+                "gelf-address" => {
+                    gelf.address = Some(parse_option_with(key, value, |address| {
+                        parse_url(address, Self::SCHEMES)
+                    })?);
+                }
+                "gelf-compression-type" => gelf.compression_type = Some(parse_option(key, value)?),
+                "gelf-compression-level" => {
+                    gelf.compression_level =
+                        Some(parse_option_with(key, value, |level| {
+                            match level.parse()? {
+                                level @ -1..=9 => Ok(level),
+                                level => Err(RangeError {
+                                    value: level.into(),
+                                    start: -1,
+                                    end: 9,
+                                }
+                                .into()),
+                            }
+                        })?);
+                }
+                "gelf-tcp-max-reconnect" => {
+                    gelf.tcp_max_reconnect = Some(parse_option(key, value)?);
+                }
+                "gelf-tcp-reconnect-delay" => {
+                    gelf.tcp_reconnect_delay = Some(parse_option(key, value)?);
+                }
+                "tag" => gelf.tag = Some(value.to_string()),
+                _ => {
+                    gelf.other.insert(key.clone(), Some(value.clone()));
+                }
+            }
+        }
+        Ok(gelf)
+    }
+}
+
+/// Parse a logging option `value` with [`FromStr`].
+fn parse_option<T>(key: &MapKey, value: &StringOrNumber) -> Result<T, InvalidOptionError>
+where
+    T: FromStr,
+    T::Err: Into<InvalidOptionKind>,
+{
+    parse_option_with(key, value, |value| value.parse().map_err(Into::into))
+}
+
+/// Parse a logging option `value` with the function `f`.
+fn parse_option_with<T, F>(
+    key: &MapKey,
+    value: &StringOrNumber,
+    f: F,
+) -> Result<T, InvalidOptionError>
+where
+    F: FnOnce(&str) -> Result<T, InvalidOptionKind>,
+{
+    let value = value.to_string();
+    f(&value).map_err(|kind| InvalidOptionError {
+        key: key.clone(),
+        value,
+        kind,
+    })
+}
+
+/// Parse a [`Url`] and ensure its scheme is one of `schemes`.
+fn parse_url(url: &str, schemes: &[&str]) -> Result<Url, InvalidOptionKind> {
+    let url: Url = url.parse()?;
+    if schemes.contains(&url.scheme()) {
+        Ok(url)
+    } else {
+        Err(InvalidOptionKind::Scheme(url.scheme().to_owned()))
+    }
+}
+
+/// Generate a fieldless enum of logging option values with [`FromStr`] and [`Display`]
+/// implementations.
+macro_rules! option_value_enum {
+    (
+        $(#[$attr:meta])*
+        $Ty:ident {
+            $($(#[$variant_attr:meta])* $Variant:ident => $value:literal,)*
+        }
+    ) => {
+        $(#[$attr])*
+        #[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+        pub enum $Ty {
+            $($(#[$variant_attr])* $Variant,)*
+        }
+
+        impl $Ty {
+            /// Option value as a static string slice.
+            #[must_use]
+            pub const fn as_str(self) -> &'static str {
+                match self {
+                    $(Self::$Variant => $value,)*
+                }
+            }
+        }
+
+        impl FromStr for $Ty {
+            type Err = InvalidOptionKind;
+
+            fn from_str(s: &str) -> Result<Self, Self::Err> {
+                match s {
+                    $($value => Ok(Self::$Variant),)*
+                    _ => Err(InvalidOptionKind::Unknown),
+                }
+            }
+        }
+
+        impl AsRef<str> for $Ty {
+            fn as_ref(&self) -> &str {
+                self.as_str()
+            }
+        }
+
+        impl Display for $Ty {
+            fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+                f.write_str(self.as_str())
+            }
+        }
+    };
+}
+
+option_value_enum! {
+    /// Facility of [`Syslog`] messages.
+    SyslogFacility {
+        /// Kernel messages.
+        Kern => "kern",
+        /// User-level messages.
+        User => "user",
+        /// Mail system.
+        Mail => "mail",
+        /// System daemons.
+        Daemon => "daemon",
+        /// Security/authentication messages.
+        Auth => "auth",
+        /// Messages generated internally by syslogd.
+        Syslog => "syslog",
+        /// Line printer subsystem.
+        Lpr => "lpr",
+        /// Network news subsystem.
+        News => "news",
+        /// UUCP subsystem.
+        Uucp => "uucp",
+        /// Clock daemon.
+        Cron => "cron",
+        /// Private security/authentication messages.
+        AuthPriv => "authpriv",
+        /// FTP daemon.
+        Ftp => "ftp",
+        /// Local use 0.
+        Local0 => "local0",
+        /// Local use 1.
+        Local1 => "local1",
+        /// Local use 2.
+        Local2 => "local2",
+        /// Local use 3.
+        Local3 => "local3",
+        /// Local use 4.
+        Local4 => "local4",
+        /// Local use 5.
+        Local5 => "local5",
+        /// Local use 6.
+        Local6 => "local6",
+        /// Local use 7.
+        Local7 => "local7",
+    }
+}
+
+option_value_enum! {
+    /// Format of [`Syslog`] messages.
+    SyslogFormat {
+        /// RFC 5424 format.
+        Rfc5424 => "rfc5424",
+        /// RFC 5424 format with microsecond timestamp resolution.
+        Rfc5424Micro => "rfc5424micro",
+        /// RFC 3164 (BSD syslog) format.
+        Rfc3164 => "rfc3164",
+    }
+}
+
+option_value_enum! {
+    /// Compression used for [`Gelf`] messages.
+    GelfCompressionType {
+        /// gzip compression.
+        Gzip => "gzip",
+        /// zlib compression.
+        Zlib => "zlib",
+        /// No compression.
+        None => "none",
+    }
+}
+
+/// Error returned when a [`Logging`] driver option has an invalid value.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+#[error("invalid value `{value}` for logging option `{key}`")]
+pub struct InvalidOptionError {
+    /// Key of the invalid option.
+    pub key: MapKey,
+
+    /// Invalid option value.
+    pub value: String,
+
+    /// Reason the option value is invalid.
+    #[source]
+    pub kind: InvalidOptionKind,
+}
+
+/// Reason a [`Logging`] driver option value is invalid.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidOptionKind {
+    /// Value is not one of the known values for the option.
+    #[error("unknown value")]
+    Unknown,
+
+    /// Error parsing a [`ByteValue`].
+    #[error("error parsing byte value")]
+    ByteValue(#[from] ParseByteValueError),
+
+    /// Error parsing an integer.
+    #[error("error parsing integer")]
+    Int(#[from] ParseIntError),
+
+    /// Integer value is out of range.
+    #[error(transparent)]
+    Range(#[from] RangeError),
+
+    /// Error parsing a boolean.
+    #[error("error parsing boolean")]
+    Bool(#[from] ParseBoolError),
+
+    /// Error parsing a duration.
+    #[error("error parsing duration")]
+    Duration(#[from] ParseDurationError),
+
+    /// Error parsing a URL.
+    #[error("error parsing URL")]
+    Url(#[from] url::ParseError),
+
+    /// URL has an unsupported scheme.
+    #[error("unsupported URL scheme `{0}`")]
+    Scheme(String),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use indexmap::indexmap;
+
+    use super::*;
+
+    fn new_logging(driver: &str, options: IndexMap<&str, StringOrNumber>) -> Logging {
+        Logging {
+            driver: Some(driver.to_owned()),
+            options: options
+                .into_iter()
+                .map(|(key, value)| (MapKey::new(key).unwrap(), Some(value)))
+                .collect(),
+            extensions: Extensions::default(),
+        }
+    }
+
+    #[test]
+    fn json_file() {
+        let logging = new_logging(
+            "json-file",
+            indexmap! {
+                "max-size" => "10m".into(),
+                "max-file" => 3_u64.into(),
+                "compress" => "true".into(),
+                "labels" => "label".into(),
+            },
+        );
+        assert_eq!(
+            logging.driver_options().unwrap(),
+            Some(DriverOptions::JsonFile(FileOptions {
+                max_size: Some(ByteValue::Megabytes(10)),
+                max_file: Some(3),
+                compress: Some(true),
+                other: indexmap! {
+                    MapKey::new("labels").unwrap() => Some("label".into()),
+                },
+            }))
+        );
+    }
+
+    #[test]
+    fn invalid_max_size() {
+        let logging = new_logging("json-file", indexmap! {"max-size" => "10x".into()});
+        let error = logging.driver_options().unwrap_err();
+        assert_eq!(error.key.as_str(), "max-size");
+        assert_eq!(error.value, "10x");
+        assert!(matches!(error.kind, InvalidOptionKind::ByteValue(_)));
+    }
+
+    #[test]
+    fn invalid_max_file() {
+        let logging = new_logging("local", indexmap! {"max-file" => 0_u64.into()});
+        assert!(matches!(
+            logging.driver_options().unwrap_err().kind,
+            InvalidOptionKind::Range(_)
+        ));
+    }
+
+    #[test]
+    fn syslog() {
+        let logging = new_logging(
+            "syslog",
+            indexmap! {
+                "syslog-address" => "tcp+tls://192.168.1.3:514".into(),
+                "syslog-facility" => "daemon".into(),
+                "syslog-format" => "rfc5424micro".into(),
+            },
+        );
+        assert_eq!(
+            logging.driver_options().unwrap(),
+            Some(DriverOptions::Syslog(Syslog {
+                address: Some("tcp+tls://192.168.1.3:514".parse().unwrap()),
+                facility: Some(SyslogFacility::Daemon),
+                format: Some(SyslogFormat::Rfc5424Micro),
+                ..Syslog::default()
+            }))
+        );
+    }
+
+    #[test]
+    fn invalid_syslog() {
+        let logging = new_logging(
+            "syslog",
+            indexmap! {"syslog-address" => "http://localhost".into()},
+        );
+        assert_eq!(
+            logging.driver_options().unwrap_err().kind,
+            InvalidOptionKind::Scheme("http".to_owned())
+        );
+
+        let logging = new_logging("syslog", indexmap! {"syslog-facility" => "unknown".into()});
+        assert_eq!(
+            logging.driver_options().unwrap_err().kind,
+            InvalidOptionKind::Unknown
+        );
+    }
+
+    #[test]
+    fn gelf() {
+        let logging = new_logging(
+            "gelf",
+            indexmap! {
+                "gelf-address" => "udp://localhost:12201".into(),
+                "gelf-compression-type" => "zlib".into(),
+                "gelf-compression-level" => 9_u64.into(),
+            },
+        );
+        assert_eq!(
+            logging.driver_options().unwrap(),
+            Some(DriverOptions::Gelf(Gelf {
+                address: Some("udp://localhost:12201".parse().unwrap()),
+                compression_type: Some(GelfCompressionType::Zlib),
+                compression_level: Some(9),
+                ..Gelf::default()
+            }))
+        );
+
+        let logging = new_logging(
+            "gelf",
+            indexmap! {"gelf-compression-level" => 10_u64.into()},
+        );
+        assert!(matches!(
+            logging.driver_options().unwrap_err().kind,
+            InvalidOptionKind::Range(_)
+        ));
+    }
+
+    #[test]
+    fn unknown_driver() {
+        let logging = new_logging("other", indexmap! {"max-size" => "10x".into()});
+        assert_eq!(logging.driver_options().unwrap(), None);
+    }
+}
@@ -15,7 +15,7 @@ use serde::{de, ser::SerializeStruct, Deserialize, Deserializer, Serialize, Seri
 use thiserror::Error;
 
 use crate::{
-    impl_from_str, AsShortIter, Extensions, Identifier, InvalidIdentifierError, MapKey,
+    impl_from_str, AsShortIter, Extensions, Identifier, InvalidIdentifierError, MapKey, Resource,
     ShortOrLong, StringOrNumber,
 };
 
@@ -48,6 +48,25 @@ pub enum NetworkConfig {
 impl NetworkConfig {
     /// Struct name for (de)serializing.
     const NAME: &'static str = "NetworkConfig";
+
+    /// Create from a network mode or the networks a container is attached to, as collected from
+    /// `--network` flags.
+    ///
+    /// The `network_mode` takes precedence. Networks without options use the short syntax. Returns
+    /// [`None`] if neither is set.
+    pub(crate) fn from_parts(
+        network_mode: Option<NetworkMode>,
+        networks: IndexMap<Identifier, Option<Network>>,
+    ) -> Option<Self> {
+        match network_mode {
+            Some(network_mode) => Some(network_mode.into()),
+            None if networks.is_empty() => None,
+            None if networks.values().all(Option::is_none) => {
+                Some(ShortOrLong::Short(networks.into_keys().collect()).into())
+            }
+            None => Some(ShortOrLong::Long(networks).into()),
+        }
+    }
 }
 
 impl From<NetworkMode> for NetworkConfig {
@@ -420,6 +439,20 @@ pub struct Network {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub priority: Option<u64>,
 
+    /// Name of the network interface in the service container.
+    ///
+    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#interface_name)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub interface_name: Option<String>,
+
+    /// Priority of the network when selecting the service container's default gateway.
+    ///
+    /// The network with the highest priority is used. The default is 0.
+    ///
+    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#gw_priority)
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub gw_priority: Option<i64>,
+
     /// Extension values, which are (de)serialized via flattening.
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/11-extension.md)
@@ -439,6 +472,8 @@ impl Network {
             mac_address,
             driver_opts,
             priority,
+            interface_name,
+            gw_priority,
             extensions,
         } = self;
 
@@ -449,11 +484,104 @@ impl Network {
             && mac_address.is_none()
             && driver_opts.is_empty()
             && priority.is_none()
-            && priority.is_none()
+            && interface_name.is_none()
+            && gw_priority.is_none()
             && extensions.is_empty()
     }
 }
 
+/// Validate the network attachments of a [`Service`](super::Service).
+///
+/// Ensures at most one attachment has the highest `gw_priority` and that IPv4 addresses are not
+/// set for networks with IPv4 disabled in the top-level `networks`.
+pub(super) fn validate_attachments(
+    attachments: &IndexMap<Identifier, Option<Network>>,
+    networks: &crate::Networks,
+) -> Result<(), NetworkAttachmentError> {
+    let mut highest: Option<(&Identifier, i64, bool)> = None;
+    let mut conflict = None;
+
+    for (name, attachment) in attachments {
+        let explicit = attachment.as_ref().and_then(|network| network.gw_priority);
+        let gw_priority = explicit.unwrap_or_default();
+        match highest {
+            Some((_, highest_priority, _)) if gw_priority < highest_priority => {}
+            Some((first, highest_priority, highest_explicit))
+                if gw_priority == highest_priority =>
+            {
+                if highest_explicit || explicit.is_some() {
+                    conflict = Some((first, name, gw_priority));
+                }
+            }
+            _ => {
+                highest = Some((name, gw_priority, explicit.is_some()));
+                conflict = None;
+            }
+        }
+
+        let ipv4_disabled = matches!(
+            networks.get(name),
+            Some(Some(Resource::Compose(crate::Network {
+                enable_ipv4: Some(false),
+                ..
+            })))
+        );
+        if ipv4_disabled {
+            let address = attachment.as_ref().and_then(|network| {
+                network.ipv4_address.or_else(|| {
+                    network.link_local_ips.iter().find_map(|ip| match ip {
+                        IpAddr::V4(ip) => Some(*ip),
+                        IpAddr::V6(_) => None,
+                    })
+                })
+            });
+            if let Some(address) = address {
+                return Err(NetworkAttachmentError::Ipv4Disabled {
+                    network: name.clone(),
+                    address,
+                });
+            }
+        }
+    }
+
+    if let Some((first, second, gw_priority)) = conflict {
+        return Err(NetworkAttachmentError::GwPriority {
+            first: first.clone(),
+            second: second.clone(),
+            gw_priority,
+        });
+    }
+
+    Ok(())
+}
+
+/// Error returned when [validating](super::Service::validate_network_attachments()) the network
+/// attachments of a [`Service`](super::Service).
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum NetworkAttachmentError {
+    /// Multiple networks have the highest `gw_priority`, so the default gateway is ambiguous.
+    #[error(
+        "networks `{first}` and `{second}` both have the highest gateway priority ({gw_priority})"
+    )]
+    GwPriority {
+        /// First network with the highest `gw_priority`.
+        first: Identifier,
+        /// Second network with the highest `gw_priority`.
+        second: Identifier,
+        /// The highest `gw_priority`.
+        gw_priority: i64,
+    },
+
+    /// An IPv4 address was set for a network with `enable_ipv4: false`.
+    #[error("IPv4 address `{address}` set for network `{network}` which has IPv4 disabled")]
+    Ipv4Disabled {
+        /// Network with IPv4 disabled.
+        network: Identifier,
+        /// IPv4 address set for the network.
+        address: Ipv4Addr,
+    },
+}
+
 impl<'a> AsShortIter<'a> for IndexMap<Identifier, Option<Network>> {
     type Iter = Keys<'a, Identifier, Option<Network>>;
 
@@ -573,7 +701,7 @@ impl Display for MacAddress {
 #[cfg(test)]
 #[allow(clippy::unwrap_used)]
 mod tests {
-    use indexmap::indexset;
+    use indexmap::{indexmap, indexset};
 
     use super::*;
 
@@ -593,6 +721,73 @@ mod tests {
         assert_eq!(serde_yaml::to_string(&config).unwrap(), string);
     }
 
+    #[test]
+    fn validate_gw_priority() {
+        let one = Identifier::new("one").unwrap();
+        let two = Identifier::new("two").unwrap();
+        let networks = crate::Networks::default();
+
+        let mut attachments = indexmap! {
+            one.clone() => None,
+            two.clone() => None,
+        };
+        assert_eq!(validate_attachments(&attachments, &networks), Ok(()));
+
+        attachments.insert(
+            two.clone(),
+            Some(Network {
+                gw_priority: Some(0),
+                ..Network::default()
+            }),
+        );
+        assert_eq!(
+            validate_attachments(&attachments, &networks),
+            Err(NetworkAttachmentError::GwPriority {
+                first: one,
+                second: two.clone(),
+                gw_priority: 0,
+            })
+        );
+
+        attachments.insert(
+            two,
+            Some(Network {
+                gw_priority: Some(1),
+                ..Network::default()
+            }),
+        );
+        assert_eq!(validate_attachments(&attachments, &networks), Ok(()));
+    }
+
+    #[test]
+    fn validate_ipv4_disabled() {
+        let network = Identifier::new("network").unwrap();
+        let address = Ipv4Addr::new(10, 0, 0, 2);
+        let attachments = indexmap! {
+            network.clone() => Some(Network {
+                ipv4_address: Some(address),
+                ..Network::default()
+            }),
+        };
+
+        let mut networks = indexmap! {
+            network.clone() => Some(Resource::Compose(crate::Network::default())),
+        };
+        assert_eq!(validate_attachments(&attachments, &networks), Ok(()));
+
+        networks.insert(
+            network.clone(),
+            Some(Resource::Compose(crate::Network {
+                enable_ipv4: Some(false),
+                ..crate::Network::default()
+            })),
+        );
+        assert_eq!(
+            validate_attachments(&attachments, &networks),
+            Err(NetworkAttachmentError::Ipv4Disabled { network, address })
+        );
+    }
+
     #[test]
     fn missing_err() {
         assert!(serde_yaml::from_str::<NetworkConfig>("{}")
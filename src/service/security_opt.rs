@@ -0,0 +1,312 @@
+//! Provides [`SecurityOpt`] for the `security_opt` field of [`Service`](super::Service).
+
+use std::{
+    fmt::{self, Display, Formatter},
+    path::PathBuf,
+};
+
+use compose_spec_macros::{DeserializeTryFromString, SerializeDisplay};
+
+use crate::impl_from_str;
+
+/// Container security option.
+///
+/// (De)serializes from/to a string in the format `{key}={value}`. The legacy `{key}:{value}`
+/// format is also accepted when parsing.
+///
+/// Unknown or malformed security options are kept as [`Other`](Self::Other).
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#security_opt)
+#[derive(SerializeDisplay, DeserializeTryFromString, Debug, Clone, PartialEq, Eq, Hash)]
+pub enum SecurityOpt {
+    /// `seccomp={profile}`: Seccomp profile to apply to the container.
+    Seccomp(Seccomp),
+
+    /// `apparmor={profile}`: AppArmor profile to apply to the container.
+    AppArmor(String),
+
+    /// `label={label}`: SELinux label to apply to the container.
+    Label(Label),
+
+    /// `no-new-privileges[={bool}]`: Whether to disable the container processes from gaining
+    /// new privileges.
+    ///
+    /// `no-new-privileges` without a value is parsed as `true`.
+    NoNewPrivileges(bool),
+
+    /// `systempaths=unconfined`: Turn off the confinement of system paths (masked and read-only
+    /// paths) for the container.
+    SystemPathsUnconfined,
+
+    /// Other security option.
+    Other(String),
+}
+
+impl SecurityOpt {
+    /// [`Self::Seccomp`] key.
+    const SECCOMP: &'static str = "seccomp";
+
+    /// [`Self::AppArmor`] key.
+    const APPARMOR: &'static str = "apparmor";
+
+    /// [`Self::Label`] key.
+    const LABEL: &'static str = "label";
+
+    /// [`Self::NoNewPrivileges`] key.
+    const NO_NEW_PRIVILEGES: &'static str = "no-new-privileges";
+
+    /// [`Self::SystemPathsUnconfined`] key.
+    const SYSTEMPATHS: &'static str = "systempaths";
+
+    /// Parse a [`SecurityOpt`] from a string in the format `{key}={value}` or `{key}:{value}`.
+    ///
+    /// Unknown or malformed security options are parsed as [`Other`](Self::Other).
+    pub fn parse<T>(security_opt: T) -> Self
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        let s = security_opt.as_ref();
+
+        if s == Self::NO_NEW_PRIVILEGES {
+            return Self::NoNewPrivileges(true);
+        }
+
+        // Format is "{key}={value}" or legacy "{key}:{value}"
+        let parsed = s.split_once(['=', ':']).and_then(|(key, value)| match key {
+            Self::SECCOMP => Some(Self::Seccomp(Seccomp::parse(value))),
+            Self::APPARMOR => Some(Self::AppArmor(value.to_owned())),
+            Self::LABEL => Label::parse(value).map(Self::Label),
+            Self::NO_NEW_PRIVILEGES => value.parse().ok().map(Self::NoNewPrivileges),
+            Self::SYSTEMPATHS => {
+                (value == Seccomp::UNCONFINED).then_some(Self::SystemPathsUnconfined)
+            }
+            _ => None,
+        });
+
+        parsed.unwrap_or_else(|| Self::Other(security_opt.into()))
+    }
+
+    /// Returns `true` if the security option is [`Other`](Self::Other).
+    #[must_use]
+    pub const fn is_other(&self) -> bool {
+        matches!(self, Self::Other(..))
+    }
+
+    /// Returns [`Some`] if the security option is [`Other`](Self::Other).
+    #[must_use]
+    pub fn as_other(&self) -> Option<&str> {
+        if let Self::Other(other) = self {
+            Some(other)
+        } else {
+            None
+        }
+    }
+}
+
+impl_from_str!(SecurityOpt);
+
+impl Display for SecurityOpt {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Seccomp(seccomp) => write!(f, "{}={seccomp}", Self::SECCOMP),
+            Self::AppArmor(profile) => write!(f, "{}={profile}", Self::APPARMOR),
+            Self::Label(label) => write!(f, "{}={label}", Self::LABEL),
+            Self::NoNewPrivileges(no_new_privileges) => {
+                write!(f, "{}={no_new_privileges}", Self::NO_NEW_PRIVILEGES)
+            }
+            Self::SystemPathsUnconfined => {
+                write!(f, "{}={}", Self::SYSTEMPATHS, Seccomp::UNCONFINED)
+            }
+            Self::Other(other) => f.write_str(other),
+        }
+    }
+}
+
+/// Seccomp profile [`SecurityOpt`].
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Seccomp {
+    /// Run the container without the default seccomp profile.
+    Unconfined,
+
+    /// Path to a seccomp profile JSON file.
+    Profile(PathBuf),
+}
+
+impl Seccomp {
+    /// [`Self::Unconfined`] string value.
+    const UNCONFINED: &'static str = "unconfined";
+
+    /// Parse a [`Seccomp`] profile from a string.
+    fn parse(seccomp: &str) -> Self {
+        if seccomp == Self::UNCONFINED {
+            Self::Unconfined
+        } else {
+            Self::Profile(seccomp.into())
+        }
+    }
+}
+
+impl Display for Seccomp {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Unconfined => f.write_str(Self::UNCONFINED),
+            Self::Profile(path) => path.display().fmt(f),
+        }
+    }
+}
+
+/// SELinux label [`SecurityOpt`].
+///
+/// Parsed from and displayed as a string in the format `{kind}:{value}` or `disable`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum Label {
+    /// `user:{user}`: SELinux user label.
+    User(String),
+
+    /// `role:{role}`: SELinux role label.
+    Role(String),
+
+    /// `type:{type}`: SELinux type label.
+    Type(String),
+
+    /// `level:{level}`: SELinux level label.
+    Level(String),
+
+    /// `disable`: Turn off SELinux label confinement for the container.
+    Disable,
+}
+
+impl Label {
+    /// [`Self::User`] prefix.
+    const USER: &'static str = "user";
+
+    /// [`Self::Role`] prefix.
+    const ROLE: &'static str = "role";
+
+    /// [`Self::Type`] prefix.
+    const TYPE: &'static str = "type";
+
+    /// [`Self::Level`] prefix.
+    const LEVEL: &'static str = "level";
+
+    /// [`Self::Disable`] string value.
+    const DISABLE: &'static str = "disable";
+
+    /// Parse a [`Label`] from a string.
+    ///
+    /// Returns [`None`] if the label is unknown.
+    fn parse(label: &str) -> Option<Self> {
+        if label == Self::DISABLE {
+            return Some(Self::Disable);
+        }
+
+        // Format is "{kind}:{value}"
+        let (kind, value) = label.split_once(':')?;
+        let value = value.to_owned();
+        match kind {
+            Self::USER => Some(Self::User(value)),
+            Self::ROLE => Some(Self::Role(value)),
+            Self::TYPE => Some(Self::Type(value)),
+            Self::LEVEL => Some(Self::Level(value)),
+            _ => None,
+        }
+    }
+}
+
+impl Display for Label {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::User(user) => write!(f, "{}:{user}", Self::USER),
+            Self::Role(role) => write!(f, "{}:{role}", Self::ROLE),
+            Self::Type(kind) => write!(f, "{}:{kind}", Self::TYPE),
+            Self::Level(level) => write!(f, "{}:{level}", Self::LEVEL),
+            Self::Disable => f.write_str(Self::DISABLE),
+        }
+    }
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{
+        arbitrary::any,
+        prop_assert_eq, prop_oneof, proptest,
+        strategy::{Just, Strategy},
+    };
+
+    use crate::service::tests::path_no_colon;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn parse_no_panic(string: String) {
+            let _ = SecurityOpt::parse(string);
+        }
+
+        #[test]
+        fn round_trip(security_opt in security_opt()) {
+            prop_assert_eq!(&security_opt, &security_opt.to_string().parse()?);
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(
+            SecurityOpt::parse("seccomp:unconfined"),
+            SecurityOpt::Seccomp(Seccomp::Unconfined)
+        );
+        assert_eq!(
+            SecurityOpt::parse("seccomp=/profile.json"),
+            SecurityOpt::Seccomp(Seccomp::Profile("/profile.json".into()))
+        );
+        assert_eq!(
+            SecurityOpt::parse("apparmor:unconfined"),
+            SecurityOpt::AppArmor("unconfined".to_owned())
+        );
+        assert_eq!(
+            SecurityOpt::parse("label=level:s0:c100,c200"),
+            SecurityOpt::Label(Label::Level("s0:c100,c200".to_owned()))
+        );
+        assert_eq!(
+            SecurityOpt::parse("label:disable"),
+            SecurityOpt::Label(Label::Disable)
+        );
+        assert_eq!(
+            SecurityOpt::parse("no-new-privileges"),
+            SecurityOpt::NoNewPrivileges(true)
+        );
+        assert_eq!(
+            SecurityOpt::parse("no-new-privileges:false"),
+            SecurityOpt::NoNewPrivileges(false)
+        );
+        assert_eq!(
+            SecurityOpt::parse("systempaths=unconfined"),
+            SecurityOpt::SystemPathsUnconfined
+        );
+        assert!(SecurityOpt::parse("label=unknown:value").is_other());
+        assert!(SecurityOpt::parse("no-new-privileges=maybe").is_other());
+    }
+
+    fn security_opt() -> impl Strategy<Value = SecurityOpt> {
+        prop_oneof![
+            Just(SecurityOpt::Seccomp(Seccomp::Unconfined)),
+            path_no_colon()
+                .prop_filter("must not be unconfined", |path| {
+                    path.as_os_str() != Seccomp::UNCONFINED
+                })
+                .prop_map(|path| SecurityOpt::Seccomp(Seccomp::Profile(path))),
+            any::<String>().prop_map(SecurityOpt::AppArmor),
+            prop_oneof![
+                any::<String>().prop_map(Label::User),
+                any::<String>().prop_map(Label::Role),
+                any::<String>().prop_map(Label::Type),
+                any::<String>().prop_map(Label::Level),
+                Just(Label::Disable),
+            ]
+            .prop_map(SecurityOpt::Label),
+            any::<bool>().prop_map(SecurityOpt::NoNewPrivileges),
+            Just(SecurityOpt::SystemPathsUnconfined),
+            "[a-z]+".prop_map(SecurityOpt::Other),
+        ]
+    }
+}
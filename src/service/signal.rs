@@ -0,0 +1,429 @@
+//! Provides [`Signal`] for the `stop_signal` field of [`Service`](super::Service).
+
+use std::fmt::{self, Display, Formatter};
+
+use compose_spec_macros::{DeserializeTryFromString, SerializeDisplay};
+use thiserror::Error;
+
+use crate::impl_from_str;
+
+use super::RangeError;
+
+/// Create the [`Signal`] enum and its associated items from a list of variants, their signal
+/// names without the `SIG` prefix, and their numbers.
+macro_rules! signals {
+    ($($(#[doc = $doc:literal])* $Variant:ident => $name:literal = $number:literal,)*) => {
+        /// Linux [**signal**(7)](https://man7.org/linux/man-pages/man7/signal.7.html) sent to a
+        /// [`Service`](super::Service) container to stop it.
+        ///
+        /// Signal numbers are those used on most Linux architectures, including x86 and ARM.
+        ///
+        /// When parsing, the `SIG` prefix is optional and case is ignored, e.g. `SIGTERM`, `TERM`,
+        /// `sigterm`, and `15` are all parsed as [`Term`](Self::Term). Real-time signals may be
+        /// given as `SIGRTMIN+{n}` or `SIGRTMAX-{n}`.
+        ///
+        /// (De)serializes from/to the signal name with the `SIG` prefix, e.g. `SIGTERM` or
+        /// `SIGRTMIN+3`.
+        ///
+        /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#stop_signal)
+        #[derive(
+            SerializeDisplay, DeserializeTryFromString, Debug, Clone, Copy, PartialEq, Eq, Hash,
+        )]
+        pub enum Signal {
+            $(
+                $(#[doc = $doc])*
+                $Variant,
+            )*
+            /// `SIGRTMIN+{n}`: Real-time signal, offset from `SIGRTMIN`.
+            RealTime(RealTimeOffset),
+        }
+
+        impl Signal {
+            /// Signal number.
+            #[must_use]
+            pub const fn number(self) -> u8 {
+                match self {
+                    $(Self::$Variant => $number,)*
+                    Self::RealTime(offset) => Self::RTMIN_NUMBER.saturating_add(offset.into_inner()),
+                }
+            }
+
+            /// Standard signal from its name without the `SIG` prefix, in upper case.
+            fn from_standard_name(name: &str) -> Option<Self> {
+                match name {
+                    $($name => Some(Self::$Variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Standard signal from its number.
+            const fn from_standard_number(number: u8) -> Option<Self> {
+                match number {
+                    $($number => Some(Self::$Variant),)*
+                    _ => None,
+                }
+            }
+
+            /// Standard signal name without the `SIG` prefix.
+            ///
+            /// Returns [`None`] for [`RealTime`](Self::RealTime) signals.
+            const fn standard_name(self) -> Option<&'static str> {
+                match self {
+                    $(Self::$Variant => Some($name),)*
+                    Self::RealTime(_) => None,
+                }
+            }
+        }
+    };
+}
+
+signals! {
+    /// `SIGHUP`: Hangup detected on controlling terminal or death of controlling process.
+    Hup => "HUP" = 1,
+    /// `SIGINT`: Interrupt from keyboard.
+    Int => "INT" = 2,
+    /// `SIGQUIT`: Quit from keyboard.
+    Quit => "QUIT" = 3,
+    /// `SIGILL`: Illegal instruction.
+    Ill => "ILL" = 4,
+    /// `SIGTRAP`: Trace/breakpoint trap.
+    Trap => "TRAP" = 5,
+    /// `SIGABRT`: Abort signal from **abort**(3), also known as `SIGIOT`.
+    Abrt => "ABRT" = 6,
+    /// `SIGBUS`: Bus error (bad memory access).
+    Bus => "BUS" = 7,
+    /// `SIGFPE`: Floating-point exception.
+    Fpe => "FPE" = 8,
+    /// `SIGKILL`: Kill signal.
+    Kill => "KILL" = 9,
+    /// `SIGUSR1`: User-defined signal 1.
+    Usr1 => "USR1" = 10,
+    /// `SIGSEGV`: Invalid memory reference.
+    Segv => "SEGV" = 11,
+    /// `SIGUSR2`: User-defined signal 2.
+    Usr2 => "USR2" = 12,
+    /// `SIGPIPE`: Broken pipe: write to pipe with no readers.
+    Pipe => "PIPE" = 13,
+    /// `SIGALRM`: Timer signal from **alarm**(2).
+    Alrm => "ALRM" = 14,
+    /// `SIGTERM`: Termination signal.
+    Term => "TERM" = 15,
+    /// `SIGSTKFLT`: Stack fault on coprocessor.
+    Stkflt => "STKFLT" = 16,
+    /// `SIGCHLD`: Child stopped or terminated, also known as `SIGCLD`.
+    Chld => "CHLD" = 17,
+    /// `SIGCONT`: Continue if stopped.
+    Cont => "CONT" = 18,
+    /// `SIGSTOP`: Stop process.
+    Stop => "STOP" = 19,
+    /// `SIGTSTP`: Stop typed at terminal.
+    Tstp => "TSTP" = 20,
+    /// `SIGTTIN`: Terminal input for background process.
+    Ttin => "TTIN" = 21,
+    /// `SIGTTOU`: Terminal output for background process.
+    Ttou => "TTOU" = 22,
+    /// `SIGURG`: Urgent condition on socket.
+    Urg => "URG" = 23,
+    /// `SIGXCPU`: CPU time limit exceeded.
+    Xcpu => "XCPU" = 24,
+    /// `SIGXFSZ`: File size limit exceeded.
+    Xfsz => "XFSZ" = 25,
+    /// `SIGVTALRM`: Virtual alarm clock.
+    Vtalrm => "VTALRM" = 26,
+    /// `SIGPROF`: Profiling timer expired.
+    Prof => "PROF" = 27,
+    /// `SIGWINCH`: Window resize signal.
+    Winch => "WINCH" = 28,
+    /// `SIGIO`: I/O now possible, also known as `SIGPOLL`.
+    Io => "IO" = 29,
+    /// `SIGPWR`: Power failure.
+    Pwr => "PWR" = 30,
+    /// `SIGSYS`: Bad system call.
+    Sys => "SYS" = 31,
+}
+
+impl Signal {
+    /// Signal name prefix.
+    const PREFIX: &'static str = "SIG";
+
+    /// `SIGRTMIN` name without the `SIG` prefix.
+    const RTMIN: &'static str = "RTMIN";
+
+    /// `SIGRTMAX` name without the `SIG` prefix.
+    const RTMAX: &'static str = "RTMAX";
+
+    /// Number of `SIGRTMIN`.
+    ///
+    /// The kernel's `SIGRTMIN` is 32, but signals 32 and 33 are reserved by the C library.
+    const RTMIN_NUMBER: u8 = 34;
+
+    /// Parse a [`Signal`] from a string.
+    ///
+    /// Accepts signal names with or without the `SIG` prefix in any case, signal numbers, and
+    /// real-time signals in the form `SIGRTMIN+{n}` or `SIGRTMAX-{n}`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the signal name is unknown, or if the signal number or real-time
+    /// signal offset is out of range.
+    pub fn parse<T>(signal: T) -> Result<Self, ParseSignalError>
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        let s = signal.as_ref();
+
+        if let Ok(number) = s.parse() {
+            return Self::from_number(number);
+        }
+
+        let name = s.to_ascii_uppercase();
+        let name = name.strip_prefix(Self::PREFIX).unwrap_or(&name);
+
+        let parsed = match name {
+            "IOT" => Some(Self::Abrt),
+            "CLD" => Some(Self::Chld),
+            "POLL" => Some(Self::Io),
+            Self::RTMIN => Some(Self::RealTime(RealTimeOffset::MIN)),
+            Self::RTMAX => Some(Self::RealTime(RealTimeOffset::MAX)),
+            name => Self::parse_real_time(name).or_else(|| Self::from_standard_name(name)),
+        };
+
+        parsed.ok_or_else(|| ParseSignalError::Unknown(signal.into()))
+    }
+
+    /// Parse a [`RealTime`](Self::RealTime) signal from a name in the form `RTMIN+{n}` or
+    /// `RTMAX-{n}`.
+    fn parse_real_time(name: &str) -> Option<Self> {
+        if let Some(offset) = name.strip_prefix("RTMIN+") {
+            return offset
+                .parse()
+                .ok()
+                .and_then(|offset| RealTimeOffset::new(offset).ok())
+                .map(Self::RealTime);
+        }
+
+        name.strip_prefix("RTMAX-")?
+            .parse()
+            .ok()
+            .and_then(|offset| RealTimeOffset::MAX.0.checked_sub(offset))
+            .and_then(|offset| RealTimeOffset::new(offset).ok())
+            .map(Self::RealTime)
+    }
+
+    /// Create a [`Signal`] from its number.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the number is not a valid Linux signal number, i.e. not in the range
+    /// 1-31 or 34-64.
+    pub fn from_number(number: u64) -> Result<Self, ParseSignalError> {
+        u8::try_from(number)
+            .ok()
+            .and_then(|number| {
+                Self::from_standard_number(number).or_else(|| {
+                    number
+                        .checked_sub(Self::RTMIN_NUMBER)
+                        .and_then(|offset| RealTimeOffset::new(offset).ok())
+                        .map(Self::RealTime)
+                })
+            })
+            .ok_or(ParseSignalError::Number(number))
+    }
+
+    /// Returns `true` if the signal is a [`RealTime`](Self::RealTime) signal.
+    #[must_use]
+    pub const fn is_real_time(self) -> bool {
+        matches!(self, Self::RealTime(..))
+    }
+}
+
+impl_from_str!(Signal => ParseSignalError);
+
+impl TryFrom<u8> for Signal {
+    type Error = ParseSignalError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::from_number(value.into())
+    }
+}
+
+impl From<Signal> for u8 {
+    fn from(value: Signal) -> Self {
+        value.number()
+    }
+}
+
+impl Display for Signal {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(Self::PREFIX)?;
+
+        match *self {
+            Self::RealTime(RealTimeOffset::MIN) => f.write_str(Self::RTMIN),
+            Self::RealTime(offset) => write!(f, "{}+{offset}", Self::RTMIN),
+            signal => f.write_str(signal.standard_name().unwrap_or_default()),
+        }
+    }
+}
+
+/// Offset of a [`RealTime`](Signal::RealTime) signal from `SIGRTMIN`.
+///
+/// The offset is between 0 (`SIGRTMIN`) and 30 (`SIGRTMAX`), inclusive.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, PartialOrd, Ord, Hash)]
+pub struct RealTimeOffset(u8);
+
+impl RealTimeOffset {
+    /// Offset of `SIGRTMIN`.
+    pub const MIN: Self = Self(0);
+
+    /// Offset of `SIGRTMAX`.
+    pub const MAX: Self = Self(30);
+
+    /// Create a new [`RealTimeOffset`].
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the offset is greater than 30.
+    pub fn new(offset: u8) -> Result<Self, RangeError> {
+        if offset <= Self::MAX.0 {
+            Ok(Self(offset))
+        } else {
+            Err(RangeError {
+                value: offset.into(),
+                start: Self::MIN.0.into(),
+                end: Self::MAX.0.into(),
+            })
+        }
+    }
+
+    /// Return the inner value.
+    #[must_use]
+    pub const fn into_inner(self) -> u8 {
+        self.0
+    }
+}
+
+impl TryFrom<u8> for RealTimeOffset {
+    type Error = RangeError;
+
+    fn try_from(value: u8) -> Result<Self, Self::Error> {
+        Self::new(value)
+    }
+}
+
+impl From<RealTimeOffset> for u8 {
+    fn from(value: RealTimeOffset) -> Self {
+        value.into_inner()
+    }
+}
+
+impl PartialEq<u8> for RealTimeOffset {
+    fn eq(&self, other: &u8) -> bool {
+        self.0.eq(other)
+    }
+}
+
+impl Display for RealTimeOffset {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        Display::fmt(&self.0, f)
+    }
+}
+
+/// Error returned when [parsing](Signal::parse()) a [`Signal`] from a string.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseSignalError {
+    /// Unknown signal name.
+    #[error("unknown signal `{0}`")]
+    Unknown(String),
+
+    /// Signal number out of range.
+    #[error("invalid signal number `{0}`, must be in the range 1-31 or 34-64")]
+    Number(u64),
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use proptest::{prop_assert, prop_assert_eq, proptest};
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn parse_no_panic(string: String) {
+            let _ = Signal::parse(string);
+        }
+
+        #[test]
+        fn real_time_offset(offset: u8) {
+            match RealTimeOffset::new(offset) {
+                Ok(offset) => {
+                    prop_assert!(offset <= RealTimeOffset::MAX);
+                    let signal = Signal::RealTime(offset);
+                    prop_assert_eq!(signal, signal.to_string().parse()?);
+                }
+                Err(_) => prop_assert!(offset > RealTimeOffset::MAX.into_inner()),
+            }
+        }
+
+        #[test]
+        fn round_trip(number in 1..=64_u64) {
+            if let Ok(signal) = Signal::from_number(number) {
+                prop_assert_eq!(signal, signal.to_string().parse()?);
+                prop_assert_eq!(u64::from(signal.number()), number);
+            }
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(Signal::parse("SIGTERM"), Ok(Signal::Term));
+        assert_eq!(Signal::parse("TERM"), Ok(Signal::Term));
+        assert_eq!(Signal::parse("sigterm"), Ok(Signal::Term));
+        assert_eq!(Signal::parse("15"), Ok(Signal::Term));
+        assert_eq!(Signal::parse("SIGIOT"), Ok(Signal::Abrt));
+        assert_eq!(Signal::parse("SIGRTMIN"), Ok(real_time(0)));
+        assert_eq!(Signal::parse("SIGRTMIN+3"), Ok(real_time(3)));
+        assert_eq!(Signal::parse("SIGRTMAX-2"), Ok(real_time(28)));
+        assert_eq!(Signal::parse("SIGRTMAX"), Ok(real_time(30)));
+        assert_eq!(Signal::parse("37"), Ok(real_time(3)));
+
+        assert_eq!(
+            Signal::parse("SIGTREM"),
+            Err(ParseSignalError::Unknown("SIGTREM".to_owned()))
+        );
+        assert_eq!(
+            Signal::parse("SIGRTMIN+31"),
+            Err(ParseSignalError::Unknown("SIGRTMIN+31".to_owned()))
+        );
+        assert_eq!(Signal::parse("0"), Err(ParseSignalError::Number(0)));
+        assert_eq!(Signal::parse("32"), Err(ParseSignalError::Number(32)));
+        assert_eq!(Signal::parse("65"), Err(ParseSignalError::Number(65)));
+    }
+
+    fn real_time(offset: u8) -> Signal {
+        Signal::RealTime(RealTimeOffset::new(offset).unwrap())
+    }
+
+    #[test]
+    fn real_time_offset_bounds() {
+        assert_eq!(RealTimeOffset::new(30), Ok(RealTimeOffset::MAX));
+        assert_eq!(
+            RealTimeOffset::new(31),
+            Err(RangeError {
+                value: 31,
+                start: 0,
+                end: 30,
+            })
+        );
+        assert_eq!(real_time(30).to_string(), "SIGRTMIN+30");
+        assert_eq!(Signal::parse("SIGRTMIN+30"), Ok(real_time(30)));
+        assert_eq!(real_time(30).number(), 64);
+    }
+
+    #[test]
+    fn display() {
+        assert_eq!(Signal::Term.to_string(), "SIGTERM");
+        assert_eq!(real_time(0).to_string(), "SIGRTMIN");
+        assert_eq!(real_time(3).to_string(), "SIGRTMIN+3");
+    }
+}
@@ -0,0 +1,227 @@
+//! Provides [`Sysctl`] for the keys of the `sysctls` field of [`Service`](super::Service).
+
+use std::fmt::{self, Display, Formatter};
+
+use compose_spec_macros::{DeserializeTryFromString, SerializeDisplay};
+use thiserror::Error;
+
+use crate::{common::key_impls, ListOrMap};
+
+/// Namespaced kernel parameter (sysctl) name, e.g. `net.core.somaxconn`.
+///
+/// Only sysctls in a namespace the container has its own copy of can be set. That is, the
+/// [`Net`](SysctlNamespace::Net) namespace (`net.*`) and the [`Ipc`](SysctlNamespace::Ipc)
+/// namespace (`kernel.shm*`, `kernel.msg*`, `kernel.sem`, and `fs.mqueue.*`).
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#sysctls)
+#[derive(
+    SerializeDisplay, DeserializeTryFromString, Debug, Clone, PartialEq, Eq, PartialOrd, Ord, Hash,
+)]
+pub struct Sysctl(Box<str>);
+
+impl Sysctl {
+    /// Create a new [`Sysctl`], validating the given string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the given string is empty, contains whitespace, or is not in a
+    /// [`SysctlNamespace`].
+    pub fn new<T>(sysctl: T) -> Result<Self, InvalidSysctlError>
+    where
+        T: AsRef<str> + Into<Box<str>>,
+    {
+        let sysctl_str = sysctl.as_ref();
+
+        if sysctl_str.is_empty() {
+            return Err(InvalidSysctlError::Empty);
+        }
+
+        if let Some(char) = sysctl_str.chars().find(|char| char.is_whitespace()) {
+            return Err(InvalidSysctlError::Whitespace(char));
+        }
+
+        if SysctlNamespace::of(sysctl_str).is_none() {
+            return Err(InvalidSysctlError::NotNamespaced(
+                sysctl.into().into_string(),
+            ));
+        }
+
+        Ok(Self(sysctl.into()))
+    }
+
+    /// The [`SysctlNamespace`] the sysctl is in.
+    #[must_use]
+    pub fn namespace(&self) -> SysctlNamespace {
+        // Namespace is checked in `Sysctl::new()`.
+        SysctlNamespace::of(&self.0).unwrap_or(SysctlNamespace::Net)
+    }
+}
+
+/// Error returned when creating a [`Sysctl`].
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum InvalidSysctlError {
+    /// Sysctl was empty.
+    #[error("sysctls cannot be empty")]
+    Empty,
+
+    /// Sysctl contained a whitespace character.
+    #[error("invalid character '{0}', sysctls cannot contain whitespace")]
+    Whitespace(char),
+
+    /// Sysctl is not namespaced and cannot be set for a container.
+    #[error(
+        "sysctl `{0}` is not namespaced, only `net.*`, `kernel.shm*`, `kernel.msg*`, \
+        `kernel.sem`, and `fs.mqueue.*` sysctls can be set"
+    )]
+    NotNamespaced(String),
+}
+
+key_impls!(Sysctl => InvalidSysctlError);
+
+/// Linux namespace a [`Sysctl`] belongs to.
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum SysctlNamespace {
+    /// Network namespace, `net.*` sysctls.
+    ///
+    /// Cannot be set when the container uses the host's network namespace.
+    Net,
+
+    /// IPC namespace, `kernel.shm*`, `kernel.msg*`, `kernel.sem`, and `fs.mqueue.*` sysctls.
+    ///
+    /// Cannot be set when the container uses the host's IPC namespace.
+    Ipc,
+}
+
+impl SysctlNamespace {
+    /// Determine the namespace of a sysctl.
+    ///
+    /// Returns [`None`] if the sysctl is not namespaced.
+    fn of(sysctl: &str) -> Option<Self> {
+        if sysctl.starts_with("net.") {
+            Some(Self::Net)
+        } else if sysctl.starts_with("kernel.shm")
+            || sysctl.starts_with("kernel.msg")
+            || sysctl == "kernel.sem"
+            || sysctl.starts_with("fs.mqueue.")
+        {
+            Some(Self::Ipc)
+        } else {
+            None
+        }
+    }
+
+    /// Namespace name as a static string slice.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Net => "network",
+            Self::Ipc => "IPC",
+        }
+    }
+}
+
+impl Display for SysctlNamespace {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
+/// Validate the keys of a [`Service`](super::Service)'s `sysctls`.
+///
+/// `host_namespaces` are the namespaces the container shares with the host.
+pub(super) fn validate(
+    sysctls: &ListOrMap,
+    host_namespaces: &[SysctlNamespace],
+) -> Result<(), SysctlError> {
+    let keys: Box<dyn Iterator<Item = &str>> = match sysctls {
+        ListOrMap::List(list) => Box::new(
+            list.iter()
+                .map(|item| item.split_once('=').map_or(item.as_str(), |(key, _)| key)),
+        ),
+        ListOrMap::Map(map) => Box::new(map.keys().map(AsRef::as_ref)),
+    };
+
+    for key in keys {
+        let sysctl = Sysctl::new(key)?;
+        let namespace = sysctl.namespace();
+        if host_namespaces.contains(&namespace) {
+            return Err(SysctlError::HostNamespace { sysctl, namespace });
+        }
+    }
+
+    Ok(())
+}
+
+/// Error returned when validating the `sysctls` of a [`Service`](super::Service).
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum SysctlError {
+    /// Invalid or non-namespaced sysctl.
+    #[error(transparent)]
+    Invalid(#[from] InvalidSysctlError),
+
+    /// Sysctl set in a namespace the container shares with the host.
+    #[error("sysctl `{sysctl}` cannot be set when using the host's {namespace} namespace")]
+    HostNamespace {
+        /// Sysctl which cannot be set.
+        sysctl: Sysctl,
+        /// Namespace shared with the host.
+        namespace: SysctlNamespace,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn new() {
+        assert_eq!(
+            Sysctl::new("net.core.somaxconn").map(|sysctl| sysctl.namespace()),
+            Ok(SysctlNamespace::Net)
+        );
+        for ipc in [
+            "kernel.shmmax",
+            "kernel.msgmnb",
+            "kernel.sem",
+            "fs.mqueue.msg_max",
+        ] {
+            assert_eq!(
+                Sysctl::new(ipc).map(|sysctl| sysctl.namespace()),
+                Ok(SysctlNamespace::Ipc)
+            );
+        }
+        assert_eq!(
+            Sysctl::new("kernel.domainname"),
+            Err(InvalidSysctlError::NotNamespaced(
+                "kernel.domainname".to_owned()
+            ))
+        );
+        assert_eq!(Sysctl::new(""), Err(InvalidSysctlError::Empty));
+    }
+
+    #[test]
+    fn validate_host_namespace() {
+        let sysctls = ListOrMap::List(
+            ["net.ipv4.ip_forward=1", "kernel.shmmax=1024"]
+                .into_iter()
+                .map(Into::into)
+                .collect(),
+        );
+
+        assert_eq!(validate(&sysctls, &[]), Ok(()));
+        assert!(matches!(
+            validate(&sysctls, &[SysctlNamespace::Net]),
+            Err(SysctlError::HostNamespace {
+                namespace: SysctlNamespace::Net,
+                ..
+            })
+        ));
+        assert!(matches!(
+            validate(&sysctls, &[SysctlNamespace::Ipc]),
+            Err(SysctlError::HostNamespace {
+                namespace: SysctlNamespace::Ipc,
+                ..
+            })
+        ));
+    }
+}
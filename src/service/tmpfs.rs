@@ -0,0 +1,467 @@
+//! Provides [`TmpfsEntry`] for the `tmpfs` field of [`Service`](super::Service).
+
+use std::{
+    fmt::{self, Display, Formatter},
+    num::ParseIntError,
+};
+
+use compose_spec_macros::{DeserializeTryFromString, SerializeDisplay};
+use thiserror::Error;
+
+use crate::impl_from_str;
+
+use super::{
+    volumes::{
+        mount::{Common, Tmpfs, TmpfsOptions},
+        AbsolutePathError,
+    },
+    AbsolutePath, ByteValue, ParseByteValueError,
+};
+
+/// Temporary file system to mount inside a [`Service`](super::Service) container.
+///
+/// (De)serializes from/to a string in the format `{path}[:{options}]`, where `options` is a
+/// comma (,) separated list of [`TmpfsOption`]s, e.g. `/run:size=64m,mode=1777,uid=1000`.
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#tmpfs)
+#[derive(SerializeDisplay, DeserializeTryFromString, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct TmpfsEntry {
+    /// Path within the container to mount the tmpfs at.
+    pub path: AbsolutePath,
+
+    /// Mount options for the tmpfs.
+    pub options: Vec<TmpfsOption>,
+}
+
+impl TmpfsEntry {
+    /// Create a [`TmpfsEntry`] without any options.
+    #[must_use]
+    pub const fn new(path: AbsolutePath) -> Self {
+        Self {
+            path,
+            options: Vec::new(),
+        }
+    }
+
+    /// Parse a [`TmpfsEntry`] from a string in the format `{path}[:{options}]`.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the path is not absolute, or if one of the options could not be
+    /// parsed.
+    pub fn parse<T>(entry: T) -> Result<Self, ParseTmpfsEntryError>
+    where
+        T: AsRef<str>,
+    {
+        // Format is "{path}[:{options}]"
+        let entry = entry.as_ref();
+        let (path, options) = entry
+            .split_once(':')
+            .map_or((entry, None), |(path, options)| (path, Some(options)));
+
+        let path = AbsolutePath::new(path)?;
+        let options = options
+            .map(|options| {
+                options
+                    .split(',')
+                    .map(TmpfsOption::parse)
+                    .collect::<Result<_, _>>()
+            })
+            .transpose()?
+            .unwrap_or_default();
+
+        Ok(Self { path, options })
+    }
+
+    /// Size of the tmpfs, if set.
+    ///
+    /// If the size option is given multiple times, the last one is used.
+    #[must_use]
+    pub fn size(&self) -> Option<ByteValue> {
+        self.options.iter().rev().find_map(|option| match option {
+            TmpfsOption::Size(size) => Some(*size),
+            _ => None,
+        })
+    }
+
+    /// File mode of the tmpfs root directory, if set.
+    ///
+    /// If the mode option is given multiple times, the last one is used.
+    #[must_use]
+    pub fn mode(&self) -> Option<u32> {
+        self.options.iter().rev().find_map(|option| match option {
+            TmpfsOption::Mode(mode) => Some(*mode),
+            _ => None,
+        })
+    }
+
+    /// User ID of the owner of the tmpfs root directory, if set.
+    ///
+    /// If the uid option is given multiple times, the last one is used.
+    #[must_use]
+    pub fn uid(&self) -> Option<u32> {
+        self.options.iter().rev().find_map(|option| match option {
+            TmpfsOption::Uid(uid) => Some(*uid),
+            _ => None,
+        })
+    }
+
+    /// Group ID of the owner of the tmpfs root directory, if set.
+    ///
+    /// If the gid option is given multiple times, the last one is used.
+    #[must_use]
+    pub fn gid(&self) -> Option<u32> {
+        self.options.iter().rev().find_map(|option| match option {
+            TmpfsOption::Gid(gid) => Some(*gid),
+            _ => None,
+        })
+    }
+
+    /// Convert into the long volume [`Tmpfs`] mount syntax if possible.
+    ///
+    /// Only the [`Size`](TmpfsOption::Size), [`Mode`](TmpfsOption::Mode),
+    /// [`ReadOnly`](TmpfsOption::ReadOnly), and [`ReadWrite`](TmpfsOption::ReadWrite) options
+    /// can be represented in the long syntax.
+    ///
+    /// # Errors
+    ///
+    /// Returns ownership if the entry contains options which cannot be represented in the long
+    /// syntax.
+    pub fn into_long(self) -> Result<Tmpfs, Self> {
+        let mut read_only = false;
+        let mut tmpfs = TmpfsOptions::default();
+
+        for option in &self.options {
+            match option {
+                TmpfsOption::Size(size) => tmpfs.size = Some(*size),
+                TmpfsOption::Mode(mode) => tmpfs.mode = Some(*mode),
+                TmpfsOption::ReadOnly => read_only = true,
+                TmpfsOption::ReadWrite => read_only = false,
+                _ => return Err(self),
+            }
+        }
+
+        Ok(Tmpfs {
+            tmpfs: (!tmpfs.is_empty()).then_some(tmpfs),
+            common: Common {
+                read_only,
+                ..Common::new(self.path)
+            },
+        })
+    }
+}
+
+impl_from_str!(TmpfsEntry => ParseTmpfsEntryError);
+
+impl From<AbsolutePath> for TmpfsEntry {
+    fn from(path: AbsolutePath) -> Self {
+        Self::new(path)
+    }
+}
+
+impl Display for TmpfsEntry {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self { path, options } = self;
+
+        // Format is "{path}[:{options}]"
+
+        path.as_path().display().fmt(f)?;
+
+        let mut options = options.iter();
+        if let Some(option) = options.next() {
+            write!(f, ":{option}")?;
+            for option in options {
+                write!(f, ",{option}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Error returned when [parsing](TmpfsEntry::parse()) a [`TmpfsEntry`] from a string.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseTmpfsEntryError {
+    /// Tmpfs path was not absolute.
+    #[error("tmpfs path must be absolute")]
+    AbsolutePath(#[from] AbsolutePathError),
+
+    /// Error parsing a [`TmpfsOption`].
+    #[error("error parsing tmpfs option")]
+    Option(#[from] ParseTmpfsOptionError),
+}
+
+/// Mount option for a [`TmpfsEntry`].
+///
+/// Parsed from and displayed as a string in the format `{key}[={value}]`.
+#[derive(Debug, Clone, PartialEq, Eq, Hash)]
+pub enum TmpfsOption {
+    /// `size={size}`: Size of the tmpfs.
+    Size(ByteValue),
+
+    /// `mode={mode}`: File mode of the tmpfs root directory, parsed and displayed in octal form.
+    Mode(u32),
+
+    /// `uid={uid}`: User ID of the owner of the tmpfs root directory.
+    Uid(u32),
+
+    /// `gid={gid}`: Group ID of the owner of the tmpfs root directory.
+    Gid(u32),
+
+    /// `nr_inodes={nr_inodes}`: Maximum number of inodes for the tmpfs.
+    NrInodes(u64),
+
+    /// `ro`: Mount the tmpfs read-only.
+    ReadOnly,
+
+    /// `rw`: Mount the tmpfs read-write.
+    ReadWrite,
+
+    /// `exec`: Allow execution of binaries on the tmpfs.
+    Exec,
+
+    /// `noexec`: Do not allow execution of binaries on the tmpfs.
+    NoExec,
+
+    /// `suid`: Honor set-user-ID and set-group-ID bits on the tmpfs.
+    Suid,
+
+    /// `nosuid`: Ignore set-user-ID and set-group-ID bits on the tmpfs.
+    NoSuid,
+
+    /// `dev`: Interpret character or block special devices on the tmpfs.
+    Dev,
+
+    /// `nodev`: Do not interpret character or block special devices on the tmpfs.
+    NoDev,
+
+    /// Other mount option.
+    Other(String),
+}
+
+impl TmpfsOption {
+    /// [`Self::Size`] option key.
+    const SIZE: &'static str = "size";
+
+    /// [`Self::Mode`] option key.
+    const MODE: &'static str = "mode";
+
+    /// [`Self::Uid`] option key.
+    const UID: &'static str = "uid";
+
+    /// [`Self::Gid`] option key.
+    const GID: &'static str = "gid";
+
+    /// [`Self::NrInodes`] option key.
+    const NR_INODES: &'static str = "nr_inodes";
+
+    /// Parse a [`TmpfsOption`] from a string in the format `{key}[={value}]`.
+    ///
+    /// Unknown options are parsed as [`Other`](Self::Other).
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the option is empty, or if the value of a known option could not be
+    /// parsed.
+    pub fn parse<T>(option: T) -> Result<Self, ParseTmpfsOptionError>
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        let Some((key, value)) = option.as_ref().split_once('=') else {
+            return match option.as_ref() {
+                "" => Err(ParseTmpfsOptionError::Empty),
+                "ro" => Ok(Self::ReadOnly),
+                "rw" => Ok(Self::ReadWrite),
+                "exec" => Ok(Self::Exec),
+                "noexec" => Ok(Self::NoExec),
+                "suid" => Ok(Self::Suid),
+                "nosuid" => Ok(Self::NoSuid),
+                "dev" => Ok(Self::Dev),
+                "nodev" => Ok(Self::NoDev),
+                _ => Ok(Self::Other(option.into())),
+            };
+        };
+
+        match key {
+            Self::SIZE => value.parse().map(Self::Size).map_err(Into::into),
+            Self::MODE => u32::from_str_radix(value, 8)
+                .map(Self::Mode)
+                .map_err(|source| ParseTmpfsOptionError::Int {
+                    key: Self::MODE,
+                    source,
+                }),
+            Self::UID => {
+                value
+                    .parse()
+                    .map(Self::Uid)
+                    .map_err(|source| ParseTmpfsOptionError::Int {
+                        key: Self::UID,
+                        source,
+                    })
+            }
+            Self::GID => {
+                value
+                    .parse()
+                    .map(Self::Gid)
+                    .map_err(|source| ParseTmpfsOptionError::Int {
+                        key: Self::GID,
+                        source,
+                    })
+            }
+            Self::NR_INODES => {
+                value
+                    .parse()
+                    .map(Self::NrInodes)
+                    .map_err(|source| ParseTmpfsOptionError::Int {
+                        key: Self::NR_INODES,
+                        source,
+                    })
+            }
+            _ => Ok(Self::Other(option.into())),
+        }
+    }
+}
+
+impl_from_str!(TmpfsOption => ParseTmpfsOptionError);
+
+impl Display for TmpfsOption {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Size(size) => write!(f, "{}={size}", Self::SIZE),
+            Self::Mode(mode) => write!(f, "{}={mode:o}", Self::MODE),
+            Self::Uid(uid) => write!(f, "{}={uid}", Self::UID),
+            Self::Gid(gid) => write!(f, "{}={gid}", Self::GID),
+            Self::NrInodes(nr_inodes) => write!(f, "{}={nr_inodes}", Self::NR_INODES),
+            Self::ReadOnly => f.write_str("ro"),
+            Self::ReadWrite => f.write_str("rw"),
+            Self::Exec => f.write_str("exec"),
+            Self::NoExec => f.write_str("noexec"),
+            Self::Suid => f.write_str("suid"),
+            Self::NoSuid => f.write_str("nosuid"),
+            Self::Dev => f.write_str("dev"),
+            Self::NoDev => f.write_str("nodev"),
+            Self::Other(other) => f.write_str(other),
+        }
+    }
+}
+
+/// Error returned when [parsing](TmpfsOption::parse()) a [`TmpfsOption`] from a string.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseTmpfsOptionError {
+    /// Empty option.
+    #[error("tmpfs option cannot be empty")]
+    Empty,
+
+    /// Error parsing the `size` option.
+    #[error("error parsing tmpfs size")]
+    Size(#[from] ParseByteValueError),
+
+    /// Error parsing the value of an integer option.
+    #[error("error parsing tmpfs `{key}` option value")]
+    Int {
+        /// Option key.
+        key: &'static str,
+        /// Source of the error.
+        source: ParseIntError,
+    },
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use proptest::{
+        arbitrary::any,
+        prop_assert_eq, prop_oneof, proptest,
+        strategy::{Just, Strategy},
+    };
+
+    use crate::service::tests::path_no_colon;
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn parse_no_panic(string: String) {
+            let _ = TmpfsEntry::parse(string);
+        }
+
+        #[test]
+        fn round_trip(entry in entry()) {
+            prop_assert_eq!(&entry, &entry.to_string().parse()?);
+        }
+    }
+
+    #[test]
+    fn parse() {
+        let entry = TmpfsEntry::parse("/run:size=64m,mode=1777,uid=1000,noexec").unwrap();
+        assert_eq!(entry.path.as_path().to_str(), Some("/run"));
+        assert_eq!(entry.size(), Some(ByteValue::Megabytes(64)));
+        assert_eq!(entry.mode(), Some(0o1777));
+        assert_eq!(entry.uid(), Some(1000));
+        assert_eq!(entry.gid(), None);
+        assert_eq!(entry.options.last(), Some(&TmpfsOption::NoExec));
+
+        assert!(matches!(
+            TmpfsEntry::parse("run"),
+            Err(ParseTmpfsEntryError::AbsolutePath(_))
+        ));
+        assert!(matches!(
+            TmpfsEntry::parse("/run:mode=999"),
+            Err(ParseTmpfsEntryError::Option(ParseTmpfsOptionError::Int {
+                key: "mode",
+                ..
+            }))
+        ));
+    }
+
+    #[test]
+    fn into_long() {
+        let tmpfs = TmpfsEntry::parse("/run:size=1g,ro")
+            .unwrap()
+            .into_long()
+            .unwrap();
+        assert_eq!(tmpfs.tmpfs.unwrap().size, Some(ByteValue::Gigabytes(1)));
+        assert!(tmpfs.common.read_only);
+
+        let entry = TmpfsEntry::parse("/run:uid=1000").unwrap();
+        assert_eq!(entry.clone().into_long().unwrap_err(), entry);
+    }
+
+    proptest! {
+        #[test]
+        fn long_round_trip(entry in entry()) {
+            if let Ok(tmpfs) = entry.into_long() {
+                prop_assert_eq!(tmpfs.clone().into_short().unwrap().into_long().unwrap(), tmpfs);
+            }
+        }
+    }
+
+    fn entry() -> impl Strategy<Value = TmpfsEntry> {
+        (
+            path_no_colon()
+                .prop_filter_map("path must be absolute", |path| AbsolutePath::new(path).ok()),
+            proptest::collection::vec(tmpfs_option(), 0..4),
+        )
+            .prop_map(|(path, options)| TmpfsEntry { path, options })
+    }
+
+    fn tmpfs_option() -> impl Strategy<Value = TmpfsOption> {
+        prop_oneof![
+            any::<u64>().prop_map(|size| TmpfsOption::Size(ByteValue::Bytes(size))),
+            any::<u32>().prop_map(TmpfsOption::Mode),
+            any::<u32>().prop_map(TmpfsOption::Uid),
+            any::<u32>().prop_map(TmpfsOption::Gid),
+            any::<u64>().prop_map(TmpfsOption::NrInodes),
+            Just(TmpfsOption::ReadOnly),
+            Just(TmpfsOption::NoExec),
+            "[a-z_]+"
+                .prop_filter("must be an unknown option", |other| {
+                    !matches!(
+                        other.as_str(),
+                        "ro" | "rw" | "exec" | "noexec" | "suid" | "nosuid" | "dev" | "nodev"
+                    )
+                })
+                .prop_map(TmpfsOption::Other),
+        ]
+    }
+}
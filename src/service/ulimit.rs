@@ -1,9 +1,11 @@
 //! Provides [`Ulimits`] for the `ulimits` field of [`Service`](super::Service) and the long
 //! [`Build`](super::Build) syntax.
 
+use std::fmt::{self, Display, Formatter};
+
 use compose_spec_macros::{DeserializeTryFromString, SerializeDisplay};
 use indexmap::IndexMap;
-use serde::{Deserialize, Serialize};
+use serde::{de, Deserialize, Deserializer, Serialize};
 use thiserror::Error;
 
 use crate::{common::key_impls, AsShort, Extensions, ShortOrLong};
@@ -21,6 +23,7 @@ pub type Ulimits = IndexMap<Resource, ShortOrLong<Limit<u64>, Ulimit>>;
 /// [`Ulimit`] resource name (e.g. "nofile").
 ///
 /// Resource names must only contain lowercase ASCII letters (a-z) and cannot be empty.
+/// Use [`Resource::known()`] to convert to a [`KnownResource`].
 ///
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#ulimits)
 #[derive(
@@ -53,6 +56,127 @@ impl Resource {
 
         Ok(Self(resource.into()))
     }
+
+    /// Convert into a [`KnownResource`].
+    ///
+    /// Returns [`None`] if the resource is not known.
+    #[must_use]
+    pub fn known(&self) -> Option<KnownResource> {
+        KnownResource::parse(&self.0)
+    }
+}
+
+impl From<KnownResource> for Resource {
+    fn from(value: KnownResource) -> Self {
+        Self(value.as_str().into())
+    }
+}
+
+/// Known [`Ulimit`] [`Resource`]s, see
+/// [**getrlimit**(2)](https://man7.org/linux/man-pages/man2/getrlimit.2.html).
+#[derive(Debug, Clone, Copy, PartialEq, Eq, Hash)]
+pub enum KnownResource {
+    /// `as`: Maximum size of the process's virtual memory (address space).
+    As,
+    /// `core`: Maximum size of a core file.
+    Core,
+    /// `cpu`: Limit, in seconds, on the amount of CPU time that the process can consume.
+    Cpu,
+    /// `data`: Maximum size of the process's data segment.
+    Data,
+    /// `fsize`: Maximum size of files that the process may create.
+    Fsize,
+    /// `locks`: Limit on the combined number of locks and leases that the process may establish.
+    Locks,
+    /// `memlock`: Maximum number of bytes of memory that may be locked into RAM.
+    Memlock,
+    /// `msgqueue`: Limit on the number of bytes that can be allocated for POSIX message queues.
+    Msgqueue,
+    /// `nice`: Ceiling to which the process's nice value can be raised.
+    Nice,
+    /// `nofile`: Maximum file descriptor number that can be opened by the process.
+    Nofile,
+    /// `nproc`: Limit on the number of threads that can be created for the real user ID.
+    Nproc,
+    /// `rss`: Limit on the number of virtual pages resident in RAM.
+    Rss,
+    /// `rtprio`: Ceiling on the real-time priority that may be set for the process.
+    Rtprio,
+    /// `rttime`: Limit, in microseconds, on the amount of CPU time that a process scheduled under
+    /// a real-time scheduling policy may consume without making a blocking system call.
+    Rttime,
+    /// `sigpending`: Limit on the number of signals that may be queued for the real user ID.
+    Sigpending,
+    /// `stack`: Maximum size of the process stack.
+    Stack,
+}
+
+impl KnownResource {
+    /// All known resources.
+    pub const ALL: &'static [Self] = &[
+        Self::As,
+        Self::Core,
+        Self::Cpu,
+        Self::Data,
+        Self::Fsize,
+        Self::Locks,
+        Self::Memlock,
+        Self::Msgqueue,
+        Self::Nice,
+        Self::Nofile,
+        Self::Nproc,
+        Self::Rss,
+        Self::Rtprio,
+        Self::Rttime,
+        Self::Sigpending,
+        Self::Stack,
+    ];
+
+    /// Parse a [`KnownResource`] from a string.
+    ///
+    /// Returns [`None`] if the resource is not known.
+    #[must_use]
+    pub fn parse(resource: &str) -> Option<Self> {
+        Self::ALL
+            .iter()
+            .copied()
+            .find(|known| known.as_str() == resource)
+    }
+
+    /// Resource name as a static string slice.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::As => "as",
+            Self::Core => "core",
+            Self::Cpu => "cpu",
+            Self::Data => "data",
+            Self::Fsize => "fsize",
+            Self::Locks => "locks",
+            Self::Memlock => "memlock",
+            Self::Msgqueue => "msgqueue",
+            Self::Nice => "nice",
+            Self::Nofile => "nofile",
+            Self::Nproc => "nproc",
+            Self::Rss => "rss",
+            Self::Rtprio => "rtprio",
+            Self::Rttime => "rttime",
+            Self::Sigpending => "sigpending",
+            Self::Stack => "stack",
+        }
+    }
+}
+
+impl AsRef<str> for KnownResource {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for KnownResource {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
 }
 
 /// Error returned when creating a [`Resource`].
@@ -75,8 +199,10 @@ key_impls!(Resource => InvalidResourceError);
 
 /// Ulimit long syntax, defines a soft and hard limit for a [`Resource`].
 ///
+/// When deserializing, an error is returned if the soft limit is greater than the hard limit.
+///
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#ulimits)
-#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[derive(Serialize, Debug, Clone, PartialEq, Eq)]
 pub struct Ulimit {
     /// Soft limit.
     pub soft: Limit<u64>,
@@ -91,6 +217,88 @@ pub struct Ulimit {
     pub extensions: Extensions,
 }
 
+impl Ulimit {
+    /// Create a new [`Ulimit`] from a soft and hard limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the soft limit is greater than the hard limit.
+    pub fn new(soft: Limit<u64>, hard: Limit<u64>) -> Result<Self, SoftExceedsHardError> {
+        let ulimit = Self {
+            soft,
+            hard,
+            extensions: Extensions::default(),
+        };
+        ulimit.validate().map(|()| ulimit)
+    }
+
+    /// Ensure the soft limit is less than or equal to the hard limit.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the soft limit is greater than the hard limit.
+    pub fn validate(&self) -> Result<(), SoftExceedsHardError> {
+        let Self { soft, hard, .. } = *self;
+        if soft <= hard {
+            Ok(())
+        } else {
+            Err(SoftExceedsHardError { soft, hard })
+        }
+    }
+
+    /// Convert into the Docker Engine API representation for the given `resource`.
+    ///
+    /// See [`EngineUlimit`] for details.
+    #[must_use]
+    pub fn to_engine(&self, resource: &Resource) -> EngineUlimit {
+        EngineUlimit {
+            name: resource.as_str().to_owned(),
+            soft: EngineUlimit::limit(self.soft),
+            hard: EngineUlimit::limit(self.hard),
+        }
+    }
+}
+
+impl<'de> Deserialize<'de> for Ulimit {
+    fn deserialize<D: Deserializer<'de>>(deserializer: D) -> Result<Self, D::Error> {
+        /// [`Ulimit`] fields, validated after deserializing.
+        #[derive(Deserialize)]
+        struct Fields {
+            /// [`Ulimit::soft`]
+            soft: Limit<u64>,
+            /// [`Ulimit::hard`]
+            hard: Limit<u64>,
+            /// [`Ulimit::extensions`]
+            #[serde(flatten)]
+            extensions: Extensions,
+        }
+
+        let Fields {
+            soft,
+            hard,
+            extensions,
+        } = Fields::deserialize(deserializer)?;
+        let ulimit = Self {
+            soft,
+            hard,
+            extensions,
+        };
+        ulimit.validate().map_err(de::Error::custom)?;
+        Ok(ulimit)
+    }
+}
+
+/// Error returned when a [`Ulimit`]'s soft limit is greater than its hard limit.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+#[error("ulimit soft limit `{soft}` is greater than the hard limit `{hard}`")]
+pub struct SoftExceedsHardError {
+    /// Soft limit.
+    pub soft: Limit<u64>,
+
+    /// Hard limit.
+    pub hard: Limit<u64>,
+}
+
 impl AsShort for Ulimit {
     type Short = Limit<u64>;
 
@@ -120,3 +328,152 @@ impl From<Limit<u64>> for Ulimit {
         }
     }
 }
+
+/// Parse a ulimit in the `{resource}={soft}[:{hard}]` format, as used by `docker run --ulimit`.
+///
+/// Returns [`None`] if the ulimit is not in that format or is invalid.
+pub(crate) fn parse_ulimit(ulimit: &str) -> Option<(Resource, ShortOrLong<Limit<u64>, Ulimit>)> {
+    let (resource, limits) = ulimit.split_once('=')?;
+    let resource = Resource::new(resource).ok()?;
+    let ulimit = match limits.split_once(':') {
+        Some((soft, hard)) => {
+            let soft = soft.parse().ok()?;
+            let hard = hard.parse().ok()?;
+            if soft == hard {
+                ShortOrLong::Short(soft)
+            } else {
+                ShortOrLong::Long(Ulimit::new(soft, hard).ok()?)
+            }
+        }
+        None => ShortOrLong::Short(limits.parse().ok()?),
+    };
+    Some((resource, ulimit))
+}
+
+/// A [`Ulimit`] as represented in the Docker Engine API's `HostConfig.Ulimits` list.
+///
+/// Serializes to `{"Name": name, "Soft": soft, "Hard": hard}` where [`Limit::Unlimited`] is `-1`.
+/// Limits greater than [`i64::MAX`] are saturated to [`i64::MAX`].
+///
+/// [Docker Engine API](https://docs.docker.com/engine/api/v1.45/#tag/Container/operation/ContainerCreate)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq)]
+#[serde(rename_all = "PascalCase")]
+pub struct EngineUlimit {
+    /// Name of the [`Resource`].
+    pub name: String,
+
+    /// Soft limit, `-1` is unlimited.
+    pub soft: i64,
+
+    /// Hard limit, `-1` is unlimited.
+    pub hard: i64,
+}
+
+impl EngineUlimit {
+    /// Convert [`Ulimits`] into a list of [`EngineUlimit`]s, in order.
+    ///
+    /// The short syntax, a single limit, is used as both the soft and hard limit.
+    ///
+    /// ```
+    /// use compose_spec::{
+    ///     service::{EngineUlimit, Limit, Resource, Ulimit, Ulimits},
+    ///     ShortOrLong,
+    /// };
+    ///
+    /// let mut ulimits = Ulimits::new();
+    /// ulimits.insert(
+    ///     Resource::new("nproc").unwrap(),
+    ///     ShortOrLong::Short(Limit::Unlimited),
+    /// );
+    /// ulimits.insert(
+    ///     Resource::new("nofile").unwrap(),
+    ///     ShortOrLong::Long(Ulimit::new(Limit::Value(1024), Limit::Value(2048)).unwrap()),
+    /// );
+    ///
+    /// assert_eq!(
+    ///     EngineUlimit::from_ulimits(&ulimits),
+    ///     [
+    ///         EngineUlimit {
+    ///             name: "nproc".to_owned(),
+    ///             soft: -1,
+    ///             hard: -1,
+    ///         },
+    ///         EngineUlimit {
+    ///             name: "nofile".to_owned(),
+    ///             soft: 1024,
+    ///             hard: 2048,
+    ///         },
+    ///     ],
+    /// );
+    /// ```
+    #[must_use]
+    pub fn from_ulimits(ulimits: &Ulimits) -> Vec<Self> {
+        ulimits
+            .iter()
+            .map(|(resource, ulimit)| match ulimit {
+                ShortOrLong::Short(limit) => Self {
+                    name: resource.as_str().to_owned(),
+                    soft: Self::limit(*limit),
+                    hard: Self::limit(*limit),
+                },
+                ShortOrLong::Long(ulimit) => ulimit.to_engine(resource),
+            })
+            .collect()
+    }
+
+    /// Convert a [`Limit`] into its Docker Engine API value.
+    fn limit(limit: Limit<u64>) -> i64 {
+        match limit {
+            Limit::Value(value) => value.try_into().unwrap_or(i64::MAX),
+            Limit::Unlimited => -1,
+        }
+    }
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn known_resource_round_trip() {
+        for known in KnownResource::ALL {
+            assert_eq!(Resource::from(*known).known(), Some(*known));
+        }
+    }
+
+    #[test]
+    fn soft_exceeds_hard() {
+        assert_eq!(
+            Ulimit::new(Limit::Value(200), Limit::Value(100)),
+            Err(SoftExceedsHardError {
+                soft: Limit::Value(200),
+                hard: Limit::Value(100),
+            })
+        );
+        assert_eq!(
+            Ulimit::new(Limit::Unlimited, Limit::Value(100)),
+            Err(SoftExceedsHardError {
+                soft: Limit::Unlimited,
+                hard: Limit::Value(100),
+            })
+        );
+        assert_eq!(
+            Ulimit::new(Limit::Value(100), Limit::Unlimited).map(|ulimit| ulimit.hard),
+            Ok(Limit::Unlimited)
+        );
+    }
+
+    #[test]
+    fn deserialize_soft_exceeds_hard() {
+        let error = serde_yaml::from_str::<Ulimit>("soft: 200\nhard: 100").unwrap_err();
+        assert!(error
+            .to_string()
+            .contains("soft limit `200` is greater than the hard limit `100`"));
+
+        serde_yaml::from_str::<Ulimits>("nofile:\n  soft: 200\n  hard: 100").unwrap_err();
+
+        let ulimit: Ulimit = serde_yaml::from_str("soft: 100\nhard: 200").unwrap();
+        assert_eq!(ulimit.soft, Limit::Value(100));
+    }
+}
@@ -0,0 +1,417 @@
+//! Provides [`UsernsMode`] for the `userns_mode` field of [`Service`](super::Service).
+
+use std::{
+    borrow::Cow,
+    fmt::{self, Display, Formatter},
+    num::ParseIntError,
+};
+
+use compose_spec_macros::{DeserializeTryFromString, SerializeDisplay};
+use thiserror::Error;
+
+use crate::impl_from_str;
+
+/// User namespace mode for a [`Service`](super::Service) container.
+///
+/// Available values are platform specific. The [`KeepId`](Self::KeepId),
+/// [`Auto`](Self::Auto), and [`NoMap`](Self::NoMap) modes, and their options, are specific to
+/// Podman.
+///
+/// (De)serializes from/to a string in the format `{mode}[:{options}]`, where `options` is a
+/// comma (,) separated list of `{key}={value}` pairs.
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#userns_mode)
+#[derive(SerializeDisplay, DeserializeTryFromString, Debug, Clone, PartialEq, Eq)]
+pub enum UsernsMode {
+    /// Use the host's user namespace.
+    Host,
+
+    /// Map the user running the container engine to the same UID and GID in the container.
+    KeepId(KeepIdOptions),
+
+    /// Automatically create a unique user namespace.
+    Auto(AutoOptions),
+
+    /// Do not map the user running the container engine into the container.
+    NoMap,
+
+    /// Other user namespace mode.
+    Other(String),
+}
+
+impl UsernsMode {
+    /// [`Self::Host`] string value.
+    const HOST: &'static str = "host";
+
+    /// [`Self::KeepId`] string value.
+    const KEEP_ID: &'static str = "keep-id";
+
+    /// [`Self::Auto`] string value.
+    const AUTO: &'static str = "auto";
+
+    /// [`Self::NoMap`] string value.
+    const NO_MAP: &'static str = "nomap";
+
+    /// Parse a [`UsernsMode`] from a string.
+    ///
+    /// # Errors
+    ///
+    /// Returns an error if the options of a [`KeepId`](Self::KeepId) or [`Auto`](Self::Auto)
+    /// user namespace mode are unknown or invalid.
+    pub fn parse<T>(userns_mode: T) -> Result<Self, ParseUsernsModeError>
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        let s = userns_mode.as_ref();
+
+        // Format is "{mode}[:{options}]"
+        let (mode, options) = s
+            .split_once(':')
+            .map_or((s, None), |(mode, options)| (mode, Some(options)));
+
+        match (mode, options) {
+            (Self::HOST, None) => Ok(Self::Host),
+            (Self::KEEP_ID, options) => options
+                .map_or_else(|| Ok(KeepIdOptions::default()), KeepIdOptions::parse)
+                .map(Self::KeepId),
+            (Self::AUTO, options) => options
+                .map_or_else(|| Ok(AutoOptions::default()), AutoOptions::parse)
+                .map(Self::Auto),
+            (Self::NO_MAP, None) => Ok(Self::NoMap),
+            _ => Ok(Self::Other(userns_mode.into())),
+        }
+    }
+
+    /// Returns `true` if the user namespace mode is [`Host`].
+    ///
+    /// [`Host`]: UsernsMode::Host
+    #[must_use]
+    pub const fn is_host(&self) -> bool {
+        matches!(self, Self::Host)
+    }
+
+    /// Returns `true` if the user namespace mode is [`Other`].
+    ///
+    /// [`Other`]: UsernsMode::Other
+    #[must_use]
+    pub const fn is_other(&self) -> bool {
+        matches!(self, Self::Other(..))
+    }
+
+    /// Returns [`Some`] if the user namespace mode is [`Other`].
+    ///
+    /// [`Other`]: UsernsMode::Other
+    #[must_use]
+    pub const fn as_other(&self) -> Option<&String> {
+        if let Self::Other(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+}
+
+impl_from_str!(UsernsMode => ParseUsernsModeError);
+
+impl Display for UsernsMode {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        match self {
+            Self::Host => f.write_str(Self::HOST),
+            Self::KeepId(options) => {
+                f.write_str(Self::KEEP_ID)?;
+                if !options.is_empty() {
+                    write!(f, ":{options}")?;
+                }
+                Ok(())
+            }
+            Self::Auto(options) => {
+                f.write_str(Self::AUTO)?;
+                if !options.is_empty() {
+                    write!(f, ":{options}")?;
+                }
+                Ok(())
+            }
+            Self::NoMap => f.write_str(Self::NO_MAP),
+            Self::Other(other) => f.write_str(other),
+        }
+    }
+}
+
+impl From<UsernsMode> for String {
+    fn from(value: UsernsMode) -> Self {
+        if let UsernsMode::Other(other) = value {
+            other
+        } else {
+            value.to_string()
+        }
+    }
+}
+
+impl From<UsernsMode> for Cow<'static, str> {
+    fn from(value: UsernsMode) -> Self {
+        match value {
+            UsernsMode::Host => Cow::Borrowed(UsernsMode::HOST),
+            UsernsMode::NoMap => Cow::Borrowed(UsernsMode::NO_MAP),
+            value => Cow::Owned(value.to_string()),
+        }
+    }
+}
+
+/// Options for the [`KeepId`](UsernsMode::KeepId) user namespace mode.
+#[derive(Debug, Default, Clone, Copy, PartialEq, Eq)]
+pub struct KeepIdOptions {
+    /// `uid={uid}`: Override the UID inside the container that the user is mapped to.
+    pub uid: Option<u32>,
+
+    /// `gid={gid}`: Override the GID inside the container that the user is mapped to.
+    pub gid: Option<u32>,
+
+    /// `size={size}`: Override the size of the configured user namespace.
+    pub size: Option<u32>,
+}
+
+impl KeepIdOptions {
+    /// Parse [`KeepIdOptions`] from a comma (,) separated list of `{key}={value}` pairs.
+    fn parse(options: &str) -> Result<Self, ParseUsernsModeError> {
+        let mut keep_id = Self::default();
+
+        for (key, value) in split_options(options) {
+            match key {
+                "uid" => keep_id.uid = Some(parse_u32(key, value)?),
+                "gid" => keep_id.gid = Some(parse_u32(key, value)?),
+                "size" => keep_id.size = Some(parse_u32(key, value)?),
+                _ => return Err(ParseUsernsModeError::UnknownOption(key.to_owned())),
+            }
+        }
+
+        Ok(keep_id)
+    }
+
+    /// Returns `true` if all options are [`None`].
+    #[must_use]
+    pub const fn is_empty(&self) -> bool {
+        let Self { uid, gid, size } = self;
+        uid.is_none() && gid.is_none() && size.is_none()
+    }
+}
+
+impl Display for KeepIdOptions {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self { uid, gid, size } = self;
+
+        let options = [("uid", uid), ("gid", gid), ("size", size)];
+        let mut options = options
+            .into_iter()
+            .filter_map(|(key, value)| value.map(|value| (key, value)));
+
+        if let Some((key, value)) = options.next() {
+            write!(f, "{key}={value}")?;
+            for (key, value) in options {
+                write!(f, ",{key}={value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Options for the [`Auto`](UsernsMode::Auto) user namespace mode.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct AutoOptions {
+    /// `size={size}`: Size of the user namespace to create.
+    pub size: Option<u32>,
+
+    /// `uidmapping={mapping}`: UID mappings to force in the user namespace, in the format
+    /// `{container_uid}:{host_uid}:{size}`.
+    pub uid_mapping: Vec<String>,
+
+    /// `gidmapping={mapping}`: GID mappings to force in the user namespace, in the format
+    /// `{container_gid}:{host_gid}:{size}`.
+    pub gid_mapping: Vec<String>,
+}
+
+impl AutoOptions {
+    /// `size` option key.
+    const SIZE: &'static str = "size";
+
+    /// `uidmapping` option key.
+    const UID_MAPPING: &'static str = "uidmapping";
+
+    /// `gidmapping` option key.
+    const GID_MAPPING: &'static str = "gidmapping";
+
+    /// Parse [`AutoOptions`] from a comma (,) separated list of `{key}={value}` pairs.
+    fn parse(options: &str) -> Result<Self, ParseUsernsModeError> {
+        let mut auto = Self::default();
+
+        for (key, value) in split_options(options) {
+            match key {
+                Self::SIZE => auto.size = Some(parse_u32(key, value)?),
+                Self::UID_MAPPING => auto.uid_mapping.push(value.to_owned()),
+                Self::GID_MAPPING => auto.gid_mapping.push(value.to_owned()),
+                _ => return Err(ParseUsernsModeError::UnknownOption(key.to_owned())),
+            }
+        }
+
+        Ok(auto)
+    }
+
+    /// Returns `true` if all options are [`None`] or empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            size,
+            uid_mapping,
+            gid_mapping,
+        } = self;
+
+        size.is_none() && uid_mapping.is_empty() && gid_mapping.is_empty()
+    }
+}
+
+impl Display for AutoOptions {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let Self {
+            size,
+            uid_mapping,
+            gid_mapping,
+        } = self;
+
+        let size = size.map(|size| (Self::SIZE, Cow::Owned(size.to_string())));
+        let uid_mapping = uid_mapping
+            .iter()
+            .map(|mapping| (Self::UID_MAPPING, Cow::Borrowed(mapping.as_str())));
+        let gid_mapping = gid_mapping
+            .iter()
+            .map(|mapping| (Self::GID_MAPPING, Cow::Borrowed(mapping.as_str())));
+        let mut options = size.into_iter().chain(uid_mapping).chain(gid_mapping);
+
+        if let Some((key, value)) = options.next() {
+            write!(f, "{key}={value}")?;
+            for (key, value) in options {
+                write!(f, ",{key}={value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+/// Split a comma (,) separated list of `{key}={value}` pairs.
+///
+/// Options without a value have an empty value.
+fn split_options(options: &str) -> impl Iterator<Item = (&str, &str)> {
+    options
+        .split(',')
+        .map(|option| option.split_once('=').unwrap_or((option, "")))
+}
+
+/// Parse the `value` of the option `key` as a [`u32`].
+fn parse_u32(key: &str, value: &str) -> Result<u32, ParseUsernsModeError> {
+    value.parse().map_err(|source| ParseUsernsModeError::Int {
+        key: key.to_owned(),
+        source,
+    })
+}
+
+/// Error returned when [parsing](UsernsMode::parse()) a [`UsernsMode`] from a string.
+#[derive(Error, Debug, Clone, PartialEq, Eq)]
+pub enum ParseUsernsModeError {
+    /// Unknown user namespace mode option.
+    #[error("unknown user namespace mode option `{0}`")]
+    UnknownOption(String),
+
+    /// Error parsing the value of an integer option.
+    #[error("error parsing user namespace mode `{key}` option value")]
+    Int {
+        /// Option key.
+        key: String,
+        /// Source of the error.
+        source: ParseIntError,
+    },
+}
+
+#[cfg(test)]
+mod tests {
+    use proptest::{
+        arbitrary::any,
+        collection::vec,
+        option, prop_assert_eq, prop_oneof, proptest,
+        strategy::{Just, Strategy},
+    };
+
+    use super::*;
+
+    proptest! {
+        #[test]
+        fn parse_no_panic(string: String) {
+            let _ = UsernsMode::parse(string);
+        }
+
+        #[test]
+        fn round_trip(userns_mode in userns_mode()) {
+            prop_assert_eq!(&userns_mode, &userns_mode.to_string().parse()?);
+        }
+    }
+
+    #[test]
+    fn parse() {
+        assert_eq!(UsernsMode::parse("host"), Ok(UsernsMode::Host));
+        assert_eq!(
+            UsernsMode::parse("keep-id"),
+            Ok(UsernsMode::KeepId(KeepIdOptions::default()))
+        );
+        assert_eq!(
+            UsernsMode::parse("keep-id:uid=1000,gid=1000"),
+            Ok(UsernsMode::KeepId(KeepIdOptions {
+                uid: Some(1000),
+                gid: Some(1000),
+                size: None,
+            }))
+        );
+        assert_eq!(
+            UsernsMode::parse("auto:size=65536,uidmapping=0:1000:1"),
+            Ok(UsernsMode::Auto(AutoOptions {
+                size: Some(0x0001_0000),
+                uid_mapping: vec!["0:1000:1".to_owned()],
+                gid_mapping: Vec::new(),
+            }))
+        );
+        assert_eq!(UsernsMode::parse("nomap"), Ok(UsernsMode::NoMap));
+        assert_eq!(
+            UsernsMode::parse("ns:/proc/1/ns/user"),
+            Ok(UsernsMode::Other("ns:/proc/1/ns/user".to_owned()))
+        );
+        assert_eq!(
+            UsernsMode::parse("keep-id:user=1000"),
+            Err(ParseUsernsModeError::UnknownOption("user".to_owned()))
+        );
+        assert!(matches!(
+            UsernsMode::parse("auto:size=big"),
+            Err(ParseUsernsModeError::Int { .. })
+        ));
+    }
+
+    fn userns_mode() -> impl Strategy<Value = UsernsMode> {
+        prop_oneof![
+            Just(UsernsMode::Host),
+            any::<(Option<u32>, Option<u32>, Option<u32>)>().prop_map(|(uid, gid, size)| {
+                UsernsMode::KeepId(KeepIdOptions { uid, gid, size })
+            }),
+            (
+                option::of(any::<u32>()),
+                vec("[0-9]+:[0-9]+:[0-9]+", 0..3),
+                vec("[0-9]+:[0-9]+:[0-9]+", 0..3),
+            )
+                .prop_map(|(size, uid_mapping, gid_mapping)| {
+                    UsernsMode::Auto(AutoOptions {
+                        size,
+                        uid_mapping,
+                        gid_mapping,
+                    })
+                }),
+            Just(UsernsMode::NoMap),
+            "private|ns:/[a-z]+".prop_map(UsernsMode::Other),
+        ]
+    }
+}
@@ -11,8 +11,13 @@ use std::{
 };
 
 use serde::{Deserialize, Serialize};
+use serde_yaml::Mapping;
 
-use crate::{service::ByteValue, Extensions, Identifier};
+use crate::{
+    common::parse_bool,
+    service::{ByteValue, Image as ImageReference, TmpfsEntry, TmpfsOption},
+    Extensions, Identifier,
+};
 
 use super::{AbsolutePath, HostPath, SELinux, ShortOptions, ShortVolume};
 
@@ -37,6 +42,9 @@ pub enum Mount {
 
     /// Cluster.
     Cluster(Cluster),
+
+    /// Read-only mount of an OCI image's filesystem.
+    Image(Image),
 }
 
 impl Mount {
@@ -54,6 +62,7 @@ impl Mount {
             Self::Tmpfs(_) => "tmpfs",
             Self::NamedPipe(_) => "npipe",
             Self::Cluster(_) => "cluster",
+            Self::Image(_) => "image",
         }
     }
 
@@ -157,6 +166,26 @@ impl Mount {
         }
     }
 
+    /// Returns `true` if the mount is an [`Image`].
+    ///
+    /// [`Image`]: Mount::Image
+    #[must_use]
+    pub const fn is_image(&self) -> bool {
+        matches!(self, Self::Image(..))
+    }
+
+    /// Returns [`Some`] if the mount is an [`Image`].
+    ///
+    /// [`Image`]: Mount::Image
+    #[must_use]
+    pub const fn as_image(&self) -> Option<&Image> {
+        if let Self::Image(v) = self {
+            Some(v)
+        } else {
+            None
+        }
+    }
+
     /// [`Common`] mount options.
     #[must_use]
     pub const fn common(&self) -> &Common {
@@ -166,6 +195,7 @@ impl Mount {
             Self::Tmpfs(mount) => &mount.common,
             Self::NamedPipe(mount) => &mount.common,
             Self::Cluster(mount) => &mount.common,
+            Self::Image(mount) => &mount.common,
         }
     }
 
@@ -178,11 +208,15 @@ impl Mount {
             Self::Tmpfs(_) => None,
             Self::NamedPipe(mount) => Some(mount.source.as_path().display().to_string()),
             Self::Cluster(mount) => Some(mount.source.clone()),
+            Self::Image(mount) => Some(mount.source.to_string()),
         }
     }
 
     /// Convert into the [`ShortVolume`] syntax if possible.
     ///
+    /// Only [`Volume`](Self::Volume) and [`Bind`](Self::Bind) mounts without options that the
+    /// short syntax cannot represent can be converted.
+    ///
     /// # Errors
     ///
     /// Returns ownership if this long syntax cannot be represented as the short syntax.
@@ -191,7 +225,7 @@ impl Mount {
         match self {
             Self::Volume(volume) => volume.into_short().map_err(Self::Volume),
             Self::Bind(bind) => bind.into_short().map_err(Self::Bind),
-            _ => Err(self),
+            Self::Tmpfs(_) | Self::NamedPipe(_) | Self::Cluster(_) | Self::Image(_) => Err(self),
         }
     }
 }
@@ -232,6 +266,12 @@ impl From<Cluster> for Mount {
     }
 }
 
+impl From<Image> for Mount {
+    fn from(value: Image) -> Self {
+        Self::Image(value)
+    }
+}
+
 /// Volume [`Mount`] type.
 ///
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#long-syntax-5)
@@ -420,9 +460,13 @@ impl Bind {
                      propagation,
                      create_host_path,
                      selinux: _,
+                     recursive,
                      extensions,
                  }| {
-                    propagation.is_none() && *create_host_path && extensions.is_empty()
+                    propagation.is_none()
+                        && *create_host_path
+                        && recursive.is_none()
+                        && extensions.is_empty()
                 },
             ) && extensions.is_empty() =>
             {
@@ -466,6 +510,12 @@ pub struct BindOptions {
     #[serde(default, skip_serializing_if = "Option::is_none")]
     pub selinux: Option<SELinux>,
 
+    /// How submounts of the `source` are bind mounted.
+    ///
+    /// Cannot be represented by the [`ShortVolume`] syntax.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub recursive: Option<BindRecursive>,
+
     /// Extension values, which are (de)serialized via flattening.
     ///
     /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/11-extension.md)
@@ -511,10 +561,15 @@ impl BindOptions {
             propagation,
             create_host_path,
             selinux,
+            recursive,
             extensions,
         } = self;
 
-        propagation.is_none() && !create_host_path && selinux.is_none() && extensions.is_empty()
+        propagation.is_none()
+            && !create_host_path
+            && selinux.is_none()
+            && recursive.is_none()
+            && extensions.is_empty()
     }
 }
 
@@ -524,12 +579,14 @@ impl PartialEq for BindOptions {
             propagation,
             create_host_path,
             selinux,
+            recursive,
             extensions,
         } = self;
 
         *propagation == other.propagation
             && *create_host_path == other.create_host_path
             && *selinux == other.selinux
+            && *recursive == other.recursive
             && extensions.as_slice() == other.extensions.as_slice()
     }
 }
@@ -540,12 +597,14 @@ impl Hash for BindOptions {
             propagation,
             create_host_path,
             selinux,
+            recursive,
             extensions,
         } = self;
 
         propagation.hash(state);
         create_host_path.hash(state);
         selinux.hash(state);
+        recursive.hash(state);
         extensions.as_slice().hash(state);
     }
 }
@@ -606,6 +665,49 @@ impl Display for BindPropagation {
     }
 }
 
+/// How submounts of a [`Bind`] [`Mount`] source are mounted.
+#[derive(Serialize, Deserialize, Debug, Clone, Copy, PartialEq, Eq, Hash)]
+#[serde(rename_all = "lowercase")]
+pub enum BindRecursive {
+    /// Submounts are bind mounted recursively, read-only if the mount is read-only.
+    ///
+    /// The default when not set.
+    Enabled,
+    /// Submounts are not bind mounted.
+    Disabled,
+    /// Submounts are bind mounted recursively and are writable, even if the mount is read-only.
+    Writable,
+    /// Submounts are bind mounted recursively and are read-only, even on older kernels which do
+    /// not support recursive read-only mounts (which results in an error).
+    #[serde(rename = "readonly")]
+    ReadOnly,
+}
+
+impl BindRecursive {
+    /// Bind recursive option as a static string slice.
+    #[must_use]
+    pub const fn as_str(self) -> &'static str {
+        match self {
+            Self::Enabled => "enabled",
+            Self::Disabled => "disabled",
+            Self::Writable => "writable",
+            Self::ReadOnly => "readonly",
+        }
+    }
+}
+
+impl AsRef<str> for BindRecursive {
+    fn as_ref(&self) -> &str {
+        self.as_str()
+    }
+}
+
+impl Display for BindRecursive {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        f.write_str(self.as_str())
+    }
+}
+
 /// Temporary file system [`Mount`] type.
 ///
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#long-syntax-5)
@@ -637,6 +739,58 @@ impl Tmpfs {
     pub fn from_target(target: AbsolutePath) -> Self {
         Self::new(Common::new(target))
     }
+
+    /// Convert into the short [`TmpfsEntry`] syntax used by the [`Service`](crate::Service)
+    /// `tmpfs` field if possible.
+    ///
+    /// # Errors
+    ///
+    /// Returns ownership if `consistency` or extensions are set, as they cannot be represented in
+    /// the short syntax.
+    #[allow(clippy::result_large_err)]
+    pub fn into_short(self) -> Result<TmpfsEntry, Self> {
+        let Self {
+            tmpfs,
+            common:
+                Common {
+                    target,
+                    read_only,
+                    consistency,
+                    extensions,
+                },
+        } = self;
+
+        if consistency.is_some()
+            || !extensions.is_empty()
+            || tmpfs
+                .as_ref()
+                .is_some_and(|tmpfs| !tmpfs.extensions.is_empty())
+        {
+            return Err(Self {
+                tmpfs,
+                common: Common {
+                    target,
+                    read_only,
+                    consistency,
+                    extensions,
+                },
+            });
+        }
+
+        let mut options = Vec::new();
+        if let Some(TmpfsOptions { size, mode, .. }) = tmpfs {
+            options.extend(size.map(TmpfsOption::Size));
+            options.extend(mode.map(TmpfsOption::Mode));
+        }
+        if read_only {
+            options.push(TmpfsOption::ReadOnly);
+        }
+
+        Ok(TmpfsEntry {
+            path: target,
+            options,
+        })
+    }
 }
 
 impl From<Common> for Tmpfs {
@@ -759,6 +913,87 @@ impl From<(String, Common)> for Cluster {
     }
 }
 
+/// Image [`Mount`] type, mounts the filesystem of an OCI image read-only.
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#long-syntax-5)
+#[derive(Serialize, Deserialize, Debug, Clone, PartialEq, Eq, Hash)]
+pub struct Image {
+    /// Image to mount.
+    pub source: ImageReference,
+
+    /// Additional image options.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub image: Option<ImageOptions>,
+
+    /// Common [`Mount`] options.
+    ///
+    /// (De)serialized via flattening.
+    #[serde(flatten)]
+    pub common: Common,
+}
+
+impl From<(ImageReference, Common)> for Image {
+    fn from((source, common): (ImageReference, Common)) -> Self {
+        Self {
+            source,
+            image: None,
+            common,
+        }
+    }
+}
+
+/// Additional [`Image`] [`Mount`] options.
+///
+/// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#long-syntax-5)
+#[derive(Serialize, Deserialize, Debug, Default, Clone, Eq)]
+pub struct ImageOptions {
+    /// Path inside the image to mount instead of the image root.
+    #[serde(default, skip_serializing_if = "Option::is_none")]
+    pub subpath: Option<PathBuf>,
+
+    /// Extension values, which are (de)serialized via flattening.
+    ///
+    /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/11-extension.md)
+    #[serde(flatten)]
+    pub extensions: Extensions,
+}
+
+impl ImageOptions {
+    /// Returns `true` if all fields are [`None`] or empty.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        let Self {
+            subpath,
+            extensions,
+        } = self;
+
+        subpath.is_none() && extensions.is_empty()
+    }
+}
+
+impl PartialEq for ImageOptions {
+    fn eq(&self, other: &Self) -> bool {
+        let Self {
+            subpath,
+            extensions,
+        } = self;
+
+        *subpath == other.subpath && extensions.as_slice() == other.extensions.as_slice()
+    }
+}
+
+impl Hash for ImageOptions {
+    fn hash<H: Hasher>(&self, state: &mut H) {
+        let Self {
+            subpath,
+            extensions,
+        } = self;
+
+        subpath.hash(state);
+        extensions.as_slice().hash(state);
+    }
+}
+
 /// Options common to all [`Mount`] types.
 ///
 /// [compose-spec](https://github.com/compose-spec/compose-spec/blob/master/05-services.md#long-syntax-5)
@@ -834,3 +1069,122 @@ impl From<AbsolutePath> for Common {
         Self::new(target)
     }
 }
+
+/// Parse the comma separated options of a `--mount` flag, as used by `docker run` and
+/// `podman run`, into a [`Mount`].
+///
+/// `tmpfs-*` options are parsed with [`TmpfsOption::parse()`], the same as the options of a
+/// [`TmpfsEntry`].
+///
+/// Returns [`None`] if an option is unknown or invalid.
+pub(crate) fn parse_mount_flag<I, S>(options: I) -> Option<Mount>
+where
+    I: IntoIterator<Item = S>,
+    S: AsRef<str>,
+{
+    let mut fields = Mapping::new();
+    let mut mount_options = Mapping::new();
+    for option in options {
+        let option = option.as_ref();
+        if let Some(tmpfs_option) = option.strip_prefix("tmpfs-") {
+            let (key, value) = match TmpfsOption::parse(tmpfs_option).ok()? {
+                TmpfsOption::Size(size) => ("size", serde_yaml::to_value(size).ok()?),
+                TmpfsOption::Mode(mode) => ("mode", mode.into()),
+                _ => return None,
+            };
+            mount_options.insert(key.into(), value);
+            continue;
+        }
+
+        let (key, value) = option.split_once('=').unwrap_or((option, "true"));
+        let (key, value) = match key {
+            "type" => ("type", value.into()),
+            "source" | "src" => ("source", value.into()),
+            "destination" | "dst" | "target" => ("target", value.into()),
+            "readonly" | "ro" => ("read_only", parse_bool(value)?.into()),
+            "consistency" => ("consistency", value.into()),
+            "bind-propagation" => ("propagation", value.into()),
+            "bind-recursive" => ("recursive", value.into()),
+            "bind-nonrecursive" if parse_bool(value)? => ("recursive", "disabled".into()),
+            "bind-nonrecursive" => continue,
+            "relabel" => (
+                "selinux",
+                match value {
+                    "shared" => "z",
+                    "private" => "Z",
+                    _ => return None,
+                }
+                .into(),
+            ),
+            "volume-nocopy" => ("nocopy", parse_bool(value)?.into()),
+            "volume-subpath" | "image-subpath" | "subpath" => ("subpath", value.into()),
+            _ => return None,
+        };
+        if matches!(
+            key,
+            "type" | "source" | "target" | "read_only" | "consistency"
+        ) {
+            fields.insert(key.into(), value);
+        } else {
+            mount_options.insert(key.into(), value);
+        }
+    }
+    if !mount_options.is_empty() {
+        let kind = fields.get("type")?.clone();
+        fields.insert(kind, mount_options.into());
+    }
+
+    serde_yaml::from_value(fields.into()).ok()
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn image_round_trip() {
+        let yaml = "\
+type: image
+source: alpine:latest
+image:
+  subpath: etc
+target: /target
+";
+        let mount: Mount = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            mount,
+            Mount::Image(Image {
+                source: "alpine:latest".parse().unwrap(),
+                image: Some(ImageOptions {
+                    subpath: Some("etc".into()),
+                    extensions: Extensions::default(),
+                }),
+                common: Common::new(AbsolutePath::new("/target").unwrap()),
+            })
+        );
+        assert_eq!(serde_yaml::to_string(&mount).unwrap(), yaml);
+        assert!(mount.into_short().is_err_and(|mount| mount.is_image()));
+    }
+
+    #[test]
+    fn bind_recursive_round_trip() {
+        let yaml = "\
+type: bind
+source: ./source
+bind:
+  create_host_path: true
+  recursive: readonly
+target: /target
+";
+        let mount: Mount = serde_yaml::from_str(yaml).unwrap();
+        assert_eq!(
+            mount
+                .as_bind()
+                .and_then(|bind| bind.bind.as_ref()?.recursive),
+            Some(BindRecursive::ReadOnly)
+        );
+        assert_eq!(serde_yaml::to_string(&mount).unwrap(), yaml);
+        assert!(mount.into_short().is_err_and(|mount| mount.is_bind()));
+    }
+}
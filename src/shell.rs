@@ -0,0 +1,119 @@
+//! Utilities for working with POSIX shell words.
+
+/// Split a string into words like a POSIX shell would, without any expansions.
+///
+/// Words are separated by whitespace. Characters within single quotes (`'`) are taken literally.
+/// Within double quotes (`"`), a backslash only escapes `$`, `` ` ``, `"`, `\`, or a newline.
+/// Outside of quotes, a backslash escapes the following character. An escaped newline is removed.
+///
+/// Returns [`None`] if a quote is not terminated or the string ends with a backslash.
+pub(crate) fn split(string: &str) -> Option<Vec<String>> {
+    let mut words = Vec::new();
+    let mut word: Option<String> = None;
+    let mut chars = string.chars();
+
+    while let Some(char) = chars.next() {
+        match char {
+            '\'' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '\'' => break,
+                        char => word.push(char),
+                    }
+                }
+            }
+            '"' => {
+                let word = word.get_or_insert_with(String::new);
+                loop {
+                    match chars.next()? {
+                        '"' => break,
+                        '\\' => match chars.next()? {
+                            '\n' => {}
+                            char @ ('$' | '`' | '"' | '\\') => word.push(char),
+                            char => {
+                                word.push('\\');
+                                word.push(char);
+                            }
+                        },
+                        char => word.push(char),
+                    }
+                }
+            }
+            '\\' => match chars.next()? {
+                '\n' => {}
+                char => word.get_or_insert_with(String::new).push(char),
+            },
+            char if char.is_whitespace() => words.extend(word.take()),
+            char => word.get_or_insert_with(String::new).push(char),
+        }
+    }
+    words.extend(word);
+
+    Some(words)
+}
+
+/// Quote a word for use in a POSIX shell command line, the inverse of [`split()`].
+///
+/// Words made up of only ASCII alphanumeric characters and `%+,-./:=@_` are returned as is, all
+/// others are wrapped in single quotes (`'`).
+pub(crate) fn quote(word: &str) -> String {
+    if !word.is_empty()
+        && word
+            .chars()
+            .all(|char| char.is_ascii_alphanumeric() || "%+,-./:=@_".contains(char))
+    {
+        return word.to_owned();
+    }
+
+    let mut quoted = String::with_capacity(word.len() + 2);
+    quoted.push('\'');
+    for char in word.chars() {
+        if char == '\'' {
+            // End the quote, add an escaped quote, and start a new quote.
+            quoted.push_str("'\\''");
+        } else {
+            quoted.push(char);
+        }
+    }
+    quoted.push('\'');
+    quoted
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn split_words() {
+        assert_eq!(
+            split(r#" echo  "hello world" 'it'\''s' a\ b "\$HOME \n" '' "#).unwrap(),
+            ["echo", "hello world", "it's", "a b", "$HOME \\n", ""],
+        );
+        assert_eq!(split("one\\\ntwo").unwrap(), ["onetwo"]);
+        assert!(split("").unwrap().is_empty());
+        assert_eq!(split(r#""unterminated"#), None);
+        assert_eq!(split("'unterminated"), None);
+        assert_eq!(split("trailing\\"), None);
+    }
+
+    #[test]
+    fn quote_words() {
+        assert_eq!(quote("--env=KEY=value"), "--env=KEY=value");
+        assert_eq!(quote(""), "''");
+        assert_eq!(quote("daemon off;"), "'daemon off;'");
+        assert_eq!(quote("it's"), r"'it'\''s'");
+
+        for word in [
+            "word",
+            "",
+            "two words",
+            "it's",
+            r#"say "hi" \ $HOME"#,
+            "line\n",
+        ] {
+            assert_eq!(split(&quote(word)).unwrap(), [word]);
+        }
+    }
+}
@@ -0,0 +1,398 @@
+//! Provides [`Unit`] for representing and parsing systemd unit files, as used by Podman Quadlet.
+//!
+//! [systemd.syntax](https://www.freedesktop.org/software/systemd/man/latest/systemd.syntax.html)
+
+use std::{
+    fmt::{self, Display, Formatter, Write},
+    str::FromStr,
+};
+
+use indexmap::IndexMap;
+use thiserror::Error;
+
+/// A systemd unit file.
+///
+/// Unit files are made up of named sections, each containing an ordered list of key-value
+/// entries. Keys may be repeated within a section.
+///
+/// The [`Display`] implementation writes the unit in the systemd INI syntax, and the [`FromStr`]
+/// implementation parses it.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Unit {
+    /// Sections of the unit, in order, by name.
+    pub sections: IndexMap<String, Section>,
+}
+
+impl Unit {
+    /// Create a new, empty [`Unit`].
+    #[must_use]
+    pub fn new() -> Self {
+        Self::default()
+    }
+
+    /// Get the section with the given `name`, if it exists.
+    #[must_use]
+    pub fn section(&self, name: &str) -> Option<&Section> {
+        self.sections.get(name)
+    }
+
+    /// Get a mutable reference to the section with the given `name`, adding it to the end of the
+    /// unit if it does not exist.
+    pub fn section_mut<T>(&mut self, name: T) -> &mut Section
+    where
+        T: AsRef<str> + Into<String>,
+    {
+        self.sections.entry(name.into()).or_default()
+    }
+
+    /// Returns `true` if the unit has no entries in any of its sections.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.sections.values().all(Section::is_empty)
+    }
+}
+
+impl Display for Unit {
+    fn fmt(&self, f: &mut Formatter) -> fmt::Result {
+        let mut first = true;
+        for (name, section) in &self.sections {
+            if !first {
+                f.write_char('\n')?;
+            }
+            first = false;
+
+            writeln!(f, "[{name}]")?;
+            for (key, value) in &section.entries {
+                writeln!(f, "{key}={value}")?;
+            }
+        }
+
+        Ok(())
+    }
+}
+
+impl FromStr for Unit {
+    type Err = ParseUnitError;
+
+    fn from_str(s: &str) -> Result<Self, Self::Err> {
+        let mut unit = Self::new();
+        let mut section = None;
+        let mut lines = s
+            .lines()
+            .enumerate()
+            .map(|(index, line)| (index + 1, line.trim()));
+
+        while let Some((line_number, line)) = lines.next() {
+            if is_ignored(line) {
+                continue;
+            }
+
+            if let Some(name) = line.strip_prefix('[') {
+                let name = name
+                    .strip_suffix(']')
+                    .ok_or(ParseUnitError::UnclosedSection { line: line_number })?;
+                unit.section_mut(name);
+                section = Some(name);
+                continue;
+            }
+
+            // A backslash at the end of a line continues it onto the next, comments within are
+            // ignored.
+            let mut line = line.to_owned();
+            while let Some(stripped) = line.strip_suffix('\\') {
+                line.truncate(stripped.len());
+                let Some(next) = lines
+                    .by_ref()
+                    .map(|(_, line)| line)
+                    .find(|line| !is_ignored(line))
+                else {
+                    break;
+                };
+                line.push(' ');
+                line.push_str(next);
+            }
+
+            let (key, value) = line
+                .split_once('=')
+                .ok_or(ParseUnitError::MissingEquals { line: line_number })?;
+            section
+                .map(|section| unit.section_mut(section))
+                .ok_or(ParseUnitError::NoSection { line: line_number })?
+                .push(key.trim_end(), value.trim_start());
+        }
+
+        Ok(unit)
+    }
+}
+
+/// Returns `true` if the line is empty or a comment.
+fn is_ignored(line: &str) -> bool {
+    line.is_empty() || line.starts_with(['#', ';'])
+}
+
+/// Error returned when parsing a [`Unit`] from a string.
+#[derive(Error, Debug, Clone, Copy, PartialEq, Eq)]
+pub enum ParseUnitError {
+    /// A section header was missing the closing `]`.
+    #[error("section header on line {line} is missing the closing `]`")]
+    UnclosedSection {
+        /// Line number of the section header.
+        line: usize,
+    },
+
+    /// An entry was missing the `=` separating its key and value.
+    #[error("entry on line {line} is missing `=`")]
+    MissingEquals {
+        /// Line number of the entry.
+        line: usize,
+    },
+
+    /// An entry came before the first section header.
+    #[error("entry on line {line} is not in a section")]
+    NoSection {
+        /// Line number of the entry.
+        line: usize,
+    },
+}
+
+/// A section of a systemd [`Unit`] file.
+#[derive(Debug, Default, Clone, PartialEq, Eq)]
+pub struct Section {
+    /// Key-value entries of the section, in order.
+    ///
+    /// Keys may be repeated.
+    pub entries: Vec<(String, String)>,
+}
+
+impl Section {
+    /// Add an entry to the end of the section.
+    pub fn push<K, V>(&mut self, key: K, value: V)
+    where
+        K: Into<String>,
+        V: Into<String>,
+    {
+        self.entries.push((key.into(), value.into()));
+    }
+
+    /// Iterator over all values for the given `key`, in order.
+    pub fn get_all<'a>(&'a self, key: &'a str) -> impl Iterator<Item = &'a str> {
+        self.entries
+            .iter()
+            .filter(move |(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Get the last value for the given `key`, as later entries override earlier ones.
+    #[must_use]
+    pub fn get(&self, key: &str) -> Option<&str> {
+        self.entries
+            .iter()
+            .rev()
+            .find(|(entry_key, _)| entry_key == key)
+            .map(|(_, value)| value.as_str())
+    }
+
+    /// Returns `true` if the section has an entry with the given `key`.
+    #[must_use]
+    pub fn contains_key(&self, key: &str) -> bool {
+        self.entries.iter().any(|(entry_key, _)| entry_key == key)
+    }
+
+    /// Returns `true` if the section has no entries.
+    #[must_use]
+    pub fn is_empty(&self) -> bool {
+        self.entries.is_empty()
+    }
+}
+
+/// Escape `%` specifiers in a value so systemd uses it literally.
+pub(crate) fn escape_specifiers(value: &str) -> String {
+    value.replace('%', "%%")
+}
+
+/// Replace escaped `%%` specifiers in a value with `%`.
+///
+/// Returns [`None`] if the value contains any other specifier, as they are expanded by systemd.
+pub(crate) fn unescape_specifiers(value: &str) -> Option<String> {
+    let mut unescaped = String::with_capacity(value.len());
+    let mut chars = value.chars();
+    while let Some(char) = chars.next() {
+        if char == '%' && chars.next() != Some('%') {
+            return None;
+        }
+        unescaped.push(char);
+    }
+    Some(unescaped)
+}
+
+/// Split a systemd value into words, the inverse of [`quote()`].
+///
+/// Words are separated by whitespace and may be quoted with `"` or `'`. Backslash escapes are
+/// resolved and `%%` specifiers are unescaped.
+///
+/// Returns [`None`] if a quote is not terminated or if the value contains any other specifier.
+pub(crate) fn split_words(value: &str) -> Option<Vec<String>> {
+    let value = unescape_specifiers(value)?;
+    let mut words = Vec::new();
+    let mut chars = value.chars().peekable();
+
+    loop {
+        while chars.next_if(|char| char.is_whitespace()).is_some() {}
+        let Some(&first) = chars.peek() else {
+            return Some(words);
+        };
+
+        let mut word = String::new();
+        let quote = matches!(first, '"' | '\'').then(|| chars.next()).flatten();
+        loop {
+            match (chars.next(), quote) {
+                (None, Some(_)) => return None,
+                (None, None) => break,
+                (Some(char), Some(quote)) if char == quote => break,
+                (Some(char), None) if char.is_whitespace() => break,
+                (Some('\\'), _) => match chars.next() {
+                    Some('n') => word.push('\n'),
+                    Some('t') => word.push('\t'),
+                    Some(char) => word.push(char),
+                    None => {}
+                },
+                (Some(char), _) => word.push(char),
+            }
+        }
+        words.push(word);
+    }
+}
+
+/// Quote a word for use in a systemd value which is split into words, e.g. `ExecStart=` or
+/// `Environment=`.
+///
+/// Words without whitespace, quotes, or backslashes are returned as is. Specifiers are escaped.
+pub(crate) fn quote(word: &str) -> String {
+    let word = escape_specifiers(word);
+    if !word.is_empty()
+        && !word
+            .chars()
+            .any(|char| char.is_whitespace() || matches!(char, '"' | '\'' | '\\'))
+    {
+        return word;
+    }
+
+    let mut quoted = String::with_capacity(word.len() + 2);
+    quoted.push('"');
+    for char in word.chars() {
+        match char {
+            '"' | '\\' => {
+                quoted.push('\\');
+                quoted.push(char);
+            }
+            '\n' => quoted.push_str("\\n"),
+            '\t' => quoted.push_str("\\t"),
+            char => quoted.push(char),
+        }
+    }
+    quoted.push('"');
+    quoted
+}
+
+#[cfg(test)]
+#[allow(clippy::unwrap_used)]
+mod tests {
+    use super::*;
+
+    #[test]
+    fn display() {
+        let mut unit = Unit::new();
+        unit.section_mut("Unit").push("Requires", "one.service");
+        unit.section_mut("Container").push("Image", "image");
+        unit.section_mut("Unit").push("Requires", "two.service");
+        unit.section_mut("Service");
+
+        assert_eq!(
+            unit.to_string(),
+            "[Unit]\n\
+            Requires=one.service\n\
+            Requires=two.service\n\
+            \n\
+            [Container]\n\
+            Image=image\n\
+            \n\
+            [Service]\n"
+        );
+    }
+
+    #[test]
+    fn parse() {
+        let unit: Unit = "\
+# comment
+[Unit]
+Requires=one.service
+Requires = two.service
+
+[Container]
+; comment
+Exec=sleep \\
+  # comment
+  infinity
+Environment=
+"
+        .parse()
+        .unwrap();
+
+        let mut expected = Unit::new();
+        let section = expected.section_mut("Unit");
+        section.push("Requires", "one.service");
+        section.push("Requires", "two.service");
+        let section = expected.section_mut("Container");
+        section.push("Exec", "sleep  infinity");
+        section.push("Environment", "");
+
+        assert_eq!(unit, expected);
+        assert_eq!(unit.to_string().parse::<Unit>().unwrap(), expected);
+    }
+
+    #[test]
+    fn parse_err() {
+        assert_eq!(
+            "[Unit".parse::<Unit>(),
+            Err(ParseUnitError::UnclosedSection { line: 1 }),
+        );
+        assert_eq!(
+            "[Unit]\nRequires".parse::<Unit>(),
+            Err(ParseUnitError::MissingEquals { line: 2 }),
+        );
+        assert_eq!(
+            "Requires=one.service".parse::<Unit>(),
+            Err(ParseUnitError::NoSection { line: 1 }),
+        );
+    }
+
+    #[test]
+    fn quote_words() {
+        assert_eq!(quote("word"), "word");
+        assert_eq!(quote("100%"), "100%%");
+        assert_eq!(quote(""), r#""""#);
+        assert_eq!(quote("two words"), r#""two words""#);
+        assert_eq!(quote(r#"say "hi" \ "#), r#""say \"hi\" \\ ""#);
+    }
+
+    #[test]
+    fn split() {
+        assert_eq!(
+            split_words(r#" one "two words" 'three' 100%% "say \"hi\"" "#).unwrap(),
+            ["one", "two words", "three", "100%", r#"say "hi""#],
+        );
+        assert_eq!(split_words(r#""unterminated"#), None);
+        assert_eq!(split_words("%h/data"), None);
+
+        for word in [
+            "word",
+            "100%",
+            "",
+            "two words",
+            r#"say "hi" \ "#,
+            "tab\tline\n",
+        ] {
+            assert_eq!(split_words(&quote(word)).unwrap(), [word]);
+        }
+    }
+}